@@ -0,0 +1,62 @@
+//! Compares the incremental sortition CDF against the factorial based reference implementation
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use hotshot::traits::election::vrf::sortition::{reference, SortitionCache, SortitionParams};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaChaRng;
+
+/// A batch of VRF outputs to locate
+fn seeds() -> Vec<[u8; 32]> {
+    let mut rng = ChaChaRng::from_seed([0; 32]);
+    (0..32)
+        .map(|_| {
+            let mut seed = [0_u8; 32];
+            rng.fill_bytes(&mut seed);
+            seed
+        })
+        .collect()
+}
+
+/// Locate a batch of seeds for replicas of increasing stake
+fn sortition(c: &mut Criterion) {
+    let seeds = seeds();
+    let mut group = c.benchmark_group("sortition");
+    for replicas_stake in [10, 100, 1000] {
+        let params = SortitionParams {
+            replicas_stake,
+            total_stake: 100 * replicas_stake,
+            sortition_parameter: 100,
+        };
+        group.bench_with_input(
+            BenchmarkId::new("reference", replicas_stake),
+            &params,
+            |b, params| {
+                b.iter(|| {
+                    for seed in &seeds {
+                        black_box(reference::find_bin_idx(*params, seed));
+                    }
+                });
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("cold", replicas_stake), &params, |b, params| {
+            b.iter(|| {
+                let mut cache = SortitionCache::default();
+                for seed in &seeds {
+                    black_box(cache.find_bin_idx(*params, seed));
+                }
+            });
+        });
+        let mut cache = SortitionCache::default();
+        group.bench_with_input(BenchmarkId::new("warm", replicas_stake), &params, |b, params| {
+            b.iter(|| {
+                for seed in &seeds {
+                    black_box(cache.find_bin_idx(*params, seed));
+                }
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sortition);
+criterion_main!(benches);
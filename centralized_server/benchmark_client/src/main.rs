@@ -10,6 +10,7 @@ use hotshot_types::traits::signature_key::{
     ed25519::{Ed25519Priv, Ed25519Pub},
     SignatureKey,
 };
+use hotshot_utils::version::ProtocolVersion;
 use std::{net::ToSocketAddrs, time::Instant};
 use tracing::{error, info};
 
@@ -50,7 +51,11 @@ async fn main() {
     let privkey = Ed25519Priv::generated_from_seed_indexed([0u8; 32], config.node_index);
     let pubkey = Ed25519Pub::from_private(&privkey);
     write
-        .send(ToServer::Identify { key: pubkey })
+        .send(ToServer::Identify {
+            key: pubkey,
+            chain_id: config.config.chain_id,
+            version: ProtocolVersion::CURRENT,
+        })
         .await
         .unwrap();
 
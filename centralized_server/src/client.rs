@@ -7,6 +7,7 @@ use async_compatibility_layer::{
     channel::{bounded, oneshot, Sender},
 };
 use hotshot_types::traits::{election::ElectionConfig, signature_key::SignatureKey};
+use hotshot_utils::version::ProtocolVersion;
 use std::{net::SocketAddr, num::NonZeroUsize};
 use tracing::{debug, warn};
 
@@ -110,8 +111,24 @@ async fn run_client<K: SignatureKey + 'static, E: ElectionConfig + 'static>(
         // Most of these messages are mapped to `ToBackground` and send to the background thread.
         // See `background_task` in `src/lib.rs` for more information
         match (msg, parent_key.is_some()) {
+            // Client tries to identify on another chain than the run is on
+            (ToServer::Identify { chain_id, .. }, false) if chain_id != config.config.chain_id => {
+                return Err(Error::WrongChain {
+                    expected: config.config.chain_id,
+                    found: chain_id,
+                });
+            }
+            // Client tries to identify with a version of the wire protocol the run cannot speak
+            (ToServer::Identify { version, .. }, false)
+                if !ProtocolVersion::CURRENT.is_compatible(version) =>
+            {
+                return Err(Error::IncompatibleVersion {
+                    expected: ProtocolVersion::CURRENT,
+                    found: version,
+                });
+            }
             // Client tries to identify with the given signature key `key`, and we don't have key yet
-            (ToServer::Identify { key }, false) => {
+            (ToServer::Identify { key, .. }, false) => {
                 // set the key for `spawn` so we can properly disconnect
                 *parent_key = Some(key.clone());
                 let sender = sender.clone();
@@ -1,10 +1,12 @@
 pub use crate::runs::RoundConfig;
 
 use crate::Run;
-use hotshot_types::{ExecutionType, HotShotConfig};
+use hotshot_types::{
+    constants::default_key_rotation_epoch, traits::signer::ChainId, ExecutionType, HotShotConfig,
+};
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    num::NonZeroUsize,
+    num::{NonZeroU64, NonZeroUsize},
     time::Duration,
 };
 
@@ -173,6 +175,12 @@ pub struct HotShotConfigFile {
     pub propose_min_round_time: Duration,
     /// The maximum amount of time a leader can wait to start a round
     pub propose_max_round_time: Duration,
+    /// The network the nodes take part in
+    #[serde(default)]
+    pub chain_id: ChainId,
+    /// Number of views in an epoch of the stake table
+    #[serde(default = "default_key_rotation_epoch")]
+    pub key_rotation_epoch: NonZeroU64,
 }
 
 impl<K, E> From<HotShotConfigFile> for HotShotConfig<K, E> {
@@ -183,6 +191,7 @@ impl<K, E> From<HotShotConfigFile> for HotShotConfig<K, E> {
             max_transactions: val.max_transactions,
             min_transactions: val.min_transactions,
             known_nodes: Vec::new(),
+            possession_proofs: Vec::new(),
             next_view_timeout: val.next_view_timeout,
             timeout_ratio: val.timeout_ratio,
             round_start_delay: val.round_start_delay,
@@ -192,6 +201,10 @@ impl<K, E> From<HotShotConfigFile> for HotShotConfig<K, E> {
             propose_max_round_time: val.propose_max_round_time,
             // TODO fix this to be from the config file
             election_config: None,
+            adaptive_timeout: None,
+            transaction_forwarding: None,
+            chain_id: val.chain_id,
+            key_rotation_epoch: val.key_rotation_epoch,
         }
     }
 }
@@ -219,6 +232,8 @@ fn default_config() -> HotShotConfigFile {
         propose_min_round_time: Duration::from_secs(0),
         propose_max_round_time: Duration::from_secs(10),
         num_bootstrap: 7,
+        chain_id: ChainId::default(),
+        key_rotation_epoch: default_key_rotation_epoch(),
     }
 }
 
@@ -15,8 +15,10 @@ use bincode::Options;
 use clients::Clients;
 use config::ClientConfig;
 use futures::FutureExt as _;
-use hotshot_types::traits::{election::ElectionConfig, signature_key::SignatureKey};
-use hotshot_utils::bincode::bincode_opts;
+use hotshot_types::traits::{
+    election::ElectionConfig, signature_key::SignatureKey, signer::ChainId,
+};
+use hotshot_utils::{bincode::bincode_opts, version::ProtocolVersion};
 use runs::RoundConfig;
 use snafu::ResultExt;
 use std::{
@@ -69,7 +71,11 @@ pub(crate) const MAX_CHUNK_SIZE: usize = 256 * 1024;
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub enum ToServer<K> {
     GetConfig,
-    Identify { key: K },
+    Identify {
+        key: K,
+        chain_id: ChainId,
+        version: ProtocolVersion,
+    },
     Broadcast { message_len: u64 },
     Direct { target: K, message_len: u64 },
     RequestClientCount,
@@ -569,6 +575,16 @@ pub enum Error {
         source_len: usize,
         target_len: usize,
     },
+    #[snafu(display("Client identified on chain {found:?}, but the run is on chain {expected:?}"))]
+    WrongChain {
+        expected: ChainId,
+        found: ChainId,
+    },
+    #[snafu(display("Client speaks protocol version {found}, incompatible with {expected}"))]
+    IncompatibleVersion {
+        expected: ProtocolVersion,
+        found: ProtocolVersion,
+    },
 }
 
 #[async_trait]
@@ -13,7 +13,8 @@ use hotshot_types::{
     traits::{
         election::{CommitteeExchangeType, ConsensusExchange},
         node_implementation::{CommitteeProposal, CommitteeVote, NodeImplementation, NodeType},
-        signature_key::SignatureKey,
+        signer::SigningKind,
+        Block,
     },
     vote::DAVote,
 };
@@ -70,7 +71,7 @@ where
     #[allow(clippy::type_complexity)]
     async fn find_valid_msg<'a>(
         &self,
-        view_leader_key: TYPES::SignatureKey,
+        view_leader_key: Option<TYPES::SignatureKey>,
     ) -> Option<TYPES::BlockType> {
         let lock = self.proposal_collection_chan.lock().await;
         let leaf = loop {
@@ -84,17 +85,36 @@ where
                 }
                 match msg {
                     ProcessedConsensusMessage::DAProposal(p, sender) => {
-                        if view_leader_key != sender {
+                        // with no known leader no proposal is accepted, and the view times out
+                        let Some(view_leader_key) =
+                            view_leader_key.as_ref().filter(|leader| **leader == sender)
+                        else {
                             continue;
-                        }
+                        };
 
                         let block_commitment = p.data.deltas.commit();
-                        if !view_leader_key.validate(&p.signature, block_commitment.as_ref()) {
+                        if !self.exchange.is_valid_signature(
+                            &view_leader_key,
+                            &p.signature,
+                            self.cur_view,
+                            SigningKind::DAProposal,
+                            block_commitment.as_ref(),
+                        ) {
                             warn!(?p.signature, "Could not verify proposal.");
                             continue;
                         }
 
-                        let vote_token = self.exchange.make_vote_token(self.cur_view);
+                        let included_txns = p.data.deltas.contained_transactions();
+                        if !included_txns.is_empty() {
+                            self.api
+                                .send_transactions_included(
+                                    self.cur_view,
+                                    included_txns.into_iter().collect(),
+                                )
+                                .await;
+                        }
+
+                        let vote_token = self.exchange.make_vote_token(self.cur_view).await;
                         match vote_token {
                             Err(e) => {
                                 error!(
@@ -109,21 +129,35 @@ where
                                 info!("We were chosen for DA committee on {:?}", self.cur_view);
 
                                 // Generate and send vote
-                                let message = self.exchange.create_da_message(
-                                    self.high_qc.commit(),
-                                    block_commitment,
-                                    self.cur_view,
-                                    vote_token,
-                                );
+                                let message = match self
+                                    .exchange
+                                    .create_da_message(
+                                        self.high_qc.commit(),
+                                        block_commitment,
+                                        self.cur_view,
+                                        vote_token,
+                                    )
+                                    .await
+                                {
+                                    Ok(message) => message,
+                                    Err(e) => {
+                                        error!(
+                                            "Failed to sign DA vote for {:?} {:?}",
+                                            self.cur_view, e
+                                        );
+                                        break p.data.deltas;
+                                    }
+                                };
 
                                 info!("Sending vote to the leader {:?}", message);
 
                                 let consensus = self.consensus.read().await;
-                                if self.api.send_direct_da_message::<CommitteeProposal<TYPES, I>, CommitteeVote<TYPES, I>>(sender, message).await.is_err() {
+                                if self.api.send_direct_da_message::<CommitteeProposal<TYPES, I>, CommitteeVote<TYPES, I>>(sender.clone(), message).await.is_err() {
                                     consensus.metrics.failed_to_send_messages.add(1);
                                     warn!("Failed to send vote to the leader");
                                 } else {
                                     consensus.metrics.outgoing_direct_messages.add(1);
+                                    self.api.send_vote_sent(self.cur_view, sender).await;
                                 }
                             }
                         }
@@ -160,7 +194,13 @@ where
     #[instrument(skip(self), fields(id = self.id, view = *self.cur_view), name = "DA Member Task", level = "error")]
     pub async fn run_view(self) -> QuorumCertificate<TYPES, SequencingLeaf<TYPES>> {
         info!("DA Committee Member task started!");
-        let view_leader_key = self.exchange.get_leader(self.cur_view);
+        let view_leader_key = match self.exchange.get_leader(self.cur_view) {
+            Ok(leader) => Some(leader),
+            Err(e) => {
+                error!(?e, "Cannot tell the leader of the view, waiting for it to time out");
+                None
+            }
+        };
 
         let maybe_block = self.find_valid_msg(view_leader_key).await;
 
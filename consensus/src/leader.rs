@@ -123,6 +123,8 @@ where
         let receiver = self.transactions.subscribe().await;
         let mut block = starting_state.next_block();
 
+        let mut rejected_txns = Vec::new();
+
         // Wait until we have min_transactions for the block or we hit propose_max_round_time
         while task_start_time.elapsed() < self.api.propose_max_round_time() {
             let txns = self.transactions.cloned().await;
@@ -152,7 +154,7 @@ where
             }
 
             // Add unclaimed transactions to the new block
-            for (_txn_hash, txn) in &unclaimed_txns {
+            for (txn_hash, txn) in &unclaimed_txns {
                 let new_block_check = block.add_transaction_raw(txn);
                 if let Ok(new_block) = new_block_check {
                     if starting_state.validate_block(&new_block, &self.cur_view) {
@@ -160,10 +162,17 @@ where
                         continue;
                     }
                 }
+                rejected_txns.push(**txn_hash);
             }
             break;
         }
 
+        if !rejected_txns.is_empty() {
+            self.api
+                .send_transactions_rejected(self.cur_view, rejected_txns)
+                .await;
+        }
+
         consensus
             .metrics
             .proposal_wait_duration
@@ -180,12 +189,22 @@ where
                 deltas: block,
                 state: new_state,
                 rejected: Vec::new(),
+                key_rotations: self.api.pending_key_rotations().await,
                 timestamp: time::OffsetDateTime::now_utc().unix_timestamp_nanos(),
                 proposer_id: pk.to_bytes(),
             };
-            let signature = self
+            let signature = match self
                 .exchange
-                .sign_validating_or_commitment_proposal::<I>(&leaf.commit());
+                .sign_validating_or_commitment_proposal::<I>(self.cur_view, &leaf.commit())
+                .await
+            {
+                Ok(signature) => signature,
+                Err(e) => {
+                    error!("Failed to sign proposal for {:?} {:?}", self.cur_view, e);
+                    return self.high_qc.clone();
+                }
+            };
+            let included_txns = leaf.deltas.contained_transactions();
             let data: ValidatingProposal<TYPES, ValidatingLeaf<TYPES>> = leaf.into();
             let message = ConsensusMessage::<TYPES, I>::Proposal(Proposal { data, signature });
             consensus
@@ -205,6 +224,14 @@ where
                 warn!(?message, ?e, "Could not broadcast leader proposal");
             } else {
                 consensus.metrics.outgoing_broadcast_messages.add(1);
+                if !included_txns.is_empty() {
+                    self.api
+                        .send_transactions_included(
+                            self.cur_view,
+                            included_txns.into_iter().collect(),
+                        )
+                        .await;
+                }
             }
         } else {
             error!("Could not append state in high qc for proposal. Failed to send out proposal.");
@@ -19,6 +19,7 @@ mod sequencing_leader;
 mod sequencing_replica;
 mod traits;
 mod utils;
+mod view_timeout;
 
 use async_compatibility_layer::async_primitives::subscribable_rwlock::SubscribableRwLock;
 pub use da_member::DAMember;
@@ -29,6 +30,7 @@ pub use sequencing_leader::{ConsensusLeader, ConsensusNextLeader, DALeader};
 pub use sequencing_replica::SequencingReplica;
 pub use traits::ConsensusApi;
 pub use utils::{SendToTasks, View, ViewInner, ViewQueue};
+pub use view_timeout::ViewTimeoutController;
 
 use commit::{Commitment, Committable};
 use derivative::Derivative;
@@ -48,6 +50,7 @@ use std::{
 };
 use tracing::{error, warn};
 use utils::Terminator;
+use view_timeout::{ObservedHistogram, ObservedSample};
 
 /// A type alias for `HashMap<Commitment<T>, T>`
 type CommitmentMap<T> = HashMap<Commitment<T>, T>;
@@ -176,12 +179,16 @@ pub struct ConsensusMetrics {
     pub broadcast_messages_received: Box<dyn Counter>,
     /// Total number of messages which couldn't be sent
     pub failed_to_send_messages: Box<dyn Counter>,
+    /// Adapts the view timeout to the points recorded in `view_duration` and
+    /// `proposal_wait_duration`
+    pub view_timeout: Arc<ViewTimeoutController>,
 }
 
 impl ConsensusMetrics {
     /// Create a new instance of this [`ConsensusMetrics`] struct, setting all the counters and gauges
     #[must_use]
     pub fn new(metrics: &dyn Metrics) -> Self {
+        let view_timeout = Arc::new(ViewTimeoutController::default());
         Self {
             current_view: metrics.create_gauge(String::from("current_view"), None),
             vote_validate_duration: metrics.create_histogram(
@@ -192,12 +199,20 @@ impl ConsensusMetrics {
                 String::from("proposal_build_duration"),
                 Some(String::from("seconds")),
             ),
-            proposal_wait_duration: metrics.create_histogram(
-                String::from("proposal_wait_duration"),
-                Some(String::from("seconds")),
-            ),
-            view_duration: metrics
-                .create_histogram(String::from("view_duration"), Some(String::from("seconds"))),
+            proposal_wait_duration: Box::new(ObservedHistogram {
+                inner: metrics.create_histogram(
+                    String::from("proposal_wait_duration"),
+                    Some(String::from("seconds")),
+                ),
+                controller: view_timeout.clone(),
+                sample: ObservedSample::ProposalWait,
+            }),
+            view_duration: Box::new(ObservedHistogram {
+                inner: metrics
+                    .create_histogram(String::from("view_duration"), Some(String::from("seconds"))),
+                controller: view_timeout.clone(),
+                sample: ObservedSample::ViewDuration,
+            }),
             number_of_views_since_last_commit: metrics
                 .create_gauge(String::from("number_of_views_since_last_commit"), None),
             number_of_views_per_decide_event: metrics
@@ -225,6 +240,7 @@ impl ConsensusMetrics {
                 .create_counter(String::from("failed_to_send_messages"), None),
             number_of_timeouts: metrics
                 .create_counter(String::from("number_of_views_timed_out"), None),
+            view_timeout,
         }
     }
 }
@@ -120,6 +120,7 @@ where
                                     self.metrics
                                         .vote_validate_duration
                                         .add_point(vote_collection_start.elapsed().as_secs_f64());
+                                    self.api.send_qc_formed(self.cur_view, qc.clone()).await;
                                     return qc;
                                 }
                             }
@@ -13,10 +13,12 @@ use hotshot_types::traits::node_implementation::{NodeImplementation, QuorumPropo
 use hotshot_types::{
     certificate::QuorumCertificate,
     data::{ValidatingLeaf, ValidatingProposal},
+    error::HotShotError,
+    finality::FinalityProof,
     message::{ConsensusMessage, InternalTrigger, ProcessedConsensusMessage},
     traits::{
-        node_implementation::NodeType, signature_key::SignatureKey, state::ValidatingConsensus,
-        Block, State,
+        node_implementation::NodeType, signature_key::EncodedSignature, signer::SigningKind,
+        state::ValidatingConsensus, Block, State,
     },
     vote::{QuorumVote, TimeoutVote},
 };
@@ -74,19 +76,21 @@ where
 {
     /// portion of the replica task that spins until a valid QC can be signed or
     /// timeout is hit.
+    ///
+    /// Returns the accepted leaf with its leader's signature on the proposal.
     #[instrument(skip_all, fields(id = self.id, view = *self.cur_view), name = "Replica Task", level = "error")]
     #[allow(clippy::type_complexity)]
     async fn find_valid_msg<'a>(
         &self,
-        view_leader_key: TYPES::SignatureKey,
+        view_leader_key: Option<TYPES::SignatureKey>,
         consensus: RwLockUpgradableReadGuard<'a, Consensus<TYPES, ValidatingLeaf<TYPES>>>,
     ) -> (
         RwLockUpgradableReadGuard<'a, Consensus<TYPES, ValidatingLeaf<TYPES>>>,
-        Option<ValidatingLeaf<TYPES>>,
+        Option<(ValidatingLeaf<TYPES>, EncodedSignature)>,
     ) {
         let lock = self.proposal_collection_chan.lock().await;
         let mut invalid_qcs = 0;
-        let leaf = loop {
+        let (leaf, signature) = loop {
             let msg = lock.recv().await;
             info!("recv-ed message {:?}", msg.clone());
             if let Ok(msg) = msg {
@@ -97,9 +101,12 @@ where
                 }
                 match msg {
                     ProcessedConsensusMessage::Proposal(p, sender) => {
-                        if view_leader_key != sender {
+                        // with no known leader no proposal is accepted, and the view times out
+                        let Some(view_leader_key) =
+                            view_leader_key.as_ref().filter(|leader| **leader == sender)
+                        else {
                             continue;
-                        }
+                        };
 
                         let Some(parent) = consensus.saved_leaves.get(&p.data.parent_commitment)
                         else {
@@ -155,6 +162,7 @@ where
                                 self.cur_view,
                                 p.data.height,
                                 Vec::new(),
+                                p.data.key_rotations,
                                 time::OffsetDateTime::now_utc().unix_timestamp_nanos(),
                                 p.data.proposer_id,
                             )
@@ -163,7 +171,13 @@ where
                             continue;
                         };
 
-                        if !view_leader_key.validate(&p.signature, leaf.commit().as_ref()) {
+                        if !self.exchange.is_valid_signature(
+                            &view_leader_key,
+                            &p.signature,
+                            self.cur_view,
+                            SigningKind::QuorumProposal,
+                            leaf.commit().as_ref(),
+                        ) {
                             warn!(?p.signature, "Could not verify proposal.");
                             continue;
                         }
@@ -198,7 +212,23 @@ where
                         }
 
                         let leaf_commitment = leaf.commit();
-                        let vote_token = self.exchange.make_vote_token(self.cur_view);
+                        self.api
+                            .send_proposal_received(
+                                self.cur_view,
+                                view_leader_key.clone(),
+                                leaf_commitment,
+                            )
+                            .await;
+                        let included_txns = leaf.deltas.contained_transactions();
+                        if !included_txns.is_empty() {
+                            self.api
+                                .send_transactions_included(
+                                    self.cur_view,
+                                    included_txns.into_iter().collect(),
+                                )
+                                .await;
+                        }
+                        let vote_token = self.exchange.make_vote_token(self.cur_view).await;
 
                         match vote_token {
                             Err(e) => {
@@ -214,19 +244,39 @@ where
                                 info!("We were chosen for committee on {:?}", self.cur_view);
 
                                 // Generate and send vote
-                                let message = self.exchange.create_yes_message(
-                                    leaf.justify_qc.commit(),
-                                    leaf_commitment,
-                                    self.cur_view,
-                                    vote_token,
-                                );
-
-                                let next_leader = self.exchange.get_leader(self.cur_view + 1);
+                                let message = match self
+                                    .exchange
+                                    .create_yes_message(
+                                        leaf.justify_qc.commit(),
+                                        leaf_commitment,
+                                        self.cur_view,
+                                        vote_token,
+                                    )
+                                    .await
+                                {
+                                    Ok(message) => message,
+                                    Err(e) => {
+                                        error!(
+                                            "Failed to sign vote for {:?} {:?}",
+                                            self.cur_view, e
+                                        );
+                                        break (leaf, p.signature);
+                                    }
+                                };
+
+                                let next_leader =
+                                    match self.exchange.get_leader(self.cur_view + 1) {
+                                        Ok(next_leader) => next_leader,
+                                        Err(e) => {
+                                            error!(?e, "Cannot tell the next leader to vote to");
+                                            break (leaf, p.signature);
+                                        }
+                                    };
 
                                 info!("Sending vote to next leader {:?}", message);
                                 if self
                                     .api
-                                    .send_direct_message::<QuorumProposal<TYPES, I>, QuorumVote<TYPES, ValidatingLeaf<TYPES>>>(next_leader, message)
+                                    .send_direct_message::<QuorumProposal<TYPES, I>, QuorumVote<TYPES, ValidatingLeaf<TYPES>>>(next_leader.clone(), message)
                                     .await
                                     .is_err()
                                 {
@@ -234,20 +284,42 @@ where
                                     warn!("Failed to send vote to next leader");
                                 } else {
                                     consensus.metrics.outgoing_direct_messages.add(1);
+                                    self.api.send_vote_sent(self.cur_view, next_leader).await;
                                 }
                             }
                         }
-                        break leaf;
+                        break (leaf, p.signature);
                     }
                     ProcessedConsensusMessage::InternalTrigger(trigger) => {
                         match trigger {
                             InternalTrigger::Timeout(_) => {
-                                let next_leader = self.exchange.get_leader(self.cur_view + 1);
-
                                 consensus.metrics.number_of_timeouts.add(1);
+                                consensus.metrics.view_timeout.record_timeout();
+
+                                let next_leader =
+                                    match self.exchange.get_leader(self.cur_view + 1) {
+                                        Ok(next_leader) => next_leader,
+                                        Err(e) => {
+                                            error!(?e, "Cannot tell the next leader");
+                                            return (consensus, None);
+                                        }
+                                    };
 
-                                let signature = self.exchange.sign_timeout_vote(self.cur_view);
-                                let vote_token = self.exchange.make_vote_token(self.cur_view);
+                                let signature = match self
+                                    .exchange
+                                    .sign_timeout_vote(self.cur_view)
+                                    .await
+                                {
+                                    Ok(signature) => signature,
+                                    Err(e) => {
+                                        error!(
+                                            "Failed to sign timeout vote for {:?} {:?}",
+                                            self.cur_view, e
+                                        );
+                                        return (consensus, None);
+                                    }
+                                };
+                                let vote_token = self.exchange.make_vote_token(self.cur_view).await;
 
                                 match vote_token {
                                     Err(e) => {
@@ -327,7 +399,7 @@ where
         consensus.invalid_qc += invalid_qcs;
         (
             RwLockWriteGuard::downgrade_to_upgradable(consensus),
-            Some(leaf),
+            Some((leaf, signature)),
         )
     }
 
@@ -337,11 +409,17 @@ where
     pub async fn run_view(self) -> QuorumCertificate<TYPES, ValidatingLeaf<TYPES>> {
         info!("Replica task started!");
         let consensus = self.consensus.upgradable_read().await;
-        let view_leader_key = self.exchange.get_leader(self.cur_view);
+        let view_leader_key = match self.exchange.get_leader(self.cur_view) {
+            Ok(leader) => Some(leader),
+            Err(e) => {
+                error!(?e, "Cannot tell the leader of the view, waiting for it to time out");
+                None
+            }
+        };
 
         let (consensus, maybe_leaf) = self.find_valid_msg(view_leader_key, consensus).await;
 
-        let Some(leaf) = maybe_leaf else {
+        let Some((leaf, signature)) = maybe_leaf else {
              // we either timed out or for some reason
              // could not accept a proposal
              return self.high_qc;
@@ -353,6 +431,7 @@ where
         let mut new_commit_reached: bool = false;
         let mut new_decide_reached = false;
         let mut new_decide_qc = None;
+        let mut new_locked_leaf = None;
         let mut leaf_views = Vec::new();
         let mut included_txns = HashSet::new();
         let old_anchor_view = consensus.last_decided_view;
@@ -375,6 +454,7 @@ where
                                 // The next leaf in the chain, if there is one, is decided, so this
                                 // leaf's justify_qc would become the QC for the decided chain.
                                 new_decide_qc = Some(leaf.justify_qc.clone());
+                                new_locked_leaf = Some(leaf.clone());
                             } else if current_chain_length == 3 {
                                 new_anchor_view = leaf.view_number;
                                 new_decide_reached = true;
@@ -493,21 +573,36 @@ where
                 .rejected_transactions
                 .add(leaf.rejected.len());
 
+            let key_rotations = self.api.record_decided_leaves(&leaf_views).await;
+            let finality_proof = FinalityProof::for_leaf_chain(
+                &leaf_views,
+                &new_locked_leaf.unwrap(),
+                &leaf,
+                signature,
+            );
             let decide_sent = self.api.send_decide(
                 consensus.last_decided_view,
                 leaf_views,
                 new_decide_qc.unwrap(),
+                finality_proof.clone(),
             );
             let old_anchor_view = consensus.last_decided_view;
             consensus
                 .collect_garbage(old_anchor_view, new_anchor_view)
                 .await;
             consensus.last_decided_view = new_anchor_view;
+            consensus.metrics.view_timeout.record_decide();
             consensus.invalid_qc = 0;
 
             // We're only storing the last QC. We could store more but we're realistically only going to retrieve the last one.
-            if let Err(e) = self.api.store_leaf(old_anchor_view, leaf).await {
+            if let Err(e) = self
+                .api
+                .store_leaf(old_anchor_view, leaf, finality_proof, key_rotations)
+                .await
+            {
                 error!("Could not insert new anchor into the storage API: {:?}", e);
+                let error = HotShotError::StorageError { source: e };
+                self.api.send_view_error(self.cur_view, Arc::new(error)).await;
             }
 
             decide_sent.await;
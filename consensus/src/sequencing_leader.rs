@@ -237,16 +237,33 @@ where
         let mut block = TYPES::BlockType::new();
         let txns = self.wait_for_transactions().await?;
 
+        let mut rejected_txns = Vec::new();
         for txn in txns {
             if let Ok(new_block) = block.add_transaction_raw(&txn) {
                 block = new_block;
                 continue;
             }
+            rejected_txns.push(txn.commit());
+        }
+        if !rejected_txns.is_empty() {
+            self.api
+                .send_transactions_rejected(self.cur_view, rejected_txns)
+                .await;
         }
         let block_commitment = block.commit();
 
         let consensus = self.consensus.read().await;
-        let signature = self.committee_exchange.sign_da_proposal(&block.commit());
+        let signature = match self
+            .committee_exchange
+            .sign_da_proposal(self.cur_view, &block.commit())
+            .await
+        {
+            Ok(signature) => signature,
+            Err(e) => {
+                error!("Failed to sign DA proposal for {:?} {:?}", self.cur_view, e);
+                return None;
+            }
+        };
         let data: DAProposal<TYPES> = DAProposal {
             deltas: block.clone(),
             view_number: self.cur_view,
@@ -272,6 +289,9 @@ where
             )
             .await
         {
+            self.api
+                .send_da_certificate_formed(self.cur_view, cert.clone())
+                .await;
             return Some((cert, block, parent_leaf));
         }
         None
@@ -343,12 +363,21 @@ where
             // the same leaf with the commitment.
             deltas: Right(block_commitment),
             rejected: vec![],
+            key_rotations: self.api.pending_key_rotations().await,
             timestamp: time::OffsetDateTime::now_utc().unix_timestamp_nanos(),
             proposer_id: self.api.public_key().to_bytes(),
         };
-        let signature = self
+        let signature = match self
             .quorum_exchange
-            .sign_validating_or_commitment_proposal::<I>(&leaf.commit());
+            .sign_validating_or_commitment_proposal::<I>(self.cur_view, &leaf.commit())
+            .await
+        {
+            Ok(signature) => signature,
+            Err(e) => {
+                error!("Failed to sign proposal for {:?} {:?}", self.cur_view, e);
+                return self.high_qc;
+            }
+        };
         // TODO: DA cert is sent as part of the proposal here, we should split this out so we don't have to wait for it.
         let proposal = CommitmentProposal {
             block_commitment,
@@ -356,6 +385,7 @@ where
             height: leaf.height,
             justify_qc: self.high_qc.clone(),
             dac: self.cert,
+            key_rotations: leaf.key_rotations.clone(),
             proposer_id: leaf.proposer_id,
         };
 
@@ -371,6 +401,13 @@ where
             .await
         {
             warn!(?message, ?e, "Could not broadcast leader proposal");
+        } else {
+            let included_txns = self.block.contained_transactions();
+            if !included_txns.is_empty() {
+                self.api
+                    .send_transactions_included(self.cur_view, included_txns.into_iter().collect())
+                    .await;
+            }
         }
         self.high_qc
     }
@@ -458,6 +495,7 @@ where
                                 accumulator = acc;
                             }
                             Either::Right(qc) => {
+                                self.api.send_qc_formed(self.cur_view, qc.clone()).await;
                                 return qc;
                             }
                         }
@@ -22,9 +22,14 @@ use hotshot_types::vote::DAVote;
 use hotshot_types::{
     certificate::{DACertificate, QuorumCertificate},
     data::{CommitmentProposal, LeafType, SequencingLeaf},
+    error::HotShotError,
+    finality::FinalityProof,
     message::{ConsensusMessage, InternalTrigger, ProcessedConsensusMessage},
     traits::{
-        election::SignedCertificate, node_implementation::NodeType, signature_key::SignatureKey,
+        election::SignedCertificate,
+        node_implementation::NodeType,
+        signature_key::{EncodedSignature, SignatureKey},
+        signer::SigningKind,
         Block,
     },
     vote::QuorumVote,
@@ -118,16 +123,16 @@ where
     /// Replica task for sequencing consensus that spins until a vote can be made or timeout is
     /// hit.
     ///
-    /// Returns the new leaf if it's valid.
+    /// Returns the new leaf if it's valid, with its leader's signature on the proposal.
     #[instrument(skip_all, fields(id = self.id, view = *self.cur_view), name = "Sequencing Replica Task", level = "error")]
     #[allow(clippy::type_complexity)]
     async fn find_valid_msg<'a>(
         &self,
-        view_leader_key: TYPES::SignatureKey,
+        view_leader_key: Option<TYPES::SignatureKey>,
         consensus: RwLockUpgradableReadGuard<'a, Consensus<TYPES, SequencingLeaf<TYPES>>>,
     ) -> (
         RwLockUpgradableReadGuard<'a, Consensus<TYPES, SequencingLeaf<TYPES>>>,
-        Option<SequencingLeaf<TYPES>>,
+        Option<(SequencingLeaf<TYPES>, EncodedSignature)>,
     ) {
         let lock = self.proposal_collection_chan.lock().await;
         let mut invalid_qc = false;
@@ -142,12 +147,15 @@ where
                 }
                 match msg {
                     ProcessedConsensusMessage::Proposal(p, sender) => {
-                        if view_leader_key != sender {
+                        // with no known leader no proposal is accepted, and the view times out
+                        let Some(view_leader_key) =
+                            view_leader_key.as_ref().filter(|leader| **leader == sender)
+                        else {
                             continue;
-                        }
+                        };
 
                         let mut valid_leaf = None;
-                        let vote_token = self.quorum_exchange.make_vote_token(self.cur_view);
+                        let vote_token = self.quorum_exchange.make_vote_token(self.cur_view).await;
                         match vote_token {
                             Err(e) => {
                                 error!(
@@ -192,6 +200,7 @@ where
                                     parent_commitment,
                                     deltas: Right(p.data.block_commitment),
                                     rejected: Vec::new(),
+                                    key_rotations: p.data.key_rotations.clone(),
                                     timestamp: time::OffsetDateTime::now_utc()
                                         .unix_timestamp_nanos(),
                                     proposer_id: sender.to_bytes(),
@@ -206,12 +215,15 @@ where
                                 {
                                     invalid_qc = true;
                                     warn!("Invalid justify_qc in proposal!.");
-                                    message = self.quorum_exchange.create_no_message(
-                                        justify_qc_commitment,
-                                        leaf_commitment,
-                                        self.cur_view,
-                                        vote_token,
-                                    );
+                                    message = self
+                                        .quorum_exchange
+                                        .create_no_message(
+                                            justify_qc_commitment,
+                                            leaf_commitment,
+                                            self.cur_view,
+                                            vote_token,
+                                        )
+                                        .await;
                                 }
                                 // Validate the `height`.
                                 else if leaf.height != parent.height + 1 {
@@ -221,12 +233,15 @@ where
                                         parent.height + 1,
                                         leaf.height
                                     );
-                                    message = self.quorum_exchange.create_no_message(
-                                        justify_qc_commitment,
-                                        leaf_commitment,
-                                        self.cur_view,
-                                        vote_token,
-                                    );
+                                    message = self
+                                        .quorum_exchange
+                                        .create_no_message(
+                                            justify_qc_commitment,
+                                            leaf_commitment,
+                                            self.cur_view,
+                                            vote_token,
+                                        )
+                                        .await;
                                 }
                                 // Validate the DAC.
                                 else if !self
@@ -234,24 +249,34 @@ where
                                     .is_valid_cert(&p.data.dac, block_commitment)
                                 {
                                     warn!("Invalid DAC in proposal! Skipping proposal.");
-                                    message = self.quorum_exchange.create_no_message(
-                                        justify_qc_commitment,
-                                        leaf_commitment,
-                                        self.cur_view,
-                                        vote_token,
-                                    );
+                                    message = self
+                                        .quorum_exchange
+                                        .create_no_message(
+                                            justify_qc_commitment,
+                                            leaf_commitment,
+                                            self.cur_view,
+                                            vote_token,
+                                        )
+                                        .await;
                                 }
                                 // Validate the signature.
-                                else if !view_leader_key
-                                    .validate(&p.signature, leaf_commitment.as_ref())
-                                {
+                                else if !self.quorum_exchange.is_valid_signature(
+                                    &view_leader_key,
+                                    &p.signature,
+                                    self.cur_view,
+                                    SigningKind::QuorumProposal,
+                                    leaf_commitment.as_ref(),
+                                ) {
                                     warn!(?p.signature, "Could not verify proposal.");
-                                    message = self.quorum_exchange.create_no_message(
-                                        justify_qc_commitment,
-                                        leaf_commitment,
-                                        self.cur_view,
-                                        vote_token,
-                                    );
+                                    message = self
+                                        .quorum_exchange
+                                        .create_no_message(
+                                            justify_qc_commitment,
+                                            leaf_commitment,
+                                            self.cur_view,
+                                            vote_token,
+                                        )
+                                        .await;
                                 }
                                 // Create a positive vote if either liveness or safety check
                                 // passes.
@@ -280,32 +305,62 @@ where
                                     // Skip if both saftey and liveness checks fail.
                                     if !safety_check && !liveness_check {
                                         warn!("Failed safety check and liveness check");
-                                        message = self.quorum_exchange.create_no_message(
-                                            justify_qc_commitment,
-                                            leaf_commitment,
-                                            self.cur_view,
-                                            vote_token,
-                                        );
+                                        message = self
+                                            .quorum_exchange
+                                            .create_no_message(
+                                                justify_qc_commitment,
+                                                leaf_commitment,
+                                                self.cur_view,
+                                                vote_token,
+                                            )
+                                            .await;
                                     } else {
                                         // A valid leaf is found.
-                                        valid_leaf = Some(leaf);
+                                        valid_leaf = Some((leaf, p.signature.clone()));
+                                        self.api
+                                            .send_proposal_received(
+                                                self.cur_view,
+                                                view_leader_key.clone(),
+                                                leaf_commitment,
+                                            )
+                                            .await;
 
                                         // Generate a message with yes vote.
-                                        message = self.quorum_exchange.create_yes_message(
-                                            justify_qc_commitment,
-                                            leaf_commitment,
-                                            self.cur_view,
-                                            vote_token,
-                                        );
+                                        message = self
+                                            .quorum_exchange
+                                            .create_yes_message(
+                                                justify_qc_commitment,
+                                                leaf_commitment,
+                                                self.cur_view,
+                                                vote_token,
+                                            )
+                                            .await;
                                     }
                                 }
 
+                                let message = match message {
+                                    Ok(message) => message,
+                                    Err(e) => {
+                                        error!(
+                                            "Failed to sign vote for {:?} {:?}",
+                                            self.cur_view, e
+                                        );
+                                        break valid_leaf;
+                                    }
+                                };
+
                                 info!("Sending vote to next leader {:?}", message);
                                 let next_leader =
-                                    self.quorum_exchange.get_leader(self.cur_view + 1);
+                                    match self.quorum_exchange.get_leader(self.cur_view + 1) {
+                                        Ok(next_leader) => next_leader,
+                                        Err(e) => {
+                                            error!(?e, "Cannot tell the next leader to vote to");
+                                            break valid_leaf;
+                                        }
+                                    };
                                 if self
                                     .api
-                                    .send_direct_message::<QuorumProposal<TYPES, I>, QuorumVoteType<TYPES, I>>(next_leader, message)
+                                    .send_direct_message::<QuorumProposal<TYPES, I>, QuorumVoteType<TYPES, I>>(next_leader.clone(), message)
                                     .await
                                     .is_err()
                                 {
@@ -313,6 +368,7 @@ where
                                     warn!("Failed to send vote to next leader");
                                 } else {
                                     consensus.metrics.outgoing_direct_messages.add(1);
+                                    self.api.send_vote_sent(self.cur_view, next_leader).await;
                                 }
                             }
                         }
@@ -321,13 +377,20 @@ where
                     ProcessedConsensusMessage::InternalTrigger(trigger) => {
                         match trigger {
                             InternalTrigger::Timeout(_) => {
-                                let next_leader =
-                                    self.quorum_exchange.get_leader(self.cur_view + 1);
-
                                 consensus.metrics.number_of_timeouts.add(1);
+                                consensus.metrics.view_timeout.record_timeout();
+
+                                let next_leader =
+                                    match self.quorum_exchange.get_leader(self.cur_view + 1) {
+                                        Ok(next_leader) => next_leader,
+                                        Err(e) => {
+                                            error!(?e, "Cannot tell the next leader");
+                                            return (consensus, None);
+                                        }
+                                    };
 
                                 let vote_token =
-                                    self.quorum_exchange.make_vote_token(self.cur_view);
+                                    self.quorum_exchange.make_vote_token(self.cur_view).await;
 
                                 match vote_token {
                                     Err(e) => {
@@ -343,12 +406,24 @@ where
                                         );
                                     }
                                     Ok(Some(vote_token)) => {
-                                        let timed_out_msg =
-                                            self.quorum_exchange.create_timeout_message(
+                                        let timed_out_msg = match self
+                                            .quorum_exchange
+                                            .create_timeout_message(
                                                 self.high_qc.clone(),
                                                 self.cur_view,
                                                 vote_token,
-                                            );
+                                            )
+                                            .await
+                                        {
+                                            Ok(message) => message,
+                                            Err(e) => {
+                                                error!(
+                                                    "Failed to sign timeout vote for {:?} {:?}",
+                                                    self.cur_view, e
+                                                );
+                                                return (consensus, None);
+                                            }
+                                        };
                                         warn!(
                                             "Timed out! Sending timeout to next leader {:?}",
                                             timed_out_msg
@@ -408,12 +483,18 @@ where
     #[instrument(skip(self), fields(id = self.id, view = *self.cur_view), name = "Sequencing Replica Task", level = "error")]
     pub async fn run_view(self) -> QuorumCertificate<TYPES, SequencingLeaf<TYPES>> {
         info!("Sequencing replica task started!");
-        let view_leader_key = self.quorum_exchange.get_leader(self.cur_view);
+        let view_leader_key = match self.quorum_exchange.get_leader(self.cur_view) {
+            Ok(leader) => Some(leader),
+            Err(e) => {
+                error!(?e, "Cannot tell the leader of the view, waiting for it to time out");
+                None
+            }
+        };
         let consensus = self.consensus.upgradable_read().await;
 
         let (consensus, maybe_leaf) = self.find_valid_msg(view_leader_key, consensus).await;
 
-        let Some(leaf) = maybe_leaf else {
+        let Some((leaf, signature)) = maybe_leaf else {
             // We either timed out or for some reason could not vote on a proposal.
             return self.high_qc;
         };
@@ -424,6 +505,7 @@ where
         let mut new_commit_reached: bool = false;
         let mut new_decide_reached = false;
         let mut new_decide_qc = None;
+        let mut new_locked_leaf = None;
         let mut leaf_views = Vec::new();
         let mut included_txns = HashSet::new();
         let old_anchor_view = consensus.last_decided_view;
@@ -446,6 +528,7 @@ where
                                 // The next leaf in the chain, if there is one, is decided, so this
                                 // leaf's justify_qc would become the QC for the decided chain.
                                 new_decide_qc = Some(leaf.justify_qc.clone());
+                                new_locked_leaf = Some(leaf.clone());
                             } else if current_chain_length == 3 {
                                 new_anchor_view = leaf.view_number;
                                 new_decide_reached = true;
@@ -579,21 +662,36 @@ where
                 .rejected_transactions
                 .add(leaf.rejected.len());
 
+            let key_rotations = self.api.record_decided_leaves(&leaf_views).await;
+            let finality_proof = FinalityProof::for_leaf_chain(
+                &leaf_views,
+                &new_locked_leaf.unwrap(),
+                &leaf,
+                signature,
+            );
             let decide_sent = self.api.send_decide(
                 consensus.last_decided_view,
                 leaf_views,
                 new_decide_qc.unwrap(),
+                finality_proof.clone(),
             );
             let old_anchor_view = consensus.last_decided_view;
             consensus
                 .collect_garbage(old_anchor_view, new_anchor_view)
                 .await;
             consensus.last_decided_view = new_anchor_view;
+            consensus.metrics.view_timeout.record_decide();
             consensus.invalid_qc = 0;
 
             // We're only storing the last QC. We could store more but we're realistically only going to retrieve the last one.
-            if let Err(e) = self.api.store_leaf(old_anchor_view, leaf).await {
+            if let Err(e) = self
+                .api
+                .store_leaf(old_anchor_view, leaf, finality_proof, key_rotations)
+                .await
+            {
                 error!("Could not insert new anchor into the storage API: {:?}", e);
+                let error = HotShotError::StorageError { source: e };
+                self.api.send_view_error(self.cur_view, Arc::new(error)).await;
             }
 
             decide_sent.await;
@@ -2,7 +2,8 @@
 
 use async_trait::async_trait;
 
-use hotshot_types::certificate::QuorumCertificate;
+use commit::Commitment;
+use hotshot_types::certificate::{DACertificate, QuorumCertificate};
 
 use hotshot_types::message::ConsensusMessage;
 
@@ -12,7 +13,9 @@ use hotshot_types::{
     data::{LeafType, ProposalType},
     error::HotShotError,
     event::{Event, EventType},
-    traits::{network::NetworkError, signature_key::SignatureKey},
+    finality::FinalityProof,
+    key_rotation::KeyRotation,
+    traits::{network::NetworkError, signer::Signer},
     vote::VoteType,
 };
 
@@ -39,13 +42,28 @@ pub trait ConsensusApi<
     /// If this time is reached, the leader has to send a propose without transactions.
     fn propose_max_round_time(&self) -> Duration;
 
-    /// Store a leaf in the storage
+    /// Store a newly decided anchor leaf, with the proof of the decision that made it the anchor
+    /// and the key rotations the decision applied to the stake table
     async fn store_leaf(
         &self,
         old_anchor_view: TYPES::Time,
         leaf: LEAF,
+        finality_proof: FinalityProof<TYPES, LEAF>,
+        key_rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
     ) -> Result<(), StorageError>;
 
+    /// Notify the memberships of the leaves decided in this view, before the next view starts, so
+    /// leader schedules depending on the chain see them, and apply the key rotations the leaves
+    /// carry. Returns the rotations applied.
+    async fn record_decided_leaves(
+        &self,
+        leaf_views: &[LEAF],
+    ) -> Vec<KeyRotation<TYPES::SignatureKey>>;
+
+    /// The key rotations submitted to this node that no decided leaf carries yet, to include in
+    /// the next leaf it proposes
+    async fn pending_key_rotations(&self) -> Vec<KeyRotation<TYPES::SignatureKey>>;
+
     /// Retuns the maximum transactions allowed in a block
     fn max_transactions(&self) -> NonZeroUsize;
 
@@ -89,8 +107,8 @@ pub trait ConsensusApi<
     /// Get a reference to the public key.
     fn public_key(&self) -> &TYPES::SignatureKey;
 
-    /// Get a reference to the private key.
-    fn private_key(&self) -> &<TYPES::SignatureKey as SignatureKey>::PrivateKey;
+    /// Get a reference to the signer holding the private key.
+    fn signer(&self) -> &Arc<dyn Signer<TYPES::SignatureKey>>;
 
     // Utility functions
 
@@ -127,12 +145,14 @@ pub trait ConsensusApi<
         view_number: TYPES::Time,
         leaf_views: Vec<LEAF>,
         decide_qc: QuorumCertificate<TYPES, LEAF>,
+        finality_proof: FinalityProof<TYPES, LEAF>,
     ) {
         self.send_event(Event {
             view_number,
             event: EventType::Decide {
                 leaf_chain: Arc::new(leaf_views),
                 qc: Arc::new(decide_qc),
+                finality_proof: Some(Arc::new(finality_proof)),
             },
         })
         .await;
@@ -147,6 +167,112 @@ pub trait ConsensusApi<
         .await;
     }
 
+    /// Sends a `ProposalReceived` event
+    async fn send_proposal_received(
+        &self,
+        view_number: TYPES::Time,
+        leader: TYPES::SignatureKey,
+        leaf_commitment: Commitment<LEAF>,
+    ) {
+        self.send_event(Event {
+            view_number,
+            event: EventType::ProposalReceived {
+                leader,
+                leaf_commitment,
+            },
+        })
+        .await;
+    }
+
+    /// Sends a `VoteSent` event
+    async fn send_vote_sent(&self, view_number: TYPES::Time, recipient: TYPES::SignatureKey) {
+        self.send_event(Event {
+            view_number,
+            event: EventType::VoteSent { recipient },
+        })
+        .await;
+    }
+
+    /// Sends a `QCFormed` event
+    async fn send_qc_formed(&self, view_number: TYPES::Time, qc: QuorumCertificate<TYPES, LEAF>) {
+        self.send_event(Event {
+            view_number,
+            event: EventType::QCFormed { qc: Arc::new(qc) },
+        })
+        .await;
+    }
+
+    /// Sends a `DACertificateFormed` event
+    async fn send_da_certificate_formed(
+        &self,
+        view_number: TYPES::Time,
+        certificate: DACertificate<TYPES>,
+    ) {
+        self.send_event(Event {
+            view_number,
+            event: EventType::DACertificateFormed {
+                certificate: Arc::new(certificate),
+            },
+        })
+        .await;
+    }
+
+    /// Sends a `TransactionsIncluded` event
+    async fn send_transactions_included(
+        &self,
+        view_number: TYPES::Time,
+        transactions: Vec<Commitment<TYPES::Transaction>>,
+    ) {
+        self.send_event(Event {
+            view_number,
+            event: EventType::TransactionsIncluded {
+                transactions: Arc::new(transactions),
+            },
+        })
+        .await;
+    }
+
+    /// Sends a `TransactionsRejected` event
+    async fn send_transactions_rejected(
+        &self,
+        view_number: TYPES::Time,
+        transactions: Vec<Commitment<TYPES::Transaction>>,
+    ) {
+        self.send_event(Event {
+            view_number,
+            event: EventType::TransactionsRejected {
+                transactions: Arc::new(transactions),
+            },
+        })
+        .await;
+    }
+
+    /// Sends a `ViewChanged` event
+    async fn send_view_changed(&self, view_number: TYPES::Time) {
+        self.send_event(Event {
+            view_number,
+            event: EventType::ViewChanged { view_number },
+        })
+        .await;
+    }
+
+    /// Sends a `LeaderElected` event for an upcoming view
+    async fn send_leader_elected(
+        &self,
+        current_view: TYPES::Time,
+        view_number: TYPES::Time,
+        leader: TYPES::SignatureKey,
+    ) {
+        self.send_event(Event {
+            view_number: current_view,
+            event: EventType::LeaderElected {
+                view_number,
+                leader,
+            },
+        })
+        .await;
+    }
+
     /// Send a broadcast to the DA comitee, stub for now
     async fn send_da_broadcast(
         &self,
@@ -0,0 +1,243 @@
+//! Contains the [`ViewTimeoutController`], which adapts the next-view timeout to observed
+//! latencies.
+
+use hotshot_types::{traits::metrics::Histogram, AdaptiveTimeoutConfig};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Derives per-view timeouts from the latencies of recent successful views.
+///
+/// The controller is fed through the `view_duration` and `proposal_wait_duration` histograms of
+/// [`ConsensusMetrics`](crate::ConsensusMetrics), and told about timeouts and decides by the
+/// consensus tasks. Without an [`AdaptiveTimeoutConfig`] it always returns the static timeout.
+#[derive(Debug, Default)]
+pub struct ViewTimeoutController {
+    /// Mutable state, shared with the histograms feeding it
+    inner: Mutex<ViewTimeoutState>,
+}
+
+/// Mutable state of the [`ViewTimeoutController`]
+#[derive(Debug, Default)]
+struct ViewTimeoutState {
+    /// bounds of the adaptive timeout, `None` if disabled
+    config: Option<AdaptiveTimeoutConfig>,
+    /// durations of recent successful views, in seconds
+    view_durations: VecDeque<f64>,
+    /// recent durations leaders waited for transactions, in seconds
+    proposal_wait_durations: VecDeque<f64>,
+    /// views that timed out since the last decide
+    consecutive_timeouts: u32,
+    /// whether the view in progress timed out, so its duration is not a useful sample
+    current_view_timed_out: bool,
+}
+
+/// Push `sample` into `window`, keeping at most `size` samples
+fn push_bounded(window: &mut VecDeque<f64>, sample: f64, size: usize) {
+    window.push_back(sample);
+    while window.len() > size {
+        window.pop_front();
+    }
+}
+
+/// The 90th percentile of `samples`, or `None` if there are none
+fn percentile_90(samples: &VecDeque<f64>) -> Option<f64> {
+    let mut sorted: Vec<f64> = samples.iter().copied().collect();
+    sorted.sort_by(f64::total_cmp);
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let idx = ((sorted.len() as f64 * 0.9).ceil() as usize).checked_sub(1)?;
+    sorted.get(idx).copied()
+}
+
+impl ViewTimeoutController {
+    /// Enable the adaptive timeout with the given bounds, or disable it with `None`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned
+    pub fn configure(&self, config: Option<AdaptiveTimeoutConfig>) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.config = config;
+        if let Some(config) = config {
+            while inner.view_durations.len() > config.window {
+                inner.view_durations.pop_front();
+            }
+            while inner.proposal_wait_durations.len() > config.window {
+                inner.proposal_wait_durations.pop_front();
+            }
+        }
+    }
+
+    /// Record the duration of a view, in seconds
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned
+    pub fn observe_view_duration(&self, seconds: f64) {
+        let mut inner = self.inner.lock().unwrap();
+        if std::mem::take(&mut inner.current_view_timed_out) {
+            return;
+        }
+        let window = inner.config.map_or(0, |config| config.window);
+        push_bounded(&mut inner.view_durations, seconds, window);
+    }
+
+    /// Record how long a leader waited for transactions, in seconds
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned
+    pub fn observe_proposal_wait(&self, seconds: f64) {
+        let mut inner = self.inner.lock().unwrap();
+        let window = inner.config.map_or(0, |config| config.window);
+        push_bounded(&mut inner.proposal_wait_durations, seconds, window);
+    }
+
+    /// Record that the current view timed out
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned
+    pub fn record_timeout(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.current_view_timed_out = true;
+        inner.consecutive_timeouts = inner.consecutive_timeouts.saturating_add(1);
+    }
+
+    /// Record a decide, which resets the backoff accumulated by timeouts
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned
+    pub fn record_decide(&self) {
+        self.inner.lock().unwrap().consecutive_timeouts = 0;
+    }
+
+    /// The timeout for the next view, in milliseconds
+    ///
+    /// `base` is the static `next_view_timeout`, used while there are no samples yet, and every
+    /// timeout since the last decide multiplies the result by `timeout_ratio`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lock is poisoned
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn next_timeout(&self, base: u64, timeout_ratio: (u64, u64)) -> u64 {
+        let inner = self.inner.lock().unwrap();
+        let Some(config) = inner.config else { return base };
+
+        let latency = percentile_90(&inner.view_durations)
+            .into_iter()
+            .chain(percentile_90(&inner.proposal_wait_durations))
+            .reduce(f64::max);
+        let estimate = match latency {
+            Some(seconds) => {
+                Duration::from_secs_f64(seconds).as_millis() as f64 * config.slack.0 as f64
+                    / config.slack.1 as f64
+            }
+            None => base as f64,
+        };
+        let ratio = timeout_ratio.0 as f64 / timeout_ratio.1 as f64;
+        let backoff = ratio.powi(i32::try_from(inner.consecutive_timeouts).unwrap_or(i32::MAX));
+        let timeout = estimate * backoff;
+        if timeout.is_finite() {
+            // not `clamp`, which panics if a misconfigured floor exceeds the ceiling
+            (timeout as u64).min(config.ceiling).max(config.floor)
+        } else {
+            config.ceiling
+        }
+    }
+}
+
+/// Which samples a [`ObservedHistogram`] forwards to the [`ViewTimeoutController`]
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum ObservedSample {
+    /// `view_duration`
+    ViewDuration,
+    /// `proposal_wait_duration`
+    ProposalWait,
+}
+
+/// A [`Histogram`] that also feeds its points to a [`ViewTimeoutController`]
+pub(crate) struct ObservedHistogram {
+    /// the histogram points are recorded in
+    pub(crate) inner: Box<dyn Histogram>,
+    /// the controller points are forwarded to
+    pub(crate) controller: Arc<ViewTimeoutController>,
+    /// what the points of this histogram measure
+    pub(crate) sample: ObservedSample,
+}
+
+impl Histogram for ObservedHistogram {
+    fn add_point(&self, point: f64) {
+        self.inner.add_point(point);
+        match self.sample {
+            ObservedSample::ViewDuration => self.controller.observe_view_duration(point),
+            ObservedSample::ProposalWait => self.controller.observe_proposal_wait(point),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller() -> ViewTimeoutController {
+        let controller = ViewTimeoutController::default();
+        controller.configure(Some(AdaptiveTimeoutConfig {
+            floor: 100,
+            ceiling: 10_000,
+            window: 4,
+            slack: (2, 1),
+        }));
+        controller
+    }
+
+    #[test]
+    fn disabled_returns_base() {
+        let controller = ViewTimeoutController::default();
+        controller.observe_view_duration(0.5);
+        assert_eq!(controller.next_timeout(1234, (11, 10)), 1234);
+    }
+
+    #[test]
+    fn follows_latency_within_bounds() {
+        let controller = controller();
+        assert_eq!(controller.next_timeout(1234, (2, 1)), 1234);
+        for _ in 0..4 {
+            controller.observe_view_duration(1.0);
+        }
+        assert_eq!(controller.next_timeout(1234, (2, 1)), 2000);
+        for _ in 0..4 {
+            controller.observe_view_duration(0.001);
+        }
+        assert_eq!(controller.next_timeout(1234, (2, 1)), 100);
+        for _ in 0..4 {
+            controller.observe_view_duration(100.0);
+        }
+        assert_eq!(controller.next_timeout(1234, (2, 1)), 10_000);
+    }
+
+    #[test]
+    fn timeouts_back_off_until_decide() {
+        let controller = controller();
+        controller.observe_view_duration(1.0);
+        controller.record_timeout();
+        // the duration of the timed out view is not a sample
+        controller.observe_view_duration(50.0);
+        assert_eq!(controller.next_timeout(1234, (2, 1)), 4000);
+        controller.record_decide();
+        assert_eq!(controller.next_timeout(1234, (2, 1)), 2000);
+    }
+}
@@ -3,10 +3,11 @@ use futures::FutureExt;
 use std::net::Ipv4Addr;
 use std::{
     cmp,
-    collections::{BTreeSet, VecDeque},
+    collections::VecDeque,
     fs, mem,
     net::IpAddr,
     num::NonZeroUsize,
+    path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
     time::{Duration, Instant},
@@ -44,6 +45,8 @@ use hotshot_types::{
         metrics::NoMetrics,
         network::CommunicationChannel,
         node_implementation::NodeType,
+        signature_key::keystore::{read_password, Keystore, KeystoreKey},
+        signer::LocalSigner,
         state::{TestableBlock, TestableState},
     },
     vote::QuorumVote,
@@ -91,18 +94,39 @@ pub fn load_config_from_file<TYPES: NodeType>(
             .expect("Unable to convert config file to TOML");
 
     let mut config: NetworkConfig<TYPES::SignatureKey, TYPES::ElectionConfigType> =
-        config_toml.into();
-
-    // Generate network's public keys
-    config.config.known_nodes = (0..config.config.total_nodes.get())
-        .map(|node_id| {
-            TYPES::SignatureKey::generated_from_seed_indexed(
-                config.seed,
-                node_id.try_into().unwrap(),
-            )
-            .0
-        })
-        .collect();
+        config_toml.clone().into();
+
+    // Take the network's public keys from the config if listed, else generate them from the seed
+    config.config.known_nodes = if config_toml.known_nodes.is_empty() {
+        let keys: Vec<_> = (0..config.config.total_nodes.get())
+            .map(|node_id| {
+                TYPES::SignatureKey::generated_from_seed_indexed(
+                    config.seed,
+                    node_id.try_into().unwrap(),
+                )
+            })
+            .collect();
+        config.config.possession_proofs = keys
+            .iter()
+            .filter_map(|(_, private_key)| TYPES::SignatureKey::prove_possession(private_key))
+            .collect();
+        keys.into_iter().map(|(key, _)| key).collect()
+    } else {
+        config.config.possession_proofs = config_toml.possession_proofs.clone();
+        assert_eq!(
+            config_toml.known_nodes.len(),
+            config.config.total_nodes.get(),
+            "known_nodes must list one public key per node"
+        );
+        config_toml
+            .known_nodes
+            .iter()
+            .map(|key| {
+                TYPES::SignatureKey::from_bytes(key)
+                    .unwrap_or_else(|| panic!("Invalid public key {key:?} in known_nodes"))
+            })
+            .collect()
+    };
 
     config
 }
@@ -163,6 +187,27 @@ pub struct ValidatorArgs {
     /// This node's public IP address, for libp2p
     /// If no IP address is passed in, it will default to 127.0.0.1
     public_ip: Option<IpAddr>,
+    /// Keystore holding this node's private key
+    /// If no keystore is passed in, the key is derived from the seed in the run config
+    #[arg(long)]
+    keystore: Option<PathBuf>,
+    /// File holding the keystore password
+    /// If no file is passed in, the password is read from `HOTSHOT_KEYSTORE_PASSWORD`
+    #[arg(long)]
+    keystore_password_file: Option<PathBuf>,
+}
+
+/// Decrypts the private key in the keystore at `path`
+/// # Panics if the keystore cannot be read or decrypted
+fn load_private_key<KEY: KeystoreKey>(
+    path: &Path,
+    password_file: Option<&Path>,
+) -> KEY::PrivateKey {
+    let password = read_password(password_file)
+        .unwrap_or_else(|e| panic!("Could not read keystore password: {e}"));
+    Keystore::load(path)
+        .and_then(|keystore| keystore.decrypt::<KEY>(password.as_bytes()))
+        .unwrap_or_else(|e| panic!("Could not load keystore {}: {e}", path.display()))
 }
 
 /// Defines the behavior of a "run" of the network with a given configuration
@@ -205,26 +250,27 @@ pub trait Run<
     HotShot<TYPES::ConsensusType, TYPES, NODE>: ViewRunner<TYPES, NODE>,
     Self: Sync,
 {
-    /// Initializes networking, returns self
+    /// Initializes networking for the node holding `private_key`, returns self
     async fn initialize_networking(
         config: NetworkConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>,
+        private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
     ) -> Self;
 
     /// Initializes the genesis state and HotShot instance; does not start HotShot consensus
     /// # Panics if it cannot generate a genesis block, fails to initialize HotShot, or cannot
     /// get the anchored view
     async fn initialize_state_and_hotshot(&self) -> (TYPES::StateType, HotShotHandle<TYPES, NODE>) {
+        let config = self.get_config();
+
         let genesis_block = TYPES::BlockType::genesis();
         let initializer =
             hotshot::HotShotInitializer::<TYPES, ValidatingLeaf<TYPES>>::from_genesis(
                 genesis_block,
+                config.config.chain_id,
             )
             .expect("Couldn't generate genesis block");
 
-        let config = self.get_config();
-
-        let (pk, sk) =
-            TYPES::SignatureKey::generated_from_seed_indexed(config.seed, config.node_index);
+        let signer = Arc::new(LocalSigner::new(self.get_private_key()));
         let known_nodes = config.config.known_nodes.clone();
 
         let network = self.get_network();
@@ -246,19 +292,18 @@ pub trait Run<
             known_nodes.clone(),
             election_config.clone(),
             network.clone(),
-            pk.clone(),
-            sk.clone(),
+            config.config.chain_id,
+            signer.clone(),
         );
         let committee_exchange = NODE::CommitteeExchange::create(
             known_nodes,
             election_config,
             network,
-            pk.clone(),
-            sk.clone(),
+            config.config.chain_id,
+            signer.clone(),
         );
         let hotshot = HotShot::init(
-            pk,
-            sk,
+            signer,
             config.node_index,
             config.config,
             MemoryStorage::empty(),
@@ -368,6 +413,9 @@ pub trait Run<
 
     /// Returns the config for this run
     fn get_config(&self) -> NetworkConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>;
+
+    /// Returns the private key of this node
+    fn get_private_key(&self) -> <TYPES::SignatureKey as SignatureKey>::PrivateKey;
 }
 
 type Proposal<T> = ValidatingProposal<T, ValidatingLeaf<T>>;
@@ -391,6 +439,8 @@ pub struct Libp2pRun<TYPES: NodeType, I: NodeImplementation<TYPES>, MEMBERSHIP:
     >,
     config:
         NetworkConfig<<TYPES as NodeType>::SignatureKey, <TYPES as NodeType>::ElectionConfigType>,
+    /// the private key of this node
+    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
 }
 
 /// yeesh maybe we should just implement SignatureKey for this...
@@ -482,12 +532,9 @@ where
 {
     async fn initialize_networking(
         config: NetworkConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>,
+        private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
     ) -> Libp2pRun<TYPES, NODE, MEMBERSHIP> {
-        let (pubkey, _privkey) =
-            <<TYPES as NodeType>::SignatureKey as SignatureKey>::generated_from_seed_indexed(
-                config.seed,
-                config.node_index,
-            );
+        let pubkey = TYPES::SignatureKey::from_private(&private_key);
         let mut config = config;
         let libp2p_config = config
             .libp2p_config
@@ -535,6 +582,7 @@ where
         let replicated_nodes = NonZeroUsize::new(config.config.total_nodes.get() - 2).unwrap();
         config_builder.replication_factor(replicated_nodes);
         config_builder.identity(identity.clone());
+        config_builder.chain_id(config.config.chain_id.0);
 
         config_builder.bound_addr(Some(bound_addr.clone()));
 
@@ -577,20 +625,7 @@ where
             )),
             bs_len,
             config.node_index as usize,
-            // NOTE: this introduces an invariant that the keys are assigned using this indexed
-            // function
-            {
-                let mut keys = BTreeSet::new();
-                for i in 0..config.config.total_nodes.get() {
-                    let pk = <TYPES::SignatureKey as SignatureKey>::generated_from_seed_indexed(
-                        config.seed,
-                        i as u64,
-                    )
-                    .0;
-                    keys.insert(pk);
-                }
-                keys
-            },
+            config.config.known_nodes.iter().cloned().collect(),
         )
         .await
         .map(
@@ -608,6 +643,7 @@ where
 
         Libp2pRun {
             config,
+            private_key,
 
             _bootstrap_nodes: bootstrap_nodes,
             _node_type: node_type,
@@ -625,6 +661,10 @@ where
         self.config.clone()
     }
 
+    fn get_private_key(&self) -> <TYPES::SignatureKey as SignatureKey>::PrivateKey {
+        self.private_key.clone()
+    }
+
     fn get_network(
         &self,
     ) -> Libp2pCommChannel<
@@ -647,6 +687,7 @@ pub struct WebServerRun<
     MEMBERSHIP: Membership<TYPES>,
 > {
     config: NetworkConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>,
+    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
     network: WebCommChannel<
         TYPES,
         I,
@@ -715,13 +756,9 @@ where
 {
     async fn initialize_networking(
         config: NetworkConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>,
+        private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
     ) -> WebServerRun<TYPES, NODE, MEMBERSHIP> {
-        // Generate our own key
-        let (pub_key, _priv_key) =
-            <<TYPES as NodeType>::SignatureKey as SignatureKey>::generated_from_seed_indexed(
-                config.seed,
-                config.node_index,
-            );
+        let pub_key = TYPES::SignatureKey::from_private(&private_key);
 
         // Get the configuration for the web server
         let WebServerConfig {
@@ -742,8 +779,13 @@ where
             port,
             wait_between_polls,
             pub_key,
+            config.config.chain_id,
         ));
-        WebServerRun { config, network }
+        WebServerRun {
+            config,
+            private_key,
+            network,
+        }
     }
 
     fn get_network(
@@ -761,6 +803,10 @@ where
     fn get_config(&self) -> NetworkConfig<TYPES::SignatureKey, TYPES::ElectionConfigType> {
         self.config.clone()
     }
+
+    fn get_private_key(&self) -> <TYPES::SignatureKey as SignatureKey>::PrivateKey {
+        self.private_key.clone()
+    }
 }
 
 /// Holds the client connection to the orchestrator
@@ -898,6 +944,7 @@ pub async fn main_entry_point<
 >(
     args: ValidatorArgs,
 ) where
+    TYPES::SignatureKey: KeystoreKey,
     <TYPES as NodeType>::StateType: TestableState,
     <TYPES as NodeType>::BlockType: TestableBlock,
     ValidatingLeaf<TYPES>: TestableLeaf,
@@ -908,6 +955,11 @@ pub async fn main_entry_point<
 
     error!("Starting validator");
 
+    // Decrypt the keystore before anything else, so a wrong password fails right away
+    let keystore_key = args.keystore.as_ref().map(|path| {
+        load_private_key::<TYPES::SignatureKey>(path, args.keystore_password_file.as_deref())
+    });
+
     let orchestrator_client: OrchestratorClient =
         OrchestratorClient::connect_to_orchestrator(args.clone()).await;
 
@@ -933,8 +985,26 @@ pub async fn main_entry_point<
     run_config.node_index = node_index.into();
     run_config.libp2p_config.as_mut().unwrap().public_ip = args.public_ip.unwrap();
 
+    let private_key = match keystore_key {
+        Some(private_key) => {
+            let public_key = TYPES::SignatureKey::from_private(&private_key);
+            assert!(
+                run_config.config.known_nodes.contains(&public_key),
+                "The keystore key {public_key:?} is not a node of this run"
+            );
+            private_key
+        }
+        None => {
+            TYPES::SignatureKey::generated_from_seed_indexed(
+                run_config.seed,
+                run_config.node_index,
+            )
+            .1
+        }
+    };
+
     error!("Initializing networking");
-    let run = RUN::initialize_networking(run_config.clone()).await;
+    let run = RUN::initialize_networking(run_config.clone(), private_key).await;
     let (_state, hotshot) = run.initialize_state_and_hotshot().await;
 
     error!("Waiting for start command from orchestrator");
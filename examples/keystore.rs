@@ -0,0 +1,237 @@
+//! Manages the keystores validators load their identity from
+//!
+//! Keys can be generated, imported from a hex private key or from the seed of a run config,
+//! exported back to hex, and inspected without the password. BLS keystores can also prove
+//! possession of their key for the `possession_proofs` of a run config. Passwords are read from
+//! `--password-file`, or else from `HOTSHOT_KEYSTORE_PASSWORD`.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use hotshot::traits::election::vrf::BlsPubKey;
+use hotshot_types::traits::signature_key::{
+    ed25519::Ed25519Pub,
+    keystore::{read_password, Keystore, KeystoreKey},
+    secp256k1::Secp256k1Pub,
+    SignatureKey,
+};
+use std::{
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+#[derive(Parser, Debug)]
+#[command(name = "keystore", about = "Manages HotShot validator keystores")]
+/// Arguments passed to the key management tool
+struct Args {
+    /// File holding the keystore password
+    /// If no file is passed in, the password is read from `HOTSHOT_KEYSTORE_PASSWORD`
+    #[arg(long, global = true)]
+    password_file: Option<PathBuf>,
+    /// What to do
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// The supported key types
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum KeyType {
+    /// `Ed25519Pub` keys
+    Ed25519,
+    /// `JfPubKey` BLS keys over BLS12-381
+    Bls,
+    /// `Secp256k1Pub` keys
+    Secp256k1,
+}
+
+#[derive(Subcommand, Debug)]
+/// Key management commands
+enum Command {
+    /// Generate a new key into a new keystore
+    Generate {
+        /// The type of key to generate
+        #[arg(long, value_enum)]
+        key_type: KeyType,
+        /// Where to write the keystore
+        out: PathBuf,
+    },
+    /// Import an existing private key into a new keystore
+    Import {
+        /// The type of the key
+        #[arg(long, value_enum)]
+        key_type: KeyType,
+        /// The private key in hex, as written by `export`
+        #[arg(long, conflicts_with_all = ["seed", "index"], required_unless_present = "seed")]
+        private_key: Option<String>,
+        /// The seed of a run config in hex, to import the key derived for node `index`
+        #[arg(long, requires = "index")]
+        seed: Option<String>,
+        /// The index of the node whose seed-derived key to import
+        #[arg(long, requires = "seed")]
+        index: Option<u64>,
+        /// Where to write the keystore
+        out: PathBuf,
+    },
+    /// Print the private key of a keystore in hex
+    Export {
+        /// The keystore to read
+        keystore: PathBuf,
+    },
+    /// Print the key type, public key and key derivation parameters of a keystore
+    Inspect {
+        /// The keystore to read
+        keystore: PathBuf,
+    },
+    /// Print a proof of possession of the private key of a keystore, as an entry of the
+    /// `possession_proofs` of a run config
+    ProvePossession {
+        /// The keystore to read
+        keystore: PathBuf,
+    },
+}
+
+/// Where an imported private key comes from
+enum Source {
+    /// A serialized private key
+    PrivateKey(Vec<u8>),
+    /// The key derived from a run config seed for a node index
+    Seed([u8; 32], u64),
+}
+
+/// Errors of the key management tool
+type Error = Box<dyn std::error::Error>;
+
+/// Encrypt `private_key` and write it to a new keystore at `out`
+fn write_keystore<KEY: KeystoreKey>(
+    private_key: &KEY::PrivateKey,
+    password_file: Option<&Path>,
+    out: &Path,
+) -> Result<(), Error> {
+    let password = read_password(password_file)?;
+    let keystore = Keystore::encrypt::<KEY>(private_key, password.as_bytes())?;
+    keystore.save(out)?;
+    println!("{}", keystore.public_key);
+    Ok(())
+}
+
+/// Generate a key of type `KEY` into a new keystore at `out`
+fn generate<KEY: KeystoreKey>(password_file: Option<&Path>, out: &Path) -> Result<(), Error> {
+    write_keystore::<KEY>(&KEY::generate_private_key(), password_file, out)
+}
+
+/// Import a key of type `KEY` from `source` into a new keystore at `out`
+fn import<KEY: KeystoreKey>(
+    source: Source,
+    password_file: Option<&Path>,
+    out: &Path,
+) -> Result<(), Error> {
+    let private_key = match source {
+        Source::PrivateKey(bytes) => {
+            KEY::private_key_from_bytes(&bytes).ok_or("invalid private key")?
+        }
+        Source::Seed(seed, index) => KEY::generated_from_seed_indexed(seed, index).1,
+    };
+    write_keystore::<KEY>(&private_key, password_file, out)
+}
+
+/// Print the private key of `keystore`, which holds a key of type `KEY`
+fn export<KEY: KeystoreKey>(
+    keystore: &Keystore,
+    password_file: Option<&Path>,
+) -> Result<(), Error> {
+    let password = read_password(password_file)?;
+    let private_key = keystore.decrypt::<KEY>(password.as_bytes())?;
+    println!("{}", hex::encode(KEY::private_key_to_bytes(&private_key)));
+    Ok(())
+}
+
+/// Print a proof of possession of the private key of `keystore`, which holds a key of type `KEY`
+fn prove_possession<KEY: KeystoreKey>(
+    keystore: &Keystore,
+    password_file: Option<&Path>,
+) -> Result<(), Error> {
+    let password = read_password(password_file)?;
+    let private_key = keystore.decrypt::<KEY>(password.as_bytes())?;
+    let proof = KEY::prove_possession(&private_key).ok_or_else(|| {
+        format!(
+            "{} keys do not take part in aggregate signatures and need no proof of possession",
+            keystore.key_type
+        )
+    })?;
+    println!("{}", serde_json::to_string(&proof)?);
+    Ok(())
+}
+
+/// Run `command`
+fn run(password_file: Option<&Path>, command: Command) -> Result<(), Error> {
+    match command {
+        Command::Generate { key_type, out } => match key_type {
+            KeyType::Ed25519 => generate::<Ed25519Pub>(password_file, &out),
+            KeyType::Bls => generate::<BlsPubKey>(password_file, &out),
+            KeyType::Secp256k1 => generate::<Secp256k1Pub>(password_file, &out),
+        },
+        Command::Import {
+            key_type,
+            private_key,
+            seed,
+            index,
+            out,
+        } => {
+            let source = match (private_key, seed, index) {
+                (Some(private_key), _, _) => Source::PrivateKey(hex::decode(private_key)?),
+                (None, Some(seed), Some(index)) => {
+                    let seed = <[u8; 32]>::try_from(hex::decode(seed)?)
+                        .map_err(|_| "the seed must be 32 bytes")?;
+                    Source::Seed(seed, index)
+                }
+                _ => return Err("pass either --private-key or --seed and --index".into()),
+            };
+            match key_type {
+                KeyType::Ed25519 => import::<Ed25519Pub>(source, password_file, &out),
+                KeyType::Bls => import::<BlsPubKey>(source, password_file, &out),
+                KeyType::Secp256k1 => import::<Secp256k1Pub>(source, password_file, &out),
+            }
+        }
+        Command::Export { keystore } => {
+            let keystore = Keystore::load(keystore)?;
+            match keystore.key_type.as_str() {
+                Ed25519Pub::KEY_TYPE => export::<Ed25519Pub>(&keystore, password_file),
+                BlsPubKey::KEY_TYPE => export::<BlsPubKey>(&keystore, password_file),
+                Secp256k1Pub::KEY_TYPE => export::<Secp256k1Pub>(&keystore, password_file),
+                other => Err(format!("unsupported key type {other}").into()),
+            }
+        }
+        Command::Inspect { keystore } => {
+            let keystore = Keystore::load(keystore)?;
+            let kdf = &keystore.crypto.kdf;
+            println!("version:     {}", keystore.version);
+            println!("key type:    {}", keystore.key_type);
+            println!("public key:  {}", keystore.public_key);
+            println!(
+                "kdf:         argon2id, {} KiB, {} iterations, {} lanes",
+                kdf.memory_kib, kdf.iterations, kdf.parallelism
+            );
+            Ok(())
+        }
+        Command::ProvePossession { keystore } => {
+            let keystore = Keystore::load(keystore)?;
+            match keystore.key_type.as_str() {
+                Ed25519Pub::KEY_TYPE => prove_possession::<Ed25519Pub>(&keystore, password_file),
+                BlsPubKey::KEY_TYPE => prove_possession::<BlsPubKey>(&keystore, password_file),
+                Secp256k1Pub::KEY_TYPE => {
+                    prove_possession::<Secp256k1Pub>(&keystore, password_file)
+                }
+                other => Err(format!("unsupported key type {other}").into()),
+            }
+        }
+    }
+}
+
+fn main() {
+    let Args {
+        password_file,
+        command,
+    } = Args::parse();
+    if let Err(e) = run(password_file.as_deref(), command) {
+        eprintln!("error: {e}");
+        exit(1);
+    }
+}
@@ -1,4 +1,5 @@
 use hotshot::demos::vdemo::VDemoTypes;
+use hotshot_types::traits::signer::ChainId;
 use std::sync::Arc;
 
 use async_compatibility_layer::channel::oneshot;
@@ -9,6 +10,6 @@ async fn main() {
     let _sender = Arc::new(server_shutdown_sender);
     let _result = hotshot_web_server::run_web_server::<
         <VDemoTypes as hotshot_types::traits::node_implementation::NodeType>::SignatureKey,
-    >(Some(server_shutdown))
+    >(Some(server_shutdown), ChainId::default())
     .await;
 }
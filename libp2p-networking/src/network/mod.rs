@@ -8,9 +8,9 @@ pub use self::{
     def::NetworkDef,
     error::NetworkError,
     node::{
-        network_node_handle_error, MeshParams, NetworkNode, NetworkNodeConfig,
-        NetworkNodeConfigBuilder, NetworkNodeConfigBuilderError, NetworkNodeHandle,
-        NetworkNodeHandleError,
+        identify_protocol_version, is_compatible_peer, network_node_handle_error, MeshParams,
+        NetworkNode, NetworkNodeConfig, NetworkNodeConfigBuilder, NetworkNodeConfigBuilderError,
+        NetworkNodeHandle, NetworkNodeHandleError,
     },
 };
 
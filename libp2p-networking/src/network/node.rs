@@ -31,6 +31,7 @@ use async_compatibility_layer::{
 };
 use either::Either;
 use futures::{select, FutureExt, StreamExt};
+use hotshot_utils::version::ProtocolVersion;
 use libp2p::{
     core::{muxing::StreamMuxerBox, transport::Boxed},
     gossipsub::{
@@ -67,6 +68,31 @@ pub const ESTABLISHED_LIMIT: NonZeroU32 =
 /// Number of connections to a single peer before logging an error
 pub const ESTABLISHED_LIMIT_UNWR: u32 = 10;
 
+/// The identify protocol version of nodes on chain `chain_id` speaking `version` of the wire
+/// protocol
+#[must_use]
+pub fn identify_protocol_version(version: ProtocolVersion, chain_id: u64) -> String {
+    format!("HotShot/identify/{version}/chain/{chain_id}")
+}
+
+/// Whether a peer identifying with `protocol_version` may stay connected to nodes on chain
+/// `chain_id`, which it may if it is on the same chain and speaks a compatible version of the wire
+/// protocol
+#[must_use]
+pub fn is_compatible_peer(protocol_version: &str, chain_id: u64) -> bool {
+    let Some((version, peer_chain_id)) = protocol_version
+        .strip_prefix("HotShot/identify/")
+        .and_then(|rest| rest.split_once("/chain/"))
+    else {
+        return false;
+    };
+    let Ok(version) = version.parse::<ProtocolVersion>() else {
+        return false;
+    };
+    peer_chain_id.parse::<u64>().ok() == Some(chain_id)
+        && ProtocolVersion::CURRENT.is_compatible(version)
+}
+
 /// Network definition
 #[derive(custom_debug::Debug)]
 pub struct NetworkNode {
@@ -229,8 +255,9 @@ impl NetworkNode {
             //   node connection information
             //   E.g. this will answer the question: how are other nodes
             //   seeing the peer from behind a NAT
-            let identify_cfg =
-                IdentifyConfig::new("HotShot/identify/1.0".to_string(), identity.public());
+            let protocol_version =
+                identify_protocol_version(ProtocolVersion::CURRENT, config.chain_id);
+            let identify_cfg = IdentifyConfig::new(protocol_version, identity.public());
             let identify = IdentifyBehaviour::new(identify_cfg);
 
             // - Build DHT needed for peer discovery
@@ -487,12 +514,27 @@ impl NetworkNode {
                                     listen_addrs,
                                     protocols: _,
                                     public_key: _,
-                                    protocol_version: _,
+                                    protocol_version,
                                     agent_version: _,
                                     observed_addr,
                                 },
                         } = *e
                         {
+                            // NOTE peers of another chain, or of an incompatible version, are
+                            // dropped, and never make it into the routing table
+                            if !is_compatible_peer(&protocol_version, self.config.chain_id) {
+                                warn!(
+                                    "Disconnecting peer {:?} identifying as {:?}",
+                                    peer_id, protocol_version
+                                );
+                                if self.swarm.disconnect_peer_id(peer_id).is_err() {
+                                    error!(
+                                        "Peer {:?} could not disconnect from pid {:?}",
+                                        self.peer_id, peer_id
+                                    );
+                                }
+                                return Ok(());
+                            }
                             let behaviour = self.swarm.behaviour_mut();
                             // NOTE in practice, we will want to NOT include this. E.g. only DNS/non localhost IPs
                             // NOTE I manually checked and peer_id corresponds to listen_addrs.
@@ -30,6 +30,10 @@ pub struct NetworkNodeConfig {
 
     /// list of addresses to connect to at initialization
     pub to_connect_addrs: HashSet<(Option<PeerId>, Multiaddr)>,
+
+    #[builder(default)]
+    /// the chain this node is on. Peers identifying with another chain are disconnected.
+    pub chain_id: u64,
 }
 
 /// NOTE: `mesh_outbound_min <= mesh_n_low <= mesh_n <= mesh_n_high`
@@ -428,9 +428,20 @@ impl<S> NetworkNodeHandle<S> {
         msg: &impl Serialize,
     ) -> Result<(), NetworkNodeHandleError> {
         let serialized_msg = bincode_opts().serialize(msg).context(SerializationSnafu)?;
+        self.direct_request_no_serialize(pid, serialized_msg).await
+    }
+
+    /// Make a direct request to `peer_id` containing the already encoded `contents`
+    /// # Errors
+    /// - Will return [`NetworkNodeHandleError::SendError`] when underlying `NetworkNode` has been killed
+    pub async fn direct_request_no_serialize(
+        &self,
+        pid: PeerId,
+        contents: Vec<u8>,
+    ) -> Result<(), NetworkNodeHandleError> {
         let req = ClientRequest::DirectRequest {
             pid,
-            contents: serialized_msg,
+            contents,
             retry_count: 1,
         };
         self.send_request(req).await
@@ -472,7 +483,18 @@ impl<S> NetworkNodeHandle<S> {
         msg: &impl Serialize,
     ) -> Result<(), NetworkNodeHandleError> {
         let serialized_msg = bincode_opts().serialize(msg).context(SerializationSnafu)?;
-        let req = ClientRequest::GossipMsg(topic, serialized_msg);
+        self.gossip_no_serialize(topic, serialized_msg).await
+    }
+
+    /// Gossip the already encoded `msg` to peers
+    /// # Errors
+    /// - Will return [`NetworkNodeHandleError::SendError`] when underlying `NetworkNode` has been killed
+    pub async fn gossip_no_serialize(
+        &self,
+        topic: String,
+        msg: Vec<u8>,
+    ) -> Result<(), NetworkNodeHandleError> {
+        let req = ClientRequest::GossipMsg(topic, msg);
         self.send_request(req).await
     }
 
@@ -0,0 +1,24 @@
+use hotshot_utils::version::ProtocolVersion;
+use libp2p_networking::network::{identify_protocol_version, is_compatible_peer};
+
+/// Peers stay connected only if they are on the same chain and speak compatible protocol versions
+#[test]
+fn test_identify_compatibility() {
+    let current = ProtocolVersion::CURRENT;
+    assert!(is_compatible_peer(&identify_protocol_version(current, 7), 7));
+    assert!(!is_compatible_peer(&identify_protocol_version(current, 8), 7));
+
+    let next_minor = ProtocolVersion {
+        minor: current.minor + 1,
+        ..current
+    };
+    assert!(is_compatible_peer(&identify_protocol_version(next_minor, 7), 7));
+    let next_major = ProtocolVersion {
+        major: current.major + 1,
+        ..current
+    };
+    assert!(!is_compatible_peer(&identify_protocol_version(next_major, 7), 7));
+
+    assert!(!is_compatible_peer("HotShot/identify/1.0", 7));
+    assert!(!is_compatible_peer("ipfs/0.1.0", 7));
+}
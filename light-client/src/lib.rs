@@ -0,0 +1,262 @@
+//! Verification that a leaf was decided, for third parties that only hold the stake table
+//!
+//! A replica decides a leaf when it sees a chain of three leaves in consecutive views, each
+//! certified by a [`QuorumCertificate`] in the `justify_qc` of the next, the last one proposed by
+//! the leader of its view. This crate checks the same rule, and the stake behind every certificate
+//! or the leader behind the last proposal, against a [`Membership`] without running
+//! [`HotShot`](https://docs.rs/hotshot) or any networking. It does no I/O, and so needs none of
+//! the async runtime features of `hotshot-types`.
+
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    rust_2018_idioms,
+    missing_docs,
+    clippy::missing_docs_in_private_items,
+    clippy::panic
+)]
+#![allow(clippy::module_name_repetitions)]
+
+use commit::Committable;
+use hotshot_types::{
+    certificate::QuorumCertificate,
+    data::{LeafHeader, LeafType},
+    finality::FinalityProof,
+    traits::{
+        election::{certificate_stake, Membership, VoteData},
+        node_implementation::NodeType,
+        signature_key::SignatureKey,
+        signer::{signing_payload, ChainId, SigningKind},
+        state::ConsensusTime,
+    },
+};
+use snafu::Snafu;
+
+/// Number of leaves in consecutive views that decide the first of them
+pub const DECIDING_CHAIN_LENGTH: usize = 3;
+
+/// Reasons a chain of leaves does not prove a decision
+#[derive(Snafu, Debug, PartialEq, Eq)]
+#[snafu(visibility(pub))]
+pub enum LightClientError<TIME: ConsensusTime> {
+    /// The chain is too short to contain a deciding chain
+    #[snafu(display("chain of {len} leaves is too short to decide a leaf"))]
+    ChainTooShort {
+        /// the number of leaves given
+        len: usize,
+    },
+    /// The number of certificates does not match the number of leaves
+    #[snafu(display("expected {expected} certificates, got {found}"))]
+    CertificateCount {
+        /// one certificate for every leaf
+        expected: usize,
+        /// the number of certificates given
+        found: usize,
+    },
+    /// A certificate is not for the leaf it should certify
+    #[snafu(display("certificate for view {view_number:?} does not certify its leaf"))]
+    WrongLeaf {
+        /// the view of the leaf
+        view_number: TIME,
+    },
+    /// A certificate does not carry the threshold of valid stake
+    #[snafu(display("certificate for view {view_number:?} is invalid"))]
+    InvalidCertificate {
+        /// the view of the certified leaf
+        view_number: TIME,
+    },
+    /// The membership cannot tell the leader of the view of the last leaf
+    #[snafu(display("leader of view {view_number:?} is not known"))]
+    UnknownLeader {
+        /// the view of the last leaf
+        view_number: TIME,
+    },
+    /// The last leaf of the chain is not signed by the leader of its view
+    #[snafu(display("proposal for view {view_number:?} is not signed by its leader"))]
+    InvalidProposal {
+        /// the view of the last leaf
+        view_number: TIME,
+    },
+    /// A leaf does not extend the previous leaf of the chain
+    #[snafu(display("leaf of view {view_number:?} does not extend the previous leaf"))]
+    BrokenChain {
+        /// the view of the leaf
+        view_number: TIME,
+    },
+    /// The last leaves of the chain are not in consecutive views
+    #[snafu(display("leaves of views {first:?} to {last:?} are not in consecutive views"))]
+    NotConsecutive {
+        /// the view of the first leaf of the deciding chain
+        first: TIME,
+        /// the view of the last leaf of the deciding chain
+        last: TIME,
+    },
+}
+
+/// Whether `qc` certifies a leaf with the threshold of valid stake in `membership`, with votes
+/// signed for the network `chain_id`
+///
+/// This is [`ConsensusExchange::is_valid_cert`] for quorum certificates, without the exchange.
+///
+/// [`ConsensusExchange::is_valid_cert`]: hotshot_types::traits::election::ConsensusExchange::is_valid_cert
+pub fn verify_certificate<TYPES, LEAF, MEMBERSHIP>(
+    membership: &MEMBERSHIP,
+    chain_id: ChainId,
+    qc: &QuorumCertificate<TYPES, LEAF>,
+) -> bool
+where
+    TYPES: NodeType,
+    LEAF: LeafType<NodeType = TYPES>,
+    MEMBERSHIP: Membership<TYPES>,
+{
+    if qc.is_genesis && qc.view_number == TYPES::Time::genesis() {
+        return true;
+    }
+    let data = VoteData::<TYPES, LEAF>::Yes(qc.leaf_commitment)
+        .signing_payload(chain_id, qc.view_number);
+    certificate_stake(membership, chain_id, &qc.signatures, &data, qc.view_number)
+        .map_or(false, |stake| stake >= u64::from(membership.threshold()))
+}
+
+/// Verify that `leaves` prove the first of them decided, and return it
+///
+/// `leaves` run from the leaf to prove, through its descendants, to the tip of a chain whose last
+/// [`DECIDING_CHAIN_LENGTH`] leaves are in consecutive views. `certificates` holds the
+/// certificate of every leaf, the tip included, in the same order, so that no leaf of the chain
+/// goes unauthenticated. Every certificate must be valid in `membership` on the network
+/// `chain_id`, and the `justify_qc` of every leaf must be for the previous leaf, so the
+/// certificates form the chain a replica would have seen. A decision is also a decision of every
+/// ancestor, so all of `leaves` but the last two are decided.
+///
+/// # Errors
+/// If the chain or any of its certificates is invalid, or it does not end in a deciding chain
+pub fn verify_decided<'a, TYPES, LEAF, MEMBERSHIP>(
+    membership: &MEMBERSHIP,
+    chain_id: ChainId,
+    leaves: &'a [LEAF],
+    certificates: &[QuorumCertificate<TYPES, LEAF>],
+) -> Result<&'a LEAF, LightClientError<TYPES::Time>>
+where
+    TYPES: NodeType,
+    LEAF: LeafType<NodeType = TYPES>,
+    MEMBERSHIP: Membership<TYPES>,
+{
+    if leaves.len() < DECIDING_CHAIN_LENGTH {
+        return Err(LightClientError::ChainTooShort { len: leaves.len() });
+    }
+    if certificates.len() != leaves.len() {
+        return Err(LightClientError::CertificateCount {
+            expected: leaves.len(),
+            found: certificates.len(),
+        });
+    }
+
+    for (leaf, qc) in leaves.iter().zip(certificates) {
+        let view_number = leaf.get_view_number();
+        if qc.leaf_commitment != leaf.commit() || qc.view_number != view_number {
+            return Err(LightClientError::WrongLeaf { view_number });
+        }
+        if !verify_certificate(membership, chain_id, qc) {
+            return Err(LightClientError::InvalidCertificate { view_number });
+        }
+    }
+
+    for pair in leaves.windows(2) {
+        let (parent, child) = (&pair[0], &pair[1]);
+        let justify_qc = child.get_justify_qc();
+        if child.get_parent_commitment() != parent.commit()
+            || justify_qc.leaf_commitment != parent.commit()
+            || justify_qc.view_number != parent.get_view_number()
+        {
+            return Err(LightClientError::BrokenChain {
+                view_number: child.get_view_number(),
+            });
+        }
+    }
+
+    let views: Vec<_> = leaves.iter().map(LeafType::get_view_number).collect();
+    check_deciding_chain(&views)?;
+
+    Ok(&leaves[0])
+}
+
+/// Verify that `proof` proves its first leaf decided, and return the header of that leaf
+///
+/// This is [`verify_decided`] for the headers of a [`FinalityProof`], whose certificates are the
+/// `justify_qc`s of the headers. The tip has no certificate yet, so its proposal must instead be
+/// signed by the leader of its view in `membership`, as a replica checks before deciding. The
+/// header returned opens the commitment of the decided leaf, so a block matching its
+/// `block_commitment` is final.
+///
+/// The proof of a whole decision proves its oldest leaf; [`FinalityProof::for_height`] cuts out
+/// the proof of any other leaf of the decision.
+///
+/// # Errors
+/// If the chain or any of its certificates is invalid, it does not end in a deciding chain, or the
+/// tip is not signed by a leader known to `membership`
+pub fn verify_finality_proof<'a, TYPES, LEAF, MEMBERSHIP>(
+    membership: &MEMBERSHIP,
+    chain_id: ChainId,
+    proof: &'a FinalityProof<TYPES, LEAF>,
+) -> Result<&'a LeafHeader<TYPES, LEAF>, LightClientError<TYPES::Time>>
+where
+    TYPES: NodeType,
+    LEAF: LeafType<NodeType = TYPES>,
+    MEMBERSHIP: Membership<TYPES>,
+{
+    let headers = &proof.headers;
+    if headers.len() < DECIDING_CHAIN_LENGTH {
+        return Err(LightClientError::ChainTooShort { len: headers.len() });
+    }
+
+    for pair in headers.windows(2) {
+        let (parent, child) = (&pair[0], &pair[1]);
+        let view_number = parent.view_number;
+        let commitment = parent.leaf_commitment();
+        let qc = &child.justify_qc;
+        if qc.leaf_commitment != commitment || qc.view_number != view_number {
+            return Err(LightClientError::WrongLeaf { view_number });
+        }
+        if !verify_certificate(membership, chain_id, qc) {
+            return Err(LightClientError::InvalidCertificate { view_number });
+        }
+        if child.parent_commitment != commitment {
+            return Err(LightClientError::BrokenChain {
+                view_number: child.view_number,
+            });
+        }
+    }
+
+    let tip = &headers[headers.len() - 1];
+    let payload = signing_payload(
+        SigningKind::QuorumProposal,
+        chain_id,
+        *tip.view_number,
+        tip.leaf_commitment().as_ref(),
+    );
+    let leader = membership
+        .get_leader(tip.view_number)
+        .map_err(|_| LightClientError::UnknownLeader {
+            view_number: tip.view_number,
+        })?;
+    if !leader.validate(&proof.tip_signature, &payload) {
+        return Err(LightClientError::InvalidProposal {
+            view_number: tip.view_number,
+        });
+    }
+
+    let views: Vec<_> = headers.iter().map(|header| header.view_number).collect();
+    check_deciding_chain(&views)?;
+
+    Ok(&headers[0])
+}
+
+/// Check that the last [`DECIDING_CHAIN_LENGTH`] of `views` are consecutive
+fn check_deciding_chain<TIME: ConsensusTime>(views: &[TIME]) -> Result<(), LightClientError<TIME>> {
+    let deciding = &views[views.len() - DECIDING_CHAIN_LENGTH..];
+    let (first, last) = (deciding[0], deciding[DECIDING_CHAIN_LENGTH - 1]);
+    if deciding.windows(2).any(|pair| *pair[1] != *pair[0] + 1) {
+        return Err(LightClientError::NotConsecutive { first, last });
+    }
+    Ok(())
+}
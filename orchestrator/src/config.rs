@@ -1,6 +1,17 @@
-use hotshot_types::{ExecutionType, HotShotConfig};
+use hotshot_types::{
+    constants::default_key_rotation_epoch,
+    traits::{
+        signature_key::{EncodedPublicKey, EncodedSignature},
+        signer::ChainId,
+    },
+    ExecutionType, HotShotConfig,
+};
 use std::net::{Ipv4Addr, SocketAddr};
-use std::{net::IpAddr, num::NonZeroUsize, time::Duration};
+use std::{
+    net::IpAddr,
+    num::{NonZeroU64, NonZeroUsize},
+    time::Duration,
+};
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct Libp2pConfig {
@@ -95,6 +106,14 @@ pub struct NetworkConfigFile {
     pub node_index: u64,
     #[serde(default)]
     pub seed: [u8; 32],
+    /// Public keys of the nodes, in tagged base64, for nodes loading their keys from keystores.
+    /// When empty, the keys are derived from `seed`.
+    #[serde(default)]
+    pub known_nodes: Vec<EncodedPublicKey>,
+    /// Proofs of possession of the private keys of `known_nodes`, in the same order, for key
+    /// schemes that aggregate signatures
+    #[serde(default)]
+    pub possession_proofs: Vec<EncodedSignature>,
     #[serde(default = "default_padding")]
     pub padding: usize,
     #[serde(default = "default_start_delay_seconds")]
@@ -172,6 +191,12 @@ pub struct HotShotConfigFile {
     pub propose_min_round_time: Duration,
     /// The maximum amount of time a leader can wait to start a round
     pub propose_max_round_time: Duration,
+    /// The network the nodes take part in
+    #[serde(default)]
+    pub chain_id: ChainId,
+    /// Number of views in an epoch of the stake table
+    #[serde(default = "default_key_rotation_epoch")]
+    pub key_rotation_epoch: NonZeroU64,
 }
 
 impl<K, E> From<HotShotConfigFile> for HotShotConfig<K, E> {
@@ -182,6 +207,7 @@ impl<K, E> From<HotShotConfigFile> for HotShotConfig<K, E> {
             max_transactions: val.max_transactions,
             min_transactions: val.min_transactions,
             known_nodes: Vec::new(),
+            possession_proofs: Vec::new(),
             next_view_timeout: val.next_view_timeout,
             timeout_ratio: val.timeout_ratio,
             round_start_delay: val.round_start_delay,
@@ -190,6 +216,10 @@ impl<K, E> From<HotShotConfigFile> for HotShotConfig<K, E> {
             propose_min_round_time: val.propose_min_round_time,
             propose_max_round_time: val.propose_max_round_time,
             election_config: None,
+            adaptive_timeout: None,
+            transaction_forwarding: None,
+            chain_id: val.chain_id,
+            key_rotation_epoch: val.key_rotation_epoch,
         }
     }
 }
@@ -216,6 +246,8 @@ fn default_config() -> HotShotConfigFile {
         propose_min_round_time: Duration::from_secs(0),
         propose_max_round_time: Duration::from_secs(10),
         num_bootstrap: 5,
+        chain_id: ChainId::default(),
+        key_rotation_epoch: default_key_rotation_epoch(),
     }
 }
 
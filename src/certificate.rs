@@ -1 +1,3 @@
-pub use hotshot_types::certificate::QuorumCertificate;
+pub use hotshot_types::certificate::{
+    AggregateSignature, CertificateSignatures, QuorumCertificate, SignerBitmap,
+};
@@ -35,8 +35,9 @@ pub mod tasks;
 use crate::{
     certificate::QuorumCertificate,
     traits::{NodeImplementation, Storage},
-    types::{Event, HotShotHandle},
+    types::{Event, EventType, HotShotHandle},
 };
+use arc_swap::{ArcSwap, Guard};
 use async_compatibility_layer::{
     art::{async_sleep, async_spawn, async_spawn_local},
     async_primitives::{broadcast::BroadcastSender, subscribable_rwlock::SubscribableRwLock},
@@ -50,7 +51,7 @@ use commit::{Commitment, Committable};
 use hotshot_consensus::{
     BlockStore, Consensus, ConsensusApi, ConsensusLeader, ConsensusMetrics, ConsensusNextLeader,
     DALeader, DAMember, NextValidatingLeader, Replica, SendToTasks, SequencingReplica,
-    ValidatingLeader, View, ViewInner, ViewQueue,
+    ValidatingLeader, View, ViewInner, ViewQueue, ViewTimeoutController,
 };
 use hotshot_types::certificate::DACertificate;
 
@@ -61,24 +62,26 @@ use hotshot_types::traits::election::QuorumExchangeType;
 use hotshot_types::traits::network::CommunicationChannel;
 use hotshot_types::{data::ProposalType, traits::election::ConsensusExchange};
 use hotshot_types::{
-    data::{LeafType, ValidatingLeaf, ValidatingProposal},
-    error::StorageSnafu,
+    data::{genesis_parent_commitment, LeafType, ValidatingLeaf, ValidatingProposal},
+    error::{InvalidKeyRotationSnafu, StorageSnafu},
+    finality::FinalityProof,
+    key_rotation::{self, KeyRotation},
     message::{
         ConsensusMessage, DataMessage, InternalTrigger, Message, MessageKind,
         ProcessedConsensusMessage,
     },
     traits::{
-        election::SignedCertificate,
+        election::{ElectionConfig, ElectionError, Membership, SignedCertificate},
         metrics::Metrics,
         network::{NetworkError, TransmitType},
         node_implementation::NodeType,
-        signature_key::SignatureKey,
+        signer::{ChainId, Signer},
         state::{ConsensusTime, ConsensusType, SequencingConsensus, ValidatingConsensus},
         storage::StoredView,
-        State,
+        Block, State,
     },
     vote::{DAVote, QuorumVote, VoteType},
-    HotShotConfig,
+    HotShotConfig, HotShotConfigUpdate,
 };
 use hotshot_utils::bincode::bincode_opts;
 use snafu::ResultExt;
@@ -86,10 +89,13 @@ use std::{
     collections::{BTreeMap, HashMap},
     marker::PhantomData,
     num::NonZeroUsize,
-    sync::{atomic::Ordering, Arc},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
-use tracing::{debug, error, info, instrument, trace, warn};
+use tracing::{debug, error, info, instrument, warn};
 // -- Rexports
 // External
 /// Reexport rand crate
@@ -108,11 +114,19 @@ pub struct HotShotInner<TYPES: NodeType, I: NodeImplementation<TYPES>> {
     /// The public key of this node
     public_key: TYPES::SignatureKey,
 
-    /// The private key of this node
-    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// The signer holding the private key of this node
+    signer: Arc<dyn Signer<TYPES::SignatureKey>>,
 
     /// Configuration items for this hotshot instance
-    config: HotShotConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>,
+    ///
+    /// Only written at view boundaries, when scheduled updates are applied.
+    config: ArcSwap<HotShotConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>>,
+
+    /// Configuration updates waiting for the view they take effect in
+    pending_config_updates: RwLock<BTreeMap<TYPES::Time, Vec<HotShotConfigUpdate>>>,
+
+    /// Key rotations submitted to this node that no decided leaf carries yet
+    pending_key_rotations: RwLock<Vec<KeyRotation<TYPES::SignatureKey>>>,
 
     /// Networking interface for this hotshot instance
     // networking: I::Networking,
@@ -132,10 +146,82 @@ pub struct HotShotInner<TYPES: NodeType, I: NodeImplementation<TYPES>> {
     /// Senders to the background tasks.
     background_task_handle: tasks::TaskHandle<TYPES>,
 
+    /// Cleared once a graceful shutdown has started
+    accepting_transactions: AtomicBool,
+
+    /// Transactions submitted through this node that no proposal has included yet
+    submitted_transactions: RwLock<HashMap<Commitment<TYPES::Transaction>, TYPES::Transaction>>,
+
     /// a reference to the metrics that the implementor is using.
     metrics: Box<dyn Metrics>,
 }
 
+impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShotInner<TYPES, I> {
+    /// Read access to the current configuration
+    fn config(&self) -> Guard<Arc<HotShotConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>>> {
+        self.config.load()
+    }
+
+    /// Stop tracking the submitted transactions that `event` shows were included or decided
+    async fn forget_settled_transactions(&self, event: &EventType<TYPES, I::Leaf>) {
+        match event {
+            EventType::TransactionsIncluded { transactions } => {
+                let mut submitted = self.submitted_transactions.write().await;
+                for transaction in transactions.iter() {
+                    submitted.remove(transaction);
+                }
+            }
+            EventType::Decide { leaf_chain, .. } => {
+                let mut submitted = self.submitted_transactions.write().await;
+                for leaf in leaf_chain.iter() {
+                    for transaction in leaf.get_rejected() {
+                        submitted.remove(&transaction.commit());
+                    }
+                    if let Ok(block) = leaf.get_deltas().try_resolve() {
+                        for transaction in block.contained_transactions() {
+                            submitted.remove(&transaction);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply a decided `rotation` to the memberships
+    ///
+    /// The committee membership only holds some of the nodes under sequencing consensus, so the
+    /// rotation is only applied to it if the old key is a member.
+    fn apply_decided_key_rotation(
+        &self,
+        rotation: &KeyRotation<TYPES::SignatureKey>,
+    ) -> Result<(), HotShotError<TYPES>> {
+        self.quorum_exchange
+            .membership()
+            .rotate_key(rotation)
+            .context(InvalidKeyRotationSnafu)?;
+        self.apply_committee_key_rotation(rotation)
+    }
+
+    /// Apply a `rotation` already applied to the quorum membership to the committee membership,
+    /// if the old key is a member of the committee
+    fn apply_committee_key_rotation(
+        &self,
+        rotation: &KeyRotation<TYPES::SignatureKey>,
+    ) -> Result<(), HotShotError<TYPES>> {
+        let committee_membership = self.committee_exchange.membership();
+        if committee_membership
+            .get_committee(TYPES::Time::new(rotation.effective_view))
+            .contains(&rotation.old_key)
+        {
+            committee_membership
+                .rotate_key(rotation)
+                .context(InvalidKeyRotationSnafu)?;
+        }
+        Ok(())
+    }
+}
+
 /// Thread safe, shared view of a `HotShot`
 #[derive(Clone)]
 pub struct HotShot<CONSENSUS: ConsensusType, TYPES: NodeType, I: NodeImplementation<TYPES>> {
@@ -150,6 +236,9 @@ pub struct HotShot<CONSENSUS: ConsensusType, TYPES: NodeType, I: NodeImplementat
     /// The hotstuff implementation
     hotstuff: Arc<RwLock<Consensus<TYPES, I::Leaf>>>,
 
+    /// Adapts the view timeout to observed latencies (shared with `Consensus` metrics)
+    view_timeout: Arc<ViewTimeoutController>,
+
     /// for sending/recv-ing things with the DA member task
     member_channel_map: Arc<RwLock<SendToTasks<TYPES, I>>>,
 
@@ -180,7 +269,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
     /// genesis block
     #[allow(clippy::too_many_arguments)]
     #[instrument(skip(
-        private_key,
+        signer,
         storage,
         quorum_exchange,
         committee_exchange,
@@ -188,8 +277,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
         metrics
     ))]
     pub async fn new(
-        public_key: TYPES::SignatureKey,
-        private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        signer: Arc<dyn Signer<TYPES::SignatureKey>>,
         nonce: u64,
         config: HotShotConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>,
         // networking: I::Networking,
@@ -200,16 +288,75 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
         metrics: Box<dyn Metrics>,
     ) -> Result<Self, HotShotError<TYPES>> {
         info!("Creating a new hotshot");
+        config
+            .validate_parameters()
+            .map_err(|context| HotShotError::InvalidConfig { context })?;
+        config
+            .validate_known_nodes()
+            .map_err(|context| HotShotError::InvalidConfig { context })?;
+        if let Some(election_config) = &config.election_config {
+            election_config
+                .validate(config.known_nodes.len())
+                .map_err(|context| HotShotError::InvalidConfig { context })?;
+        }
+        // every election picks leaders by indexing into the committee
+        if quorum_exchange
+            .membership()
+            .get_committee(TYPES::Time::genesis())
+            .is_empty()
+        {
+            return Err(HotShotError::InvalidConfig {
+                context: "the committee must not be empty".to_string(),
+            });
+        }
+        if signer.local_private_key().is_none()
+            && (quorum_exchange.membership().needs_local_key()
+                || committee_exchange.membership().needs_local_key())
+        {
+            return Err(HotShotError::InvalidConfig {
+                context: "the election draws vote tokens with the private key, which the signer \
+                          does not hold in memory"
+                    .to_string(),
+            });
+        }
+        if quorum_exchange.chain_id() != config.chain_id
+            || committee_exchange.chain_id() != config.chain_id
+        {
+            return Err(HotShotError::InvalidConfig {
+                context: format!(
+                    "the exchanges must sign for the configured chain id {:?}",
+                    config.chain_id
+                ),
+            });
+        }
+        // the genesis leaf binds the chain to its chain id, so a node started from the genesis of
+        // another chain would never agree with its peers
+        let anchored_leaf = &initializer.inner;
+        if anchored_leaf.get_height() == 0
+            && anchored_leaf.get_parent_commitment()
+                != genesis_parent_commitment(config.chain_id)
+        {
+            return Err(HotShotError::InvalidConfig {
+                context: format!(
+                    "the genesis leaf is not the one of the configured chain id {:?}",
+                    config.chain_id
+                ),
+            });
+        }
         let inner: Arc<HotShotInner<TYPES, I>> = Arc::new(HotShotInner {
-            public_key,
-            private_key,
-            config,
+            public_key: signer.public_key().clone(),
+            signer,
+            config: ArcSwap::from_pointee(config),
+            pending_config_updates: RwLock::default(),
+            pending_key_rotations: RwLock::default(),
             // networking,
             storage,
             quorum_exchange: Arc::new(quorum_exchange),
             committee_exchange: Arc::new(committee_exchange),
             event_sender: RwLock::default(),
             background_task_handle: tasks::TaskHandle::default(),
+            accepting_transactions: AtomicBool::new(true),
+            submitted_transactions: RwLock::default(),
             metrics,
         });
 
@@ -222,6 +369,65 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
             .await
             .context(StorageSnafu)?;
 
+        // replay the key rotations decided before the restart, so that the stake table matches
+        // the one of the other nodes
+        let key_rotations = inner
+            .storage
+            .get_key_rotations()
+            .await
+            .context(StorageSnafu)?;
+        for rotation in &key_rotations {
+            inner.apply_decided_key_rotation(rotation)?;
+        }
+
+        // rebuild the randomness beacon and the outcomes of past views from the leaves decided
+        // before the anchor, so that a restarted node knows the seeds of the views it takes part
+        // in and schedules the same leaders as the other nodes
+        let mut height = 1;
+        while height <= anchored_leaf.get_height() {
+            let proof = inner
+                .storage
+                .get_finality_proof(height)
+                .await
+                .context(StorageSnafu)?;
+            let decided = proof.as_ref().map_or(&[][..], FinalityProof::decided_headers);
+            if decided.is_empty() {
+                // without the leaf the node would draw other leaders than the rest of the network
+                return Err(HotShotError::InvalidState {
+                    context: format!(
+                        "no finality proof is stored for the decided leaf at height {height}, so \
+                         the leader schedule cannot be rebuilt"
+                    ),
+                });
+            }
+            for header in decided {
+                let parent_view = header.justify_qc.view_number;
+                let quorum_membership = inner.quorum_exchange.membership();
+                quorum_membership.record_decided_leaf(
+                    header.view_number,
+                    parent_view,
+                    &header.proposer_id,
+                );
+                quorum_membership.record_decided_qc(&header.justify_qc);
+                let committee_membership = inner.committee_exchange.membership();
+                committee_membership.record_decided_leaf(
+                    header.view_number,
+                    parent_view,
+                    &header.proposer_id,
+                );
+                committee_membership.record_decided_qc(&header.justify_qc);
+            }
+            height += decided.len() as u64;
+        }
+        inner
+            .quorum_exchange
+            .membership()
+            .record_decided_qc(&anchored_leaf.get_justify_qc());
+        inner
+            .committee_exchange
+            .membership()
+            .record_decided_qc(&anchored_leaf.get_justify_qc());
+
         // insert genesis (or latest block) to state map
         let mut state_map = BTreeMap::default();
         state_map.insert(
@@ -242,6 +448,14 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
 
         let start_view = anchored_leaf.get_view_number();
 
+        let consensus_metrics = Arc::new(ConsensusMetrics::new(
+            &*inner.metrics.subgroup("consensus".to_string()),
+        ));
+        consensus_metrics
+            .view_timeout
+            .configure(inner.config().adaptive_timeout);
+        let view_timeout = consensus_metrics.view_timeout.clone();
+
         let hotstuff = Consensus {
             state_map,
             cur_view: start_view,
@@ -254,9 +468,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
             locked_view: anchored_leaf.get_view_number(),
             high_qc: anchored_leaf.get_justify_qc(),
 
-            metrics: Arc::new(ConsensusMetrics::new(
-                &*inner.metrics.subgroup("consensus".to_string()),
-            )),
+            metrics: consensus_metrics,
             invalid_qc: 0,
         };
         let hotstuff = Arc::new(RwLock::new(hotstuff));
@@ -269,6 +481,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
             inner,
             transactions: txns,
             hotstuff,
+            view_timeout,
             member_channel_map: Arc::new(RwLock::new(SendToTasks::new(start_view))),
             replica_channel_map: Arc::new(RwLock::new(SendToTasks::new(start_view))),
             next_leader_channel_map: Arc::new(RwLock::new(SendToTasks::new(start_view))),
@@ -339,28 +552,91 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
 
     /// Publishes a transaction to the network
     ///
+    /// The transaction is broadcast to every node, unless `transaction_forwarding` is configured,
+    /// in which case it is only sent to the leaders of the upcoming views.
+    ///
     /// # Errors
     ///
-    /// Will generate an error if an underlying network error occurs
+    /// Will generate an error if an underlying network error occurs, or
+    /// [`HotShotError::ShuttingDown`] once a graceful shutdown has started
     #[instrument(skip(self), err)]
     pub async fn publish_transaction_async(
         &self,
         transaction: TYPES::Transaction,
     ) -> Result<(), HotShotError<TYPES>> {
-        // Add the transaction to our own queue first
-        trace!("Adding transaction to our own queue");
+        if !self.inner.accepting_transactions.load(Ordering::Relaxed) {
+            return Err(HotShotError::ShuttingDown {});
+        }
+        self.inner
+            .submitted_transactions
+            .write()
+            .await
+            .insert(transaction.commit(), transaction.clone());
         // Wrap up a message
         // TODO place a view number here that makes sense
         // we haven't worked out how this will work yet
-        let message = DataMessage::SubmitTransaction(transaction, TYPES::Time::new(0));
+        let message = DataMessage::SubmitTransaction(transaction.clone(), TYPES::Time::new(0));
 
+        let forwarding = self.inner.config().transaction_forwarding;
         let api = self.clone();
-        async_spawn(async move {
-            let _result = api.send_broadcast_message(message).await.is_err();
-        });
+        // the transaction enters the mempool of the nodes receiving it, which with forwarding are
+        // only the upcoming leaders, ourselves included if we lead one of the views
+        if let Some(lookahead) = forwarding {
+            let cur_view = self.hotstuff.read().await.cur_view;
+            let mut leaders = self
+                .inner
+                .quorum_exchange
+                .leader_schedule(cur_view, lookahead.get());
+            // a node may lead several of the upcoming views
+            let mut seen = std::collections::HashSet::new();
+            leaders.retain(|leader| seen.insert(leader.clone()));
+            async_spawn(async move {
+                for leader in leaders {
+                    if leader == api.inner.public_key {
+                        api.add_transaction(transaction.clone()).await;
+                    } else if api
+                        .send_direct_message(message.clone(), leader)
+                        .await
+                        .is_err()
+                    {
+                        warn!("Failed to forward transaction to an upcoming leader");
+                    }
+                }
+            });
+        } else {
+            async_spawn(async move {
+                let _result = api.send_broadcast_message(message).await.is_err();
+            });
+        }
         Ok(())
     }
 
+    /// Add a transaction to the pool of transactions this node may propose
+    async fn add_transaction(&self, transaction: TYPES::Transaction) {
+        let size = bincode_opts().serialized_size(&transaction).unwrap_or(0);
+
+        // The API contract requires the hash to be unique
+        // so we can assume entry == incoming txn
+        // even if eq not satisfied
+        // so insert is an idempotent operation
+        let mut new = false;
+        self.transactions
+            .modify(|txns| {
+                new = txns.insert(transaction.commit(), transaction).is_none();
+            })
+            .await;
+
+        if new {
+            // If this is a new transaction, update metrics.
+            let consensus = self.hotstuff.read().await;
+            consensus.metrics.outstanding_transactions.update(1);
+            consensus
+                .metrics
+                .outstanding_transactions_memory_size
+                .update(i64::try_from(size).unwrap_or(i64::MAX));
+        }
+    }
+
     /// Returns a copy of the state
     ///
     /// # Panics
@@ -391,8 +667,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
     /// Will return an error when the storage failed to insert the first `QuorumCertificate`
     #[allow(clippy::too_many_arguments)]
     pub async fn init(
-        public_key: TYPES::SignatureKey,
-        private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        signer: Arc<dyn Signer<TYPES::SignatureKey>>,
         node_id: u64,
         config: HotShotConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>,
         storage: I::Storage,
@@ -406,8 +681,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
     {
         // Save a clone of the storage for the handle
         let hotshot = Self::new(
-            public_key,
-            private_key,
+            signer,
             node_id,
             config,
             storage,
@@ -441,7 +715,11 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
                 .quorum_exchange
                 .network()
                 .broadcast_message(
-                    Message { sender: pk, kind },
+                    Message {
+                        sender: pk,
+                        chain_id: inner.quorum_exchange.chain_id(),
+                        kind,
+                    },
                     // TODO this is morally wrong
                     &inner.quorum_exchange.membership().clone(),
                 )
@@ -472,6 +750,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
             .direct_message(
                 Message {
                     sender: self.inner.public_key.clone(),
+                    chain_id: self.inner.quorum_exchange.chain_id(),
                     kind: kind.into(),
                 },
                 recipient,
@@ -555,6 +834,14 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
 
     /// decide which handler to call based on the message variant and `transmit_type`
     async fn handle_message(&self, item: Message<TYPES, I>, transmit_type: TransmitType) {
+        if item.chain_id != self.inner.quorum_exchange.chain_id() {
+            warn!(
+                chain_id = ?item.chain_id,
+                sender = ?item.sender,
+                "Dropping a message sent on another chain"
+            );
+            return;
+        }
         match (item.kind, transmit_type) {
             (MessageKind::Consensus(msg), TransmitType::Broadcast) => {
                 self.handle_broadcast_consensus_message(msg, item.sender)
@@ -656,34 +943,17 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
         // TODO validate incoming broadcast message based on sender signature key
         match msg {
             DataMessage::SubmitTransaction(transaction, _view_number) => {
-                let size = bincode_opts().serialized_size(&transaction).unwrap_or(0);
-
-                // The API contract requires the hash to be unique
-                // so we can assume entry == incoming txn
-                // even if eq not satisfied
-                // so insert is an idempotent operation
-                let mut new = false;
-                self.transactions
-                    .modify(|txns| {
-                        new = txns.insert(transaction.commit(), transaction).is_none();
-                    })
-                    .await;
-
-                if new {
-                    // If this is a new transaction, update metrics.
-                    let consensus = self.hotstuff.read().await;
-                    consensus.metrics.outstanding_transactions.update(1);
-                    consensus
-                        .metrics
-                        .outstanding_transactions_memory_size
-                        .update(i64::try_from(size).unwrap_or(i64::MAX));
+                self.add_transaction(transaction).await;
+            }
+            DataMessage::KeyRotation(rotation) => {
+                if let Err(e) = self.add_key_rotation(rotation).await {
+                    warn!(?e, "Rejected a key rotation");
                 }
             }
         }
     }
 
     /// Handle an incoming [`DataMessage`] that directed at this node
-    #[allow(clippy::unused_async)] // async for API compatibility reasons
     async fn handle_direct_data_message(
         &self,
         msg: DataMessage<TYPES>,
@@ -691,17 +961,163 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> HotShot<TYPES::ConsensusType
     ) {
         debug!(?msg, "Incoming direct data message");
         match msg {
-            DataMessage::SubmitTransaction(_, _) => {
-                // Log exceptional situation and proceed
-                warn!(?msg, "Broadcast message received over direct channel");
+            // forwarded to us as an upcoming leader
+            DataMessage::SubmitTransaction(transaction, _view_number) => {
+                self.add_transaction(transaction).await;
+            }
+            DataMessage::KeyRotation(rotation) => {
+                if let Err(e) = self.add_key_rotation(rotation).await {
+                    warn!(?e, "Rejected a key rotation");
+                }
             }
         }
     }
 
     /// return the timeout for a view for `self`
+    ///
+    /// This is the configured `next_view_timeout`, unless an adaptive timeout is configured.
     #[must_use]
     pub fn get_next_view_timeout(&self) -> u64 {
-        self.inner.config.next_view_timeout
+        let (base, timeout_ratio) = {
+            let config = self.inner.config();
+            (config.next_view_timeout, config.timeout_ratio)
+        };
+        self.view_timeout.next_timeout(base, timeout_ratio)
+    }
+
+    /// Schedule `update` to be applied at the start of `activation_view`, or of the next view if
+    /// `activation_view` is `None`.
+    ///
+    /// Nodes that derive `activation_view` from a decided leaf, e.g. one carrying an on-chain
+    /// parameter change, will all switch to the new parameters in the same view.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HotShotError::InvalidConfig`] if applying `update` to the current configuration
+    /// would leave it inconsistent, or if `activation_view` has already started.
+    pub async fn update_config(
+        &self,
+        update: HotShotConfigUpdate,
+        activation_view: Option<TYPES::Time>,
+    ) -> Result<(), HotShotError<TYPES>> {
+        let cur_view = self.hotstuff.read().await.cur_view;
+        let activation_view = activation_view.unwrap_or(cur_view + 1);
+        if activation_view <= cur_view {
+            return Err(HotShotError::InvalidConfig {
+                context: format!(
+                    "cannot schedule a config update for view {activation_view:?}, which has already started"
+                ),
+            });
+        }
+
+        let mut config = HotShotConfig::clone(&self.inner.config());
+        update.apply(&mut config);
+        config
+            .validate_parameters()
+            .map_err(|context| HotShotError::InvalidConfig { context })?;
+
+        self.inner
+            .pending_config_updates
+            .write()
+            .await
+            .entry(activation_view)
+            .or_default()
+            .push(update);
+        Ok(())
+    }
+
+    /// Hand the stake slot of `rotation.old_key` to `rotation.new_key` from
+    /// `rotation.effective_view` on, by broadcasting the rotation so that leaders include it in
+    /// the leaves they propose.
+    ///
+    /// The rotation only changes the stake table once a leaf carrying it is decided, and every
+    /// node then applies it in the same order, so a conflicting rotation of the same key decided
+    /// later is skipped. The effective view must start an epoch of
+    /// [`key_rotation_epoch`](HotShotConfig::key_rotation_epoch) views, at least a whole epoch
+    /// after the current one, so that the rotation is decided before it takes effect. Decided
+    /// rotations are kept in storage and replayed when the node restarts.
+    ///
+    /// A node keeps signing with the key it was started with, as messages are addressed by
+    /// signature key. To rotate the key of a running node, restart it with the new key once the
+    /// effective view is reached.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HotShotError::InvalidKeyRotation`] if the rotation is for another chain, is not
+    /// signed by both keys, or does not take effect at the start of a late enough epoch.
+    pub async fn rotate_key(
+        &self,
+        rotation: KeyRotation<TYPES::SignatureKey>,
+    ) -> Result<(), HotShotError<TYPES>> {
+        self.add_key_rotation(rotation.clone()).await?;
+        let api = self.clone();
+        async_spawn(async move {
+            let message = DataMessage::KeyRotation(rotation);
+            if api.send_broadcast_message(message).await.is_err() {
+                warn!("Failed to broadcast key rotation");
+            }
+        });
+        Ok(())
+    }
+
+    /// Add `rotation` to the rotations this node includes in the leaves it proposes, if it could
+    /// still be applied when decided
+    async fn add_key_rotation(
+        &self,
+        rotation: KeyRotation<TYPES::SignatureKey>,
+    ) -> Result<(), HotShotError<TYPES>> {
+        let invalid = |reason: String| HotShotError::InvalidKeyRotation {
+            source: ElectionError::InvalidKeyRotation { reason },
+        };
+        if rotation.chain_id != self.inner.quorum_exchange.chain_id() {
+            return Err(invalid(format!("the rotation is for chain {:?}", rotation.chain_id)));
+        }
+        if !rotation.is_valid() {
+            return Err(invalid("the rotation is not signed by both keys".to_string()));
+        }
+        let cur_view = self.hotstuff.read().await.cur_view;
+        let epoch_length = self.inner.config().key_rotation_epoch;
+        if !rotation.is_timely(*cur_view, epoch_length) {
+            return Err(invalid(format!(
+                "view {} does not start an epoch of {epoch_length} views after the next one",
+                rotation.effective_view
+            )));
+        }
+
+        let mut pending = self.inner.pending_key_rotations.write().await;
+        if !pending.contains(&rotation) {
+            pending.push(rotation);
+        }
+        Ok(())
+    }
+
+    /// Apply the config updates scheduled for `cur_view` or earlier. Called at the start of every
+    /// view, before any task of that view reads the configuration.
+    async fn apply_config_updates(&self, cur_view: TYPES::Time) {
+        let due = {
+            let mut pending = self.inner.pending_config_updates.write().await;
+            let later = pending.split_off(&(cur_view + 1));
+            std::mem::replace(&mut *pending, later)
+        };
+        if due.is_empty() {
+            return;
+        }
+
+        let mut config = HotShotConfig::clone(&self.inner.config());
+        for update in due.into_values().flatten() {
+            let mut new_config = config.clone();
+            update.apply(&mut new_config);
+            // updates were validated when scheduled, but an earlier update may have changed the
+            // values they were validated against
+            if let Err(e) = new_config.validate_parameters() {
+                error!(?update, "Skipping config update in view {:?}: {}", cur_view, e);
+                continue;
+            }
+            info!(?update, "Applying config update in view {:?}", cur_view);
+            config = new_config;
+        }
+        self.view_timeout.configure(config.adaptive_timeout);
+        self.inner.config.store(Arc::new(config));
     }
 
     /// given a view number and a upgradable read lock on a channel map, inserts entry into map if it
@@ -828,6 +1244,7 @@ where
             drop(consensus);
             (cur_view, high_qc, txns)
         };
+        hotshot.apply_config_updates(cur_view).await;
 
         // notify networking to start worrying about the (`cur_view + LOOK_AHEAD`)th leader ahead of the current view
         if hotshot
@@ -841,6 +1258,7 @@ where
 
         info!("Starting tasks for View {:?}!", cur_view);
         metrics.current_view.set(*cur_view as usize);
+        c_api.send_view_changed(cur_view).await;
 
         let mut task_handles = Vec::new();
 
@@ -899,7 +1317,7 @@ where
         let children_finished = futures::future::join_all(task_handles);
 
         async_spawn({
-            let next_view_timeout = hotshot.inner.config.next_view_timeout;
+            let next_view_timeout = hotshot.get_next_view_timeout();
             let next_view_timeout = next_view_timeout;
             let hotshot: HotShot<TYPES::ConsensusType, TYPES, I> = hotshot.clone();
             async move {
@@ -969,6 +1387,7 @@ where
     // #[instrument]
     #[allow(clippy::too_many_lines)]
     async fn run_view(hotshot: HotShot<SequencingConsensus, TYPES, I>) -> Result<(), ()> {
+        let start = Instant::now();
         let c_api = HotShotConsensusApi {
             inner: hotshot.inner.clone(),
         };
@@ -988,6 +1407,7 @@ where
             .await;
             (vq.sender_chan, vq.receiver_chan, cur_view)
         };
+        hotshot.apply_config_updates(cur_view).await;
 
         // Set up vote collection channel for commitment proposals/votes
         let mut send_to_next_leader = hotshot.next_leader_channel_map.write().await;
@@ -1010,6 +1430,7 @@ where
             let txns = consensus.transactions.clone();
             (high_qc, txns)
         };
+        c_api.send_view_changed(cur_view).await;
         let mut send_to_member = hotshot.member_channel_map.write().await;
         let member_last_view: TYPES::Time = send_to_member.cur_view;
         send_to_member.channel_map.remove(&member_last_view);
@@ -1118,7 +1539,7 @@ where
         let children_finished = futures::future::join_all(task_handles);
 
         async_spawn({
-            let next_view_timeout = hotshot.inner.config.next_view_timeout;
+            let next_view_timeout = hotshot.get_next_view_timeout();
             let hotshot: HotShot<TYPES::ConsensusType, TYPES, I> = hotshot.clone();
             async move {
                 async_sleep(Duration::from_millis(next_view_timeout)).await;
@@ -1148,6 +1569,10 @@ where
 
         let mut consensus = hotshot.hotstuff.write().await;
         consensus.high_qc = high_qc;
+        consensus
+            .metrics
+            .view_duration
+            .add_point(start.elapsed().as_secs_f64());
         c_api.send_view_finished(consensus.cur_view).await;
         Ok(())
     }
@@ -1165,23 +1590,23 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>>
     hotshot_consensus::ConsensusApi<TYPES, I::Leaf, I> for HotShotConsensusApi<TYPES, I>
 {
     fn total_nodes(&self) -> NonZeroUsize {
-        self.inner.config.total_nodes
+        self.inner.config().total_nodes
     }
 
     fn propose_min_round_time(&self) -> Duration {
-        self.inner.config.propose_min_round_time
+        self.inner.config().propose_min_round_time
     }
 
     fn propose_max_round_time(&self) -> Duration {
-        self.inner.config.propose_max_round_time
+        self.inner.config().propose_max_round_time
     }
 
     fn max_transactions(&self) -> NonZeroUsize {
-        self.inner.config.max_transactions
+        self.inner.config().max_transactions
     }
 
     fn min_transactions(&self) -> usize {
-        self.inner.config.min_transactions
+        self.inner.config().min_transactions
     }
 
     /// Generates and encodes a vote token
@@ -1207,6 +1632,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>>
                 .direct_message(
                     Message {
                         sender: inner.public_key.clone(),
+                        chain_id: inner.quorum_exchange.chain_id(),
                         kind: message.into(),
                     },
                     recipient,
@@ -1233,6 +1659,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>>
                 .direct_message(
                     Message {
                         sender: inner.public_key.clone(),
+                        chain_id: inner.quorum_exchange.chain_id(),
                         kind: message.into(),
                     },
                     recipient,
@@ -1257,6 +1684,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>>
             .broadcast_message(
                 Message {
                     sender: self.inner.public_key.clone(),
+                    chain_id: self.inner.quorum_exchange.chain_id(),
                     kind: message.into(),
                 },
                 // TODO this is morally wrong!
@@ -1277,6 +1705,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>>
             .broadcast_message(
                 Message {
                     sender: self.inner.public_key.clone(),
+                    chain_id: self.inner.quorum_exchange.chain_id(),
                     kind: message.into(),
                 },
                 // TODO this is morally wrong!
@@ -1288,6 +1717,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>>
 
     async fn send_event(&self, event: Event<TYPES, I::Leaf>) {
         debug!(?event, "send_event");
+        self.inner.forget_settled_transactions(&event.event).await;
         let mut event_sender = self.inner.event_sender.write().await;
         if let Some(sender) = &*event_sender {
             if let Err(e) = sender.send_async(event).await {
@@ -1301,18 +1731,76 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>>
         &self.inner.public_key
     }
 
-    fn private_key(&self) -> &<TYPES::SignatureKey as SignatureKey>::PrivateKey {
-        &self.inner.private_key
+    fn signer(&self) -> &Arc<dyn Signer<TYPES::SignatureKey>> {
+        &self.inner.signer
+    }
+
+    async fn record_decided_leaves(
+        &self,
+        leaf_views: &[I::Leaf],
+    ) -> Vec<KeyRotation<TYPES::SignatureKey>> {
+        let chain_id = self.inner.quorum_exchange.chain_id();
+        let epoch_length = self.inner.config().key_rotation_epoch;
+        let mut decided: Vec<_> = leaf_views.iter().collect();
+        decided.sort_by_key(|leaf| leaf.get_view_number());
+        let mut applied = Vec::new();
+        let mut carried = Vec::new();
+        for leaf in decided {
+            let view_number = leaf.get_view_number();
+            let proposer = leaf.get_proposer_id();
+            let justify_qc = leaf.get_justify_qc();
+            let parent_view = justify_qc.view_number;
+            let quorum_membership = self.inner.quorum_exchange.membership();
+            quorum_membership.record_decided_leaf(view_number, parent_view, &proposer);
+            quorum_membership.record_decided_qc(&justify_qc);
+            let committee_membership = self.inner.committee_exchange.membership();
+            committee_membership.record_decided_leaf(view_number, parent_view, &proposer);
+            committee_membership.record_decided_qc(&justify_qc);
+
+            let key_rotations = leaf.get_key_rotations();
+            for rotation in key_rotation::apply_decided::<TYPES, _>(
+                quorum_membership,
+                chain_id,
+                epoch_length,
+                view_number,
+                &key_rotations,
+            ) {
+                if let Err(e) = self.inner.apply_committee_key_rotation(&rotation) {
+                    error!(?rotation, ?e, "Failed to rotate a key of the DA committee");
+                }
+                applied.push(rotation);
+            }
+            carried.extend(key_rotations);
+        }
+
+        // drop the rotations that were decided or can no longer be applied
+        if let Some(last) = leaf_views.iter().map(LeafType::get_view_number).max() {
+            let mut pending = self.inner.pending_key_rotations.write().await;
+            pending.retain(|rotation| {
+                !carried.contains(rotation) && rotation.is_timely(*last, epoch_length)
+            });
+        }
+        applied
+    }
+
+    async fn pending_key_rotations(&self) -> Vec<KeyRotation<TYPES::SignatureKey>> {
+        self.inner.pending_key_rotations.read().await.clone()
     }
 
     async fn store_leaf(
         &self,
         old_anchor_view: TYPES::Time,
         leaf: I::Leaf,
+        finality_proof: FinalityProof<TYPES, I::Leaf>,
+        key_rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
     ) -> std::result::Result<(), hotshot_types::traits::storage::StorageError> {
         let view_to_insert = StoredView::from(leaf);
         let storage = &self.inner.storage;
         storage.append_single_view(view_to_insert).await?;
+        storage.append_finality_proof(finality_proof).await?;
+        if !key_rotations.is_empty() {
+            storage.append_key_rotations(key_rotations).await?;
+        }
         storage.cleanup_storage_up_to_view(old_anchor_view).await?;
         storage.commit().await?;
         Ok(())
@@ -1326,10 +1814,14 @@ pub struct HotShotInitializer<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>>
 }
 
 impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> HotShotInitializer<TYPES, LEAF> {
-    /// initialize from genesis
+    /// initialize from genesis on the chain `chain_id`, which must be the `chain_id` of the
+    /// configuration passed to [`HotShot::new`]
     /// # Errors
     /// If we are unable to apply the genesis block to the default state
-    pub fn from_genesis(genesis_block: TYPES::BlockType) -> Result<Self, HotShotError<TYPES>> {
+    pub fn from_genesis(
+        genesis_block: TYPES::BlockType,
+        chain_id: ChainId,
+    ) -> Result<Self, HotShotError<TYPES>> {
         let state = TYPES::StateType::default()
             .append(&genesis_block, &TYPES::Time::new(0))
             .map_err(|err| HotShotError::Misc {
@@ -1337,10 +1829,10 @@ impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> HotShotInitializer<TYPES
             })?;
         let time = TYPES::Time::genesis();
         let justify_qc = QuorumCertificate::<TYPES, LEAF>::genesis();
+        let mut inner = LEAF::new(time, justify_qc, genesis_block, state);
+        inner.set_parent_commitment(genesis_parent_commitment(chain_id));
 
-        Ok(Self {
-            inner: LEAF::new(time, justify_qc, genesis_block, state),
-        })
+        Ok(Self { inner })
     }
 
     /// reload previous state based on most recent leaf
@@ -3,11 +3,12 @@
 use crate::{types::HotShotHandle, HotShot, HotShotConsensusApi, ViewRunner};
 use async_compatibility_layer::{
     art::{async_sleep, async_spawn, async_spawn_local, async_timeout},
-    async_primitives::broadcast::channel,
+    async_primitives::{broadcast::channel, subscribable_mutex::SubscribableMutex},
     channel::{unbounded, UnboundedReceiver, UnboundedSender},
 };
 use async_lock::RwLock;
 
+use hotshot_consensus::ConsensusApi;
 use hotshot_types::message::Message;
 use hotshot_types::traits::election::ConsensusExchange;
 use hotshot_types::{
@@ -27,7 +28,7 @@ use std::{
     },
     time::Duration,
 };
-use tracing::{error, info, info_span, trace, Instrument};
+use tracing::{error, info, info_span, trace, warn, Instrument};
 
 #[cfg(feature = "async-std-executor")]
 use async_std::task::{yield_now, JoinHandle};
@@ -36,6 +37,27 @@ use tokio::task::{yield_now, JoinHandle};
 #[cfg(not(any(feature = "async-std-executor", feature = "tokio-executor")))]
 std::compile_error! {"Either feature \"async-std-executor\" or feature \"tokio-executor\" must be enabled for this crate."}
 
+/// Whether the round runner may start new views, and whether it is in one
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RunnerState {
+    /// Set by [`TaskHandle::pause`] to stop starting new views until [`TaskHandle::resume`]
+    paused: bool,
+    /// Set by the round runner while no view is in progress
+    idle: bool,
+    /// Set when the node shuts down, to wake a paused round runner
+    shut_down: bool,
+}
+
+impl Default for RunnerState {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            idle: true,
+            shut_down: false,
+        }
+    }
+}
+
 /// A handle with senders to send events to the background runners.
 #[derive(Default)]
 pub struct TaskHandle<TYPES: NodeType> {
@@ -77,6 +99,47 @@ impl<TYPES: NodeType> TaskHandle<TYPES> {
         }
     }
 
+    /// Pause the round runner after the view currently in progress.
+    ///
+    /// The node stops proposing and voting until [`TaskHandle::resume`] is called. Use
+    /// [`TaskHandle::wait_paused`] to wait for the in-flight view to finish.
+    pub async fn pause(&self) {
+        let handle = self.inner.read().await;
+        if let Some(handle) = handle.as_ref() {
+            handle.runner.modify(|state| state.paused = true).await;
+        }
+    }
+
+    /// Resume a round runner paused with [`TaskHandle::pause`]
+    pub async fn resume(&self) {
+        let handle = self.inner.read().await;
+        if let Some(handle) = handle.as_ref() {
+            handle.runner.modify(|state| state.paused = false).await;
+        }
+    }
+
+    /// Returns `true` if [`TaskHandle::pause`] has been called without a matching
+    /// [`TaskHandle::resume`]
+    pub async fn is_paused(&self) -> bool {
+        let handle = self.inner.read().await;
+        match handle.as_ref() {
+            Some(handle) => handle.runner.cloned().await.paused,
+            None => false,
+        }
+    }
+
+    /// Wait until a paused round runner has finished the view that was in progress
+    pub async fn wait_paused(&self) {
+        let runner = {
+            let handle = self.inner.read().await;
+            let Some(handle) = handle.as_ref() else { return };
+            handle.runner.clone()
+        };
+        runner
+            .wait_until(|state| !state.paused || state.idle)
+            .await;
+    }
+
     /// Wait until all underlying handles are shut down
     ///
     /// # Panics
@@ -84,6 +147,7 @@ impl<TYPES: NodeType> TaskHandle<TYPES> {
     /// If the [`TaskHandle`] has not been properly initialized.
     pub async fn wait_shutdown(&self, send_network_lookup: UnboundedSender<Option<TYPES::Time>>) {
         let inner = self.inner.write().await.take().unwrap();
+        inner.runner.modify(|state| state.shut_down = true).await;
 
         // this shuts down the networking task
         if send_network_lookup.send(None).await.is_err() {
@@ -147,6 +211,9 @@ struct TaskHandleInner {
     /// Global to signify the `HotShot` should be started
     pub(crate) started: Arc<AtomicBool>,
 
+    /// Whether the round runner is paused, and whether it is in a view
+    pub(crate) runner: Arc<SubscribableMutex<RunnerState>>,
+
     /// same as hotshot's view_timeout such that
     /// there is not an accidental race between the two
     shutdown_timeout: Duration,
@@ -163,6 +230,7 @@ where
 {
     let shut_down = Arc::new(AtomicBool::new(false));
     let started = Arc::new(AtomicBool::new(false));
+    let runner = Arc::new(SubscribableMutex::new(RunnerState::default()));
 
     let exchange = hotshot.inner.quorum_exchange.clone();
     let committee_exchange = hotshot.inner.committee_exchange.clone();
@@ -210,7 +278,7 @@ where
             .instrument(info_span!("HotShot network lookup task",)),
     );
 
-    let (handle_channels, task_channels) = match hotshot.inner.config.execution_type {
+    let (handle_channels, task_channels) = match hotshot.inner.config().execution_type {
         ExecutionType::Continuous => (None, None),
         ExecutionType::Incremental => {
             let (send_consensus_start, recv_consensus_start) = unbounded();
@@ -222,6 +290,7 @@ where
         view_runner(
             hotshot.clone(),
             started.clone(),
+            runner.clone(),
             shut_down.clone(),
             task_channels,
         )
@@ -246,9 +315,10 @@ where
         committee_network_broadcast_task_handle,
         committee_network_direct_task_handle,
         consensus_task_handle,
-        shutdown_timeout: Duration::from_millis(hotshot.inner.config.next_view_timeout),
+        shutdown_timeout: Duration::from_millis(hotshot.inner.config().next_view_timeout),
         run_view_channels: handle_channels,
         started,
+        runner,
     });
 
     handle
@@ -258,6 +328,7 @@ where
 pub async fn view_runner<TYPES: NodeType, I: NodeImplementation<TYPES>>(
     hotshot: HotShot<TYPES::ConsensusType, TYPES, I>,
     started: Arc<AtomicBool>,
+    runner: Arc<SubscribableMutex<RunnerState>>,
     shut_down: Arc<AtomicBool>,
     run_once: Option<UnboundedReceiver<()>>,
 ) where
@@ -271,7 +342,26 @@ pub async fn view_runner<TYPES: NodeType, I: NodeImplementation<TYPES>>(
         if let Some(ref recv) = run_once {
             let _ = recv.recv().await;
         }
+        // A view requested while paused is only started once resumed
+        let mut start = false;
+        while !start {
+            runner
+                .wait_until(|state| !state.paused || state.shut_down)
+                .await;
+            let mut stop = false;
+            runner
+                .modify(|state| {
+                    stop = state.shut_down;
+                    start = !state.paused && !stop;
+                    state.idle = !start;
+                })
+                .await;
+            if stop {
+                return;
+            }
+        }
         let _ = HotShot::<TYPES::ConsensusType, TYPES, I>::run_view(hotshot.clone()).await;
+        runner.modify(|state| state.idle = true).await;
     }
 }
 
@@ -302,6 +392,16 @@ pub async fn network_lookup_task<TYPES: NodeType, I: NodeImplementation<TYPES>>(
                 .await;
 
             let view_to_lookup = cur_view + LOOK_AHEAD;
+            let leader = match c_api.inner.quorum_exchange.get_leader(view_to_lookup) {
+                Ok(leader) => leader,
+                Err(e) => {
+                    warn!(?e, "Cannot look up the leader of {:?} yet", view_to_lookup);
+                    continue;
+                }
+            };
+            c_api
+                .send_leader_elected(cur_view, view_to_lookup, leader.clone())
+                .await;
 
             // perform pruning
             // TODO in the future btreemap would be better
@@ -329,9 +429,7 @@ pub async fn network_lookup_task<TYPES: NodeType, I: NodeImplementation<TYPES>>(
                 let networking = networking.clone();
                 async_spawn_local(async move {
                     info!("starting lookup for {:?}", view_to_lookup);
-                    let _result = networking
-                        .lookup_node(c_api.inner.quorum_exchange.get_leader(view_to_lookup))
-                        .await;
+                    let _result = networking.lookup_node(leader).await;
                     info!("finished lookup for {:?}", view_to_lookup);
                 });
             }
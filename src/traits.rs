@@ -2,6 +2,8 @@
 pub mod election;
 mod networking;
 mod node_implementation;
+#[cfg(unix)]
+mod signer;
 mod storage;
 
 pub use hotshot_types::traits::{Block, State};
@@ -20,6 +22,9 @@ pub mod implementations {
         },
         storage::memory_storage::MemoryStorage, // atomic_storage::AtomicStorage,
     };
+
+    #[cfg(unix)]
+    pub use super::signer::{serve_signer, RemoteSigner};
 }
 
 /// Dummy testing implementations
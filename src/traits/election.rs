@@ -1,5 +1,8 @@
 //! elections used for consensus
 
+/// round robin committee election which skips recently failed leaders
+pub mod reputation;
+
 /// static (round robin) committee election
 pub mod static_committee;
 
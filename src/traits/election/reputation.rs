@@ -0,0 +1,438 @@
+use super::{static_committee::StaticVoteToken, vrf::JfPubKey};
+use ark_bls12_381::Parameters as Param381;
+use async_trait::async_trait;
+use hotshot_types::{
+    data::LeafType,
+    traits::{
+        election::{
+            Checked, ElectionConfig, ElectionError, Membership, StakeThresholds, VoteToken,
+        },
+        node_implementation::NodeType,
+        signature_key::{EncodedPublicKey, SignatureKey},
+        signer::{ChainId, Signer},
+    },
+};
+use jf_primitives::signatures::BLSSignatureScheme;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    marker::PhantomData,
+    num::NonZeroU64,
+    sync::{Arc, Mutex},
+};
+
+/// Round robin [`Membership`] which skips leaders that recently failed to get a leaf decided
+///
+/// The outcome of every view up to the last decided one is part of the decided chain: a view
+/// either has a decided leaf, in which case its proposer succeeded, or it lies between a decided
+/// leaf and the parent its `justify_qc` certifies, in which case its leader failed. The leader of
+/// view `v` is the round robin leader, unless that node failed in the window of views
+/// `[v - lag - window, v - lag)` and has not succeeded since; then the view is handed to the next
+/// eligible node. Since the outcomes are read off the chain rather than off the order in which a
+/// node happened to decide, all nodes which have decided up to `v - lag` compute the same leader.
+///
+/// The committee must not be empty, which [`HotShot::new`](crate::HotShot::new) checks.
+#[derive(Clone, Debug)]
+pub struct GeneralReputationCommittee<T, LEAF: LeafType<NodeType = T>, PUBKEY: SignatureKey> {
+    /// The nodes participating
+    nodes: Vec<PUBKEY>,
+    /// Parameters of the schedule
+    config: ReputationElectionConfig,
+    /// Outcomes of recent views, shared between clones of this membership
+    state: Arc<Mutex<ReputationState<PUBKEY>>>,
+    /// Node type phantom
+    _type_phantom: PhantomData<T>,
+    /// Leaf phantom
+    _leaf_phantom: PhantomData<LEAF>,
+}
+
+/// Reputation committee of BLS keys
+pub type ReputationCommittee<T, LEAF> =
+    GeneralReputationCommittee<T, LEAF, JfPubKey<BLSSignatureScheme<Param381>>>;
+
+impl<T, LEAF: LeafType<NodeType = T>, PUBKEY: SignatureKey>
+    GeneralReputationCommittee<T, LEAF, PUBKEY>
+{
+    /// Creates a new reputation committee
+    #[must_use]
+    pub fn new(nodes: Vec<PUBKEY>, config: ReputationElectionConfig) -> Self {
+        Self {
+            nodes,
+            config,
+            state: Arc::default(),
+            _type_phantom: PhantomData,
+            _leaf_phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, LEAF: LeafType<NodeType = T>, PUBKEY: SignatureKey> PartialEq
+    for GeneralReputationCommittee<T, LEAF, PUBKEY>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.nodes == other.nodes && self.config == other.config
+    }
+}
+
+impl<T, LEAF: LeafType<NodeType = T>, PUBKEY: SignatureKey> Eq
+    for GeneralReputationCommittee<T, LEAF, PUBKEY>
+{
+}
+
+/// configuration for the reputation committee
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ReputationElectionConfig {
+    /// Number of views whose outcome is considered when scheduling a leader
+    pub window: u64,
+    /// Number of views between the end of the window and the view being scheduled. Should be
+    /// larger than the number of views it takes to decide a leaf, so that nodes agree on the
+    /// outcomes in the window.
+    pub lag: u64,
+    /// Maximum number of nodes excluded at once. If `None`, at most `(n - 1) / 3` nodes are
+    /// excluded, which is the most that can be faulty.
+    pub max_excluded: Option<usize>,
+    /// Fractions of the nodes needed for quorum and DA certificates
+    #[serde(default)]
+    pub thresholds: StakeThresholds,
+}
+
+impl Default for ReputationElectionConfig {
+    fn default() -> Self {
+        Self {
+            window: 20,
+            lag: 5,
+            max_excluded: None,
+            thresholds: StakeThresholds::default(),
+        }
+    }
+}
+
+impl ElectionConfig for ReputationElectionConfig {
+    fn validate(&self, num_nodes: usize) -> Result<(), String> {
+        if num_nodes == 0 {
+            return Err("the committee must not be empty".to_string());
+        }
+        if self.window == 0 {
+            return Err("the reputation window must be non-zero".to_string());
+        }
+        self.thresholds.validate()
+    }
+}
+
+/// Outcomes of the views up to the last decided one
+#[derive(Debug)]
+struct ReputationState<PUBKEY> {
+    /// The view of the last decided leaf, to ignore replayed decides; `None` before the first one
+    last_decided: Option<u64>,
+    /// The leader of each recent view, and whether it got its leaf decided
+    outcomes: BTreeMap<u64, (PUBKEY, bool)>,
+}
+
+impl<PUBKEY> Default for ReputationState<PUBKEY> {
+    fn default() -> Self {
+        Self {
+            last_decided: None,
+            outcomes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<PUBKEY: SignatureKey> ReputationState<PUBKEY> {
+    /// The leader of `view_number`, given the outcomes known so far
+    fn leader(
+        &self,
+        nodes: &[PUBKEY],
+        config: &ReputationElectionConfig,
+        view_number: u64,
+    ) -> PUBKEY {
+        let index = (view_number % nodes.len() as u64) as usize;
+        let candidate = &nodes[index];
+
+        let end = view_number.saturating_sub(config.lag);
+        let start = end.saturating_sub(config.window);
+        // the latest outcome of each leader in the window
+        let mut latest: BTreeMap<&PUBKEY, (u64, bool)> = BTreeMap::new();
+        for (view, (leader, succeeded)) in self.outcomes.range(start..end) {
+            latest.insert(leader, (*view, *succeeded));
+        }
+        let mut failed: Vec<(u64, &PUBKEY)> = latest
+            .into_iter()
+            .filter(|(_, (_, succeeded))| !succeeded)
+            .map(|(leader, (view, _))| (view, leader))
+            .collect();
+        // most recent failures first, ties broken by key so the order is total
+        failed.sort_by(|a, b| b.cmp(a));
+        let max_excluded = config
+            .max_excluded
+            .unwrap_or((nodes.len() - 1) / 3)
+            .min(nodes.len() - 1);
+        let excluded: BTreeSet<&PUBKEY> = failed
+            .into_iter()
+            .take(max_excluded)
+            .map(|(_, leader)| leader)
+            .collect();
+
+        if !excluded.contains(candidate) {
+            return candidate.clone();
+        }
+        let eligible: Vec<&PUBKEY> = nodes
+            .iter()
+            .filter(|node| !excluded.contains(node))
+            .collect();
+        let index = (view_number % eligible.len() as u64) as usize;
+        eligible[index].clone()
+    }
+
+    /// Record that the leaf of `view_number`, proposed by `proposer` on top of the leaf of
+    /// `parent_view`, was decided
+    ///
+    /// Every view between the parent and this leaf is recorded as failed by its leader.
+    fn record_decide(
+        &mut self,
+        nodes: &[PUBKEY],
+        config: &ReputationElectionConfig,
+        view_number: u64,
+        parent_view: u64,
+        proposer: Option<PUBKEY>,
+    ) {
+        if self
+            .last_decided
+            .map_or(false, |last_decided| view_number <= last_decided)
+        {
+            return;
+        }
+        for view in parent_view + 1..view_number {
+            let leader = self.leader(nodes, config, view);
+            self.outcomes.insert(view, (leader, false));
+        }
+        let proposer = proposer.unwrap_or_else(|| self.leader(nodes, config, view_number));
+        self.outcomes.insert(view_number, (proposer, true));
+        self.last_decided = Some(view_number);
+
+        // only views at least as recent as the window of the last decided view are ever queried
+        let horizon = view_number.saturating_sub(config.lag + config.window);
+        self.outcomes = self.outcomes.split_off(&horizon);
+    }
+}
+
+#[async_trait]
+impl<TYPES, LEAF: LeafType<NodeType = TYPES>, PUBKEY: SignatureKey + 'static> Membership<TYPES>
+    for GeneralReputationCommittee<TYPES, LEAF, PUBKEY>
+where
+    TYPES: NodeType<
+        SignatureKey = PUBKEY,
+        VoteTokenType = StaticVoteToken<PUBKEY>,
+        ElectionConfigType = ReputationElectionConfig,
+    >,
+{
+    /// Just use the vector of public keys for the stake table
+    type StakeTable = Vec<PUBKEY>;
+
+    /// Clone the static table
+    fn get_stake_table(
+        &self,
+        _view_number: TYPES::Time,
+        _state: &TYPES::StateType,
+    ) -> Self::StakeTable {
+        self.nodes.clone()
+    }
+
+    /// Round robin over the nodes, skipping those that recently failed
+    fn get_leader(&self, view_number: TYPES::Time) -> Result<PUBKEY, ElectionError> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .leader(&self.nodes, &self.config, *view_number))
+    }
+
+    /// Computes every leader under a single lock of the reputation state
+    fn leader_schedule(&self, from: TYPES::Time, count: usize) -> Vec<PUBKEY> {
+        let state = self.state.lock().unwrap();
+        (0..count as u64)
+            .map(|offset| state.leader(&self.nodes, &self.config, *from + offset))
+            .collect()
+    }
+
+    /// Simply make the partial signature
+    async fn make_vote_token(
+        &self,
+        view_number: TYPES::Time,
+        chain_id: ChainId,
+        signer: &dyn Signer<PUBKEY>,
+    ) -> std::result::Result<Option<StaticVoteToken<PUBKEY>>, ElectionError> {
+        let stake = NonZeroU64::new(1).unwrap();
+        StaticVoteToken::from_signer(*view_number, chain_id, signer, stake)
+            .await
+            .map(Some)
+            .map_err(|source| ElectionError::Signer { source })
+    }
+
+    fn validate_vote_token(
+        &self,
+        view_number: TYPES::Time,
+        chain_id: ChainId,
+        pub_key: PUBKEY,
+        token: Checked<TYPES::VoteTokenType>,
+    ) -> Result<Checked<TYPES::VoteTokenType>, ElectionError> {
+        match token {
+            // every node has a single vote, signed with its key
+            Checked::Valid(t) | Checked::Unchecked(t)
+                if t.vote_count().get() == 1 && t.is_signed_by(&pub_key, *view_number, chain_id) =>
+            {
+                Ok(Checked::Valid(t))
+            }
+            Checked::Valid(t) | Checked::Unchecked(t) | Checked::Inval(t) => Ok(Checked::Inval(t)),
+        }
+    }
+
+    fn default_election_config(num_nodes: u64) -> TYPES::ElectionConfigType {
+        ReputationElectionConfig {
+            window: 2 * num_nodes,
+            ..ReputationElectionConfig::default()
+        }
+    }
+
+    fn create_election(keys: Vec<PUBKEY>, config: TYPES::ElectionConfigType) -> Self {
+        Self::new(keys, config)
+    }
+
+    fn threshold(&self) -> NonZeroU64 {
+        self.config.thresholds.quorum_threshold(self.nodes.len() as u64)
+    }
+
+    fn da_threshold(&self) -> NonZeroU64 {
+        self.config.thresholds.da_threshold(self.nodes.len() as u64)
+    }
+
+    fn get_committee(
+        &self,
+        _view_number: <TYPES as NodeType>::Time,
+    ) -> std::collections::BTreeSet<<TYPES as NodeType>::SignatureKey> {
+        self.nodes.clone().into_iter().collect()
+    }
+
+    fn record_decided_leaf(
+        &self,
+        view_number: TYPES::Time,
+        parent_view: TYPES::Time,
+        proposer: &EncodedPublicKey,
+    ) {
+        let proposer = PUBKEY::from_bytes(proposer).filter(|key| self.nodes.contains(key));
+        self.state.lock().unwrap().record_decide(
+            &self.nodes,
+            &self.config,
+            *view_number,
+            *parent_view,
+            proposer,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hotshot_types::traits::signature_key::ed25519::Ed25519Pub;
+
+    fn nodes(n: u64) -> Vec<Ed25519Pub> {
+        (0..n)
+            .map(|i| Ed25519Pub::generated_from_seed_indexed([0_u8; 32], i).0)
+            .collect()
+    }
+
+    const CONFIG: ReputationElectionConfig = ReputationElectionConfig {
+        window: 8,
+        lag: 2,
+        max_excluded: None,
+        thresholds: StakeThresholds {
+            quorum: (2, 3),
+            da: (2, 3),
+        },
+    };
+
+    #[test]
+    fn round_robin_without_failures() {
+        let nodes = nodes(4);
+        let mut state = ReputationState::default();
+        for view in 1..20 {
+            assert_eq!(state.leader(&nodes, &CONFIG, view), nodes[view as usize % 4]);
+            let proposer = state.leader(&nodes, &CONFIG, view);
+            state.record_decide(&nodes, &CONFIG, view, view - 1, Some(proposer));
+        }
+    }
+
+    #[test]
+    fn failed_leader_is_skipped_until_window_passes() {
+        let nodes = nodes(4);
+        let mut state = ReputationState::default();
+        state.record_decide(&nodes, &CONFIG, 1, 0, Some(nodes[1].clone()));
+        // view 2 fails, its leader is nodes[2]
+        state.record_decide(&nodes, &CONFIG, 3, 1, Some(nodes[3].clone()));
+
+        // within the window, the slots of nodes[2] go to someone else
+        for view in 5..=11 {
+            assert_ne!(state.leader(&nodes, &CONFIG, view), nodes[2]);
+        }
+        assert_eq!(state.leader(&nodes, &CONFIG, 4), nodes[0]);
+        // once the failure leaves the window, nodes[2] is scheduled again
+        assert_eq!(state.leader(&nodes, &CONFIG, 14), nodes[2]);
+    }
+
+    #[test]
+    fn schedule_is_deterministic() {
+        let nodes = nodes(7);
+        let mut a = ReputationState::default();
+        let mut b = ReputationState::default();
+        let chain = [0, 1, 2, 5, 6, 9, 12, 13];
+        for pair in chain.windows(2) {
+            let proposer = a.leader(&nodes, &CONFIG, pair[1]);
+            a.record_decide(&nodes, &CONFIG, pair[1], pair[0], Some(proposer));
+        }
+        for pair in chain.windows(2) {
+            let proposer = b.leader(&nodes, &CONFIG, pair[1]);
+            b.record_decide(&nodes, &CONFIG, pair[1], pair[0], Some(proposer));
+            // `b` also sees a replay of an old decide, which changes nothing
+            b.record_decide(&nodes, &CONFIG, 1, 0, None);
+        }
+        for view in 14..40 {
+            assert_eq!(
+                a.leader(&nodes, &CONFIG, view),
+                b.leader(&nodes, &CONFIG, view)
+            );
+        }
+    }
+
+    #[test]
+    fn excludes_at_most_a_third() {
+        let nodes = nodes(4);
+        let mut state = ReputationState::default();
+        state.record_decide(&nodes, &CONFIG, 1, 0, None);
+        // views 2 and 3 fail
+        state.record_decide(&nodes, &CONFIG, 4, 1, None);
+        let leaders: BTreeSet<_> = (7..11)
+            .map(|view| state.leader(&nodes, &CONFIG, view))
+            .collect();
+        // only the most recent failure, nodes[3], is excluded
+        assert!(!leaders.contains(&nodes[3]));
+        assert!(leaders.contains(&nodes[2]));
+    }
+
+    #[test]
+    fn failures_are_read_off_the_chain() {
+        let nodes = nodes(4);
+        let mut a = ReputationState::default();
+        a.record_decide(&nodes, &CONFIG, 1, 0, None);
+        a.record_decide(&nodes, &CONFIG, 4, 1, None);
+        // `b` only starts following the chain at the leaf of view 4, but still learns from its
+        // `justify_qc` that views 2 and 3 failed
+        let mut b = ReputationState::default();
+        b.record_decide(&nodes, &CONFIG, 4, 1, None);
+        for view in 5..20 {
+            assert_eq!(
+                a.leader(&nodes, &CONFIG, view),
+                b.leader(&nodes, &CONFIG, view)
+            );
+        }
+        assert_ne!(b.leader(&nodes, &CONFIG, 7), nodes[3]);
+    }
+}
@@ -1,27 +1,50 @@
 use super::vrf::JfPubKey;
 use ark_bls12_381::Parameters as Param381;
+use async_trait::async_trait;
 use commit::{Commitment, Committable, RawCommitmentBuilder};
 use espresso_systems_common::hotshot::tag;
 use hotshot_types::{
     data::LeafType,
+    key_rotation::KeyRotation,
     traits::{
-        election::{Checked, ElectionConfig, ElectionError, Membership, VoteToken},
+        election::{
+            Checked, ElectionConfig, ElectionError, Membership, StakeThresholds, VoteToken,
+        },
         node_implementation::NodeType,
         signature_key::{EncodedSignature, SignatureKey},
+        signer::{signing_payload, ChainId, Signer, SignerError, SigningKind, SigningRequest},
     },
 };
 use jf_primitives::signatures::BLSSignatureScheme;
 #[allow(deprecated)]
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 use std::marker::PhantomData;
 use std::num::NonZeroU64;
+use std::sync::{Arc, Mutex};
 
-/// Dummy implementation of [`Membership`]
-
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// Static implementation of [`Membership`], where each node has a fixed amount of stake
+///
+/// Leaders are scheduled in proportion to their stake. With equal stake, this is round robin.
+///
+/// The stake of each node sits in a slot, whose key can be replaced through a [`KeyRotation`]
+/// without changing its stake or its place in the leader schedule.
+#[derive(Clone, Debug)]
 pub struct GeneralStaticCommittee<T, LEAF: LeafType<NodeType = T>, PUBKEY: SignatureKey> {
     /// The nodes participating
     nodes: Vec<PUBKEY>,
+    /// The stake of each node, in the order of `nodes`
+    stake: Vec<NonZeroU64>,
+    /// Running totals of `stake`, so the last entry is the total stake
+    cumulative_stake: Vec<u64>,
+    /// Step through the units of stake between consecutive views, coprime with the total stake
+    /// so that every unit is visited once every `total stake` views
+    stride: u64,
+    /// Fractions of the stake needed for certificates
+    thresholds: StakeThresholds,
+    /// The keys that took over each slot, by the view they took over in, shared between clones of
+    /// this membership
+    rotations: Arc<Mutex<Vec<BTreeMap<u64, PUBKEY>>>>,
     /// Node type phantom
     _type_phantom: PhantomData<T>,
     /// Leaf phantom
@@ -35,15 +58,125 @@ pub type StaticCommittee<T, LEAF> =
 impl<T, LEAF: LeafType<NodeType = T>, PUBKEY: SignatureKey>
     GeneralStaticCommittee<T, LEAF, PUBKEY>
 {
-    /// Creates a new dummy elector
+    /// Creates a new elector where every node has one unit of stake
     #[must_use]
     pub fn new(nodes: Vec<PUBKEY>) -> Self {
+        let stake = vec![NonZeroU64::new(1).unwrap(); nodes.len()];
+        Self::with_stake(nodes, stake)
+    }
+
+    /// Creates a new elector where node `nodes[i]` has `stake[i]` units of stake
+    ///
+    /// [`ElectionConfig::validate`] checks that a [`StaticElectionConfig`] gives the stake of
+    /// every node and that the total stake fits in a `u64`. Unchecked, the total saturates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nodes` and `stake` differ in length
+    #[must_use]
+    pub fn with_stake(nodes: Vec<PUBKEY>, stake: Vec<NonZeroU64>) -> Self {
+        assert_eq!(
+            nodes.len(),
+            stake.len(),
+            "every node of a static committee needs a stake"
+        );
+        let cumulative_stake: Vec<u64> = stake
+            .iter()
+            .scan(0_u64, |total, stake| {
+                *total = total.saturating_add(stake.get());
+                Some(*total)
+            })
+            .collect();
+        let total_stake = cumulative_stake.last().copied().unwrap_or(0);
+        let stride = if stake.windows(2).all(|pair| pair[0] == pair[1]) {
+            1
+        } else {
+            // spread the units of each node across the schedule by stepping about the golden
+            // ratio of the total stake
+            #[allow(clippy::cast_possible_truncation)]
+            let mut stride = (((u128::from(total_stake) * 0x9E37_79B9) >> 32) as u64).max(1);
+            while gcd(stride, total_stake) != 1 {
+                stride += 1;
+            }
+            stride
+        };
         Self {
+            rotations: Arc::new(Mutex::new(vec![BTreeMap::new(); nodes.len()])),
             nodes,
+            stake,
+            cumulative_stake,
+            stride,
+            thresholds: StakeThresholds::default(),
             _type_phantom: PhantomData,
             _leaf_phantom: PhantomData,
         }
     }
+
+    /// The stake of `key` in `view_number`, or `None` if it does not hold a slot in that view
+    #[must_use]
+    pub fn get_stake(&self, view_number: u64, key: &PUBKEY) -> Option<NonZeroU64> {
+        self.keys_at(view_number)
+            .iter()
+            .position(|node| node == key)
+            .map(|index| self.stake[index])
+    }
+
+    /// The key holding each slot in `view_number`, in the order of the slots
+    #[must_use]
+    pub fn keys_at(&self, view_number: u64) -> Vec<PUBKEY> {
+        let rotations = self.rotations.lock().unwrap();
+        (0..self.nodes.len())
+            .map(|slot| slot_key(&self.nodes, &rotations, slot, view_number).clone())
+            .collect()
+    }
+
+    /// Use `thresholds` instead of the default two thirds of the stake
+    #[must_use]
+    pub fn with_thresholds(mut self, thresholds: StakeThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// The total stake of the committee
+    #[must_use]
+    pub fn total_stake(&self) -> u64 {
+        self.cumulative_stake.last().copied().unwrap_or(0)
+    }
+}
+
+impl<T, LEAF: LeafType<NodeType = T>, PUBKEY: SignatureKey> PartialEq
+    for GeneralStaticCommittee<T, LEAF, PUBKEY>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.nodes == other.nodes && self.stake == other.stake && self.thresholds == other.thresholds
+    }
+}
+
+impl<T, LEAF: LeafType<NodeType = T>, PUBKEY: SignatureKey> Eq
+    for GeneralStaticCommittee<T, LEAF, PUBKEY>
+{
+}
+
+/// The key holding `slot` in `view_number`: the key of the last rotation of the slot effective
+/// by then, or the original key
+fn slot_key<'a, PUBKEY>(
+    nodes: &'a [PUBKEY],
+    rotations: &'a [BTreeMap<u64, PUBKEY>],
+    slot: usize,
+    view_number: u64,
+) -> &'a PUBKEY {
+    rotations[slot]
+        .range(..=view_number)
+        .next_back()
+        .map_or(&nodes[slot], |(_, key)| key)
+}
+
+/// Greatest common divisor of `a` and `b`
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
@@ -54,11 +187,66 @@ pub struct StaticVoteToken<K: SignatureKey> {
     signature: EncodedSignature,
     /// public key
     pub_key: K,
+    /// stake of `pub_key`
+    stake: NonZeroU64,
+}
+
+impl<PUBKEY: SignatureKey> StaticVoteToken<PUBKEY> {
+    /// Sign `view_number` on `chain_id` with `private_key` to make a vote token worth `stake`
+    /// votes
+    #[must_use]
+    pub fn new(
+        view_number: u64,
+        chain_id: ChainId,
+        private_key: &PUBKEY::PrivateKey,
+        stake: NonZeroU64,
+    ) -> Self {
+        let payload = signing_payload(SigningKind::VoteToken, chain_id, view_number, &[]);
+        let signature = PUBKEY::sign(private_key, &payload);
+        Self {
+            signature,
+            pub_key: PUBKEY::from_private(private_key),
+            stake,
+        }
+    }
+
+    /// Have `signer` sign `view_number` on `chain_id` to make a vote token worth `stake` votes
+    ///
+    /// # Errors
+    /// If `signer` refuses to sign the token
+    pub async fn from_signer(
+        view_number: u64,
+        chain_id: ChainId,
+        signer: &dyn Signer<PUBKEY>,
+        stake: NonZeroU64,
+    ) -> Result<Self, SignerError> {
+        // the view is part of every payload, so there is nothing else to sign
+        let signature = signer
+            .sign(SigningRequest {
+                view_number,
+                kind: SigningKind::VoteToken,
+                chain_id,
+                data: Vec::new(),
+            })
+            .await?;
+        Ok(Self {
+            signature,
+            pub_key: signer.public_key().clone(),
+            stake,
+        })
+    }
+
+    /// Whether the token is the one `pub_key` signed for `view_number` on `chain_id`
+    #[must_use]
+    pub fn is_signed_by(&self, pub_key: &PUBKEY, view_number: u64, chain_id: ChainId) -> bool {
+        let payload = signing_payload(SigningKind::VoteToken, chain_id, view_number, &[]);
+        self.pub_key == *pub_key && pub_key.validate(&self.signature, &payload)
+    }
 }
 
 impl<PUBKEY: SignatureKey> VoteToken for StaticVoteToken<PUBKEY> {
     fn vote_count(&self) -> NonZeroU64 {
-        NonZeroU64::new(1).unwrap()
+        self.stake
     }
 }
 
@@ -67,6 +255,7 @@ impl<PUBKEY: SignatureKey> Committable for StaticVoteToken<PUBKEY> {
         RawCommitmentBuilder::new("StaticVoteToken")
             .var_size_field("signature", &self.signature.0)
             .var_size_field("pub_key", &self.pub_key.to_bytes().0)
+            .u64_field("stake", self.stake.get())
             .finalize()
     }
 
@@ -75,12 +264,42 @@ impl<PUBKEY: SignatureKey> Committable for StaticVoteToken<PUBKEY> {
     }
 }
 
-/// configuration for static committee. stub for now
+/// configuration for static committee
 #[derive(Default, Clone, Serialize, Deserialize, core::fmt::Debug)]
-pub struct StaticElectionConfig {}
+pub struct StaticElectionConfig {
+    /// The stake of each node, in the order of the known nodes. If empty, every node has one
+    /// unit of stake.
+    #[serde(default)]
+    pub stake: Vec<NonZeroU64>,
+    /// Fractions of the stake needed for quorum and DA certificates
+    #[serde(default)]
+    pub thresholds: StakeThresholds,
+}
 
-impl ElectionConfig for StaticElectionConfig {}
+impl ElectionConfig for StaticElectionConfig {
+    fn validate(&self, num_nodes: usize) -> Result<(), String> {
+        if num_nodes == 0 {
+            return Err("the committee must not be empty".to_string());
+        }
+        if !self.stake.is_empty() && self.stake.len() != num_nodes {
+            return Err(format!(
+                "the stake of {} nodes is given for a committee of {num_nodes}",
+                self.stake.len()
+            ));
+        }
+        if self
+            .stake
+            .iter()
+            .try_fold(0_u64, |total, stake| total.checked_add(stake.get()))
+            .is_none()
+        {
+            return Err("the total stake overflows".to_string());
+        }
+        self.thresholds.validate()
+    }
+}
 
+#[async_trait]
 impl<TYPES, LEAF: LeafType<NodeType = TYPES>, PUBKEY: SignatureKey + 'static> Membership<TYPES>
     for GeneralStaticCommittee<TYPES, LEAF, PUBKEY>
 where
@@ -96,64 +315,122 @@ where
     /// Clone the static table
     fn get_stake_table(
         &self,
-        _view_number: TYPES::Time,
+        view_number: TYPES::Time,
         _state: &TYPES::StateType,
     ) -> Self::StakeTable {
-        self.nodes.clone()
+        self.keys_at(*view_number)
     }
-    /// Index the vector of public keys with the current view number
-    fn get_leader(&self, view_number: TYPES::Time) -> PUBKEY {
-        let index = (*view_number % self.nodes.len() as u64) as usize;
-        self.nodes[index].clone()
+    /// Step through the units of stake with the view number, and pick the node owning the unit
+    fn get_leader(&self, view_number: TYPES::Time) -> Result<PUBKEY, ElectionError> {
+        let total_stake = u128::from(self.total_stake());
+        #[allow(clippy::cast_possible_truncation)]
+        let unit = ((u128::from(*view_number) % total_stake) * u128::from(self.stride)
+            % total_stake) as u64;
+        let index = self.cumulative_stake.partition_point(|total| *total <= unit);
+        let rotations = self.rotations.lock().unwrap();
+        Ok(slot_key(&self.nodes, &rotations, index, *view_number).clone())
     }
 
-    /// Simply make the partial signature
-    fn make_vote_token(
+    /// Make the partial signature, worth the stake of this node
+    async fn make_vote_token(
         &self,
         view_number: TYPES::Time,
-        private_key: &<PUBKEY as SignatureKey>::PrivateKey,
+        chain_id: ChainId,
+        signer: &dyn Signer<PUBKEY>,
     ) -> std::result::Result<Option<StaticVoteToken<PUBKEY>>, ElectionError> {
-        let mut message: Vec<u8> = vec![];
-        message.extend(view_number.to_le_bytes());
-        let signature = PUBKEY::sign(private_key, &message);
-        Ok(Some(StaticVoteToken {
-            signature,
-            pub_key: PUBKEY::from_private(private_key),
-        }))
+        let Some(stake) = self.get_stake(*view_number, signer.public_key()) else {
+            return Ok(None);
+        };
+        StaticVoteToken::from_signer(*view_number, chain_id, signer, stake)
+            .await
+            .map(Some)
+            .map_err(|source| ElectionError::Signer { source })
     }
 
+    /// Check that the token is signed by its key for the view and chain, and claims the stake of
+    /// its signer
     fn validate_vote_token(
         &self,
-        _view_number: TYPES::Time,
-        _pub_key: PUBKEY,
+        view_number: TYPES::Time,
+        chain_id: ChainId,
+        pub_key: PUBKEY,
         token: Checked<TYPES::VoteTokenType>,
     ) -> Result<Checked<TYPES::VoteTokenType>, ElectionError> {
         match token {
-            Checked::Valid(t) | Checked::Unchecked(t) => Ok(Checked::Valid(t)),
+            Checked::Valid(t) | Checked::Unchecked(t) => {
+                let stake = self.get_stake(*view_number, &pub_key);
+                if stake == Some(t.stake) && t.is_signed_by(&pub_key, *view_number, chain_id) {
+                    Ok(Checked::Valid(t))
+                } else {
+                    Ok(Checked::Inval(t))
+                }
+            }
             Checked::Inval(t) => Ok(Checked::Inval(t)),
         }
     }
 
     fn default_election_config(_num_nodes: u64) -> TYPES::ElectionConfigType {
-        StaticElectionConfig {}
+        StaticElectionConfig::default()
     }
 
-    fn create_election(keys: Vec<PUBKEY>, _config: TYPES::ElectionConfigType) -> Self {
-        Self {
-            nodes: keys,
-            _type_phantom: PhantomData,
-            _leaf_phantom: PhantomData,
-        }
+    fn create_election(keys: Vec<PUBKEY>, config: TYPES::ElectionConfigType) -> Self {
+        let committee = if config.stake.is_empty() {
+            Self::new(keys)
+        } else {
+            Self::with_stake(keys, config.stake)
+        };
+        committee.with_thresholds(config.thresholds)
     }
 
+    /// The configured fraction of the total stake, two thirds by default
     fn threshold(&self) -> NonZeroU64 {
-        NonZeroU64::new(((self.nodes.len() as u64 * 2) / 3) + 1).unwrap()
+        self.thresholds.quorum_threshold(self.total_stake())
+    }
+
+    fn da_threshold(&self) -> NonZeroU64 {
+        self.thresholds.da_threshold(self.total_stake())
     }
 
     fn get_committee(
         &self,
-        _view_number: <TYPES as NodeType>::Time,
-    ) -> std::collections::BTreeSet<<TYPES as NodeType>::SignatureKey> {
-        self.nodes.clone().into_iter().collect()
+        view_number: <TYPES as NodeType>::Time,
+    ) -> BTreeSet<<TYPES as NodeType>::SignatureKey> {
+        self.keys_at(*view_number).into_iter().collect()
+    }
+
+    /// Record the new key of the slot, unless it was already recorded
+    fn rotate_key(&self, rotation: &KeyRotation<PUBKEY>) -> Result<(), ElectionError> {
+        let invalid = |reason: &str| ElectionError::InvalidKeyRotation {
+            reason: reason.to_string(),
+        };
+        if !rotation.is_valid() {
+            return Err(invalid("not signed by both keys"));
+        }
+        let view_number = rotation.effective_view;
+        let mut rotations = self.rotations.lock().unwrap();
+
+        // the same rotation may arrive more than once, e.g. from the config and the network
+        let recorded = (0..self.nodes.len()).any(|slot| {
+            let previous = rotations[slot].range(..view_number).next_back();
+            rotations[slot].get(&view_number) == Some(&rotation.new_key)
+                && previous.map_or(&self.nodes[slot], |(_, key)| key) == &rotation.old_key
+        });
+        if recorded {
+            return Ok(());
+        }
+
+        let holder = |slot| slot_key(&self.nodes, &rotations, slot, view_number);
+        let slot = (0..self.nodes.len())
+            .find(|slot| holder(*slot) == &rotation.old_key)
+            .ok_or_else(|| invalid("the old key does not hold a slot in the effective view"))?;
+        let mut known = self.nodes.iter().chain(rotations.iter().flat_map(BTreeMap::values));
+        if known.any(|key| key == &rotation.new_key) {
+            return Err(invalid("the new key holds or held a slot already"));
+        }
+        if rotations[slot].range(view_number..).next().is_some() {
+            return Err(invalid("the slot is rotated again in or after the effective view"));
+        }
+        rotations[slot].insert(view_number, rotation.new_key.clone());
+        Ok(())
     }
 }
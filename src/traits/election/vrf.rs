@@ -1,18 +1,27 @@
-use ark_bls12_381::Parameters as Param381;
+use ark_bls12_381::{Fr, G1Projective, G2Projective, Parameters as Param381};
 use ark_ec::bls12::Bls12Parameters;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use async_trait::async_trait;
 use bincode::Options;
 use blake3::Hasher;
 use commit::{Commitment, Committable, RawCommitmentBuilder};
 use derivative::Derivative;
 use espresso_systems_common::hotshot::tag;
 use hotshot_types::{
+    certificate::QuorumCertificate,
     data::LeafType,
     traits::{
         election::{
-            Checked, ElectionConfig, ElectionError, Membership, TestableElection, VoteToken,
+            Checked, ElectionConfig, ElectionError, Membership, StakeThresholds,
+            TestableElection, VoteToken,
         },
         node_implementation::NodeType,
-        signature_key::{EncodedPublicKey, EncodedSignature, SignatureKey, TestableSignatureKey},
+        randomness_beacon::{BeaconConfig, QcRandomnessBeacon, RandomnessBeacon},
+        signature_key::{
+            keystore::KeystoreKey, possession_payload, EncodedPublicKey, EncodedSignature,
+            SignatureKey, TestableSignatureKey,
+        },
+        signer::{ChainId, Signer, SignerError},
     },
 };
 use hotshot_utils::bincode::bincode_opts;
@@ -20,17 +29,15 @@ use jf_primitives::{
     hash_to_group::SWHashToGroup,
     signatures::{
         bls::{BLSSignature, BLSVerKey},
-        BLSSignatureScheme, SignatureScheme,
+        AggregateableSignatureSchemes, BLSSignatureScheme, SignatureScheme,
     },
     vrf::{blsvrf::BLSVRFScheme, Vrf},
 };
-#[allow(deprecated)]
-use num::{rational::Ratio, BigUint, ToPrimitive};
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
 use rand_chacha::ChaChaRng;
 use serde::{de, Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::BTreeMap,
     fmt::Debug,
     hash::Hash,
     marker::PhantomData,
@@ -38,7 +45,10 @@ use std::{
     ops::Deref,
     sync::{Arc, Mutex},
 };
-use tracing::{error, info, instrument};
+use sortition::{SortitionCache, SortitionParams};
+
+/// Sortition math: how much of a replica's stake a VRF output selects
+pub mod sortition;
 
 // TODO wrong palce for this
 /// the sortition committee size parameter
@@ -70,6 +80,60 @@ impl<VRF, VRFHASHER, VRFPARAMS> Clone for VRFStakeTable<VRF, VRFHASHER, VRFPARAM
 /// concrete type for bls public key
 pub type BlsPubKey = JfPubKey<BLSSignatureScheme<Param381>>;
 
+/// Signature schemes usable for a [`JfPubKey`], which can check a batch of signatures at once
+pub trait BatchSignatureScheme:
+    AggregateableSignatureSchemes<PublicParameter = (), MessageUnit = u8>
+{
+    /// Whether every signature of `batch` is valid for its key and data
+    ///
+    /// Must accept exactly the batches whose every signature passes [`SignatureScheme::verify`],
+    /// except with negligible probability.
+    fn batch_verify(batch: &[(&Self::VerificationKey, &Self::Signature, &[u8])]) -> bool;
+}
+
+impl BatchSignatureScheme for BLSSignatureScheme<Param381> {
+    /// Signatures on the same data are checked together: each signature and its key are scaled by
+    /// a fresh random 128 bit coefficient, and the sum of the scaled signatures is checked against
+    /// the sum of the scaled keys, with one pairing check per distinct data. Invalid signatures
+    /// only cancel out in the sum if they were crafted knowing the coefficients, which they can
+    /// guess with probability 2^-128.
+    fn batch_verify(batch: &[(&Self::VerificationKey, &Self::Signature, &[u8])]) -> bool {
+        let mut rng = rand::thread_rng();
+        let mut by_data: BTreeMap<&[u8], (Vec<G2Projective>, Vec<G1Projective>)> =
+            BTreeMap::new();
+        for (key, signature, data) in batch {
+            let (Some(mut key), Some(mut signature)) = (
+                reencode::<_, G2Projective>(*key),
+                reencode::<_, G1Projective>(*signature),
+            ) else {
+                return false;
+            };
+            let coefficient = Fr::from(rng.gen::<u128>());
+            key *= coefficient;
+            signature *= coefficient;
+            let (keys, signatures) = by_data.entry(*data).or_default();
+            keys.push(key);
+            signatures.push(signature);
+        }
+        by_data.into_iter().all(|(data, (keys, signatures))| {
+            let key = reencode(&keys.into_iter().sum::<G2Projective>());
+            let signature = reencode(&signatures.into_iter().sum::<G1Projective>());
+            match (key, signature) {
+                (Some(key), Some(signature)) => Self::verify(&(), &key, data, &signature).is_ok(),
+                _ => false,
+            }
+        })
+    }
+}
+
+/// Reinterpret `value` as a `T` with the same canonical serialization, such as a BLS signature as
+/// the curve point it consists of
+fn reencode<S: CanonicalSerialize, T: CanonicalDeserialize>(value: &S) -> Option<T> {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes).ok()?;
+    T::deserialize(&bytes[..]).ok()
+}
+
 /// type wrapper for VRF's public key
 #[derive(Deserialize, Serialize)]
 pub struct JfPubKey<SIGSCHEME>
@@ -85,7 +149,7 @@ where
 
 impl<SIGSCHEME> TestableSignatureKey for JfPubKey<SIGSCHEME>
 where
-    SIGSCHEME: SignatureScheme<PublicParameter = (), MessageUnit = u8> + Sync + Send,
+    SIGSCHEME: BatchSignatureScheme + Sync + Send,
     SIGSCHEME::VerificationKey: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
     SIGSCHEME::SigningKey: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
     SIGSCHEME::Signature: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
@@ -126,7 +190,7 @@ where
 
 impl<SIGSCHEME> Debug for JfPubKey<SIGSCHEME>
 where
-    SIGSCHEME: SignatureScheme<PublicParameter = (), MessageUnit = u8>,
+    SIGSCHEME: AggregateableSignatureSchemes<PublicParameter = (), MessageUnit = u8>,
     SIGSCHEME::VerificationKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::SigningKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::Signature: Clone + for<'a> Deserialize<'a> + Serialize,
@@ -137,7 +201,7 @@ where
 }
 impl<SIGSCHEME> PartialEq for JfPubKey<SIGSCHEME>
 where
-    SIGSCHEME: SignatureScheme<PublicParameter = (), MessageUnit = u8>,
+    SIGSCHEME: AggregateableSignatureSchemes<PublicParameter = (), MessageUnit = u8>,
     SIGSCHEME::VerificationKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::SigningKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::Signature: Clone + for<'a> Deserialize<'a> + Serialize,
@@ -148,7 +212,7 @@ where
 }
 impl<SIGSCHEME> Eq for JfPubKey<SIGSCHEME>
 where
-    SIGSCHEME: SignatureScheme<PublicParameter = (), MessageUnit = u8>,
+    SIGSCHEME: AggregateableSignatureSchemes<PublicParameter = (), MessageUnit = u8>,
     SIGSCHEME::VerificationKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::SigningKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::Signature: Clone + for<'a> Deserialize<'a> + Serialize,
@@ -156,7 +220,7 @@ where
 }
 impl<SIGSCHEME> Hash for JfPubKey<SIGSCHEME>
 where
-    SIGSCHEME: SignatureScheme<PublicParameter = (), MessageUnit = u8>,
+    SIGSCHEME: AggregateableSignatureSchemes<PublicParameter = (), MessageUnit = u8>,
     SIGSCHEME::VerificationKey: Clone + de::DeserializeOwned + Serialize + Send + Sync,
     SIGSCHEME::SigningKey: Clone + de::DeserializeOwned + Serialize + Send + Sync,
     SIGSCHEME::Signature: Clone + for<'a> Deserialize<'a> + Serialize,
@@ -168,7 +232,7 @@ where
 
 impl<SIGSCHEME> PartialOrd for JfPubKey<SIGSCHEME>
 where
-    SIGSCHEME: SignatureScheme<PublicParameter = (), MessageUnit = u8>,
+    SIGSCHEME: AggregateableSignatureSchemes<PublicParameter = (), MessageUnit = u8>,
     SIGSCHEME::VerificationKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::SigningKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::Signature: Clone + for<'a> Deserialize<'a> + Serialize,
@@ -180,7 +244,7 @@ where
 
 impl<SIGSCHEME> Ord for JfPubKey<SIGSCHEME>
 where
-    SIGSCHEME: SignatureScheme<PublicParameter = (), MessageUnit = u8>,
+    SIGSCHEME: AggregateableSignatureSchemes<PublicParameter = (), MessageUnit = u8>,
     SIGSCHEME::VerificationKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::SigningKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::Signature: Clone + for<'a> Deserialize<'a> + Serialize,
@@ -193,7 +257,7 @@ where
 
 impl<SIGSCHEME> SignatureKey for JfPubKey<SIGSCHEME>
 where
-    SIGSCHEME: SignatureScheme<PublicParameter = (), MessageUnit = u8>,
+    SIGSCHEME: BatchSignatureScheme,
     SIGSCHEME::VerificationKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::SigningKey: Clone + for<'a> Deserialize<'a> + Serialize + Send + Sync,
     SIGSCHEME::Signature: Clone + for<'a> Deserialize<'a> + Serialize,
@@ -244,6 +308,57 @@ where
         })
     }
 
+    fn batch_validate(batch: &[(Self, EncodedSignature, &[u8])]) -> bool {
+        let Some(signatures) = batch
+            .iter()
+            .map(|(_, signature, _)| bincode_opts().deserialize(&signature.0).ok())
+            .collect::<Option<Vec<SIGSCHEME::Signature>>>()
+        else {
+            return false;
+        };
+        let batch: Vec<_> = batch
+            .iter()
+            .zip(&signatures)
+            .map(|((key, _, data), signature)| (&key.pk, signature, *data))
+            .collect();
+        SIGSCHEME::batch_verify(&batch)
+    }
+
+    fn aggregate(keys: &[Self], signatures: &[EncodedSignature]) -> Option<EncodedSignature> {
+        if keys.is_empty() || keys.len() != signatures.len() {
+            return None;
+        }
+        let verification_keys: Vec<_> = keys.iter().map(|key| key.pk.clone()).collect();
+        let signatures = signatures
+            .iter()
+            .map(|signature| bincode_opts().deserialize(&signature.0).ok())
+            .collect::<Option<Vec<SIGSCHEME::Signature>>>()?;
+        let aggregate = SIGSCHEME::aggregate(&(), &verification_keys, &signatures).ok()?;
+        bincode_opts().serialize(&aggregate).ok().map(EncodedSignature)
+    }
+
+    fn validate_aggregate(keys: &[Self], signature: &EncodedSignature, data: &[u8]) -> bool {
+        if keys.is_empty() {
+            return false;
+        }
+        let Ok(signature) = bincode_opts().deserialize::<SIGSCHEME::Signature>(&signature.0) else {
+            return false;
+        };
+        let verification_keys: Vec<_> = keys.iter().map(|key| key.pk.clone()).collect();
+        // every key signed the same data, so this is a single pairing check against the sum of
+        // the keys
+        SIGSCHEME::multi_sig_verify(&(), &verification_keys, data, &signature).is_ok()
+    }
+
+    fn prove_possession(private_key: &Self::PrivateKey) -> Option<EncodedSignature> {
+        let key = Self::from_private(private_key).to_bytes();
+        Some(Self::sign(private_key, &possession_payload(&key)))
+    }
+
+    fn verify_possession(&self, proof: &EncodedSignature) -> bool {
+        self.validate(proof, &possession_payload(&self.to_bytes()))
+    }
+
     fn generated_from_seed_indexed(_seed: [u8; 32], index: u64) -> (Self, Self::PrivateKey) {
         let mut hasher = blake3::Hasher::new();
         hasher.update(&index.to_le_bytes());
@@ -261,12 +376,61 @@ where
     }
 }
 
+impl KeystoreKey for BlsPubKey {
+    const KEY_TYPE: &'static str = "bls12-381";
+
+    fn generate_private_key() -> Self::PrivateKey {
+        BLSSignatureScheme::<Param381>::key_gen(&(), &mut rand::rngs::OsRng)
+            .expect("BLS key generation should not be able to fail")
+    }
+
+    fn private_key_to_bytes(private_key: &Self::PrivateKey) -> Vec<u8> {
+        bincode_opts()
+            .serialize(private_key)
+            .expect("Serialization should not be able to fail")
+    }
+
+    fn private_key_from_bytes(bytes: &[u8]) -> Option<Self::PrivateKey> {
+        bincode_opts().deserialize(bytes).ok()
+    }
+}
+
 impl<VRF, VRFHASHER, VRFPARAMS> VRFStakeTable<VRF, VRFHASHER, VRFPARAMS> {
     /// get total stake
     #[must_use]
     pub fn get_all_stake(&self) -> NonZeroU64 {
         self.total_stake
     }
+
+    /// Select a key with probability proportional to its stake, using `seed` as the source of
+    /// randomness
+    ///
+    /// The seed picks a unit of stake, and the key owning that unit is selected. Keys are walked in
+    /// the order of the stake table, so every replica selects the same key for the same seed.
+    ///
+    /// # Panics
+    /// If the stake table is empty
+    #[must_use]
+    pub fn select_by_stake(&self, seed: &[u8; 32]) -> &EncodedPublicKey {
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&seed[..16]);
+        // reducing 128 random bits modulo a 64 bit total keeps the bias below 2^-64
+        #[allow(clippy::cast_possible_truncation)]
+        let mut unit = (u128::from_le_bytes(bytes) % u128::from(self.total_stake.get())) as u64;
+        self.mapping
+            .iter()
+            .find(|(_, stake)| {
+                if unit < stake.get() {
+                    true
+                } else {
+                    unit -= stake.get();
+                    false
+                }
+            })
+            .or_else(|| self.mapping.iter().next_back())
+            .map(|(key, _)| key)
+            .unwrap()
+    }
 }
 
 impl<VRF, VRFHASHER, VRFPARAMS> VRFStakeTable<VRF, VRFHASHER, VRFPARAMS>
@@ -281,11 +445,7 @@ where
     /// If converting non-zero stake into `NonZeroU64` fails
     pub fn get_stake<SIGSCHEME>(&self, pk: &JfPubKey<SIGSCHEME>) -> Option<NonZeroU64>
     where
-        SIGSCHEME: SignatureScheme<
-            VerificationKey = VRF::PublicKey,
-            PublicParameter = (),
-            MessageUnit = u8,
-        >,
+        SIGSCHEME: BatchSignatureScheme<VerificationKey = VRF::PublicKey>,
         SIGSCHEME::VerificationKey: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
         SIGSCHEME::SigningKey: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
         SIGSCHEME::Signature: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
@@ -315,11 +475,16 @@ where
     prng: std::sync::Arc<std::sync::Mutex<rand_chacha::ChaChaRng>>,
     /// the committee parameter
     sortition_parameter: NonZeroU64,
+    /// fractions of the expected committee stake needed for certificates
+    thresholds: StakeThresholds,
     /// the chain commitment seed
     chain_seed: [u8; 32],
-    /// pdf cache
+    /// the beacon seeding each view, if randomness is derived from decided certificates
+    #[derivative(PartialEq = "ignore")]
+    beacon: Option<Arc<QcRandomnessBeacon>>,
+    /// cumulative distributions used by sortition, shared between clones
     #[derivative(PartialEq = "ignore")]
-    _sortition_cache: std::sync::Arc<std::sync::Mutex<HashMap<BinomialQuery, Ratio<BigUint>>>>,
+    sortition_cache: Arc<Mutex<SortitionCache>>,
 
     /// phantom data
     _pd: PhantomData<(TYPES, LEAF, SIGSCHEME, VRF, VRFHASHER, VRFPARAMS)>,
@@ -336,8 +501,10 @@ where
             proof_parameters: (),
             prng: self.prng.clone(),
             sortition_parameter: self.sortition_parameter,
+            thresholds: self.thresholds,
             chain_seed: self.chain_seed,
-            _sortition_cache: Arc::default(),
+            beacon: self.beacon.clone(),
+            sortition_cache: self.sortition_cache.clone(),
             _pd: PhantomData,
         }
     }
@@ -420,10 +587,11 @@ where
 }
 
 // KEY is VRFPubKey
+#[async_trait]
 impl<VRFHASHER, VRFPARAMS, VRF, SIGSCHEME, TYPES, LEAF: LeafType<NodeType = TYPES>>
     Membership<TYPES> for VrfImpl<TYPES, LEAF, SIGSCHEME, VRF, VRFHASHER, VRFPARAMS>
 where
-    SIGSCHEME: SignatureScheme<PublicParameter = (), MessageUnit = u8> + Sync + Send + 'static,
+    SIGSCHEME: BatchSignatureScheme + Sync + Send + 'static,
     SIGSCHEME::VerificationKey: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
     SIGSCHEME::SigningKey: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
     SIGSCHEME::Signature: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
@@ -462,31 +630,61 @@ where
         self.stake_table.clone()
     }
 
-    fn get_leader(&self, view_number: TYPES::Time) -> JfPubKey<SIGSCHEME> {
-        // TODO fst2 (ct) this is round robin, we should make this dependent on
-        // the VRF + some source of randomness
+    /// VRF proofs cannot be requested from a signer, so sortition needs the key in memory
+    fn needs_local_key(&self) -> bool {
+        true
+    }
 
-        // TODO for now do by stake table of how much stake each
-        // participant has
-        let mapping = &self.stake_table.mapping;
-        let index = ((*view_number) as usize) % mapping.len();
-        let encoded = mapping.keys().nth(index).unwrap();
-        SignatureKey::from_bytes(encoded).unwrap()
+    /// Feed the randomness beacon, if one is configured
+    fn record_decided_qc<QCLEAF: LeafType<NodeType = TYPES>>(
+        &self,
+        qc: &QuorumCertificate<TYPES, QCLEAF>,
+    ) {
+        if let Some(beacon) = &self.beacon {
+            beacon.record_decided_qc(qc);
+        }
+    }
+
+    /// Select the leader with probability proportional to its stake, seeded by the view number and
+    /// the chain seed or beacon, so that every replica computes the same leader
+    ///
+    /// A replica that does not know the randomness of the view yet gets
+    /// [`ElectionError::UnknownRandomness`] rather than a guess, which could differ from the leader
+    /// the other replicas agree on.
+    fn get_leader(&self, view_number: TYPES::Time) -> Result<JfPubKey<SIGSCHEME>, ElectionError> {
+        let seed = self
+            .view_seed(view_number)
+            .ok_or(ElectionError::UnknownRandomness {
+                view_number: *view_number,
+            })?;
+        let encoded = self.stake_table.select_by_stake(&seed);
+        Ok(SignatureKey::from_bytes(encoded).unwrap())
     }
 
     // what this is doing:
     // -
-    fn make_vote_token(
+    async fn make_vote_token(
         // TODO see if we can make this take &mut self
         // because we're using a mutable prng
         &self,
         view_number: TYPES::Time,
-        private_key: &(SIGSCHEME::SigningKey, SIGSCHEME::VerificationKey),
+        chain_id: ChainId,
+        signer: &dyn Signer<JfPubKey<SIGSCHEME>>,
     ) -> Result<Option<TYPES::VoteTokenType>, ElectionError> {
+        // VRF proofs cannot be requested from a signer, so sortition needs the key in memory
+        let private_key = signer.local_private_key().ok_or(ElectionError::Signer {
+            source: SignerError::NoLocalKey,
+        })?;
         let pub_key = JfPubKey::<SIGSCHEME>::from_native(private_key.1.clone());
         let Some(replicas_stake) = self.stake_table.get_stake(&pub_key) else { return Ok(None) };
 
-        let view_seed = generate_view_seed::<TYPES, VRFHASHER>(view_number, &self.chain_seed);
+        let view_seed = self
+            .view_seed(view_number)
+            .ok_or(ElectionError::UnknownRandomness {
+                view_number: *view_number,
+            })?;
+        // the proof is over the chain too, so it does not count on another network
+        let view_seed = generate_vote_seed::<VRFHASHER>(&view_seed, chain_id);
 
         let proof = Self::internal_get_vrf_proof(
             &private_key.0,
@@ -501,6 +699,7 @@ where
             self.stake_table.get_all_stake(),
             replicas_stake,
             self.sortition_parameter,
+            &mut self.sortition_cache.lock().unwrap(),
         );
 
         match selected_stake {
@@ -527,6 +726,7 @@ where
     fn validate_vote_token(
         &self,
         view_number: TYPES::Time,
+        chain_id: ChainId,
         pub_key: JfPubKey<SIGSCHEME>,
         token: Checked<TYPES::VoteTokenType>,
     ) -> Result<Checked<TYPES::VoteTokenType>, ElectionError> {
@@ -534,7 +734,11 @@ where
             Checked::Unchecked(token) => {
                 let stake: Option<NonZeroU64> = self.stake_table.get_stake(&pub_key);
                 let view_seed =
-                    generate_view_seed::<TYPES, VRFHASHER>(view_number, &self.chain_seed);
+                    self.view_seed(view_number)
+                        .ok_or(ElectionError::UnknownRandomness {
+                            view_number: *view_number,
+                        })?;
+                let view_seed = generate_vote_seed::<VRFHASHER>(&view_seed, chain_id);
                 if let Some(stake) = stake {
                     Self::internal_check_sortition(
                         &pub_key.pk,
@@ -545,6 +749,7 @@ where
                         self.sortition_parameter,
                         token.count,
                         &view_seed,
+                        &mut self.sortition_cache.lock().unwrap(),
                     )
                     .map(|c| match c {
                         Checked::Inval(_) => Checked::Inval(token),
@@ -576,11 +781,17 @@ where
         VRFStakeTableConfig {
             sortition_parameter: NonZeroU64::new(SORTITION_PARAMETER).unwrap(),
             distribution: stake,
+            thresholds: StakeThresholds::default(),
+            beacon: None,
         }
     }
 
     fn threshold(&self) -> NonZeroU64 {
-        NonZeroU64::new(((u64::from(self.sortition_parameter) * 2) / 3) + 1).unwrap()
+        self.thresholds.quorum_threshold(self.sortition_parameter.get())
+    }
+
+    fn da_threshold(&self) -> NonZeroU64 {
+        self.thresholds.da_threshold(self.sortition_parameter.get())
     }
 
     /// TODO if we ever come back to using this, we'll need to change this
@@ -598,26 +809,6 @@ where
     }
 }
 
-/// checks that the expected aomunt of stake matches the VRF output
-/// TODO this can be optimized most likely
-fn check_bin_idx(
-    expected_amount_of_stake: u64,
-    replicas_stake: u64,
-    total_stake: u64,
-    sortition_parameter: u64,
-    unnormalized_seed: &[u8; 32],
-    cache: &mut HashMap<BinomialQuery, Ratio<BigUint>>,
-) -> Option<bool> {
-    let bin_idx = find_bin_idx(
-        replicas_stake,
-        total_stake,
-        sortition_parameter,
-        unnormalized_seed,
-        cache,
-    );
-    bin_idx.map(|idx| idx == NonZeroU64::new(expected_amount_of_stake).unwrap())
-}
-
 /// generates the seed from algorand paper
 /// baseed on `view_number` and a constant as of now, but in the future will be other things
 /// this is a stop-gap
@@ -633,221 +824,21 @@ fn generate_view_seed<TYPES: NodeType, HASHER: digest::Digest>(
     output
 }
 
-/// represents a binomial query made by sortition
-/// `B(stake_attempt; replicas_stake; sortition_parameter / total_stake)`
-#[derive(Hash, Eq, PartialEq, Clone, Debug)]
-pub struct BinomialQuery {
-    /// the number of heads
-    stake_attempt: u64,
-    /// the total number of coin flips
-    replicas_stake: u64,
-    /// the total amount of stake
-    total_stake: u64,
-    /// the sortition parameter
-    sortition_parameter: u64,
-}
-
-impl BinomialQuery {
-    /// get the committee parameter
-    /// for this query
-    #[must_use]
-    pub fn get_p(&self) -> Ratio<BigUint> {
-        let sortition_parameter_big: BigUint = BigUint::from(self.sortition_parameter);
-        let total_stake_big: BigUint = BigUint::from(self.total_stake);
-        Ratio::new(sortition_parameter_big, total_stake_big)
-    }
-}
-
-#[instrument]
-fn calculate_threshold_from_cache(
-    previous_calculation: Option<(BinomialQuery, Ratio<BigUint>)>,
-    query: BinomialQuery,
-) -> Option<Ratio<BigUint>> {
-    if let Some((previous_query, previous_result)) = previous_calculation {
-        let expected_previous_query = BinomialQuery {
-            stake_attempt: query.stake_attempt - 1,
-            ..query
-        };
-        if previous_query == expected_previous_query {
-            let permutation = Ratio::new(
-                BigUint::from(query.replicas_stake - query.stake_attempt + 1),
-                BigUint::from(query.stake_attempt),
-            );
-            let p = query.get_p();
-            assert!(p.numer() < p.denom());
-            let reciprocal = Ratio::recip(&(Ratio::from_integer(BigUint::from(1_u32)) - p.clone()));
-            let result = previous_result * p * reciprocal * permutation;
-            assert!(result.numer() < result.denom());
-
-            return Some(result);
-        }
-    }
-    calculate_threshold(query)
-}
-
-// Calculates B(j; w; p) where B means bernoulli distribution.
-// That is: run w trials, with p probability of success for each trial, and return the probability
-// of j successes.
-// p = tau / W, where tau is the sortition parameter (controlling committee size)
-// this is the only usage of W and tau
-//
-// Translation:
-// stake_attempt: our guess at what the stake might be. This is j
-// replicas_stake: the units of stake owned by the replica. This is w
-// total_stake: the units of stake owned in total. This is W
-// sorition_parameter: the parameter controlling the committee size. This is tau
-//
-// TODO (ct) better error handling
-// returns none if one of our calculations fails
-//
-// TODO keep data around from last iteration so less calculation is needed
-// TODO test this "correct/simple" implementation against any optimized version
-#[instrument]
-// fn calculate_threshold(stake_attempt: u32, replicas_stake: u64, total_stake: u64, sortition_parameter: u64) -> Option<Ratio<BigUint>> {
-fn calculate_threshold(query: BinomialQuery) -> Option<Ratio<BigUint>> {
-    let stake_attempt = query.stake_attempt;
-    tracing::info!("Running calculate threshold");
-    // TODO (ct) better error handling
-    if stake_attempt > query.replicas_stake {
-        error!("j is larger than amount of stake we are allowed");
-        return None;
-    }
-
-    let sortition_parameter_big: BigUint = BigUint::from(query.sortition_parameter);
-    let total_stake_big: BigUint = BigUint::from(query.total_stake);
-    let one_big = BigUint::from(1_u32);
-
-    // this is the p parameter for the bernoulli distribution
-    let p = Ratio::new(sortition_parameter_big, total_stake_big);
-
-    assert!(p.numer() <= p.denom());
-
-    info!("p is {p:?}");
-
-    // number of tails in bernoulli
-    let failed_num = query.replicas_stake - stake_attempt;
-
-    // TODO cancel things out (avoid calculating factorial)
-    // TODO can just do division
-    let num_permutations = Ratio::new(
-        factorial(query.replicas_stake),
-        factorial(stake_attempt) * factorial(failed_num),
-    );
-
-    info!("num permutations is {num_permutations:?}, failed_num is {failed_num:?}");
-
-    let one = Ratio::from_integer(one_big);
-
-    // TODO can keep results from last try
-    let result = num_permutations
-        * (p.pow(i32::try_from(stake_attempt).ok()?)
-            * (one - p).pow(i32::try_from(failed_num).ok()?));
-
-    assert!(result.numer() < result.denom());
-
-    info!("result is is {result:?}");
-
-    Some(result)
-}
-
-/// compute i! as a biguint
-fn factorial(mut i: u64) -> BigUint {
-    if i == 0 {
-        return BigUint::from(1u32);
-    }
-
-    let mut result = BigUint::from(1u32);
-    while i > 0 {
-        result *= i;
-        i -= 1;
-    }
-    result
-}
-
-/// find the amount of stake we rolled.
-/// NOTE: in the future this requires a view numb
-/// Returns None if zero stake was rolled
-#[instrument]
-fn find_bin_idx(
-    replicas_stake: u64,
-    total_stake: u64,
-    sortition_parameter: u64,
-    unnormalized_seed: &[u8; 32],
-    cache: &mut HashMap<BinomialQuery, Ratio<BigUint>>,
-) -> Option<NonZeroU64> {
-    let unnormalized_seed = BigUint::from_bytes_le(unnormalized_seed);
-    let normalized_seed = Ratio::new(unnormalized_seed, BigUint::from(2_u32).pow(256));
-    assert!(normalized_seed.numer() < normalized_seed.denom());
-    let mut j: u64 = 0;
-
-    // [j, j+1)
-    // [cdf(j),cdf(j+1))
-
-    // left_threshold corresponds to the sum of all bernoulli distributions
-    // from i in 0 to j: B(i; replicas_stake; p). Where p is calculated later and corresponds to
-    // algorands paper
-    let mut left_threshold = Ratio::from_integer(BigUint::from(0u32));
-
-    loop {
-        // check cache
-
-        // if cache miss, feed in with previous val from cache
-        // that *probably* exists
-
-        assert!(left_threshold.numer() < left_threshold.denom());
-        let query = BinomialQuery {
-            stake_attempt: j + 1,
-            replicas_stake,
-            total_stake,
-            sortition_parameter,
-        };
-
-        let bin_val = {
-            // we already computed this value
-            if let Some(result) = cache.get(&query) {
-                result.clone()
-            } else {
-                // we haven't computed this value, but maybe
-                // we already computed the previous value
-
-                let mut maybe_old_query = query.clone();
-                maybe_old_query.stake_attempt -= 1;
-                let old_result = cache
-                    .get(&maybe_old_query)
-                    .map(|x| (maybe_old_query, x.clone()));
-                let result = calculate_threshold_from_cache(old_result, query.clone())?;
-                cache.insert(query, result.clone());
-                result
-            }
-        };
-
-        // corresponds to right range from apper
-        let right_threshold = left_threshold + bin_val.clone();
-
-        // debugging info. Unnecessary
-        {
-            let right_threshold_float = ToPrimitive::to_f64(&right_threshold.clone());
-            let bin_val_float = ToPrimitive::to_f64(&bin_val.clone());
-            let normalized_seed_float = ToPrimitive::to_f64(&normalized_seed.clone());
-            info!("rightthreshold: {right_threshold_float:?}, bin: {bin_val_float:?}, seed: {normalized_seed_float:?}");
-        }
-
-        // from i in 0 to j + 1: B(i; replicas_stake; p)
-        if normalized_seed < right_threshold {
-            match j {
-                0 => return None,
-                _ => return Some(NonZeroU64::new(j).unwrap()),
-            }
-        }
-        left_threshold = right_threshold;
-        j += 1;
-    }
+/// The VRF input of the vote tokens of the view with seed `view_seed` on the network `chain_id`,
+/// so that a token made for one network is not valid on another sharing the stake table
+fn generate_vote_seed<HASHER: digest::Digest>(view_seed: &[u8; 32], chain_id: ChainId) -> [u8; 32] {
+    let mut hasher = HASHER::new();
+    hasher.update(view_seed);
+    hasher.update(chain_id.0.to_le_bytes());
+    let mut output = [0u8; 32];
+    output.copy_from_slice(hasher.finalize().as_ref());
+    output
 }
 
 impl<TYPES, LEAF: LeafType<NodeType = TYPES>, SIGSCHEME, VRF, VRFHASHER, VRFPARAMS>
     VrfImpl<TYPES, LEAF, SIGSCHEME, VRF, VRFHASHER, VRFPARAMS>
 where
-    SIGSCHEME: SignatureScheme<PublicParameter = (), MessageUnit = u8> + Sync + Send,
+    SIGSCHEME: BatchSignatureScheme + Sync + Send,
     SIGSCHEME::VerificationKey: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
     SIGSCHEME::SigningKey: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
     SIGSCHEME::Signature: Clone + Serialize + for<'a> Deserialize<'a> + Sync + Send,
@@ -895,13 +886,29 @@ where
             },
             proof_parameters: (),
             chain_seed: genesis_seed,
+            beacon: config
+                .beacon
+                .map(|beacon| Arc::new(QcRandomnessBeacon::new(beacon))),
             prng: Arc::new(Mutex::new(ChaChaRng::from_seed(Default::default()))),
             _pd: PhantomData,
             sortition_parameter: config.sortition_parameter,
-            _sortition_cache: Arc::default(),
+            thresholds: config.thresholds,
+            sortition_cache: Arc::default(),
         }
     }
 
+    /// The seed of `view_number`: derived from the randomness beacon if one is configured, and
+    /// from the genesis seed otherwise
+    ///
+    /// Returns `None` while the beacon has not determined the randomness of the view.
+    fn view_seed(&self, view_number: TYPES::Time) -> Option<[u8; 32]> {
+        let chain_seed = match &self.beacon {
+            Some(beacon) => RandomnessBeacon::<TYPES>::seed_for_view(&**beacon, view_number)?,
+            None => self.chain_seed,
+        };
+        Some(generate_view_seed::<TYPES, VRFHASHER>(view_number, &chain_seed))
+    }
+
     /// stateless delegate for VRF proof generation
     /// # Errors
     ///
@@ -923,18 +930,16 @@ where
         total_stake: NonZeroU64,
         voter_stake: NonZeroU64,
         sortition_parameter: NonZeroU64,
+        cache: &mut SortitionCache,
     ) -> Option<NonZeroU64> {
         // TODO (ct) this can fail, return result::err
         let hash = VRF::evaluate(proof_param, proof).unwrap();
-        let mut cache: HashMap<BinomialQuery, Ratio<BigUint>> = HashMap::new();
-
-        find_bin_idx(
-            u64::from(voter_stake),
-            u64::from(total_stake),
-            sortition_parameter.into(),
-            &hash,
-            &mut cache,
-        )
+        let params = SortitionParams {
+            replicas_stake: voter_stake.get(),
+            total_stake: total_stake.get(),
+            sortition_parameter: sortition_parameter.get(),
+        };
+        cache.find_bin_idx(params, &hash)
     }
 
     /// stateless delegate for VRF sortition confirmation
@@ -950,18 +955,17 @@ where
         sortition_parameter: NonZeroU64,
         sortition_claim: NonZeroU64,
         vrf_in_seed: &VRF::Input,
+        cache: &mut SortitionCache,
     ) -> Result<Checked<()>, hotshot_types::traits::election::ElectionError> {
         if let Ok(true) = VRF::verify(proof_param, proof, public_key, vrf_in_seed) {
             let seed = VRF::evaluate(proof_param, proof).map_err(|_| ElectionError::StubError)?;
-            if let Some(res) = check_bin_idx(
-                u64::from(sortition_claim),
-                u64::from(voter_stake),
-                u64::from(total_stake),
-                u64::from(sortition_parameter),
-                &seed,
-                &mut HashMap::new(),
-            ) {
-                if res {
+            let params = SortitionParams {
+                replicas_stake: voter_stake.get(),
+                total_stake: total_stake.get(),
+                sortition_parameter: sortition_parameter.get(),
+            };
+            if let Some(selected) = cache.find_bin_idx(params, &seed) {
+                if selected == sortition_claim {
                     Ok(Checked::Valid(()))
                 } else {
                     Ok(Checked::Inval(()))
@@ -974,13 +978,16 @@ where
         }
     }
 
-    /// Stateless method to produce VRF proof and sortition for a given view number
+    /// Stateless method to produce VRF proof and sortition for a given view number on the network
+    /// `chain_id`
     /// # Errors
     ///
+    #[allow(clippy::too_many_arguments)]
     pub fn get_sortition_proof(
         private_key: &SIGSCHEME::SigningKey,
         proof_param: &VRF::PublicParameter,
         chain_seed: &VRF::Input,
+        chain_id: ChainId,
         view_number: TYPES::Time,
         total_stake: NonZeroU64,
         voter_stake: NonZeroU64,
@@ -989,6 +996,7 @@ where
     {
         let mut rng = ChaChaRng::from_seed(Default::default()); // maybe use something else that isn't deterministic?
         let view_seed = generate_view_seed::<TYPES, VRFHASHER>(view_number, chain_seed);
+        let view_seed = generate_vote_seed::<VRFHASHER>(&view_seed, chain_id);
         let proof = Self::internal_get_vrf_proof(private_key, proof_param, &mut rng, &view_seed)?;
         let sortition = Self::internal_get_sortition_for_proof(
             proof_param,
@@ -996,11 +1004,13 @@ where
             total_stake,
             voter_stake,
             sortition_parameter,
+            &mut SortitionCache::default(),
         );
         Ok((proof, sortition))
     }
 
-    /// Stateless method to verify VRF proof and sortition for a given view number
+    /// Stateless method to verify VRF proof and sortition for a given view number on the network
+    /// `chain_id`
     /// # Errors
     ///
     #[allow(clippy::too_many_arguments)]
@@ -1013,9 +1023,11 @@ where
         sortition_parameter: NonZeroU64,
         sortition_claim: NonZeroU64,
         chain_seed: &VRF::Input,
+        chain_id: ChainId,
         view_number: TYPES::Time,
     ) -> Result<bool, hotshot_types::traits::election::ElectionError> {
         let view_seed = generate_view_seed::<TYPES, VRFHASHER>(view_number, chain_seed);
+        let view_seed = generate_vote_seed::<VRFHASHER>(&view_seed, chain_id);
         Self::internal_check_sortition(
             &public_key.pk,
             proof_param,
@@ -1025,6 +1037,7 @@ where
             sortition_parameter,
             sortition_claim,
             &view_seed,
+            &mut SortitionCache::default(),
         )
         .map(|c| matches!(c, Checked::Valid(_)))
     }
@@ -1058,6 +1071,12 @@ pub struct VRFStakeTableConfig {
     pub sortition_parameter: NonZeroU64,
     /// the ordered distribution of stake across nodes
     pub distribution: Vec<NonZeroU64>,
+    /// fractions of the expected committee stake needed for quorum and DA certificates
+    #[serde(default)]
+    pub thresholds: StakeThresholds,
+    /// derive view seeds from decided certificates instead of the genesis seed alone
+    #[serde(default)]
+    pub beacon: Option<BeaconConfig>,
 }
 
 impl Default for VRFStakeTableConfig {
@@ -1065,189 +1084,245 @@ impl Default for VRFStakeTableConfig {
         VRFStakeTableConfig {
             sortition_parameter: NonZeroU64::new(SORTITION_PARAMETER).unwrap(),
             distribution: Vec::new(),
+            thresholds: StakeThresholds::default(),
+            beacon: None,
+        }
+    }
+}
+
+impl ElectionConfig for VRFStakeTableConfig {
+    fn validate(&self, num_nodes: usize) -> Result<(), String> {
+        if num_nodes == 0 {
+            return Err("the committee must not be empty".to_string());
+        }
+        if self.distribution.len() != num_nodes {
+            return Err(format!(
+                "the stake of {} nodes is given for a committee of {num_nodes}",
+                self.distribution.len()
+            ));
         }
+        self.thresholds.validate()
     }
 }
 
-impl ElectionConfig for VRFStakeTableConfig {}
-
-// Tests have been commented out, so `mod tests` isn't used.
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-//     use ark_bls12_381::Parameters as Param381;
-//     use ark_std::test_rng;
-
-//     use blake3::Hasher;
-//     use hotshot_types::{
-//         data::ViewNumber,
-//         traits::{
-//             block_contents::dummy::{DummyBlock, DummyTransaction},
-//             state::{dummy::DummyState, ValidatingConsensus},
-//         },
-//     };
-//     use jf_primitives::{
-//         signatures::{
-//             bls::{BLSSignature, BLSVerKey},
-//             BLSSignatureScheme,
-//         },
-//         vrf::blsvrf::BLSVRFScheme,
-//     };
-//     use std::{num::NonZeroUsize, time::Duration};
-
-//     #[derive(
-//         Copy,
-//         Clone,
-//         Debug,
-//         Default,
-//         Hash,
-//         PartialEq,
-//         Eq,
-//         PartialOrd,
-//         Ord,
-//         serde::Serialize,
-//         serde::Deserialize,
-//     )]
-//     struct TestTypes;
-//     impl NodeType for TestTypes {
-//         // TODO (da) can this be SequencingConsensus?
-//         type ConsensusType = ValidatingConsensus;
-//         type Time = ViewNumber;
-//         type BlockType = DummyBlock;
-//         type SignatureKey = JfPubKey<BLSSignatureScheme<Param381>>;
-//         type VoteTokenType = VRFVoteToken<
-//             BLSVerKey<ark_bls12_381::Parameters>,
-//             BLSSignature<ark_bls12_381::Parameters>,
-//         >;
-//         type Transaction = DummyTransaction;
-//         type ElectionConfigType = VRFStakeTableConfig;
-//         type StateType = DummyState;
-//     }
-
-//     fn gen_vrf_impl<LEAF: LeafType<NodeType = TestTypes>>(
-//         num_nodes: usize,
-//     ) -> (
-//         VrfImpl<
-//             TestTypes,
-//             LEAF,
-//             BLSSignatureScheme<Param381>,
-//             BLSVRFScheme<Param381>,
-//             Hasher,
-//             Param381,
-//         >,
-//         Vec<(
-//             jf_primitives::signatures::bls::BLSSignKey<Param381>,
-//             jf_primitives::signatures::bls::BLSVerKey<Param381>,
-//         )>,
-//     ) {
-//         let mut known_nodes = Vec::new();
-//         let mut keys = Vec::new();
-//         let rng = &mut test_rng();
-//         let mut stake_distribution = Vec::new();
-//         let stake_per_node = NonZeroU64::new(100).unwrap();
-//         let genesis_seed = [0u8; 32];
-//         for _i in 0..num_nodes {
-//             let (sk, pk) = BLSSignatureScheme::<Param381>::key_gen(&(), rng).unwrap();
-//             keys.push((sk.clone(), pk.clone()));
-//             known_nodes.push(JfPubKey::from_native(pk.clone()));
-//             stake_distribution.push(stake_per_node);
-//         }
-//         let stake_table = VrfImpl::with_initial_stake(
-//             known_nodes,
-//             &VRFStakeTableConfig {
-//                 sortition_parameter: std::num::NonZeroU64::new(SORTITION_PARAMETER).unwrap(),
-//                 distribution: stake_distribution,
-//             },
-//             genesis_seed,
-//         );
-//         (stake_table, keys)
-//     }
-
-//     pub fn check_if_valid<T>(token: &Checked<T>) -> bool {
-//         match token {
-//             Checked::Valid(_) => true,
-//             Checked::Inval(_) | Checked::Unchecked(_) => false,
-//         }
-//     }
-
-//     // #[test]
-//     // pub fn test_sortition() {
-//     //     setup_logging();
-//     //     let (vrf_impl, keys) = gen_vrf_impl::<ValidatingLeaf<TestTypes>>(10);
-//     //     let views = 100;
-
-//     //     for view in 0..views {
-//     //         for (node_idx, (sk, pk)) in keys.iter().enumerate() {
-//     //             let token_result = vrf_impl
-//     //                 .make_vote_token(ViewNumber::new(view), &(sk.clone(), pk.clone()))
-//     //                 .unwrap();
-//     //             match token_result {
-//     //                 Some(token) => {
-//     //                     let count = token.count;
-//     //                     let result = vrf_impl
-//     //                         .validate_vote_token(
-//     //                             ViewNumber::new(view),
-//     //                             JfPubKey::from_native(pk.clone()),
-//     //                             Checked::Unchecked(token),
-//     //                         )
-//     //                         .unwrap();
-//     //                     let result_is_valid = check_if_valid(&result);
-//     //                     error!("view {view:?}, node_idx {node_idx:?}, stake {count:?} ");
-//     //                     assert!(result_is_valid);
-//     //                 }
-//     //                 _ => continue,
-//     //             }
-//     //         }
-//     //     }
-//     // }
-
-//     #[test]
-//     pub fn test_factorial() {
-//         assert_eq!(factorial(0), BigUint::from(1u32));
-//         assert_eq!(factorial(1), BigUint::from(1u32));
-//         assert_eq!(factorial(2), BigUint::from(2u32));
-//         assert_eq!(factorial(3), BigUint::from(6u32));
-//         assert_eq!(factorial(4), BigUint::from(24u32));
-//         assert_eq!(factorial(5), BigUint::from(120u32));
-//     }
-
-//     // TODO add failure case
-
-//     #[test]
-//     fn network_config_is_serializable() {
-//         // validate that `RunResults` can be serialized
-//         // Note that there is currently an issue with `VRFPubKey` where it can't be serialized with toml
-//         // so instead we only test with serde_json
-//         let key =
-//             <JfPubKey<BLSSignatureScheme<Param381>> as TestableSignatureKey>::generate_test_key(1);
-//         let pub_key = JfPubKey::<BLSSignatureScheme<Param381>>::from_private(&key);
-//         let mut config = hotshot_centralized_server::NetworkConfig {
-//             config: hotshot_types::HotShotConfig {
-//                 election_config: Some(super::VRFStakeTableConfig {
-//                     distribution: vec![NonZeroU64::new(1).unwrap()],
-//                     sortition_parameter: NonZeroU64::new(1).unwrap(),
-//                 }),
-//                 known_nodes: vec![pub_key],
-//                 execution_type: hotshot_types::ExecutionType::Incremental,
-//                 total_nodes: NonZeroUsize::new(1).unwrap(),
-//                 min_transactions: 1,
-//                 max_transactions: NonZeroUsize::new(1).unwrap(),
-//                 next_view_timeout: 1,
-//                 timeout_ratio: (1, 1),
-//                 round_start_delay: 1,
-//                 start_delay: 1,
-//                 num_bootstrap: 1,
-//                 propose_min_round_time: Duration::from_secs(1),
-//                 propose_max_round_time: Duration::from_secs(1),
-//             },
-//             ..Default::default()
-//         };
-//         serde_json::to_string(&config).unwrap();
-//         assert!(toml::to_string(&config).is_err());
-
-//         // validate that this is indeed a `pub_key` issue
-//         config.config.known_nodes.clear();
-//         serde_json::to_string(&config).unwrap();
-//         toml::to_string(&config).unwrap();
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::dummy::DummyState;
+    use ark_std::test_rng;
+    use async_compatibility_layer::logging::setup_logging;
+    use futures::executor::block_on;
+    use hotshot_types::{
+        data::{ValidatingLeaf, ViewNumber},
+        traits::{
+            block_contents::dummy::{DummyBlock, DummyTransaction},
+            signer::LocalSigner,
+            state::{ConsensusTime, ValidatingConsensus},
+        },
+    };
+    use jf_primitives::signatures::bls::BLSSignKey;
+    use std::{num::NonZeroUsize, time::Duration};
+
+    #[derive(
+        Copy,
+        Clone,
+        Debug,
+        Default,
+        Hash,
+        PartialEq,
+        Eq,
+        PartialOrd,
+        Ord,
+        serde::Serialize,
+        serde::Deserialize,
+    )]
+    struct TestTypes;
+    impl NodeType for TestTypes {
+        type ConsensusType = ValidatingConsensus;
+        type Time = ViewNumber;
+        type BlockType = DummyBlock;
+        type SignatureKey = BlsPubKey;
+        type VoteTokenType = VRFVoteToken<BLSVerKey<Param381>, BLSSignature<Param381>>;
+        type Transaction = DummyTransaction;
+        type ElectionConfigType = VRFStakeTableConfig;
+        type StateType = DummyState;
+    }
+
+    type TestVrfImpl = VrfImpl<
+        TestTypes,
+        ValidatingLeaf<TestTypes>,
+        BLSSignatureScheme<Param381>,
+        BLSVRFScheme<Param381>,
+        Hasher,
+        Param381,
+    >;
+
+    type KeyPair = (BLSSignKey<Param381>, BLSVerKey<Param381>);
+
+    fn gen_vrf_impl(num_nodes: usize) -> (TestVrfImpl, Vec<KeyPair>) {
+        let mut known_nodes = Vec::new();
+        let mut keys = Vec::new();
+        let rng = &mut test_rng();
+        let mut stake_distribution = Vec::new();
+        let stake_per_node = NonZeroU64::new(100).unwrap();
+        let genesis_seed = [0u8; 32];
+        for _i in 0..num_nodes {
+            let (sk, pk) = BLSSignatureScheme::<Param381>::key_gen(&(), rng).unwrap();
+            keys.push((sk.clone(), pk.clone()));
+            known_nodes.push(JfPubKey::from_native(pk.clone()));
+            stake_distribution.push(stake_per_node);
+        }
+        let stake_table = VrfImpl::with_initial_stake(
+            known_nodes,
+            &VRFStakeTableConfig {
+                sortition_parameter: NonZeroU64::new(SORTITION_PARAMETER).unwrap(),
+                distribution: stake_distribution,
+                thresholds: StakeThresholds::default(),
+                beacon: None,
+            },
+            genesis_seed,
+        );
+        (stake_table, keys)
+    }
+
+    #[test]
+    pub fn test_sortition() {
+        setup_logging();
+        let (vrf_impl, keys) = gen_vrf_impl(10);
+        let views = 100;
+        let mut selected = 0;
+
+        for view in 0..views {
+            for (sk, pk) in &keys {
+                let signer = LocalSigner::new((sk.clone(), pk.clone()));
+                let view_number = ViewNumber::new(view);
+                let token = vrf_impl.make_vote_token(view_number, ChainId::default(), &signer);
+                let Some(token) = block_on(token).unwrap() else { continue };
+                selected += token.count.get();
+                let result = vrf_impl
+                    .validate_vote_token(
+                        view_number,
+                        ChainId::default(),
+                        JfPubKey::from_native(pk.clone()),
+                        Checked::Unchecked(token),
+                    )
+                    .unwrap();
+                assert!(matches!(result, Checked::Valid(_)));
+            }
+        }
+        // every view selects `SORTITION_PARAMETER` units of stake on average
+        let expected = views * SORTITION_PARAMETER;
+        assert!(selected > expected * 9 / 10 && selected < expected * 11 / 10);
+    }
+
+    #[test]
+    pub fn test_sortition_rejects_mismatched_tokens() {
+        let (vrf_impl, keys) = gen_vrf_impl(10);
+        let (sk, pk) = &keys[0];
+        let signer = LocalSigner::new((sk.clone(), pk.clone()));
+        let (view, token) = (0..)
+            .find_map(|view| {
+                let view_number = ViewNumber::new(view);
+                block_on(vrf_impl.make_vote_token(view_number, ChainId::default(), &signer))
+                    .unwrap()
+                    .map(|token| (view, token))
+            })
+            .unwrap();
+
+        // the token of another view
+        let result = vrf_impl
+            .validate_vote_token(
+                ViewNumber::new(view + 1),
+                ChainId::default(),
+                JfPubKey::from_native(pk.clone()),
+                Checked::Unchecked(token.clone()),
+            )
+            .unwrap();
+        assert!(matches!(result, Checked::Inval(_)));
+
+        // the token of another replica
+        let result = vrf_impl
+            .validate_vote_token(
+                ViewNumber::new(view),
+                ChainId::default(),
+                JfPubKey::from_native(keys[1].1.clone()),
+                Checked::Unchecked(token.clone()),
+            )
+            .unwrap();
+        assert!(matches!(result, Checked::Inval(_)));
+
+        // a token claiming more stake than was selected
+        let inflated = VRFVoteToken {
+            count: token.count.checked_add(1).unwrap(),
+            ..token.clone()
+        };
+        let result = vrf_impl
+            .validate_vote_token(
+                ViewNumber::new(view),
+                ChainId::default(),
+                JfPubKey::from_native(pk.clone()),
+                Checked::Unchecked(inflated),
+            )
+            .unwrap();
+        assert!(matches!(result, Checked::Inval(_)));
+
+        // the token of another network
+        let result = vrf_impl
+            .validate_vote_token(
+                ViewNumber::new(view),
+                ChainId(1),
+                JfPubKey::from_native(pk.clone()),
+                Checked::Unchecked(token),
+            )
+            .unwrap();
+        assert!(matches!(result, Checked::Inval(_)));
+    }
+
+    #[test]
+    pub fn test_factorial() {
+        use num::BigUint;
+        use sortition::reference::factorial;
+        assert_eq!(factorial(0), BigUint::from(1u32));
+        assert_eq!(factorial(1), BigUint::from(1u32));
+        assert_eq!(factorial(2), BigUint::from(2u32));
+        assert_eq!(factorial(3), BigUint::from(6u32));
+        assert_eq!(factorial(4), BigUint::from(24u32));
+        assert_eq!(factorial(5), BigUint::from(120u32));
+    }
+
+    #[test]
+    fn network_config_is_serializable() {
+        // validate that `NetworkConfig` can be serialized with a VRF election config
+        let key = <BlsPubKey as TestableSignatureKey>::generate_test_key(1);
+        let pub_key = BlsPubKey::from_private(&key);
+        let config = hotshot_centralized_server::NetworkConfig {
+            config: hotshot_types::HotShotConfig {
+                election_config: Some(super::VRFStakeTableConfig {
+                    distribution: vec![NonZeroU64::new(1).unwrap()],
+                    sortition_parameter: NonZeroU64::new(1).unwrap(),
+                    thresholds: StakeThresholds::default(),
+                    beacon: None,
+                }),
+                known_nodes: vec![pub_key],
+                possession_proofs: BlsPubKey::prove_possession(&key).into_iter().collect(),
+                execution_type: hotshot_types::ExecutionType::Incremental,
+                total_nodes: NonZeroUsize::new(1).unwrap(),
+                min_transactions: 1,
+                max_transactions: NonZeroUsize::new(1).unwrap(),
+                next_view_timeout: 1,
+                timeout_ratio: (1, 1),
+                round_start_delay: 1,
+                start_delay: 1,
+                num_bootstrap: 1,
+                propose_min_round_time: Duration::from_secs(1),
+                propose_max_round_time: Duration::from_secs(1),
+                adaptive_timeout: None,
+                transaction_forwarding: None,
+                chain_id: ChainId::default(),
+                key_rotation_epoch: NonZeroU64::new(100).unwrap(),
+            },
+            ..Default::default()
+        };
+        serde_json::to_string(&config).unwrap();
+    }
+}
@@ -0,0 +1,358 @@
+//! Sortition math of the VRF election: how many units of a replica's stake a VRF output selects
+//!
+//! Each unit of stake is selected with probability `p = sortition_parameter / total_stake`, so the
+//! selected stake of a replica follows the binomial distribution `B(replicas_stake, p)`. The VRF
+//! output is read as a number in `[0, 1)` and located among the bins of the cumulative
+//! distribution.
+
+use num::BigUint;
+use std::{
+    collections::{HashMap, VecDeque},
+    num::NonZeroU64,
+};
+
+/// The most distributions a [`SortitionCache`] keeps
+///
+/// Stake tables change with every epoch and key rotation, each bringing new total stakes, so the
+/// distributions of past stake tables are evicted rather than kept forever.
+pub const MAX_CACHED_DISTRIBUTIONS: usize = 256;
+
+/// The binomial distribution followed by the stake selected for one replica
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
+pub struct SortitionParams {
+    /// the units of stake owned by the replica, the number of coin flips
+    pub replicas_stake: u64,
+    /// the units of stake owned in total
+    pub total_stake: u64,
+    /// the expected committee size
+    pub sortition_parameter: u64,
+}
+
+/// The cumulative distribution of one [`SortitionParams`], extended as far as it has been queried
+///
+/// Every probability is kept as an exact integer over the common denominator
+/// `total_stake ^ replicas_stake`. The probability of `j` heads,
+/// `C(w, j) * tau^j * (W - tau)^(w - j)`, follows from the one of `j - 1` heads by multiplying by
+/// `(w - j + 1) * tau` and dividing by `j * (W - tau)`, which avoids computing any factorial.
+#[derive(Debug)]
+struct BinomialCdf {
+    /// the distribution
+    params: SortitionParams,
+    /// `total_stake ^ replicas_stake`
+    denominator: BigUint,
+    /// the (scaled) probability of `heads` heads
+    term: BigUint,
+    /// the number of heads `term` is the probability of
+    heads: u64,
+    /// `bins[j]` is the (scaled) probability of getting between 1 and `j + 1` heads
+    bins: Vec<BigUint>,
+}
+
+impl BinomialCdf {
+    /// Start the distribution of `params`
+    fn new(params: SortitionParams) -> Self {
+        let w = params.replicas_stake;
+        let big_w = BigUint::from(params.total_stake);
+        let tau = BigUint::from(params.sortition_parameter);
+        let tails = BigUint::from(params.total_stake.saturating_sub(params.sortition_parameter));
+        let denominator = pow(&big_w, w);
+        // the probability of zero heads
+        let term = pow(&tails, w);
+        debug_assert!(tau <= big_w);
+        Self {
+            params,
+            denominator,
+            term,
+            heads: 0,
+            bins: Vec::new(),
+        }
+    }
+
+    /// Advance `term` to the probability of one more head, or return `false` if all heads are
+    /// already accounted for
+    fn advance(&mut self) -> bool {
+        let w = self.params.replicas_stake;
+        if self.heads >= w {
+            return false;
+        }
+        let j = self.heads + 1;
+        let tau = self.params.sortition_parameter;
+        let tails = self.params.total_stake.saturating_sub(tau);
+        self.term = if tails == 0 {
+            // every coin lands heads
+            if j == w {
+                pow(&BigUint::from(tau), w)
+            } else {
+                BigUint::from(0_u32)
+            }
+        } else {
+            &self.term * BigUint::from(w - j + 1) * BigUint::from(tau)
+                / (BigUint::from(j) * BigUint::from(tails))
+        };
+        self.heads = j;
+        true
+    }
+
+    /// The bin `seed` falls in: `None` if it selected no stake, `Some(j)` if it selected `j`
+    /// units
+    ///
+    /// `seed` is read as the little endian numerator of a fraction over `2^256`.
+    fn find_bin(&mut self, seed: &[u8; 32]) -> Option<NonZeroU64> {
+        // seed / 2^256 < bin / denominator  <=>  seed * denominator < bin * 2^256
+        let scaled_seed = BigUint::from_bytes_le(seed) * &self.denominator;
+        let above_seed = |bin: &BigUint| (bin << 256_usize) > scaled_seed;
+
+        let mut j = self.bins.partition_point(|bin| !above_seed(bin));
+        while j == self.bins.len() {
+            if !self.advance() {
+                return None;
+            }
+            let previous = self.bins.last().cloned().unwrap_or_default();
+            let bin = previous + &self.term;
+            let found = above_seed(&bin);
+            self.bins.push(bin);
+            if !found {
+                j += 1;
+            }
+        }
+        NonZeroU64::new(j as u64)
+    }
+}
+
+/// `base ^ exp`
+fn pow(base: &BigUint, exp: u64) -> BigUint {
+    let mut result = BigUint::from(1_u32);
+    let mut base = base.clone();
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result *= &base;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = &base * &base;
+        }
+    }
+    result
+}
+
+/// Cache of the cumulative distributions queried most recently
+///
+/// There is one distribution per distinct amount of stake in the stake table, and each is only
+/// extended as far as VRF outputs have reached. At most [`MAX_CACHED_DISTRIBUTIONS`] are kept,
+/// evicting the least recently used one first.
+#[derive(Debug, Default)]
+pub struct SortitionCache {
+    /// the distributions, by parameters
+    cdfs: HashMap<SortitionParams, BinomialCdf>,
+    /// the parameters of `cdfs`, least recently used first
+    recency: VecDeque<SortitionParams>,
+}
+
+impl SortitionCache {
+    /// Find the amount of stake selected by the VRF output `seed`
+    ///
+    /// Returns `None` if no stake was selected.
+    pub fn find_bin_idx(
+        &mut self,
+        params: SortitionParams,
+        seed: &[u8; 32],
+    ) -> Option<NonZeroU64> {
+        if let Some(position) = self.recency.iter().position(|used| *used == params) {
+            self.recency.remove(position);
+        } else if self.recency.len() >= MAX_CACHED_DISTRIBUTIONS {
+            if let Some(evicted) = self.recency.pop_front() {
+                self.cdfs.remove(&evicted);
+            }
+        }
+        self.recency.push_back(params);
+        self.cdfs
+            .entry(params)
+            .or_insert_with(|| BinomialCdf::new(params))
+            .find_bin(seed)
+    }
+
+    /// The number of distributions kept
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cdfs.len()
+    }
+
+    /// Whether no distribution is kept
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cdfs.is_empty()
+    }
+}
+
+/// The original implementation of sortition, which computes every probability from factorials
+///
+/// Kept as a reference to check and benchmark [`SortitionCache`] against.
+#[cfg(any(test, feature = "hotshot-testing"))]
+pub mod reference {
+    use super::SortitionParams;
+    use num::{rational::Ratio, BigUint};
+    use std::num::NonZeroU64;
+
+    /// `B(stake_attempt; replicas_stake; sortition_parameter / total_stake)`
+    ///
+    /// Returns `None` if `stake_attempt` is larger than the replica's stake.
+    #[must_use]
+    pub fn calculate_threshold(
+        stake_attempt: u64,
+        params: SortitionParams,
+    ) -> Option<Ratio<BigUint>> {
+        if stake_attempt > params.replicas_stake {
+            return None;
+        }
+        let p = Ratio::new(
+            BigUint::from(params.sortition_parameter),
+            BigUint::from(params.total_stake),
+        );
+        let failed_num = params.replicas_stake - stake_attempt;
+        let num_permutations = Ratio::new(
+            factorial(params.replicas_stake),
+            factorial(stake_attempt) * factorial(failed_num),
+        );
+        let one = Ratio::from_integer(BigUint::from(1_u32));
+        Some(
+            num_permutations
+                * (p.pow(i32::try_from(stake_attempt).ok()?)
+                    * (one - p).pow(i32::try_from(failed_num).ok()?)),
+        )
+    }
+
+    /// compute i! as a biguint
+    #[must_use]
+    pub fn factorial(mut i: u64) -> BigUint {
+        let mut result = BigUint::from(1u32);
+        while i > 0 {
+            result *= i;
+            i -= 1;
+        }
+        result
+    }
+
+    /// Find the amount of stake selected by `seed`, summing freshly computed probabilities
+    #[must_use]
+    pub fn find_bin_idx(params: SortitionParams, seed: &[u8; 32]) -> Option<NonZeroU64> {
+        let normalized_seed = Ratio::new(
+            BigUint::from_bytes_le(seed),
+            BigUint::from(2_u32).pow(256),
+        );
+        let mut j: u64 = 0;
+        let mut left_threshold = Ratio::from_integer(BigUint::from(0u32));
+        loop {
+            let bin_val = calculate_threshold(j + 1, params)?;
+            let right_threshold = left_threshold + bin_val;
+            if normalized_seed < right_threshold {
+                return NonZeroU64::new(j);
+            }
+            left_threshold = right_threshold;
+            j += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::rational::Ratio;
+    use rand::{RngCore, SeedableRng};
+    use rand_chacha::ChaChaRng;
+
+    const PARAMS: [SortitionParams; 5] = [
+        SortitionParams {
+            replicas_stake: 1,
+            total_stake: 10,
+            sortition_parameter: 5,
+        },
+        SortitionParams {
+            replicas_stake: 10,
+            total_stake: 100,
+            sortition_parameter: 50,
+        },
+        SortitionParams {
+            replicas_stake: 100,
+            total_stake: 1000,
+            sortition_parameter: 500,
+        },
+        SortitionParams {
+            replicas_stake: 37,
+            total_stake: 1234,
+            sortition_parameter: 1000,
+        },
+        SortitionParams {
+            replicas_stake: 3,
+            total_stake: 3,
+            sortition_parameter: 3,
+        },
+    ];
+
+    #[test]
+    fn bins_match_reference_probabilities() {
+        for params in PARAMS {
+            let mut cdf = BinomialCdf::new(params);
+            let mut expected = Ratio::from_integer(BigUint::from(0_u32));
+            for j in 1..=params.replicas_stake {
+                assert!(cdf.advance());
+                expected += reference::calculate_threshold(j, params).unwrap();
+                cdf.bins.push(cdf.bins.last().cloned().unwrap_or_default() + &cdf.term);
+                let bin = Ratio::new(cdf.bins.last().unwrap().clone(), cdf.denominator.clone());
+                assert_eq!(bin, expected, "{params:?}, {j} heads");
+            }
+            assert!(!cdf.advance());
+        }
+    }
+
+    #[test]
+    fn find_bin_idx_matches_reference() {
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        let mut cache = SortitionCache::default();
+        for params in PARAMS {
+            for _ in 0..200 {
+                let mut seed = [0_u8; 32];
+                rng.fill_bytes(&mut seed);
+                assert_eq!(
+                    cache.find_bin_idx(params, &seed),
+                    reference::find_bin_idx(params, &seed),
+                    "{params:?}, seed {seed:?}"
+                );
+            }
+            // the extremes of the seed range
+            for seed in [[0_u8; 32], [u8::MAX; 32]] {
+                assert_eq!(
+                    cache.find_bin_idx(params, &seed),
+                    reference::find_bin_idx(params, &seed),
+                    "{params:?}, seed {seed:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cache_is_bounded() {
+        let mut cache = SortitionCache::default();
+        let params = |total_stake| SortitionParams {
+            replicas_stake: 1,
+            total_stake,
+            sortition_parameter: 1,
+        };
+        let seed = [0_u8; 32];
+        for total_stake in 1..=MAX_CACHED_DISTRIBUTIONS as u64 {
+            cache.find_bin_idx(params(total_stake), &seed);
+        }
+        // using the oldest distribution again saves it from the next eviction
+        cache.find_bin_idx(params(1), &seed);
+        cache.find_bin_idx(params(MAX_CACHED_DISTRIBUTIONS as u64 + 1), &seed);
+        assert_eq!(cache.len(), MAX_CACHED_DISTRIBUTIONS);
+        assert!(cache.cdfs.contains_key(&params(1)));
+        assert!(!cache.cdfs.contains_key(&params(2)));
+    }
+
+    #[test]
+    fn reference_factorial() {
+        assert_eq!(reference::factorial(0), BigUint::from(1_u32));
+        assert_eq!(reference::factorial(5), BigUint::from(120_u32));
+    }
+}
@@ -15,7 +15,6 @@ use async_compatibility_layer::{
 };
 use async_lock::{RwLock, RwLockUpgradableReadGuard};
 use async_trait::async_trait;
-use bincode::Options;
 use futures::{future::BoxFuture, FutureExt};
 use hotshot_centralized_server::{
     FromServer, NetworkConfig, Run, RunResults, TcpStreamRecvUtil, TcpStreamSendUtil,
@@ -30,18 +29,16 @@ use hotshot_types::{
         election::{ElectionConfig, Membership},
         metrics::{Metrics, NoMetrics},
         network::{
-            CommunicationChannel, ConnectedNetwork, FailedToDeserializeSnafu,
-            FailedToSerializeSnafu, NetworkError, NetworkMsg, TestableNetworkingImplementation,
-            TransmitType,
+            decode_message, encode_message, CommunicationChannel, ConnectedNetwork, NetworkError,
+            NetworkMsg, TestableNetworkingImplementation, TransmitType,
         },
         node_implementation::NodeType,
         signature_key::{ed25519::Ed25519Pub, SignatureKey, TestableSignatureKey},
+        signer::ChainId,
     },
     vote::VoteType,
 };
-use hotshot_utils::bincode::bincode_opts;
-use serde::{de::DeserializeOwned, Serialize};
-use snafu::ResultExt;
+use hotshot_utils::version::ProtocolVersion;
 use std::{
     cmp,
     collections::{hash_map::Entry, BTreeSet, HashMap},
@@ -244,9 +241,7 @@ impl<K: SignatureKey, E: ElectionConfig> Inner<K, E> {
     }
 
     /// Get all the incoming broadcast messages received from the server. Returning 0 messages if nothing was received.
-    async fn get_broadcasts<M: Serialize + DeserializeOwned + Send + Sync + Clone + 'static>(
-        &self,
-    ) -> Vec<Result<M, bincode::Error>> {
+    async fn get_broadcasts<M: NetworkMsg>(&self) -> Vec<Result<M, NetworkError>> {
         self.remove_messages_from_queue(|msg, index, context_map| {
             match msg {
                 (FromServer::Broadcast {
@@ -275,7 +270,7 @@ impl<K: SignatureKey, E: ElectionConfig> Inner<K, E> {
                             tracing::error!("FromServer::Broadcast with message_len {message_len}b, payload is {}b", payload.len());
                             MsgStepOutcome::Skip
                         },
-                        cmp::Ordering::Equal => MsgStepOutcome::Complete(consumed_indexes, bincode_opts().deserialize(payload)),
+                        cmp::Ordering::Equal => MsgStepOutcome::Complete(consumed_indexes, decode_message(payload)),
                     }
                 },
                 (FromServer::BroadcastPayload { source, .. }, payload) => {
@@ -283,7 +278,7 @@ impl<K: SignatureKey, E: ElectionConfig> Inner<K, E> {
                         context.get_mut().consumed_indexes.insert(index);
                         if context.get().accumulated_stream.is_empty() && context.get().message_len as usize == payload.len() {
                             let (_, context) = context.remove_entry();
-                            MsgStepOutcome::Complete(context.consumed_indexes, bincode_opts().deserialize(payload))
+                            MsgStepOutcome::Complete(context.consumed_indexes, decode_message(payload))
                         } else {
                             context.get_mut().accumulated_stream.append(&mut payload.clone());
                             match context.get().accumulated_stream.len().cmp(&(context.get().message_len as usize)) {
@@ -295,7 +290,7 @@ impl<K: SignatureKey, E: ElectionConfig> Inner<K, E> {
                                 }
                                 cmp::Ordering::Equal => {
                                     let (_, context) = context.remove_entry();
-                                    MsgStepOutcome::Complete(context.consumed_indexes, bincode_opts().deserialize(&context.accumulated_stream))
+                                    MsgStepOutcome::Complete(context.consumed_indexes, decode_message(&context.accumulated_stream))
                                 }
                             }
                         }
@@ -311,11 +306,7 @@ impl<K: SignatureKey, E: ElectionConfig> Inner<K, E> {
     }
 
     /// Get all the incoming direct messages received from the server. Returning 0 messages if nothing was received.
-    async fn get_direct_messages<
-        M: Serialize + DeserializeOwned + Send + Sync + Clone + 'static,
-    >(
-        &self,
-    ) -> Vec<Result<M, bincode::Error>> {
+    async fn get_direct_messages<M: NetworkMsg>(&self) -> Vec<Result<M, NetworkError>> {
         self.remove_messages_from_queue(|msg, index, context_map| {
             match msg {
                 (FromServer::Direct {
@@ -345,7 +336,7 @@ impl<K: SignatureKey, E: ElectionConfig> Inner<K, E> {
                             MsgStepOutcome::Skip
                         },
                         cmp::Ordering::Equal => {
-                            MsgStepOutcome::Complete(consumed_indexes, bincode_opts().deserialize(payload))
+                            MsgStepOutcome::Complete(consumed_indexes, decode_message(payload))
                         },
                     }
                 },
@@ -354,7 +345,7 @@ impl<K: SignatureKey, E: ElectionConfig> Inner<K, E> {
                         context.get_mut().consumed_indexes.insert(index);
                         if context.get().accumulated_stream.is_empty() && context.get().message_len as usize == payload.len() {
                             let (_, context) = context.remove_entry();
-                            MsgStepOutcome::Complete(context.consumed_indexes, bincode_opts().deserialize(payload))
+                            MsgStepOutcome::Complete(context.consumed_indexes, decode_message(payload))
                         } else {
                             context.get_mut().accumulated_stream.append(&mut payload.clone());
                             match context.get().accumulated_stream.len().cmp(&(context.get().message_len as usize)) {
@@ -368,7 +359,7 @@ impl<K: SignatureKey, E: ElectionConfig> Inner<K, E> {
                                 }
                                 cmp::Ordering::Equal => {
                                     let (_, context) = context.remove_entry();
-                                    MsgStepOutcome::Complete(context.consumed_indexes, bincode_opts().deserialize(&context.accumulated_stream))
+                                    MsgStepOutcome::Complete(context.consumed_indexes, decode_message(&context.accumulated_stream))
                                 }
                             }
                         }
@@ -443,6 +434,7 @@ impl<K: SignatureKey + 'static, E: ElectionConfig + 'static> CentralizedServerNe
 
         let (pub_key, _priv_key) = K::generated_from_seed_indexed(config.seed, config.node_index);
         let known_nodes = config.config.known_nodes.clone();
+        let chain_id = config.config.chain_id;
 
         let mut streams = Some(streams);
 
@@ -461,6 +453,7 @@ impl<K: SignatureKey + 'static, E: ElectionConfig + 'static> CentralizedServerNe
                 .boxed()
             },
             pub_key,
+            chain_id,
         );
         (*config, run, result)
     }
@@ -510,9 +503,21 @@ impl<K: SignatureKey + 'static, E: ElectionConfig + 'static> CentralizedServerNe
         }
         .boxed()
     }
-    /// Connect to a centralized server
-    pub fn connect(metrics: &dyn Metrics, known_nodes: Vec<K>, addr: SocketAddr, key: K) -> Self {
-        Self::create(metrics, known_nodes, move || Self::connect_to(addr), key)
+    /// Connect to a centralized server, identifying on the chain `chain_id`
+    pub fn connect(
+        metrics: &dyn Metrics,
+        known_nodes: Vec<K>,
+        addr: SocketAddr,
+        key: K,
+        chain_id: ChainId,
+    ) -> Self {
+        Self::create(
+            metrics,
+            known_nodes,
+            move || Self::connect_to(addr),
+            key,
+            chain_id,
+        )
     }
 
     /// Create a `CentralizedServerNetwork`. Every time a new TCP connection is needed, `create_connection` is called.
@@ -523,6 +528,7 @@ impl<K: SignatureKey + 'static, E: ElectionConfig + 'static> CentralizedServerNe
         known_nodes: Vec<K>,
         mut create_connection: F,
         key: K,
+        chain_id: ChainId,
     ) -> Self
     where
         F: FnMut() -> BoxFuture<'static, (TcpStreamRecvUtil, TcpStreamSendUtil)> + Send + 'static,
@@ -555,6 +561,7 @@ impl<K: SignatureKey + 'static, E: ElectionConfig + 'static> CentralizedServerNe
                         recv_stream,
                         send_stream,
                         key.clone(),
+                        chain_id,
                         &mut to_background,
                         from_background_sender.clone(),
                         Arc::clone(&inner),
@@ -584,7 +591,7 @@ impl<K: SignatureKey + 'static, E: ElectionConfig + 'static> CentralizedServerNe
     }
 }
 
-/// Initialize a `TcpStreamUtil`. This will send an identify with key `key`.
+/// Initialize a `TcpStreamUtil`. This will send an identify with key `key` on chain `chain_id`.
 ///
 /// - All messages sent to the sender of `to_background` will be sent to the server.
 /// - All messages received from the TCP stream will be sent to `from_background_sender`.
@@ -592,13 +599,18 @@ async fn run_background<K: SignatureKey, E: ElectionConfig>(
     recv_stream: TcpStreamRecvUtil,
     mut send_stream: TcpStreamSendUtil,
     key: K,
+    chain_id: ChainId,
     to_background: &mut UnboundedReceiver<((ToServer<K>, Vec<u8>), Option<OneShotSender<()>>)>,
     from_background_sender: UnboundedSender<(FromServer<K, E>, Vec<u8>)>,
     connection: Arc<Inner<K, E>>,
 ) -> Result<(), Error> {
     // send identify
     send_stream
-        .send(ToServer::Identify { key: key.clone() })
+        .send(ToServer::Identify {
+            key: key.clone(),
+            chain_id,
+            version: ProtocolVersion::CURRENT,
+        })
         .await?;
     connection.connected.store(true, Ordering::Relaxed);
 
@@ -779,11 +791,7 @@ impl<M: NetworkMsg, K: SignatureKey + 'static, E: ElectionConfig + 'static>
         _recipients: BTreeSet<K>,
     ) -> Result<(), NetworkError> {
         self.inner
-            .broadcast(
-                bincode_opts()
-                    .serialize(&message)
-                    .context(FailedToSerializeSnafu)?,
-            )
+            .broadcast(encode_message(&message)?)
             .await;
         Ok(())
     }
@@ -791,12 +799,7 @@ impl<M: NetworkMsg, K: SignatureKey + 'static, E: ElectionConfig + 'static>
     #[instrument(name = "CentralizedServer::direct_message", skip_all)]
     async fn direct_message(&self, message: M, recipient: K) -> Result<(), NetworkError> {
         self.inner
-            .direct_message(
-                recipient,
-                bincode_opts()
-                    .serialize(&message)
-                    .context(FailedToSerializeSnafu)?,
-            )
+            .direct_message(recipient, encode_message(&message)?)
             .await;
         Ok(())
     }
@@ -809,15 +812,13 @@ impl<M: NetworkMsg, K: SignatureKey + 'static, E: ElectionConfig + 'static>
                 .get_direct_messages()
                 .await
                 .into_iter()
-                .collect::<Result<Vec<_>, _>>()
-                .context(FailedToDeserializeSnafu),
+                .collect(),
             TransmitType::Broadcast => self
                 .inner
                 .get_broadcasts()
                 .await
                 .into_iter()
-                .collect::<Result<Vec<_>, _>>()
-                .context(FailedToDeserializeSnafu),
+                .collect(),
         }
     }
 
@@ -989,6 +990,7 @@ where
                 known_nodes.clone(),
                 addr,
                 known_nodes[id as usize].clone(),
+                ChainId::default(),
             );
             network.server_shutdown_signal = Some(sender);
             CentralizedCommChannel(network, PhantomData::default())
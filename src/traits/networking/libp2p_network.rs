@@ -11,7 +11,6 @@ use async_compatibility_layer::{
 use async_lock::RwLock;
 use async_trait::async_trait;
 use bimap::BiHashMap;
-use bincode::Options;
 use hotshot_types::traits::network::ViewMessage;
 use hotshot_types::{
     data::ProposalType,
@@ -20,7 +19,7 @@ use hotshot_types::{
         election::Membership,
         metrics::{Metrics, NoMetrics},
         network::{
-            CommunicationChannel, ConnectedNetwork, FailedToSerializeSnafu, NetworkError,
+            decode_message, encode_message, CommunicationChannel, ConnectedNetwork, NetworkError,
             NetworkMsg, TestableNetworkingImplementation, TransmitType,
         },
         node_implementation::NodeType,
@@ -28,7 +27,6 @@ use hotshot_types::{
     },
     vote::VoteType,
 };
-use hotshot_utils::bincode::bincode_opts;
 use libp2p_identity::PeerId;
 use libp2p_networking::{
     network::{
@@ -40,7 +38,6 @@ use libp2p_networking::{
     reexport::Multiaddr,
 };
 use serde::Serialize;
-use snafu::ResultExt;
 use std::{
     collections::{BTreeSet, HashSet},
     marker::PhantomData,
@@ -428,7 +425,7 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> Libp2pNetwork<M, K> {
             while let Ok(msg) = handle.inner.handle.receiver().recv().await {
                 match msg {
                     GossipMsg(msg, _topic) => {
-                        let result: Result<M, _> = bincode_opts().deserialize(&msg);
+                        let result: Result<M, _> = decode_message(&msg);
                         if let Ok(result) = result {
                             broadcast_send
                                 .send(result)
@@ -437,9 +434,7 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> Libp2pNetwork<M, K> {
                         }
                     }
                     DirectRequest(msg, _pid, chan) => {
-                        let result: Result<M, _> = bincode_opts()
-                            .deserialize(&msg)
-                            .context(FailedToSerializeSnafu);
+                        let result: Result<M, _> = decode_message(&msg);
                         if let Ok(result) = result {
                             direct_send
                                 .send(result)
@@ -457,9 +452,7 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> Libp2pNetwork<M, K> {
                         };
                     }
                     DirectResponse(msg, _) => {
-                        let _result: Result<M, _> = bincode_opts()
-                            .deserialize(&msg)
-                            .context(FailedToSerializeSnafu);
+                        let _result: Result<M, _> = decode_message(&msg);
                     }
                     NetworkEvent::IsBootstrapped => {
                         is_bootstrapped.store(true, std::sync::atomic::Ordering::Relaxed);
@@ -530,7 +523,8 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, M, K> for Lib
                 .map_err(|_| NetworkError::ShutDown)?;
         }
 
-        match self.inner.handle.gossip(topic, &message).await {
+        let message = encode_message(&message)?;
+        match self.inner.handle.gossip_no_serialize(topic, message).await {
             Ok(()) => {
                 self.inner.metrics.outgoing_message_count.add(1);
                 Ok(())
@@ -591,7 +585,13 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, M, K> for Lib
             self.inner.metrics.message_failed_to_send.add(1);
             return Err(e.into());
         }
-        match self.inner.handle.direct_request(pid, &message).await {
+        let message = encode_message(&message)?;
+        match self
+            .inner
+            .handle
+            .direct_request_no_serialize(pid, message)
+            .await
+        {
             Ok(()) => {
                 self.inner.metrics.outgoing_message_count.add(1);
                 Ok(())
@@ -3,14 +3,13 @@
 //! This module provides an in-memory only simulation of an actual network, useful for unit and
 //! integration tests.
 
-use super::{FailedToSerializeSnafu, NetworkError, NetworkReliability, NetworkingMetrics};
+use super::{NetworkError, NetworkReliability, NetworkingMetrics};
 use async_compatibility_layer::{
     art::{async_sleep, async_spawn},
     channel::{bounded, Receiver, SendError, Sender},
 };
 use async_lock::{Mutex, RwLock};
 use async_trait::async_trait;
-use bincode::Options;
 use dashmap::DashMap;
 use futures::StreamExt;
 use hotshot_types::{
@@ -20,20 +19,18 @@ use hotshot_types::{
         election::Membership,
         metrics::{Metrics, NoMetrics},
         network::{
-            CommunicationChannel, ConnectedNetwork, NetworkMsg, TestableNetworkingImplementation,
-            TransmitType,
+            decode_message, encode_message, CommunicationChannel, ConnectedNetwork, NetworkMsg,
+            TestableNetworkingImplementation, TransmitType,
         },
         node_implementation::NodeType,
         signature_key::{SignatureKey, TestableSignatureKey},
     },
     vote::VoteType,
 };
-use hotshot_utils::bincode::bincode_opts;
 
 use crate::NodeImplementation;
 use hotshot_types::traits::network::ViewMessage;
 use rand::Rng;
-use snafu::ResultExt;
 use std::{
     collections::BTreeSet,
     fmt::Debug,
@@ -164,7 +161,7 @@ impl<M: NetworkMsg, K: SignatureKey> MemoryNetwork<M, K> {
                         Combo::Direct(vec) => {
                             trace!(?vec, "Incoming direct message");
                             // Attempt to decode message
-                            let x = bincode_opts().deserialize(&vec);
+                            let x = decode_message(&vec);
                             match x {
                                 Ok(x) => {
                                     let dts = direct_task_send.clone();
@@ -202,7 +199,7 @@ impl<M: NetworkMsg, K: SignatureKey> MemoryNetwork<M, K> {
                         Combo::Broadcast(vec) => {
                             trace!(?vec, "Incoming broadcast message");
                             // Attempt to decode message
-                            let x = bincode_opts().deserialize(&vec);
+                            let x = decode_message(&vec);
                             match x {
                                 Ok(x) => {
                                     let bts = broadcast_task_send.clone();
@@ -347,11 +344,9 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, M, K> for Mem
         recipients: BTreeSet<K>,
     ) -> Result<(), NetworkError> {
         debug!(?message, "Broadcasting message");
-        // Bincode the message
-        let vec = bincode_opts()
-            .serialize(&message)
-            .context(FailedToSerializeSnafu)?;
-        trace!("Message bincoded, sending");
+        // Encode the message for the wire
+        let vec = encode_message(&message)?;
+        trace!("Message encoded, sending");
         for node in self.inner.master_map.map.iter() {
             let (key, node) = node.pair();
             if !recipients.contains(key) {
@@ -376,11 +371,9 @@ impl<M: NetworkMsg, K: SignatureKey + 'static> ConnectedNetwork<M, M, K> for Mem
     #[instrument(name = "MemoryNetwork::direct_message")]
     async fn direct_message(&self, message: M, recipient: K) -> Result<(), NetworkError> {
         debug!(?message, ?recipient, "Sending direct message");
-        // Bincode the message
-        let vec = bincode_opts()
-            .serialize(&message)
-            .context(FailedToSerializeSnafu)?;
-        trace!("Message bincoded, finding recipient");
+        // Encode the message for the wire
+        let vec = encode_message(&message)?;
+        trace!("Message encoded, finding recipient");
         if let Some(node) = self.inner.master_map.map.get(&recipient) {
             let node = node.value();
             let res = node.direct_input(vec).await;
@@ -556,6 +549,7 @@ mod tests {
         message::{DataMessage, MessageKind},
         traits::{
             signature_key::ed25519::{Ed25519Priv, Ed25519Pub},
+            signer::ChainId,
             state::ConsensusTime,
         },
         vote::QuorumVote,
@@ -645,6 +639,7 @@ mod tests {
         for i in 0..num_messages {
             let message = Message {
                 sender: pk,
+                chain_id: ChainId::default(),
                 kind: MessageKind::Data(DataMessage::SubmitTransaction(
                     VDemoTransaction {
                         add: Addition {
@@ -26,14 +26,16 @@ use hotshot_types::{
     traits::{
         election::{ElectionConfig, Membership},
         network::{
-            CommunicationChannel, ConnectedNetwork, NetworkError, NetworkMsg,
-            TestableNetworkingImplementation, TransmitType, WebServerNetworkError,
+            decode_message, encode_message, CommunicationChannel, ConnectedNetwork, NetworkError,
+            NetworkMsg, TestableNetworkingImplementation, TransmitType, WebServerNetworkError,
         },
         node_implementation::NodeType,
         signature_key::{SignatureKey, TestableSignatureKey},
+        signer::ChainId,
     },
     vote::VoteType,
 };
+use hotshot_utils::version::ProtocolVersion;
 use serde::{Deserialize, Serialize};
 
 use hotshot_types::traits::network::ViewMessage;
@@ -112,7 +114,9 @@ impl<
                 }
             },
             hotshot_types::message::MessageKind::Data(message_kind) => match message_kind {
-                hotshot_types::message::DataMessage::SubmitTransaction(_, _) => {
+                // key rotations are relayed like transactions, so that leaders can include them
+                hotshot_types::message::DataMessage::SubmitTransaction(_, _)
+                | hotshot_types::message::DataMessage::KeyRotation(_) => {
                     config::post_transactions_route()
                 }
             },
@@ -153,11 +157,14 @@ impl<
 {
     /// Post a message to the web server and return the result
     async fn post_message_to_web_server(&self, message: SendMsg<M>) -> Result<(), NetworkError> {
+        let Some(body) = message.get_message() else {
+            return Ok(());
+        };
         let result: Result<(), ClientError> = self
             .inner
             .client
             .post(&message.get_endpoint())
-            .body_binary(&message.get_message())
+            .body_binary(&encode_message(&body)?)
             .unwrap()
             .send()
             .await;
@@ -206,6 +213,8 @@ struct Inner<
     client: surf_disco::Client<ClientError>,
     /// The duration to wait between poll attempts
     wait_between_polls: Duration,
+    /// The chain the web server must be serving
+    chain_id: ChainId,
 }
 
 impl<
@@ -217,6 +226,61 @@ impl<
         VOTE: VoteType<TYPES>,
     > Inner<M, KEY, ELECTIONCONFIG, TYPES, PROPOSAL, VOTE>
 {
+    /// Waits until the web server tells its chain id, and returns whether it is ours
+    async fn check_chain_id(&self) -> bool {
+        while self.running.load(Ordering::Relaxed) {
+            match self
+                .client
+                .get::<ChainId>(&config::get_chain_id_route())
+                .send()
+                .await
+            {
+                Ok(chain_id) if chain_id == self.chain_id => return true,
+                Ok(chain_id) => {
+                    error!(
+                        ?chain_id,
+                        expected = ?self.chain_id,
+                        "Web server serves another chain"
+                    );
+                    return false;
+                }
+                Err(e) => {
+                    error!(?e, "Could not get the chain id of the web server");
+                    async_sleep(self.wait_between_polls).await;
+                }
+            }
+        }
+        false
+    }
+
+    /// Waits until the web server tells its version of the wire protocol, and returns whether it is
+    /// compatible with ours
+    async fn check_version(&self) -> bool {
+        while self.running.load(Ordering::Relaxed) {
+            match self
+                .client
+                .get::<ProtocolVersion>(&config::get_version_route())
+                .send()
+                .await
+            {
+                Ok(version) if ProtocolVersion::CURRENT.is_compatible(version) => return true,
+                Ok(version) => {
+                    error!(
+                        %version,
+                        ours = %ProtocolVersion::CURRENT,
+                        "Web server speaks an incompatible protocol version"
+                    );
+                    return false;
+                }
+                Err(e) => {
+                    error!(?e, "Could not get the protocol version of the web server");
+                    async_sleep(self.wait_between_polls).await;
+                }
+            }
+        }
+        false
+    }
+
     /// Polls the web server at a given endpoint while the client is running
     async fn poll_web_server(
         &self,
@@ -312,11 +376,12 @@ impl<
             Ok(Some(messages)) => {
                 let mut deserialized_messages = Vec::new();
                 for message in &messages {
-                    let deserialized_message = bincode::deserialize(message);
-                    if let Err(e) = deserialized_message {
-                        return Err(NetworkError::FailedToDeserialize { source: e });
-                    }
-                    deserialized_messages.push(deserialized_message.unwrap());
+                    // The web server stores the bodies it was sent, which wrap the encoded messages
+                    let encoded: Vec<u8> = bincode::deserialize(message)
+                        .map_err(|source| NetworkError::FailedToDeserialize { source })?;
+                    deserialized_messages.push(RecvMsg {
+                        message: Some(decode_message(&encoded)?),
+                    });
                 }
                 Ok(Some(deserialized_messages))
             }
@@ -385,7 +450,8 @@ impl<
         VOTE: VoteType<TYPES> + 'static,
     > WebServerNetwork<M, K, E, TYPES, PROPOSAL, VOTE>
 {
-    /// Creates a new instance of the `WebServerNetwork`
+    /// Creates a new instance of the `WebServerNetwork`, taking part once the web server is found
+    /// to serve `chain_id`
     /// # Panics
     /// if the web server url is malformed
     pub fn create(
@@ -393,6 +459,7 @@ impl<
         port: u16,
         wait_between_polls: Duration,
         key: TYPES::SignatureKey,
+        chain_id: ChainId,
     ) -> Self {
         let base_url_string = format!("http://{host}:{port}");
         error!("Connecting to web server at {base_url_string:?}");
@@ -402,7 +469,6 @@ impl<
             error!("Web server url {:?} is malformed", base_url_string);
         }
 
-        // TODO ED Wait for healthcheck
         let client = surf_disco::Client::<ClientError>::new(base_url.unwrap());
 
         let inner = Arc::new(Inner {
@@ -414,9 +480,9 @@ impl<
             connected: AtomicBool::new(false),
             client,
             wait_between_polls,
+            chain_id,
             _own_key: key,
         });
-        inner.connected.store(true, Ordering::Relaxed);
 
         async_spawn({
             let inner = Arc::clone(&inner);
@@ -444,6 +510,12 @@ impl<
     async fn run_background_receive(
         inner: Arc<Inner<M, K, E, TYPES, PROPOSAL, VOTE>>,
     ) -> Result<(), ClientError> {
+        if !inner.check_chain_id().await || !inner.check_version().await {
+            inner.running.store(false, Ordering::Relaxed);
+            return Ok(());
+        }
+        inner.connected.store(true, Ordering::Relaxed);
+
         let proposal_handle = async_spawn({
             let inner_clone = inner.clone();
             async move {
@@ -737,6 +809,7 @@ where
         // Start web server
         async_spawn(hotshot_web_server::run_web_server::<TYPES::SignatureKey>(
             Some(server_shutdown),
+            ChainId::default(),
         ));
 
         let known_nodes = (0..expected_node_count as u64)
@@ -753,6 +826,7 @@ where
                 9000,
                 Duration::from_millis(100),
                 known_nodes[id as usize].clone(),
+                ChainId::default(),
             );
             network.server_shutdown_signal = Some(sender);
             WebCommChannel::new(network)
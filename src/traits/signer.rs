@@ -0,0 +1,251 @@
+//! A [`Signer`] in another process, reached over a Unix socket
+//!
+//! The node side is [`RemoteSigner`]; the process holding the key runs [`serve_signer`] around
+//! any other [`Signer`], typically a [`LocalSigner`](hotshot_types::traits::signer::LocalSigner)
+//! or a front end to an HSM or KMS. The anti-double-sign rule is enforced by the served signer,
+//! and so holds across every node process that connects to it.
+//!
+//! Each message is a little endian `u32` length followed by that many bytes of bincode.
+
+use async_trait::async_trait;
+use bincode::Options;
+use futures::{channel::oneshot, executor::block_on};
+use hotshot_types::traits::{
+    signature_key::{EncodedSignature, SignatureKey},
+    signer::{Signer, SignerError, SigningRequest},
+};
+use hotshot_utils::bincode::bincode_opts;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    io::{self, Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    time::Duration,
+};
+use tracing::{debug, warn};
+
+/// Largest message accepted from the other end of the socket
+const MAX_MESSAGE_LEN: u32 = 1 << 20;
+
+/// How long to wait on the signer before reporting it unavailable
+const SIGNER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Requests from a node to a signer process
+#[derive(Serialize, Deserialize)]
+enum Request {
+    /// Ask for the public key of the signer
+    PublicKey,
+    /// Ask for a signature
+    Sign(SigningRequest),
+}
+
+/// Responses from a signer process
+#[derive(Serialize, Deserialize)]
+enum Response<KEY> {
+    /// The public key of the signer
+    PublicKey(KEY),
+    /// The signature, or why the signer refused
+    Signature(Result<EncodedSignature, SignerError>),
+}
+
+/// Write `message` to `stream` as one length-prefixed message
+fn write_message<T: Serialize>(stream: &mut UnixStream, message: &T) -> io::Result<()> {
+    let bytes = bincode_opts()
+        .serialize(message)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let len = u32::try_from(bytes.len())
+        .ok()
+        .filter(|len| *len <= MAX_MESSAGE_LEN)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "message too long"))?;
+    stream.write_all(&len.to_le_bytes())?;
+    stream.write_all(&bytes)?;
+    stream.flush()
+}
+
+/// Read one length-prefixed message from `stream`
+fn read_message<T: DeserializeOwned>(stream: &mut UnixStream) -> io::Result<T> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len);
+    if len > MAX_MESSAGE_LEN {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "message too long"));
+    }
+    let mut bytes = vec![0u8; len as usize];
+    stream.read_exact(&mut bytes)?;
+    bincode_opts()
+        .deserialize(&bytes)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Turn an I/O error talking to the signer into a [`SignerError`]
+fn unavailable(error: &io::Error) -> SignerError {
+    SignerError::Unavailable {
+        reason: error.to_string(),
+    }
+}
+
+/// A request waiting for the connection thread of a [`RemoteSigner`], and where to answer it
+type Job<KEY> = (Request, oneshot::Sender<Result<Response<KEY>, SignerError>>);
+
+/// A [`Signer`] forwarding every request to a signer process listening on a Unix socket
+///
+/// The connection is owned by a dedicated thread, so waiting on the signer never blocks an
+/// executor thread: [`Signer::sign`] hands the request over and awaits the answer, which comes
+/// within [`SIGNER_TIMEOUT`]. A lost connection is reopened on the next request. A request whose
+/// answer was lost is simply sent again, which the signer allows since the payload is identical.
+///
+/// Remote signers do not hold the private key, so nodes drawing vote tokens with a VRF cannot
+/// use them: their elections fail with [`SignerError::NoLocalKey`].
+pub struct RemoteSigner<KEY: SignatureKey> {
+    /// path of the signer's socket
+    path: PathBuf,
+    /// the public key of the signer, fetched when connecting
+    public_key: KEY,
+    /// hands requests to the connection thread
+    jobs: Mutex<mpsc::Sender<Job<KEY>>>,
+}
+
+impl<KEY: SignatureKey + 'static> RemoteSigner<KEY> {
+    /// Connect to the signer listening on `path` and fetch its public key
+    ///
+    /// # Errors
+    /// If the signer cannot be reached or does not answer with a public key
+    pub fn connect(path: impl AsRef<Path>) -> Result<Self, SignerError> {
+        let path = path.as_ref().to_path_buf();
+        let mut stream = Self::open(&path).map_err(|e| unavailable(&e))?;
+        let Response::PublicKey(public_key) =
+            Self::exchange(&mut stream, &Request::PublicKey).map_err(|e| unavailable(&e))?
+        else {
+            return Err(SignerError::Unavailable {
+                reason: "expected a public key".to_string(),
+            });
+        };
+        let (jobs, receiver) = mpsc::channel();
+        let thread_path = path.clone();
+        std::thread::Builder::new()
+            .name("remote-signer".to_string())
+            .spawn(move || Self::run_connection(&thread_path, Some(stream), &receiver))
+            .map_err(|e| unavailable(&e))?;
+        Ok(Self {
+            path,
+            public_key,
+            jobs: Mutex::new(jobs),
+        })
+    }
+
+    /// Open a connection to the signer on `path`
+    fn open(path: &Path) -> io::Result<UnixStream> {
+        let stream = UnixStream::connect(path)?;
+        stream.set_read_timeout(Some(SIGNER_TIMEOUT))?;
+        stream.set_write_timeout(Some(SIGNER_TIMEOUT))?;
+        Ok(stream)
+    }
+
+    /// Send `request` on `stream` and wait for the response
+    fn exchange(stream: &mut UnixStream, request: &Request) -> io::Result<Response<KEY>> {
+        write_message(stream, request)?;
+        read_message(stream)
+    }
+
+    /// Answer the jobs from `receiver` over a connection to `path`, until the signer is dropped
+    fn run_connection(
+        path: &Path,
+        mut stream: Option<UnixStream>,
+        receiver: &mpsc::Receiver<Job<KEY>>,
+    ) {
+        while let Ok((request, answer)) = receiver.recv() {
+            let _ = answer.send(Self::request(path, &mut stream, &request));
+        }
+    }
+
+    /// Send `request`, reconnecting once if the open connection fails
+    fn request(
+        path: &Path,
+        stream: &mut Option<UnixStream>,
+        request: &Request,
+    ) -> Result<Response<KEY>, SignerError> {
+        if let Some(connection) = stream.as_mut() {
+            match Self::exchange(connection, request) {
+                Ok(response) => return Ok(response),
+                Err(e) => {
+                    warn!(?e, ?path, "Lost connection to signer, reconnecting");
+                    *stream = None;
+                }
+            }
+        }
+        let mut connection = Self::open(path).map_err(|e| unavailable(&e))?;
+        let response = Self::exchange(&mut connection, request).map_err(|e| unavailable(&e))?;
+        *stream = Some(connection);
+        Ok(response)
+    }
+}
+
+impl<KEY: SignatureKey> std::fmt::Debug for RemoteSigner<KEY> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteSigner")
+            .field("path", &self.path)
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<KEY: SignatureKey + 'static> Signer<KEY> for RemoteSigner<KEY> {
+    fn public_key(&self) -> &KEY {
+        &self.public_key
+    }
+
+    async fn sign(&self, request: SigningRequest) -> Result<EncodedSignature, SignerError> {
+        let (answer, response) = oneshot::channel();
+        let sent = self.jobs.lock().unwrap().send((Request::Sign(request), answer));
+        let stopped = || SignerError::Unavailable {
+            reason: "the signer connection thread stopped".to_string(),
+        };
+        sent.map_err(|_| stopped())?;
+        match response.await.map_err(|_| stopped())?? {
+            Response::Signature(result) => result,
+            Response::PublicKey(_) => Err(SignerError::Unavailable {
+                reason: "expected a signature".to_string(),
+            }),
+        }
+    }
+}
+
+/// Answer requests from [`RemoteSigner`]s connecting to `listener` with `signer`
+///
+/// Each connection is served on its own thread, all sharing `signer` and so its anti-double-sign
+/// state. Runs until accepting a connection fails, blocking the calling thread, so it belongs in
+/// a dedicated signer process or thread rather than on an executor.
+///
+/// # Errors
+/// If accepting a connection fails
+pub fn serve_signer<KEY: SignatureKey + 'static>(
+    listener: &UnixListener,
+    signer: &Arc<dyn Signer<KEY>>,
+) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let signer = signer.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = serve_connection(stream, signer.as_ref()) {
+                debug!(?e, "Signer connection closed");
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Answer requests on `stream` with `signer` until the connection closes
+fn serve_connection<KEY: SignatureKey>(
+    mut stream: UnixStream,
+    signer: &dyn Signer<KEY>,
+) -> io::Result<()> {
+    loop {
+        let response = match read_message(&mut stream)? {
+            Request::PublicKey => Response::PublicKey(signer.public_key().clone()),
+            Request::Sign(request) => Response::Signature(block_on(signer.sign(request))),
+        };
+        write_message(&mut stream, &response)?;
+    }
+}
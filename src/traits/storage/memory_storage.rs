@@ -6,6 +6,8 @@ use async_lock::RwLock;
 use async_trait::async_trait;
 use hotshot_types::{
     data::LeafType,
+    finality::FinalityProof,
+    key_rotation::KeyRotation,
     traits::{
         node_implementation::NodeType,
         storage::{
@@ -24,6 +26,10 @@ struct MemoryStorageInternal<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>>
     stored: BTreeMap<TYPES::Time, StoredView<TYPES, LEAF>>,
     /// The views that have failed
     failed: BTreeSet<TYPES::Time>,
+    /// The proofs of each decision, by the height of its oldest leaf
+    finality_proofs: BTreeMap<u64, FinalityProof<TYPES, LEAF>>,
+    /// The key rotations applied to the stake table, in order
+    key_rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
 }
 
 /// In memory, ephemeral, storage for a [`HotShot`](crate::HotShot) instance
@@ -40,6 +46,8 @@ impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> MemoryStorage<TYPES, LEA
         let inner = MemoryStorageInternal {
             stored: BTreeMap::new(),
             failed: BTreeSet::new(),
+            finality_proofs: BTreeMap::new(),
+            key_rotations: Vec::new(),
         };
         Self {
             inner: Arc::new(RwLock::new(inner)),
@@ -111,6 +119,34 @@ impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> Storage<TYPES, LEAF>
     async fn commit(&self) -> Result {
         Ok(()) // do nothing
     }
+
+    async fn append_finality_proof(&self, proof: FinalityProof<TYPES, LEAF>) -> Result {
+        let mut inner = self.inner.write().await;
+        if let Some(decided) = proof.decided() {
+            inner.finality_proofs.insert(decided.height, proof);
+        }
+        Ok(())
+    }
+
+    async fn get_finality_proof(&self, height: u64) -> Result<Option<FinalityProof<TYPES, LEAF>>> {
+        let inner = self.inner.read().await;
+        let decision = inner.finality_proofs.range(..=height).next_back();
+        Ok(decision.and_then(|(_, proof)| proof.for_height(height)))
+    }
+
+    async fn append_key_rotations(
+        &self,
+        rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
+    ) -> Result {
+        let mut inner = self.inner.write().await;
+        inner.key_rotations.extend(rotations);
+        Ok(())
+    }
+
+    async fn get_key_rotations(&self) -> Result<Vec<KeyRotation<TYPES::SignatureKey>>> {
+        let inner = self.inner.read().await;
+        Ok(inner.key_rotations.clone())
+    }
 }
 
 #[cfg(test)]
@@ -119,7 +155,7 @@ mod test {
     use crate::traits::election::static_committee::StaticVoteToken;
 
     use super::*;
-    use hotshot_types::certificate::QuorumCertificate;
+    use hotshot_types::certificate::{CertificateSignatures, QuorumCertificate};
     use hotshot_types::constants::genesis_proposer_id;
     use hotshot_types::data::fake_commitment;
     use hotshot_types::data::{ValidatingLeaf, ViewNumber};
@@ -129,7 +165,6 @@ mod test {
     use hotshot_types::traits::state::ConsensusTime;
     use hotshot_types::traits::state::ValidatingConsensus;
     use hotshot_types::traits::Block;
-    use std::collections::BTreeMap;
     use std::fmt::Debug;
     use std::hash::Hash;
     use tracing::instrument;
@@ -174,7 +209,7 @@ mod test {
                 // block_commitment: dummy_block_commit,
                 is_genesis: view_number == ViewNumber::genesis(),
                 leaf_commitment: dummy_leaf_commit,
-                signatures: BTreeMap::new(),
+                signatures: CertificateSignatures::default(),
                 view_number,
             },
             DummyBlock::random(rng),
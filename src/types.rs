@@ -1,9 +1,11 @@
 mod event;
 mod handle;
+mod receipt;
 
 pub use event::{Event, EventType};
 
 pub use handle::HotShotHandle;
+pub use receipt::{TransactionReceipt, TransactionStatus};
 
 pub(crate) use hotshot_types::error::HotShotError;
 pub use hotshot_types::{
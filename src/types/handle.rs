@@ -4,23 +4,28 @@ use crate::Message;
 use crate::QuorumCertificate;
 use crate::{
     traits::{NetworkError::ShutDown, NodeImplementation},
-    types::{Event, HotShotError::NetworkFault},
+    types::{Event, HotShotError::NetworkFault, TransactionReceipt},
     HotShot,
 };
-use async_compatibility_layer::async_primitives::broadcast::{BroadcastReceiver, BroadcastSender};
+use async_compatibility_layer::async_primitives::{
+    broadcast::{BroadcastReceiver, BroadcastSender},
+    subscribable_rwlock::ReadView,
+};
 use commit::Committable;
-use hotshot_types::traits::election::QuorumExchangeType;
+use futures::{future, Stream, StreamExt};
 use hotshot_types::traits::node_implementation::CommitteeNetwork;
 use hotshot_types::traits::node_implementation::QuorumNetwork;
 use hotshot_types::{
     data::LeafType,
     error::{HotShotError, RoundTimedoutState},
     event::EventType,
+    key_rotation::KeyRotation,
     traits::{
         election::ConsensusExchange, election::SignedCertificate, network::CommunicationChannel,
         node_implementation::NodeType, state::ConsensusTime, storage::Storage,
     },
     vote::QuorumVote,
+    HotShotConfigUpdate,
 };
 use std::sync::{
     atomic::{AtomicBool, Ordering},
@@ -28,12 +33,18 @@ use std::sync::{
 };
 use tracing::{debug, error};
 
-#[cfg(feature = "hotshot-testing")]
-use crate::HotShotConsensusApi;
 #[cfg(feature = "hotshot-testing")]
 use commit::Commitment;
 #[cfg(feature = "hotshot-testing")]
-use hotshot_types::{message::ConsensusMessage, traits::signature_key::EncodedSignature};
+use hotshot_types::{
+    message::ConsensusMessage,
+    traits::{
+        election::{ElectionError, VoteData},
+        signature_key::{EncodedSignature, SignatureKey},
+        signer::{signing_payload, SignerError, SigningKind},
+    },
+    vote::YesOrNoVote,
+};
 
 /// Event streaming handle for a [`HotShot`] instance running in the background
 ///
@@ -111,6 +122,33 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static> HotShotHandle<TYPE
         Ok(output)
     }
 
+    /// Returns a [`Stream`] of all events emitted by the [`HotShot`] instance from now on
+    ///
+    /// Every call creates a new, independent subscriber, so several streams can be consumed
+    /// concurrently without stealing events from each other or from this handle. The stream ends
+    /// once the underlying [`HotShot`] has shut down.
+    pub fn event_stream(&self) -> impl Stream<Item = Event<TYPES, I::Leaf>> + Send + 'static {
+        futures::stream::unfold(self.sender_handle.handle_sync(), |mut receiver| async move {
+            let event = receiver.recv_async().await.ok()?;
+            Some((event, receiver))
+        })
+    }
+
+    /// Returns a [`Stream`] of the events whose [`EventType`] passes `filter`
+    ///
+    /// Intended for selecting variants, e.g. `|e| matches!(e, EventType::Decide { .. })`. Like
+    /// [`Self::event_stream`], each call creates a new, independent subscriber.
+    pub fn filtered_event_stream<F>(
+        &self,
+        filter: F,
+    ) -> impl Stream<Item = Event<TYPES, I::Leaf>> + Send + 'static
+    where
+        F: Fn(&EventType<TYPES, I::Leaf>) -> bool + Send + 'static,
+    {
+        self.event_stream()
+            .filter(move |event| future::ready(filter(&event.event)))
+    }
+
     /// Gets the current committed state of the [`HotShot`] instance
     ///
     /// # Errors
@@ -130,7 +168,8 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static> HotShotHandle<TYPE
 
     /// Submits a transaction to the backing [`HotShot`] instance.
     ///
-    /// The current node broadcasts the transaction to all nodes on the network.
+    /// The current node broadcasts the transaction to all nodes on the network. The returned
+    /// [`TransactionReceipt`] can be used to follow the transaction until it is decided or rejected.
     ///
     /// # Errors
     ///
@@ -139,8 +178,11 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static> HotShotHandle<TYPE
     pub async fn submit_transaction(
         &self,
         tx: TYPES::Transaction,
-    ) -> Result<(), HotShotError<TYPES>> {
-        self.hotshot.publish_transaction_async(tx).await
+    ) -> Result<TransactionReceipt<TYPES, I::Leaf>, HotShotError<TYPES>> {
+        // subscribe before publishing so that no event about the transaction can be missed
+        let receipt = TransactionReceipt::new(&tx, self.sender_handle.handle_async().await);
+        self.hotshot.publish_transaction_async(tx).await?;
+        Ok(receipt)
     }
 
     /// Signals to the underlying [`HotShot`] to unpause
@@ -160,6 +202,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static> HotShotHandle<TYPE
                     event: EventType::Decide {
                         leaf_chain: Arc::new(vec![leaf]),
                         qc: Arc::new(qc),
+                        finality_proof: None,
                     },
                 };
                 if self.sender_handle.send_async(event).await.is_err() {
@@ -269,17 +312,111 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static> HotShotHandle<TYPE
             .await;
     }
 
+    /// Stop proposing and voting once the view currently in progress has finished.
+    ///
+    /// Messages keep being received while paused, so the node can catch up after [`Self::resume`].
+    pub async fn pause(&self) {
+        self.hotshot.inner.background_task_handle.pause().await;
+    }
+
+    /// Continue running consensus after [`Self::pause`]
+    pub async fn resume(&self) {
+        self.hotshot.inner.background_task_handle.resume().await;
+    }
+
+    /// Returns `true` if the underlying [`HotShot`] has been paused
+    pub async fn is_paused(&self) -> bool {
+        self.hotshot.inner.background_task_handle.is_paused().await
+    }
+
+    /// Shut down the inner hotshot without interrupting the view in progress.
+    ///
+    /// New transactions are refused, the in-flight view is allowed to finish and storage is
+    /// committed before the background tasks are torn down. Returns the transactions submitted
+    /// through this node that no proposal included and no leaf decided, so they can be
+    /// resubmitted elsewhere.
+    pub async fn shut_down_gracefully(self) -> Vec<TYPES::Transaction> {
+        self.hotshot
+            .inner
+            .accepting_transactions
+            .store(false, Ordering::Relaxed);
+        self.pause().await;
+        self.hotshot
+            .inner
+            .background_task_handle
+            .wait_paused()
+            .await;
+
+        if let Err(e) = self.storage.commit().await {
+            error!(?e, "Could not commit storage during graceful shutdown");
+        }
+
+        let unsubmitted = self
+            .hotshot
+            .inner
+            .submitted_transactions
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect();
+        self.shut_down().await;
+        unsubmitted
+    }
+
+    /// Change consensus parameters of the underlying [`HotShot`] at a view boundary.
+    ///
+    /// `update` takes effect at the start of `activation_view`, or of the next view if `None`. To
+    /// switch every node at once, e.g. after an on-chain parameter change has been decided, derive
+    /// `activation_view` from the decided leaf on all nodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HotShotError::InvalidConfig`] if the update would leave the configuration
+    /// inconsistent or `activation_view` has already started.
+    pub async fn update_config(
+        &self,
+        update: HotShotConfigUpdate,
+        activation_view: Option<TYPES::Time>,
+    ) -> Result<(), HotShotError<TYPES>> {
+        self.hotshot.update_config(update, activation_view).await
+    }
+
+    /// Submit a key rotation to the underlying [`HotShot`], which broadcasts it so that it is
+    /// decided and applied by every node. See [`HotShot::rotate_key`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HotShotError::InvalidKeyRotation`] if the rotation is for another chain, is not
+    /// signed by both keys, or does not take effect at the start of a late enough epoch.
+    pub async fn rotate_key(
+        &self,
+        rotation: KeyRotation<TYPES::SignatureKey>,
+    ) -> Result<(), HotShotError<TYPES>> {
+        self.hotshot.rotate_key(rotation).await
+    }
+
     /// return the timeout for a view of the underlying `HotShot`
     pub fn get_next_view_timeout(&self) -> u64 {
         self.hotshot.get_next_view_timeout()
     }
 
+    /// The leaders of the `count` views starting at `from`, in view order
+    ///
+    /// Useful to pre-connect to upcoming leaders or to route traffic to them.
+    pub fn leader_schedule(&self, from: TYPES::Time, count: usize) -> Vec<TYPES::SignatureKey> {
+        self.hotshot.inner.quorum_exchange.leader_schedule(from, count)
+    }
+
     // Below is for testing only:
 
     /// Wrapper for `HotShotConsensusApi`'s `get_leader` function
     #[allow(clippy::unused_async)] // async for API compatibility reasons
     #[cfg(feature = "hotshot-testing")]
-    pub async fn get_leader(&self, view_number: TYPES::Time) -> TYPES::SignatureKey {
+    pub async fn get_leader(
+        &self,
+        view_number: TYPES::Time,
+    ) -> Result<TYPES::SignatureKey, ElectionError> {
         self.hotshot.inner.quorum_exchange.get_leader(view_number)
     }
 
@@ -295,24 +432,51 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static> HotShotHandle<TYPE
         self.hotshot.hotstuff.read().await.cur_view
     }
 
-    /// Wrapper around `HotShotConsensusApi`'s `sign_validating_or_commitment_proposal` function
+    /// The transactions waiting in this node's mempool
+    #[cfg(feature = "hotshot-testing")]
+    pub async fn get_pending_transactions(&self) -> Vec<TYPES::Transaction> {
+        self.hotshot
+            .transactions
+            .cloned()
+            .await
+            .into_values()
+            .collect()
+    }
+
+    /// Sign a validating or commitment proposal for `view_number` with this node's private key
+    ///
+    /// Signs directly rather than through the node's signer, so tests can inject conflicting
+    /// proposals without tripping its anti-double-sign rule.
+    ///
+    /// # Errors
+    /// If this node's signer does not hold its private key in memory
     #[cfg(feature = "hotshot-testing")]
     pub fn sign_validating_or_commitment_proposal(
         &self,
+        view_number: TYPES::Time,
         leaf_commitment: &Commitment<I::Leaf>,
-    ) -> EncodedSignature
-    where
-        I::QuorumExchange: QuorumExchangeType<TYPES, I::Leaf, Message<TYPES, I>>,
-    {
-        let api = HotShotConsensusApi {
-            inner: self.hotshot.inner.clone(),
-        };
-        api.inner
-            .quorum_exchange
-            .sign_validating_or_commitment_proposal::<I>(leaf_commitment)
+    ) -> Result<EncodedSignature, SignerError> {
+        let private_key = self
+            .hotshot
+            .inner
+            .signer
+            .local_private_key()
+            .ok_or(SignerError::NoLocalKey)?;
+        let payload = signing_payload(
+            SigningKind::QuorumProposal,
+            self.hotshot.inner.quorum_exchange.chain_id(),
+            *view_number,
+            leaf_commitment.as_ref(),
+        );
+        Ok(TYPES::SignatureKey::sign(private_key, &payload))
     }
 
-    /// create a yes message
+    /// create a yes message, signed with this node's private key
+    ///
+    /// Like [`Self::sign_validating_or_commitment_proposal`], this bypasses the node's signer.
+    ///
+    /// # Errors
+    /// If this node's signer does not hold its private key in memory
     #[cfg(feature = "hotshot-testing")]
     pub fn create_yes_message(
         &self,
@@ -320,25 +484,28 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static> HotShotHandle<TYPE
         leaf_commitment: Commitment<I::Leaf>,
         current_view: TYPES::Time,
         vote_token: TYPES::VoteTokenType,
-    ) -> ConsensusMessage<TYPES, I>
+    ) -> Result<ConsensusMessage<TYPES, I>, SignerError>
     where
-        I::QuorumExchange: QuorumExchangeType<
+        I::QuorumExchange: ConsensusExchange<
             TYPES,
             I::Leaf,
             Message<TYPES, I>,
-            Certificate = QuorumCertificate<TYPES, I::Leaf>,
             Vote = QuorumVote<TYPES, I::Leaf>,
         >,
     {
-        let api = HotShotConsensusApi {
-            inner: self.hotshot.inner.clone(),
-        };
-        api.inner.quorum_exchange.create_yes_message(
+        let signer = &self.hotshot.inner.signer;
+        let private_key = signer.local_private_key().ok_or(SignerError::NoLocalKey)?;
+        let chain_id = self.hotshot.inner.quorum_exchange.chain_id();
+        let data = VoteData::<TYPES, I::Leaf>::Yes(leaf_commitment);
+        let signature =
+            TYPES::SignatureKey::sign(private_key, &data.signing_payload(chain_id, current_view));
+        Ok(ConsensusMessage::Vote(QuorumVote::Yes(YesOrNoVote {
             justify_qc_commitment,
+            signature: (signer.public_key().to_bytes(), signature),
             leaf_commitment,
             current_view,
             vote_token,
-        )
+        })))
     }
 
     /// Wrapper around `HotShotConsensusApi`'s `send_broadcast_consensus_message` function
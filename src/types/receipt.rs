@@ -0,0 +1,249 @@
+//! Tracking of submitted transactions through consensus
+
+use crate::{
+    traits::NetworkError::ShutDown,
+    types::{Event, EventType, HotShotError::NetworkFault},
+};
+use async_compatibility_layer::async_primitives::broadcast::BroadcastReceiver;
+use commit::{Commitment, Committable};
+use hotshot_types::{
+    data::{DeltasType, LeafType},
+    error::HotShotError,
+    traits::{node_implementation::NodeType, Block},
+};
+
+/// The status of a transaction submitted through a [`HotShotHandle`](crate::types::HotShotHandle)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransactionStatus<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> {
+    /// The transaction is waiting in the mempool
+    Pending,
+    /// The transaction was included in a proposal, which has not been decided yet
+    Included {
+        /// The view of the proposal
+        view_number: TYPES::Time,
+    },
+    /// The transaction was decided
+    Decided {
+        /// The height of the leaf containing the transaction
+        height: u64,
+        /// Commitment to the leaf containing the transaction
+        leaf: Commitment<LEAF>,
+    },
+    /// This node, as leader, left the transaction out of its proposal because it failed
+    /// validation. The transaction stays in the mempool, and may still be included by later leaders
+    RejectedInView {
+        /// The view of the proposal the transaction was left out of
+        view_number: TYPES::Time,
+    },
+    /// The transaction was rejected by a decided leaf
+    Rejected {
+        /// The view in which the transaction was rejected
+        view_number: TYPES::Time,
+    },
+}
+
+impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> TransactionStatus<TYPES, LEAF> {
+    /// Returns `true` if the status of the transaction can no longer change
+    #[must_use]
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Decided { .. } | Self::Rejected { .. })
+    }
+}
+
+/// A receipt for a transaction submitted through a [`HotShotHandle`](crate::types::HotShotHandle)
+///
+/// The receipt follows the events of the [`HotShot`](crate::HotShot) instance it was submitted to,
+/// and can either be polled with [`TransactionReceipt::status`] or awaited with
+/// [`TransactionReceipt::wait`]. Events are buffered for as long as the receipt is alive, so it
+/// should be dropped once the caller is no longer interested in the transaction.
+pub struct TransactionReceipt<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> {
+    /// Commitment to the submitted transaction
+    commitment: Commitment<TYPES::Transaction>,
+    /// The latest known status
+    status: TransactionStatus<TYPES, LEAF>,
+    /// Events of the `HotShot` instance, subscribed to before the transaction was submitted
+    events: BroadcastReceiver<Event<TYPES, LEAF>>,
+}
+
+impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> TransactionReceipt<TYPES, LEAF> {
+    /// Create a receipt for `transaction`, fed by `events`
+    pub(crate) fn new(
+        transaction: &TYPES::Transaction,
+        events: BroadcastReceiver<Event<TYPES, LEAF>>,
+    ) -> Self {
+        Self {
+            commitment: transaction.commit(),
+            status: TransactionStatus::Pending,
+            events,
+        }
+    }
+
+    /// Commitment to the submitted transaction
+    pub fn commitment(&self) -> Commitment<TYPES::Transaction> {
+        self.commitment
+    }
+
+    /// Returns the latest known status, without waiting for new events
+    pub fn status(&mut self) -> &TransactionStatus<TYPES, LEAF> {
+        while !self.status.is_final() {
+            match self.events.try_recv() {
+                Some(event) => self.apply(&event),
+                None => break,
+            }
+        }
+        &self.status
+    }
+
+    /// Waits until the transaction is either decided or rejected by a decided leaf
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HotShotError::NetworkFault`] if the underlying [`HotShot`](crate::HotShot) shuts
+    /// down first.
+    pub async fn wait(&mut self) -> Result<TransactionStatus<TYPES, LEAF>, HotShotError<TYPES>> {
+        while !self.status.is_final() {
+            let event = self
+                .events
+                .recv_async()
+                .await
+                .map_err(|_| NetworkFault { source: ShutDown })?;
+            self.apply(&event);
+        }
+        Ok(self.status.clone())
+    }
+
+    /// Update the status with a single event
+    fn apply(&mut self, event: &Event<TYPES, LEAF>) {
+        match &event.event {
+            EventType::TransactionsIncluded { transactions }
+                if matches!(
+                    self.status,
+                    TransactionStatus::Pending | TransactionStatus::RejectedInView { .. }
+                ) && transactions.contains(&self.commitment) =>
+            {
+                self.status = TransactionStatus::Included {
+                    view_number: event.view_number,
+                };
+            }
+            EventType::TransactionsRejected { transactions }
+                if matches!(
+                    self.status,
+                    TransactionStatus::Pending | TransactionStatus::RejectedInView { .. }
+                ) && transactions.contains(&self.commitment) =>
+            {
+                self.status = TransactionStatus::RejectedInView {
+                    view_number: event.view_number,
+                };
+            }
+            EventType::Decide { leaf_chain, .. } => {
+                for leaf in leaf_chain.iter() {
+                    if leaf
+                        .get_rejected()
+                        .iter()
+                        .any(|txn| txn.commit() == self.commitment)
+                    {
+                        self.status = TransactionStatus::Rejected {
+                            view_number: leaf.get_view_number(),
+                        };
+                        return;
+                    }
+                    if let Ok(block) = leaf.get_deltas().try_resolve() {
+                        if block.contained_transactions().contains(&self.commitment) {
+                            self.status = TransactionStatus::Decided {
+                                height: leaf.get_height(),
+                                leaf: leaf.commit(),
+                            };
+                            return;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(all(test, feature = "demo"))]
+mod tests {
+    use super::*;
+    use crate::demos::vdemo::{Addition, Subtraction, VDemoTransaction, VDemoTypes};
+    use async_compatibility_layer::async_primitives::broadcast::channel;
+    use hotshot_types::data::{ValidatingLeaf, ViewNumber};
+    use hotshot_types::traits::state::ConsensusTime;
+    use std::sync::Arc;
+
+    type Receipt = TransactionReceipt<VDemoTypes, ValidatingLeaf<VDemoTypes>>;
+
+    fn receipt() -> Receipt {
+        let transaction = VDemoTransaction {
+            add: Addition {
+                account: "joe".to_string(),
+                amount: 10,
+            },
+            sub: Subtraction {
+                account: "nathan".to_string(),
+                amount: 10,
+            },
+            nonce: 0,
+            padding: Vec::new(),
+        };
+        let (_, events) = channel();
+        Receipt::new(&transaction, events)
+    }
+
+    fn event(
+        view: u64,
+        event: EventType<VDemoTypes, ValidatingLeaf<VDemoTypes>>,
+    ) -> Event<VDemoTypes, ValidatingLeaf<VDemoTypes>> {
+        Event {
+            view_number: ViewNumber::new(view),
+            event,
+        }
+    }
+
+    #[test]
+    fn rejection_after_inclusion_is_ignored() {
+        let mut receipt = receipt();
+        let transactions = Arc::new(vec![receipt.commitment()]);
+
+        receipt.apply(&event(
+            1,
+            EventType::TransactionsIncluded {
+                transactions: transactions.clone(),
+            },
+        ));
+        receipt.apply(&event(2, EventType::TransactionsRejected { transactions }));
+        assert_eq!(
+            receipt.status,
+            TransactionStatus::Included {
+                view_number: ViewNumber::new(1)
+            }
+        );
+    }
+
+    #[test]
+    fn rejected_transaction_can_be_included_later() {
+        let mut receipt = receipt();
+        let transactions = Arc::new(vec![receipt.commitment()]);
+
+        receipt.apply(&event(
+            1,
+            EventType::TransactionsRejected {
+                transactions: transactions.clone(),
+            },
+        ));
+        assert_eq!(
+            receipt.status,
+            TransactionStatus::RejectedInView {
+                view_number: ViewNumber::new(1)
+            }
+        );
+        receipt.apply(&event(2, EventType::TransactionsIncluded { transactions }));
+        assert_eq!(
+            receipt.status,
+            TransactionStatus::Included {
+                view_number: ViewNumber::new(2)
+            }
+        );
+    }
+}
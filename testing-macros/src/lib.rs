@@ -16,6 +16,36 @@ enum SupportedConsensusTypes {
     SequencingConsensus,
 }
 
+/// Supported membership types by macro
+#[derive(Debug, Clone)]
+enum SupportedMembershipTypes {
+    StaticCommittee,
+    Vrf,
+}
+
+impl SupportedMembershipTypes {
+    /// parse a membership type from the identifier naming it
+    fn from_expr(expr: &Expr) -> Self {
+        let Expr::Path(expr_path) = expr else { panic!("Expected path expr for Membership, got {:?}", expr) };
+        let Some(ident) = expr_path.path.get_ident() else { panic!("Expected ident, got {:?}", expr_path.path) };
+        if ident == "StaticCommittee" {
+            SupportedMembershipTypes::StaticCommittee
+        } else if ident == "Vrf" {
+            SupportedMembershipTypes::Vrf
+        } else {
+            panic!("Unsupported membership type: {ident:?}")
+        }
+    }
+
+    /// name of the module the tests using this membership are generated in
+    fn module_name(&self) -> Ident {
+        match self {
+            SupportedMembershipTypes::StaticCommittee => format_ident!("static_committee"),
+            SupportedMembershipTypes::Vrf => format_ident!("vrf"),
+        }
+    }
+}
+
 /// description of a crosstest
 #[derive(derive_builder::Builder, Debug, Clone)]
 struct CrossTestData {
@@ -29,6 +59,9 @@ struct CrossTestData {
     comm_channels: ExprArray,
     /// storage impls
     storages: ExprArray,
+    /// membership impls, the static committee if not given
+    #[builder(default)]
+    memberships: Option<ExprArray>,
     /// name of the test
     test_name: Ident,
     /// test description/spec
@@ -107,6 +140,7 @@ struct TestData {
     signature_key_type: ExprPath,
     comm_channel: ExprPath,
     storage: ExprPath,
+    membership: SupportedMembershipTypes,
     test_name: Ident,
     test_description: Expr,
     slow: LitBool,
@@ -158,6 +192,7 @@ impl TestData {
             slow,
             comm_channel,
             storage,
+            membership,
         } = self;
 
         let (supported_consensus_type, demo_state) = {
@@ -196,12 +231,18 @@ impl TestData {
                     let leaf = quote! {
                         hotshot_types::data::SequencingLeaf<TestTypes>
                     };
-                    let vote = quote! {
+                    let da_vote = quote! {
                         hotshot_types::vote::DAVote<TestTypes, #leaf>
                     };
-                    let proposal = quote! {
+                    let da_proposal = quote! {
                         hotshot_types::data::DAProposal<TestTypes>
                     };
+                    let vote = quote! {
+                        hotshot_types::vote::QuorumVote<TestTypes, #leaf>
+                    };
+                    let proposal = quote! {
+                        hotshot_types::data::CommitmentProposal<TestTypes, #leaf>
+                    };
                     let committee_exchange = quote! {
                         hotshot_types::traits::election::CommitteeExchange<
                             TestTypes,
@@ -210,8 +251,8 @@ impl TestData {
                             #comm_channel<
                                 TestTypes,
                                 TestNodeImpl,
-                                #proposal,
-                                #vote,
+                                #da_proposal,
+                                #da_vote,
                                 CommitteeMembership,
                             >,
                             hotshot_types::message::Message<TestTypes, TestNodeImpl>,
@@ -240,6 +281,45 @@ impl TestData {
                 }
             };
 
+        let (vote_token, election_config, membership) = match membership {
+            SupportedMembershipTypes::StaticCommittee => (
+                quote! {
+                    hotshot::traits::election::static_committee::StaticVoteToken<Self::SignatureKey>
+                },
+                quote! {
+                    hotshot::traits::election::static_committee::StaticElectionConfig
+                },
+                quote! {
+                    hotshot::traits::election::static_committee::GeneralStaticCommittee<
+                        TestTypes,
+                        #leaf,
+                        #signature_key_type,
+                    >
+                },
+            ),
+            SupportedMembershipTypes::Vrf => (
+                quote! {
+                    hotshot::traits::election::vrf::VRFVoteToken<
+                        jf_primitives::signatures::bls::BLSVerKey<ark_bls12_381::Parameters>,
+                        jf_primitives::signatures::bls::BLSSignature<ark_bls12_381::Parameters>,
+                    >
+                },
+                quote! {
+                    hotshot::traits::election::vrf::VRFStakeTableConfig
+                },
+                quote! {
+                    hotshot::traits::election::vrf::VrfImpl<
+                        TestTypes,
+                        #leaf,
+                        jf_primitives::signatures::BLSSignatureScheme<ark_bls12_381::Parameters>,
+                        jf_primitives::vrf::blsvrf::BLSVRFScheme<ark_bls12_381::Parameters>,
+                        blake3::Hasher,
+                        ark_bls12_381::Parameters,
+                    >
+                },
+            ),
+        };
+
         quote! {
 
                 #[derive(
@@ -280,11 +360,11 @@ impl TestData {
                     type SignatureKey = #signature_key_type;
                     type Transaction = <<#demo_state as hotshot_types::traits::State>::BlockType as hotshot_types::traits::Block>::Transaction;
                     type StateType = #demo_state;
-                    type VoteTokenType = hotshot::traits::election::static_committee::StaticVoteToken<Self::SignatureKey>;
-                    type ElectionConfigType = hotshot::traits::election::static_committee::StaticElectionConfig;
+                    type VoteTokenType = #vote_token;
+                    type ElectionConfigType = #election_config;
                 }
 
-                type CommitteeMembership = hotshot::traits::election::static_committee::GeneralStaticCommittee<TestTypes, #leaf, #signature_key_type>;
+                type CommitteeMembership = #membership;
 
                 type TestQuorumExchange =
                         hotshot_types::traits::election::QuorumExchange<
@@ -341,6 +421,7 @@ mod keywords {
     syn::custom_keyword!(Slow);
     syn::custom_keyword!(CommChannel);
     syn::custom_keyword!(Storage);
+    syn::custom_keyword!(Membership);
 }
 
 impl Parse for CrossTestData {
@@ -373,6 +454,11 @@ impl Parse for CrossTestData {
                 input.parse::<Token![:]>()?;
                 let storages = input.parse::<ExprArray>()?;
                 description.storages(storages);
+            } else if input.peek(keywords::Membership) {
+                let _ = input.parse::<keywords::Membership>()?;
+                input.parse::<Token![:]>()?;
+                let memberships = input.parse::<ExprArray>()?;
+                description.memberships(Some(memberships));
             } else if input.peek(keywords::TestName) {
                 let _ = input.parse::<keywords::TestName>()?;
                 input.parse::<Token![:]>()?;
@@ -389,12 +475,22 @@ impl Parse for CrossTestData {
                 let slow = input.parse::<LitBool>()?;
                 description.slow(slow);
             } else {
-                panic!("Unexpected token. Expected one f: Time, DemoType, SignatureKey, CommChannel, Storage, TestName, TestDescription, Slow");
+                panic!("Unexpected token. Expected one f: Time, DemoType, SignatureKey, CommChannel, Storage, Membership, TestName, TestDescription, Slow");
             }
             if input.peek(Token![,]) {
                 input.parse::<Token![,]>()?;
             }
         }
+        // `Membership` is optional, so it may follow the last required argument
+        if input.peek(keywords::Membership) {
+            let _ = input.parse::<keywords::Membership>()?;
+            input.parse::<Token![:]>()?;
+            let memberships = input.parse::<ExprArray>()?;
+            description.memberships(Some(memberships));
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            }
+        }
         description
             .build()
             .map_err(|e| syn::Error::new(proc_macro2::Span::call_site(), format!("{}", e)))
@@ -432,6 +528,16 @@ fn cross_tests_internal(test_spec: CrossTestData) -> TokenStream {
         p
     });
 
+    // without an explicit `Membership` list, tests use the static committee and keep their paths
+    let memberships = match &test_spec.memberships {
+        Some(memberships) => memberships
+            .elems
+            .iter()
+            .map(|t| (SupportedMembershipTypes::from_expr(t), true))
+            .collect::<Vec<_>>(),
+        None => vec![(SupportedMembershipTypes::StaticCommittee, false)],
+    };
+
     let mut result = quote! {};
 
     for demo_type in demo_types.clone() {
@@ -443,18 +549,33 @@ fn cross_tests_internal(test_spec: CrossTestData) -> TokenStream {
                 for time_type in time_types.clone() {
                     let mut time_mod = quote! {};
                     for signature_key_type in signature_key_types.clone() {
-                        let test_data = TestDataBuilder::create_empty()
-                            .time_type(time_type.clone())
-                            .demo_types(demo_type.clone())
-                            .signature_key_type(signature_key_type.clone())
-                            .comm_channel(comm_channel.clone())
-                            .storage(storage.clone())
-                            .test_name(test_spec.test_name.clone())
-                            .test_description(test_spec.test_description.clone())
-                            .slow(test_spec.slow.clone())
-                            .build()
-                            .unwrap();
-                        let test = test_data.generate_test();
+                        let mut test = quote! {};
+                        for (membership, named) in &memberships {
+                            let test_data = TestDataBuilder::create_empty()
+                                .time_type(time_type.clone())
+                                .demo_types(demo_type.clone())
+                                .signature_key_type(signature_key_type.clone())
+                                .comm_channel(comm_channel.clone())
+                                .storage(storage.clone())
+                                .membership(membership.clone())
+                                .test_name(test_spec.test_name.clone())
+                                .test_description(test_spec.test_description.clone())
+                                .slow(test_spec.slow.clone())
+                                .build()
+                                .unwrap();
+                            let membership_test = test_data.generate_test();
+                            if *named {
+                                let membership_str = membership.module_name();
+                                test.extend(quote! {
+                                    pub mod #membership_str {
+                                        use super::*;
+                                        #membership_test
+                                    }
+                                });
+                            } else {
+                                test.extend(membership_test);
+                            }
+                        }
 
                         let signature_key_str =
                             format_ident!("{}", signature_key_type.to_lower_snake_str());
@@ -522,6 +643,10 @@ fn cross_tests_internal(test_spec: CrossTestData) -> TokenStream {
 /// - `TestName: example_test` - the name of the test
 /// - `TestDescription: { some_test_description_expression }` - the `TestDescription` to use
 /// - `Storage: Storage1, Storage2, ...` - a list of `Storage` implementations to use
+/// - `Membership: [StaticCommittee, Vrf]` - optional, the elections to use. Defaults to the static
+///   committee. `Vrf` requires the signature key to be
+///   `hotshot::traits::election::vrf::BlsPubKey` and the calling crate to depend on
+///   `ark-bls12-381`, `blake3` and `jf-primitives`
 /// - `Slow`: whether or not this set of tests are hidden behind the `slow` feature flag
 /// Example usage:
 /// ```
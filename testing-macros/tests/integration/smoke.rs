@@ -33,3 +33,18 @@ cross_tests!(
     },
     Slow: false,
 );
+
+cross_tests!(
+    DemoType: [
+        (ValidatingConsensus, hotshot::demos::vdemo::VDemoState),
+        (SequencingConsensus, hotshot::demos::sdemo::SDemoState)
+    ],
+    SignatureKey: [ hotshot::traits::election::vrf::BlsPubKey ],
+    CommChannel: [ hotshot::traits::implementations::MemoryCommChannel ],
+    Storage: [ hotshot::traits::implementations::MemoryStorage ],
+    Time: [ hotshot_types::data::ViewNumber ],
+    Membership: [ StaticCommittee, Vrf ],
+    TestName: multiple_rounds_by_membership,
+    TestDescription: hotshot_testing::test_description::GeneralTestDescriptionBuilder::default_multiple_rounds(),
+    Slow: false,
+);
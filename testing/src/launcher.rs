@@ -5,7 +5,11 @@ use hotshot::types::SignatureKey;
 
 use hotshot_types::traits::node_implementation::{CommitteeNetwork, QuorumNetwork};
 use hotshot_types::{
-    traits::node_implementation::{NodeImplementation, NodeType},
+    constants::default_key_rotation_epoch,
+    traits::{
+        node_implementation::{NodeImplementation, NodeType},
+        signer::ChainId,
+    },
     ExecutionType, HotShotConfig,
 };
 use std::{num::NonZeroUsize, time::Duration};
@@ -28,11 +32,16 @@ impl<TYPES: NodeType, I: TestableNodeImplementation<TYPES>> TestLauncher<TYPES,
         min_transactions: usize,
         election_config: TYPES::ElectionConfigType,
     ) -> Self {
-        let known_nodes = (0..expected_node_count)
-            .map(|id| {
-                let priv_key = I::generate_test_key(id as u64);
-                TYPES::SignatureKey::from_private(&priv_key)
-            })
+        let private_keys: Vec<_> = (0..expected_node_count)
+            .map(|id| I::generate_test_key(id as u64))
+            .collect();
+        let known_nodes = private_keys
+            .iter()
+            .map(TYPES::SignatureKey::from_private)
+            .collect();
+        let possession_proofs = private_keys
+            .iter()
+            .filter_map(TYPES::SignatureKey::prove_possession)
             .collect();
         let config = HotShotConfig {
             execution_type: ExecutionType::Incremental,
@@ -41,6 +50,7 @@ impl<TYPES: NodeType, I: TestableNodeImplementation<TYPES>> TestLauncher<TYPES,
             min_transactions,
             max_transactions: NonZeroUsize::new(99999).unwrap(),
             known_nodes,
+            possession_proofs,
             next_view_timeout: 500,
             timeout_ratio: (11, 10),
             round_start_delay: 1,
@@ -48,6 +58,10 @@ impl<TYPES: NodeType, I: TestableNodeImplementation<TYPES>> TestLauncher<TYPES,
             propose_min_round_time: Duration::from_millis(0),
             propose_max_round_time: Duration::from_millis(1000),
             election_config: Some(election_config),
+            adaptive_timeout: None,
+            transaction_forwarding: None,
+            chain_id: ChainId::default(),
+            key_rotation_epoch: default_key_rotation_epoch(),
         };
 
         Self {
@@ -22,7 +22,7 @@ pub use self::launcher::TestLauncher;
 use futures::future::LocalBoxFuture;
 use hotshot::{
     traits::{NodeImplementation, TestableNodeImplementation},
-    types::{HotShotHandle, SignatureKey},
+    types::HotShotHandle,
     HotShot, HotShotError, HotShotInitializer, ViewRunner, H_256,
 };
 use hotshot_types::traits::election::ConsensusExchange;
@@ -31,11 +31,14 @@ use hotshot_types::message::Message;
 use hotshot_types::traits::node_implementation::{CommitteeNetwork, QuorumNetwork};
 use hotshot_types::{
     data::LeafType,
-    traits::{election::Membership, metrics::NoMetrics, node_implementation::NodeType},
+    traits::{
+        election::Membership, metrics::NoMetrics, network::CommunicationChannel,
+        node_implementation::NodeType, signer::LocalSigner,
+    },
     HotShotConfig,
 };
 use snafu::Snafu;
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 use tracing::{debug, error, info, warn};
 
 /// Wrapper for a function that takes a `node_id` and returns an instance of `T`.
@@ -148,8 +151,11 @@ impl<TYPES: NodeType, I: TestableNodeImplementation<TYPES>> TestRunner<TYPES, I>
             let committee_network = (self.committee_network_generator)(node_id);
             let storage = (self.storage_generator)(node_id);
             let config = self.default_node_config.clone();
-            let initializer =
-                HotShotInitializer::<TYPES, I::Leaf>::from_genesis(I::block_genesis()).unwrap();
+            let initializer = HotShotInitializer::<TYPES, I::Leaf>::from_genesis(
+                I::block_genesis(),
+                config.chain_id,
+            )
+            .unwrap();
             let node_id = self
                 .add_node_with_config(
                     quorum_network,
@@ -194,10 +200,72 @@ impl<TYPES: NodeType, I: TestableNodeImplementation<TYPES>> TestRunner<TYPES, I>
     {
         let node_id = self.next_node_id;
         self.next_node_id += 1;
+        let handle = self
+            .init_node(
+                node_id,
+                quorum_network,
+                committee_network,
+                storage,
+                initializer,
+                config,
+            )
+            .await;
+        self.nodes.push(Node { handle, node_id });
+        node_id
+    }
+
+    /// Restart the node with id `node_id` as after a crash: it is shut down, then started again
+    /// with the same key, storage and config from the leaf it last decided, on fresh networks.
+    /// # Errors
+    /// returns [`ConsensusRoundError::NoSuchNode`] if the node is not running
+    pub async fn restart(&mut self, node_id: u64) -> Result<(), ConsensusRoundError>
+    where
+        HotShot<TYPES::ConsensusType, TYPES, I>: ViewRunner<TYPES, I>,
+    {
+        let Some(idx) = self.nodes.iter().position(|n| n.node_id == node_id) else {
+            return Err(ConsensusRoundError::NoSuchNode {
+                node_ids: self.ids(),
+                requested_id: node_id,
+            });
+        };
+        let node = self.nodes.remove(idx);
+        let storage = node.handle.storage().clone();
+        let anchor = node.handle.get_decided_leaf().await;
+        node.handle.shut_down().await;
+
+        let quorum_network = (self.quorum_network_generator)(node_id);
+        let committee_network = (self.committee_network_generator)(node_id);
+        let handle = self
+            .init_node(
+                node_id,
+                quorum_network,
+                committee_network,
+                storage,
+                HotShotInitializer::from_reload(anchor),
+                self.default_node_config.clone(),
+            )
+            .await;
+        handle.quorum_network().wait_for_ready().await;
+        handle.committee_network().wait_for_ready().await;
+        self.nodes.push(Node { handle, node_id });
+        Ok(())
+    }
 
+    /// Start the node `node_id` with the given networks, storage, initializer and config
+    async fn init_node(
+        &self,
+        node_id: u64,
+        quorum_network: QuorumNetwork<TYPES, I>,
+        committee_network: CommitteeNetwork<TYPES, I>,
+        storage: I::Storage,
+        initializer: HotShotInitializer<TYPES, I::Leaf>,
+        config: HotShotConfig<TYPES::SignatureKey, TYPES::ElectionConfigType>,
+    ) -> HotShotHandle<TYPES, I>
+    where
+        HotShot<TYPES::ConsensusType, TYPES, I>: ViewRunner<TYPES, I>,
+    {
         let known_nodes = config.known_nodes.clone();
-        let private_key = I::generate_test_key(node_id);
-        let public_key = TYPES::SignatureKey::from_private(&private_key);
+        let signer = Arc::new(LocalSigner::new(I::generate_test_key(node_id)));
         let election_config = config.election_config.clone().unwrap_or_else(|| {
             <<I as NodeImplementation<TYPES>>::QuorumExchange as ConsensusExchange<
                 TYPES,
@@ -209,19 +277,18 @@ impl<TYPES: NodeType, I: TestableNodeImplementation<TYPES>> TestRunner<TYPES, I>
             known_nodes.clone(),
             election_config.clone(),
             quorum_network,
-            public_key.clone(),
-            private_key.clone(),
+            config.chain_id,
+            signer.clone(),
         );
         let committee_exchange = I::CommitteeExchange::create(
             known_nodes,
             election_config,
             committee_network,
-            public_key.clone(),
-            private_key.clone(),
+            config.chain_id,
+            signer.clone(),
         );
         let handle = HotShot::init(
-            public_key,
-            private_key,
+            signer,
             node_id,
             config,
             storage,
@@ -231,9 +298,7 @@ impl<TYPES: NodeType, I: TestableNodeImplementation<TYPES>> TestRunner<TYPES, I>
             NoMetrics::boxed(),
         )
         .await
-        .expect("Could not init hotshot");
-        self.nodes.push(Node { handle, node_id });
-        node_id
+        .expect("Could not init hotshot")
     }
 
     /// Iterate over the [`HotShotHandle`] nodes in this runner.
@@ -12,7 +12,7 @@ use hotshot::{traits::NetworkReliability, types::Message, HotShot, HotShotError,
 use hotshot_types::traits::election::ConsensusExchange;
 use hotshot_types::{
     traits::{
-        election::Membership,
+        election::{Membership, StakeThresholds},
         network::CommunicationChannel,
         node_implementation::{NodeImplementation, NodeType},
     },
@@ -457,7 +457,7 @@ impl<TYPES: NodeType, I: TestableNodeImplementation<TYPES>>
 /// given `num_nodes`, calculate min number of honest nodes
 /// for consensus to function properly
 pub fn get_threshold(num_nodes: u64) -> u64 {
-    ((num_nodes * 2) / 3) + 1
+    StakeThresholds::default().quorum_threshold(num_nodes).get()
 }
 
 /// given `num_nodes`, calculate max number of byzantine nodes
@@ -2,11 +2,17 @@ use crate::TestRunner;
 use ark_bls12_381::Parameters as Param381;
 use blake3::Hasher;
 use hotshot::{
-    demos::vdemo::{VDemoBlock, VDemoState, VDemoTransaction},
+    demos::{
+        sdemo::{SDemoBlock, SDemoState, SDemoTransaction},
+        vdemo::{VDemoBlock, VDemoState, VDemoTransaction},
+    },
     traits::{
         dummy::DummyState,
         election::{
-            static_committee::{StaticCommittee, StaticElectionConfig, StaticVoteToken},
+            reputation::{ReputationCommittee, ReputationElectionConfig},
+            static_committee::{
+                GeneralStaticCommittee, StaticCommittee, StaticElectionConfig, StaticVoteToken,
+            },
             vrf::{JfPubKey, VRFStakeTableConfig, VRFVoteToken, VrfImpl},
         },
         implementations::{MemoryCommChannel, MemoryStorage},
@@ -15,14 +21,18 @@ use hotshot::{
 };
 use hotshot_types::message::Message;
 use hotshot_types::{
-    data::{ValidatingLeaf, ValidatingProposal, ViewNumber},
+    data::{
+        CommitmentProposal, DAProposal, SequencingLeaf, ValidatingLeaf, ValidatingProposal,
+        ViewNumber,
+    },
     traits::{
         block_contents::dummy::{DummyBlock, DummyTransaction},
-        election::QuorumExchange,
+        election::{CommitteeExchange, QuorumExchange},
         node_implementation::NodeType,
-        state::ValidatingConsensus,
+        signature_key::secp256k1::Secp256k1Pub,
+        state::{SequencingConsensus, ValidatingConsensus},
     },
-    vote::QuorumVote,
+    vote::{DAVote, QuorumVote},
 };
 use jf_primitives::{
     signatures::{
@@ -45,10 +55,11 @@ use jf_primitives::{
     serde::Serialize,
     serde::Deserialize,
 )]
-/// vrf test types
+/// vrf test types, for validating consensus
+///
+/// See [`SequencingVrfTestTypes`] for sequencing consensus.
 pub struct VrfTestTypes;
 impl NodeType for VrfTestTypes {
-    // TODO (da) can this be SequencingConsensus?
     type ConsensusType = ValidatingConsensus;
     type Time = ViewNumber;
     type BlockType = DummyBlock;
@@ -59,6 +70,32 @@ impl NodeType for VrfTestTypes {
     type StateType = DummyState;
 }
 
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+/// vrf test types, for sequencing consensus
+pub struct SequencingVrfTestTypes;
+impl NodeType for SequencingVrfTestTypes {
+    type ConsensusType = SequencingConsensus;
+    type Time = ViewNumber;
+    type BlockType = SDemoBlock;
+    type SignatureKey = JfPubKey<BLSSignatureScheme<Param381>>;
+    type VoteTokenType = VRFVoteToken<BLSVerKey<Param381>, BLSSignature<Param381>>;
+    type Transaction = SDemoTransaction;
+    type ElectionConfigType = VRFStakeTableConfig;
+    type StateType = SDemoState;
+}
+
 #[derive(
     Copy,
     Clone,
@@ -85,6 +122,58 @@ impl NodeType for StaticCommitteeTestTypes {
     type StateType = VDemoState;
 }
 
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+/// static committee test types with secp256k1 keys
+pub struct Secp256k1TestTypes;
+impl NodeType for Secp256k1TestTypes {
+    type ConsensusType = ValidatingConsensus;
+    type Time = ViewNumber;
+    type BlockType = VDemoBlock;
+    type SignatureKey = Secp256k1Pub;
+    type VoteTokenType = StaticVoteToken<Secp256k1Pub>;
+    type Transaction = VDemoTransaction;
+    type ElectionConfigType = StaticElectionConfig;
+    type StateType = VDemoState;
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+/// reputation committee test types
+pub struct ReputationTestTypes;
+impl NodeType for ReputationTestTypes {
+    type ConsensusType = ValidatingConsensus;
+    type Time = ViewNumber;
+    type BlockType = VDemoBlock;
+    type SignatureKey = JfPubKey<BLSSignatureScheme<Param381>>;
+    type VoteTokenType = StaticVoteToken<JfPubKey<BLSSignatureScheme<Param381>>>;
+    type Transaction = VDemoTransaction;
+    type ElectionConfigType = ReputationElectionConfig;
+    type StateType = VDemoState;
+}
+
 /// type alias for a "usable" node impl type
 #[derive(Clone, Debug)]
 pub struct StandardNodeImplType {}
@@ -108,6 +197,38 @@ pub type VrfCommunication = MemoryCommChannel<
     VrfMembership,
 >;
 
+/// node implementation running sequencing consensus with a vrf election
+#[derive(Clone, Debug)]
+pub struct SequencingVrfNodeImplType {}
+
+/// type alias for membership using vrf types, for sequencing consensus
+pub type SequencingVrfMembership = VrfImpl<
+    SequencingVrfTestTypes,
+    SequencingLeaf<SequencingVrfTestTypes>,
+    BLSSignatureScheme<Param381>,
+    BLSVRFScheme<Param381>,
+    Hasher,
+    Param381,
+>;
+
+/// type alias for the quorum comm channel using vrf, for sequencing consensus
+type SequencingVrfQuorumCommunication = MemoryCommChannel<
+    SequencingVrfTestTypes,
+    SequencingVrfNodeImplType,
+    CommitmentProposal<SequencingVrfTestTypes, SequencingLeaf<SequencingVrfTestTypes>>,
+    QuorumVote<SequencingVrfTestTypes, SequencingLeaf<SequencingVrfTestTypes>>,
+    SequencingVrfMembership,
+>;
+
+/// type alias for the DA comm channel using vrf, for sequencing consensus
+type SequencingVrfDACommunication = MemoryCommChannel<
+    SequencingVrfTestTypes,
+    SequencingVrfNodeImplType,
+    DAProposal<SequencingVrfTestTypes>,
+    DAVote<SequencingVrfTestTypes, SequencingLeaf<SequencingVrfTestTypes>>,
+    SequencingVrfMembership,
+>;
+
 /// type alias for static committee node
 #[derive(Clone, Debug)]
 pub struct StaticNodeImplType {}
@@ -137,6 +258,26 @@ impl NodeImplementation<VrfTestTypes> for StandardNodeImplType {
     type CommitteeExchange = Self::QuorumExchange;
 }
 
+impl NodeImplementation<SequencingVrfTestTypes> for SequencingVrfNodeImplType {
+    type Storage = MemoryStorage<SequencingVrfTestTypes, SequencingLeaf<SequencingVrfTestTypes>>;
+    type Leaf = SequencingLeaf<SequencingVrfTestTypes>;
+    type QuorumExchange = QuorumExchange<
+        SequencingVrfTestTypes,
+        SequencingLeaf<SequencingVrfTestTypes>,
+        CommitmentProposal<SequencingVrfTestTypes, SequencingLeaf<SequencingVrfTestTypes>>,
+        SequencingVrfMembership,
+        SequencingVrfQuorumCommunication,
+        Message<SequencingVrfTestTypes, Self>,
+    >;
+    type CommitteeExchange = CommitteeExchange<
+        SequencingVrfTestTypes,
+        SequencingLeaf<SequencingVrfTestTypes>,
+        SequencingVrfMembership,
+        SequencingVrfDACommunication,
+        Message<SequencingVrfTestTypes, Self>,
+    >;
+}
+
 impl NodeImplementation<StaticCommitteeTestTypes> for StaticNodeImplType {
     type Storage =
         MemoryStorage<StaticCommitteeTestTypes, ValidatingLeaf<StaticCommitteeTestTypes>>;
@@ -152,5 +293,65 @@ impl NodeImplementation<StaticCommitteeTestTypes> for StaticNodeImplType {
     type CommitteeExchange = Self::QuorumExchange;
 }
 
+/// type alias for static committee node with secp256k1 keys
+#[derive(Clone, Debug)]
+pub struct Secp256k1NodeImplType {}
+
+/// type alias for the static committee of secp256k1 keys
+pub type Secp256k1Membership =
+    GeneralStaticCommittee<Secp256k1TestTypes, ValidatingLeaf<Secp256k1TestTypes>, Secp256k1Pub>;
+
+type Secp256k1Communication = MemoryCommChannel<
+    Secp256k1TestTypes,
+    Secp256k1NodeImplType,
+    ValidatingProposal<Secp256k1TestTypes, ValidatingLeaf<Secp256k1TestTypes>>,
+    QuorumVote<Secp256k1TestTypes, ValidatingLeaf<Secp256k1TestTypes>>,
+    Secp256k1Membership,
+>;
+
+impl NodeImplementation<Secp256k1TestTypes> for Secp256k1NodeImplType {
+    type Storage = MemoryStorage<Secp256k1TestTypes, ValidatingLeaf<Secp256k1TestTypes>>;
+    type Leaf = ValidatingLeaf<Secp256k1TestTypes>;
+    type QuorumExchange = QuorumExchange<
+        Secp256k1TestTypes,
+        ValidatingLeaf<Secp256k1TestTypes>,
+        ValidatingProposal<Secp256k1TestTypes, ValidatingLeaf<Secp256k1TestTypes>>,
+        Secp256k1Membership,
+        Secp256k1Communication,
+        Message<Secp256k1TestTypes, Self>,
+    >;
+    type CommitteeExchange = Self::QuorumExchange;
+}
+
+/// type alias for reputation committee node
+#[derive(Clone, Debug)]
+pub struct ReputationNodeImplType {}
+
+/// type alias for the reputation committee of the reputation test types
+pub type ReputationMembership =
+    ReputationCommittee<ReputationTestTypes, ValidatingLeaf<ReputationTestTypes>>;
+
+type ReputationCommunication = MemoryCommChannel<
+    ReputationTestTypes,
+    ReputationNodeImplType,
+    ValidatingProposal<ReputationTestTypes, ValidatingLeaf<ReputationTestTypes>>,
+    QuorumVote<ReputationTestTypes, ValidatingLeaf<ReputationTestTypes>>,
+    ReputationMembership,
+>;
+
+impl NodeImplementation<ReputationTestTypes> for ReputationNodeImplType {
+    type Storage = MemoryStorage<ReputationTestTypes, ValidatingLeaf<ReputationTestTypes>>;
+    type Leaf = ValidatingLeaf<ReputationTestTypes>;
+    type QuorumExchange = QuorumExchange<
+        ReputationTestTypes,
+        ValidatingLeaf<ReputationTestTypes>,
+        ValidatingProposal<ReputationTestTypes, ValidatingLeaf<ReputationTestTypes>>,
+        ReputationMembership,
+        ReputationCommunication,
+        Message<ReputationTestTypes, Self>,
+    >;
+    type CommitteeExchange = Self::QuorumExchange;
+}
+
 /// type alias for the test runner type
 pub type AppliedTestRunner<TYPES, I> = TestRunner<TYPES, I>;
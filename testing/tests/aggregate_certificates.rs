@@ -0,0 +1,218 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroU64,
+};
+
+use hotshot::{
+    certificate::CertificateSignatures,
+    traits::election::static_committee::{StaticCommittee, StaticElectionConfig, StaticVoteToken},
+};
+use hotshot_testing::test_types::StaticCommitteeTestTypes;
+use hotshot_types::{
+    certificate::SignerBitmap,
+    data::{ValidatingLeaf, ViewNumber},
+    key_rotation::KeyRotation,
+    traits::{
+        election::Membership,
+        node_implementation::NodeType,
+        signature_key::{EncodedPublicKey, EncodedSignature, SignatureKey},
+        signer::ChainId,
+        state::ConsensusTime,
+    },
+};
+
+type Key = <StaticCommitteeTestTypes as NodeType>::SignatureKey;
+
+type Committee =
+    StaticCommittee<StaticCommitteeTestTypes, ValidatingLeaf<StaticCommitteeTestTypes>>;
+
+type Signatures = BTreeMap<EncodedPublicKey, (EncodedSignature, StaticVoteToken<Key>)>;
+
+/// The committee of `size` nodes and their private keys
+fn committee(size: u64) -> Vec<(Key, <Key as SignatureKey>::PrivateKey)> {
+    (0..size)
+        .map(|i| Key::generated_from_seed_indexed([0u8; 32], i))
+        .collect()
+}
+
+/// Votes on `data` by the members of `committee` at `signers`
+fn votes(
+    committee: &[(Key, <Key as SignatureKey>::PrivateKey)],
+    signers: &[usize],
+    data: &[u8],
+) -> Signatures {
+    signers
+        .iter()
+        .map(|i| {
+            let (key, private_key) = &committee[*i];
+            let stake = NonZeroU64::new(1).unwrap();
+            let token = StaticVoteToken::new(1, ChainId::default(), private_key, stake);
+            (key.to_bytes(), (Key::sign(private_key, data), token))
+        })
+        .collect()
+}
+
+/// The members of `committee` marked in `signers`, as a verifier reads them
+fn signers_of(committee: &BTreeSet<Key>, signers: &SignerBitmap) -> Vec<Key> {
+    committee
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| signers.get(*index))
+        .map(|(_, key)| key.clone())
+        .collect()
+}
+
+/// BLS votes are aggregated into one signature that verifies against exactly the signers' keys
+#[test]
+fn test_bls_votes_aggregate() {
+    let committee = committee(5);
+    let stake_table: BTreeSet<Key> = committee.iter().map(|(key, _)| key.clone()).collect();
+    let data = b"leaf commitment";
+    let signatures = votes(&committee, &[0, 2, 3], data);
+
+    let CertificateSignatures::Aggregated(aggregate) =
+        CertificateSignatures::aggregate(signatures.clone(), &stake_table)
+    else {
+        panic!("BLS signatures should aggregate");
+    };
+    assert_eq!(aggregate.tokens.len(), 3);
+    assert_eq!(aggregate.signers.len(), 5);
+
+    let signers = signers_of(&stake_table, &aggregate.signers);
+    for (key, token) in signers.iter().zip(&aggregate.tokens) {
+        assert_eq!(signatures[&key.to_bytes()].1, *token);
+    }
+    assert!(Key::validate_aggregate(&signers, &aggregate.signature, data));
+
+    // other data, a missing signer or an extra signer all fail the check
+    assert!(!Key::validate_aggregate(&signers, &aggregate.signature, b"other"));
+    assert!(!Key::validate_aggregate(&signers[1..], &aggregate.signature, data));
+    let mut extra = signers.clone();
+    extra.push(committee[1].0.clone());
+    assert!(!Key::validate_aggregate(&extra, &aggregate.signature, data));
+    assert!(!Key::validate_aggregate(&[], &aggregate.signature, data));
+}
+
+/// Signatures from outside the stake table are kept individually
+#[test]
+fn test_aggregation_falls_back_for_unknown_signers() {
+    let committee = committee(5);
+    let stake_table: BTreeSet<Key> = committee[..4].iter().map(|(key, _)| key.clone()).collect();
+    let signatures = votes(&committee, &[0, 4], b"leaf commitment");
+
+    assert_eq!(
+        CertificateSignatures::aggregate(signatures.clone(), &stake_table),
+        CertificateSignatures::Individual(signatures)
+    );
+}
+
+/// The two representations commit differently, and the aggregate commitment covers the signers
+#[test]
+fn test_aggregate_commitment_bytes() {
+    let committee = committee(4);
+    let stake_table: BTreeSet<Key> = committee.iter().map(|(key, _)| key.clone()).collect();
+    let data = b"leaf commitment";
+    let individual = CertificateSignatures::Individual(votes(&committee, &[0, 1, 2], data));
+    let aggregated =
+        CertificateSignatures::aggregate(votes(&committee, &[0, 1, 2], data), &stake_table);
+    let other =
+        CertificateSignatures::aggregate(votes(&committee, &[0, 1, 3], data), &stake_table);
+
+    assert_eq!(aggregated.num_signers(), individual.num_signers());
+    assert_ne!(aggregated.commitment_bytes(), individual.commitment_bytes());
+    assert_ne!(aggregated.commitment_bytes(), other.commitment_bytes());
+}
+
+/// A batch of BLS signatures validates only if it contains no invalid signature
+#[test]
+fn test_bls_batch_validate() {
+    let committee = committee(4);
+    let data: [&[u8]; 4] = [b"yes", b"no", b"yes", b"timeout"];
+    let mut batch: Vec<_> = committee
+        .iter()
+        .zip(&data)
+        .map(|((key, private_key), data)| (key.clone(), Key::sign(private_key, data), *data))
+        .collect();
+    assert!(Key::batch_validate(&batch));
+
+    // a signature on other data, a signature by another key, and a malformed signature
+    batch[0].2 = data[1];
+    assert!(!Key::batch_validate(&batch));
+    batch[0].2 = data[0];
+    batch[1].0 = committee[0].0.clone();
+    assert!(!Key::batch_validate(&batch));
+    batch[1].0 = committee[1].0.clone();
+    batch[3].1 = EncodedSignature(vec![1, 2, 3]);
+    assert!(!Key::batch_validate(&batch));
+}
+
+/// One bad signature among many on the same data fails the whole batch, even when the sum of the
+/// signatures is right
+#[test]
+fn test_bls_batch_rejects_one_bad_signature() {
+    let committee = committee(6);
+    let data: &[u8] = b"yes";
+    let mut batch: Vec<_> = committee
+        .iter()
+        .map(|(key, private_key)| (key.clone(), Key::sign(private_key, data), data))
+        .collect();
+    assert!(Key::batch_validate(&batch));
+
+    // one signature by a key outside the batch
+    let (_, outsider) = Key::generated_from_seed_indexed([1u8; 32], 0);
+    let honest = std::mem::replace(&mut batch[4].1, Key::sign(&outsider, data));
+    assert!(!Key::batch_validate(&batch));
+    assert!(!batch[4].0.validate(&batch[4].1, data));
+
+    // two signatures swapped between their keys, which leaves the aggregate unchanged
+    batch[4].1 = honest;
+    let first = batch[1].1.clone();
+    batch[1].1 = std::mem::replace(&mut batch[2].1, first);
+    assert!(!Key::batch_validate(&batch));
+}
+
+/// A proof of possession verifies only against the key it was made with
+#[test]
+fn test_bls_proof_of_possession() {
+    let committee = committee(2);
+    let (key, private_key) = &committee[0];
+    let proof = Key::prove_possession(private_key).expect("BLS keys aggregate");
+    assert!(key.verify_possession(&proof));
+
+    // the proof of another key, a signature on the bare key and no proof all fail the check
+    assert!(!committee[1].0.verify_possession(&proof));
+    assert!(!key.verify_possession(&Key::sign(private_key, &key.to_bytes().0)));
+    assert!(!key.verify_possession(&EncodedSignature(Vec::new())));
+}
+
+/// The signer bitmap is read against the committee of the certificate's view, in which a rotated
+/// key may sit at another position
+#[test]
+fn test_bitmap_follows_rotated_committee() {
+    let members = committee(4);
+    let election = Committee::create_election(
+        members.iter().map(|(key, _)| key.clone()).collect(),
+        StaticElectionConfig::default(),
+    );
+    let (new_key, new_private_key) = Key::generated_from_seed_indexed([1u8; 32], 0);
+    let rotation = KeyRotation::new(&members[0].1, &new_private_key, ChainId::default(), 10);
+    election.rotate_key(&rotation).unwrap();
+
+    let before = election.get_committee(ViewNumber::new(5));
+    let after = election.get_committee(ViewNumber::new(12));
+    assert!(after.contains(&new_key) && !after.contains(&members[0].0));
+
+    let mut rotated = members.clone();
+    rotated[0] = (new_key, new_private_key);
+    let data = b"leaf commitment";
+    let CertificateSignatures::Aggregated(aggregate) =
+        CertificateSignatures::aggregate(votes(&rotated, &[0, 1, 2], data), &after)
+    else {
+        panic!("BLS signatures should aggregate");
+    };
+
+    let signers = signers_of(&after, &aggregate.signers);
+    assert!(Key::validate_aggregate(&signers, &aggregate.signature, data));
+    let stale_signers = signers_of(&before, &aggregate.signers);
+    assert!(!Key::validate_aggregate(&stale_signers, &aggregate.signature, data));
+}
@@ -0,0 +1,43 @@
+use commit::Committable;
+use hotshot::demos::vdemo::{VDemoBlock, VDemoState};
+use hotshot_testing::test_types::StaticCommitteeTestTypes;
+use hotshot_types::{
+    certificate::QuorumCertificate,
+    data::{LeafType, ValidatingLeaf, ViewNumber},
+    traits::{
+        signer::ChainId,
+        state::{ConsensusTime, TestableBlock},
+    },
+};
+
+type Leaf = ValidatingLeaf<StaticCommitteeTestTypes>;
+
+/// A child of `parent` in the next view, with the same contents on every chain
+fn child(parent: &Leaf) -> Leaf {
+    let mut leaf = <Leaf as LeafType>::new(
+        parent.get_view_number() + 1,
+        QuorumCertificate::genesis(),
+        VDemoBlock::genesis(),
+        VDemoState::default(),
+    );
+    leaf.parent_commitment = parent.commit();
+    leaf.height = parent.height + 1;
+    leaf
+}
+
+/// Chains sharing a genesis block but not a chain id have no leaf in common
+#[test]
+fn test_leaves_are_bound_to_chain() {
+    let mainnet = Leaf::genesis(VDemoBlock::genesis(), ChainId(1));
+    let testnet = Leaf::genesis(VDemoBlock::genesis(), ChainId(2));
+    assert_eq!(mainnet.get_view_number(), ViewNumber::genesis());
+    assert_ne!(mainnet.commit(), testnet.commit());
+    assert_eq!(
+        mainnet.commit(),
+        Leaf::genesis(VDemoBlock::genesis(), ChainId(1)).commit()
+    );
+
+    let (mainnet, testnet) = (child(&mainnet), child(&testnet));
+    assert_ne!(mainnet.commit(), testnet.commit());
+    assert_ne!(child(&mainnet).commit(), child(&testnet).commit());
+}
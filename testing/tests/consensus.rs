@@ -4,11 +4,11 @@ use commit::Committable;
 use either::Right;
 use futures::{
     future::{join_all, LocalBoxFuture},
-    FutureExt,
+    FutureExt, StreamExt,
 };
 use hotshot::{
     certificate::QuorumCertificate, demos::vdemo::random_validating_leaf,
-    traits::TestableNodeImplementation,
+    traits::TestableNodeImplementation, types::TransactionStatus,
 };
 use hotshot_testing::{
     test_description::{DetailedTestDescriptionBuilder, GeneralTestDescriptionBuilder},
@@ -31,6 +31,7 @@ use hotshot_types::{
         state::{ConsensusTime, ValidatingConsensus},
     },
     vote::QuorumVote,
+    AdaptiveTimeoutConfig, HotShotConfigUpdate,
 };
 
 use snafu::{ensure, OptionExt};
@@ -71,7 +72,7 @@ where
     >,
 {
     let handle = runner.get_handle(node_id).unwrap();
-    let leader = handle.get_leader(view_number).await;
+    let leader = handle.get_leader(view_number).await.unwrap();
     leader == handle.get_public_key()
 }
 
@@ -101,7 +102,9 @@ async fn submit_validating_proposal<
     let mut leaf = random_validating_leaf::<TYPES>(genesis, &mut rng);
     leaf.view_number = view_number;
     leaf.set_height(handle.get_decided_leaf().await.get_height() + 1);
-    let signature = handle.sign_validating_or_commitment_proposal(&leaf.commit());
+    let signature = handle
+        .sign_validating_or_commitment_proposal(view_number, &leaf.commit())
+        .unwrap();
     let msg = ConsensusMessage::Proposal(Proposal {
         data: leaf.into(),
         signature,
@@ -143,7 +146,7 @@ async fn submit_validating_vote<
         leaf.commit(),
         leaf.view_number,
 <<I::QuorumExchange as ConsensusExchange<TYPES, I::Leaf, Message<TYPES, I>>>::Membership as TestableElection<TYPES>>::generate_test_vote_token(),
-    );
+    ).unwrap();
 
     let recipient = runner
         .get_handle(recipient_node_id)
@@ -755,7 +758,7 @@ async fn test_decide_leaf_chain() {
                                 description: "round did not produce a Decide or ViewFinished event",
                             })?;
                         match event.event {
-                            EventType::Decide { leaf_chain, qc } => break (leaf_chain, qc),
+                            EventType::Decide { leaf_chain, qc, .. } => break (leaf_chain, qc),
                             EventType::ViewFinished { view_number } => {
                                 tracing::warn!(
                                     "round {:?} did not produce a decide, skipping safety check",
@@ -814,3 +817,255 @@ async fn test_decide_leaf_chain() {
     }
     test.execute().await.unwrap();
 }
+
+/// Tests that independent event streams each observe the events of a round.
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_event_streams() {
+    let mut test = GeneralTestDescriptionBuilder {
+        num_succeeds: 5,
+        failure_threshold: 0,
+        ..Default::default()
+    }
+    .build::<StaticCommitteeTestTypes, StaticNodeImplType>();
+    for round in &mut test.rounds {
+        // The streams must be created before the round starts so that they see its events.
+        let streams = Arc::new(Mutex::new(vec![]));
+
+        {
+            let streams = streams.clone();
+            round.safety_check_pre = Some(Box::new(move |runner| {
+                async move {
+                    let mut streams = streams.lock().await;
+                    for handle in runner.nodes() {
+                        streams.push(handle.event_stream().boxed());
+                        streams.push(
+                            handle
+                                .filtered_event_stream(|event| {
+                                    matches!(event, EventType::ViewFinished { .. })
+                                })
+                                .boxed(),
+                        );
+                    }
+                    Ok(())
+                }
+                .boxed_local()
+            }));
+        }
+        round.safety_check_post = Some(Box::new(move |_, _| {
+            async move {
+                for mut stream in std::mem::take(&mut *streams.lock().await) {
+                    // Every stream should have buffered the `ViewFinished` event of this round.
+                    loop {
+                        let event = stream.next().now_or_never().flatten().context(
+                            SafetyFailedSnafu {
+                                description: "event stream did not yield a ViewFinished event",
+                            },
+                        )?;
+                        if matches!(event.event, EventType::ViewFinished { .. }) {
+                            break;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            .boxed_local()
+        }));
+    }
+    test.execute().await.unwrap();
+}
+
+/// Tests that a transaction receipt follows its transaction until it is decided.
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_transaction_receipt() {
+    let mut test = GeneralTestDescriptionBuilder {
+        num_succeeds: 10,
+        failure_threshold: 0,
+        ..Default::default()
+    }
+    .build::<StaticCommitteeTestTypes, StaticNodeImplType>();
+    let receipt = Arc::new(Mutex::new(None));
+
+    // Submit a single transaction before the first round.
+    {
+        let receipt = receipt.clone();
+        test.rounds[0].safety_check_pre = Some(Box::new(move |runner| {
+            async move {
+                let handle = runner.get_handle(DEFAULT_NODE_ID).unwrap();
+                let leaf = handle.get_decided_leaf().await;
+                let txn = <StaticNodeImplType as TestableNodeImplementation<
+                    StaticCommitteeTestTypes,
+                >>::leaf_create_random_transaction(
+                    &leaf, &mut rand::thread_rng(), 0
+                );
+                *receipt.lock().await = Some(handle.submit_transaction(txn).await.unwrap());
+                Ok(())
+            }
+            .boxed_local()
+        }));
+    }
+
+    // After the last round the transaction must have been decided.
+    let last_round = test.rounds.len() - 1;
+    test.rounds[last_round].safety_check_post = Some(Box::new(move |_, _| {
+        async move {
+            let mut receipt = receipt.lock().await;
+            let receipt = receipt.as_mut().unwrap();
+            let status = receipt.status();
+            ensure!(
+                matches!(status, TransactionStatus::Decided { .. }),
+                SafetyFailedSnafu {
+                    description: format!("transaction was not decided: {status:?}"),
+                }
+            );
+            Ok(())
+        }
+        .boxed_local()
+    }));
+    test.execute().await.unwrap();
+}
+
+/// Tests that config updates are applied at the next view boundary.
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_update_config() {
+    let mut test = GeneralTestDescriptionBuilder {
+        num_succeeds: 3,
+        failure_threshold: 0,
+        ..Default::default()
+    }
+    .build::<StaticCommitteeTestTypes, StaticNodeImplType>();
+    let original_timeout = Arc::new(Mutex::new(0));
+
+    {
+        let original_timeout = original_timeout.clone();
+        test.rounds[0].safety_check_pre = Some(Box::new(move |runner| {
+            async move {
+                for handle in runner.nodes() {
+                    let timeout = handle.get_next_view_timeout();
+                    *original_timeout.lock().await = timeout;
+                    // an inconsistent update is refused
+                    ensure!(
+                        handle
+                            .update_config(
+                                HotShotConfigUpdate {
+                                    next_view_timeout: Some(0),
+                                    ..Default::default()
+                                },
+                                None,
+                            )
+                            .await
+                            .is_err(),
+                        SafetyFailedSnafu {
+                            description: "a zero view timeout was accepted",
+                        }
+                    );
+                    handle
+                        .update_config(
+                            HotShotConfigUpdate {
+                                next_view_timeout: Some(timeout + 1),
+                                ..Default::default()
+                            },
+                            None,
+                        )
+                        .await
+                        .unwrap();
+                    // not applied before the next view starts
+                    ensure!(
+                        handle.get_next_view_timeout() == timeout,
+                        SafetyFailedSnafu {
+                            description: "config update was applied before the view boundary",
+                        }
+                    );
+                }
+                Ok(())
+            }
+            .boxed_local()
+        }));
+    }
+    test.rounds[0].safety_check_post = Some(Box::new(move |runner, _| {
+        async move {
+            let original_timeout = *original_timeout.lock().await;
+            for handle in runner.nodes() {
+                ensure!(
+                    handle.get_next_view_timeout() == original_timeout + 1,
+                    SafetyFailedSnafu {
+                        description: "config update was not applied",
+                    }
+                );
+            }
+            Ok(())
+        }
+        .boxed_local()
+    }));
+    test.execute().await.unwrap();
+}
+
+/// Tests that config updates can turn the adaptive timeout on.
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_update_adaptive_timeout() {
+    // a floor equal to the ceiling pins the timeout whatever the observed latency
+    const PINNED_TIMEOUT: u64 = 12_345;
+
+    let mut test = GeneralTestDescriptionBuilder {
+        num_succeeds: 3,
+        failure_threshold: 0,
+        ..Default::default()
+    }
+    .build::<StaticCommitteeTestTypes, StaticNodeImplType>();
+    test.rounds[0].safety_check_pre = Some(Box::new(move |runner| {
+        async move {
+            for handle in runner.nodes() {
+                handle
+                    .update_config(
+                        HotShotConfigUpdate {
+                            adaptive_timeout: Some(Some(AdaptiveTimeoutConfig {
+                                floor: PINNED_TIMEOUT,
+                                ceiling: PINNED_TIMEOUT,
+                                ..Default::default()
+                            })),
+                            ..Default::default()
+                        },
+                        None,
+                    )
+                    .await
+                    .unwrap();
+            }
+            Ok(())
+        }
+        .boxed_local()
+    }));
+    test.rounds[0].safety_check_post = Some(Box::new(move |runner, _| {
+        async move {
+            for handle in runner.nodes() {
+                ensure!(
+                    handle.get_next_view_timeout() == PINNED_TIMEOUT,
+                    SafetyFailedSnafu {
+                        description: "adaptive timeout update was not applied",
+                    }
+                );
+            }
+            Ok(())
+        }
+        .boxed_local()
+    }));
+    test.execute().await.unwrap();
+}
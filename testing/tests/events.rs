@@ -0,0 +1,181 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_compatibility_layer::art::async_timeout;
+use commit::Committable;
+use hotshot::{
+    demos::vdemo::{Addition, Subtraction, VDemoTransaction},
+    traits::{election::static_committee::StaticCommittee, TestableNodeImplementation},
+    types::TransactionStatus,
+};
+use hotshot_testing::{
+    test_types::{StaticCommitteeTestTypes, StaticNodeImplType},
+    TestLauncher,
+};
+use hotshot_types::{
+    data::{LeafType, ValidatingLeaf},
+    event::EventType,
+    traits::election::Membership,
+};
+use tracing::instrument;
+
+type TestCommittee =
+    StaticCommittee<StaticCommitteeTestTypes, ValidatingLeaf<StaticCommitteeTestTypes>>;
+
+/// The number of nodes in the test network
+const NUM_NODES: usize = 4;
+
+/// The number of rounds the valid transaction has to be decided in
+const MAX_ROUNDS: usize = 10;
+
+/// How long a round may take
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A transaction every leader rejects, as it spends from an account that does not exist
+fn invalid_transaction() -> VDemoTransaction {
+    VDemoTransaction {
+        add: Addition {
+            account: "nobody".to_string(),
+            amount: 1,
+        },
+        sub: Subtraction {
+            account: "nobody".to_string(),
+            amount: 1,
+        },
+        nonce: u64::MAX,
+        padding: Vec::new(),
+    }
+}
+
+/// Running views emits the proposal, vote, leader, transaction and decide events with the payloads
+/// matching what happened
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_view_events() {
+    let election_config =
+        <TestCommittee as Membership<StaticCommitteeTestTypes>>::default_election_config(
+            NUM_NODES as u64,
+        );
+    let mut runner = TestLauncher::new(NUM_NODES, NUM_NODES, 0, election_config).launch();
+    runner.add_nodes(NUM_NODES).await;
+    let mut handles: Vec<_> = runner
+        .ids()
+        .into_iter()
+        .map(|id| runner.get_handle(id).unwrap())
+        .collect();
+    // Subscribed before any round starts, so they see every event
+    let mut observers = handles.clone();
+
+    let leaf = handles[0].get_decided_leaf().await;
+    let valid = <StaticNodeImplType as TestableNodeImplementation<
+        StaticCommitteeTestTypes,
+    >>::leaf_create_random_transaction(&leaf, &mut rand::thread_rng(), 0);
+    let mut receipt = handles[0].submit_transaction(valid).await.unwrap();
+    let valid = receipt.commitment();
+    let invalid = handles[0]
+        .submit_transaction(invalid_transaction())
+        .await
+        .unwrap()
+        .commitment();
+
+    for _ in 0..MAX_ROUNDS {
+        for handle in &handles {
+            handle.start_one_round().await;
+        }
+        for handle in &mut handles {
+            async_timeout(TIMEOUT, handle.collect_round_events())
+                .await
+                .expect("Round did not finish")
+                .unwrap();
+        }
+        if receipt.status().is_final() {
+            break;
+        }
+    }
+    assert!(matches!(
+        receipt.status(),
+        TransactionStatus::Decided { .. }
+    ));
+
+    let events: Vec<_> = observers
+        .iter_mut()
+        .flat_map(|observer| observer.available_events().unwrap())
+        .collect();
+    let handle = &handles[0];
+    let mut proposals = Vec::new();
+    let mut decided = HashMap::new();
+    let (mut votes, mut leaders) = (0, 0);
+    let (mut included, mut rejected) = (false, false);
+    for event in events {
+        match event.event {
+            EventType::ProposalReceived {
+                leader,
+                leaf_commitment,
+            } => {
+                assert_eq!(leader, handle.get_leader(event.view_number).await.unwrap());
+                proposals.push((event.view_number, leaf_commitment));
+            }
+            EventType::VoteSent { recipient } => {
+                let next_leader = handle.get_leader(event.view_number + 1).await.unwrap();
+                assert_eq!(recipient, next_leader);
+                votes += 1;
+            }
+            EventType::LeaderElected {
+                view_number,
+                leader,
+            } => {
+                assert_eq!(leader, handle.get_leader(view_number).await.unwrap());
+                leaders += 1;
+            }
+            EventType::TransactionsIncluded { transactions } => {
+                assert!(!transactions.contains(&invalid));
+                included |= transactions.contains(&valid);
+            }
+            EventType::TransactionsRejected { transactions } => {
+                assert!(!transactions.contains(&valid));
+                rejected |= transactions.contains(&invalid);
+            }
+            EventType::Decide {
+                leaf_chain,
+                finality_proof,
+                ..
+            } => {
+                // only the genesis leaf is decided without a proof
+                let Some(proof) = finality_proof else {
+                    assert!(leaf_chain.iter().all(|leaf| leaf.get_height() == 0));
+                    continue;
+                };
+                let proved: Vec<_> = proof
+                    .decided_headers()
+                    .iter()
+                    .map(|header| header.leaf_commitment())
+                    .collect();
+                let chain: Vec<_> = leaf_chain.iter().rev().map(Committable::commit).collect();
+                assert_eq!(proved, chain);
+                for leaf in leaf_chain.iter() {
+                    decided.insert(leaf.get_view_number(), leaf.commit());
+                }
+            }
+            _ => {}
+        }
+    }
+    assert!(!decided.is_empty(), "No Decide event with a finality proof");
+    // the proposal received in a decided view is the leaf decided for it
+    let decided_proposals = proposals
+        .iter()
+        .filter(|(view_number, _)| decided.contains_key(view_number))
+        .inspect(|(view_number, leaf_commitment)| {
+            assert_eq!(decided[view_number], *leaf_commitment);
+        })
+        .count();
+    assert!(decided_proposals > 0, "No ProposalReceived event for a decided view");
+    assert!(votes > 0, "No VoteSent event");
+    assert!(leaders > 0, "No LeaderElected event");
+    assert!(included, "The valid transaction was never reported included");
+    assert!(rejected, "The invalid transaction was never reported rejected");
+
+    runner.shutdown_all().await;
+}
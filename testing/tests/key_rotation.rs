@@ -0,0 +1,279 @@
+use commit::Committable;
+use futures::executor::block_on;
+use hotshot::{
+    certificate::{CertificateSignatures, QuorumCertificate},
+    demos::vdemo::{VDemoBlock, VDemoState},
+    traits::election::static_committee::{StaticCommittee, StaticElectionConfig},
+};
+use hotshot_light_client::{verify_certificate, verify_decided};
+use hotshot_testing::test_types::StaticCommitteeTestTypes;
+use hotshot_types::{
+    data::{LeafType, ValidatingLeaf, ViewNumber},
+    key_rotation::{apply_decided, KeyRotation},
+    traits::{
+        election::{ElectionError, Membership, VoteData},
+        node_implementation::NodeType,
+        signature_key::SignatureKey,
+        signer::{ChainId, LocalSigner},
+        state::ConsensusTime,
+    },
+};
+use std::num::NonZeroU64;
+
+type Types = StaticCommitteeTestTypes;
+type Leaf = ValidatingLeaf<Types>;
+type Key = <Types as NodeType>::SignatureKey;
+type Committee = StaticCommittee<Types, Leaf>;
+type Qc = QuorumCertificate<Types, Leaf>;
+type PrivateKey = <Key as SignatureKey>::PrivateKey;
+
+/// The network the certificates of these tests are signed for
+const CHAIN_ID: ChainId = ChainId(7);
+
+/// The view the rotations of these tests take effect in
+const EFFECTIVE_VIEW: u64 = 10;
+
+/// The private keys of a committee with `stake`, and the committee itself
+fn committee(stake: &[u64]) -> (Vec<PrivateKey>, Committee) {
+    let keys: Vec<_> = (0..stake.len() as u64)
+        .map(|i| Key::generated_from_seed_indexed([0u8; 32], i))
+        .collect();
+    let config = StaticElectionConfig {
+        stake: stake.iter().map(|s| NonZeroU64::new(*s).unwrap()).collect(),
+        ..StaticElectionConfig::default()
+    };
+    let committee =
+        Committee::create_election(keys.iter().map(|(key, _)| key.clone()).collect(), config);
+    let private_keys = keys.into_iter().map(|(_, private_key)| private_key);
+    (private_keys.collect(), committee)
+}
+
+/// A private key outside of any committee of these tests
+fn fresh_key(index: u64) -> PrivateKey {
+    Key::generated_from_seed_indexed([1u8; 32], index).1
+}
+
+/// A certificate for `leaf` with the votes of `signers`
+fn certify(committee: &Committee, signers: &[PrivateKey], leaf: &Leaf) -> Qc {
+    let view_number = leaf.get_view_number();
+    let data = VoteData::<Types, Leaf>::Yes(leaf.commit())
+        .signing_payload(CHAIN_ID, view_number);
+    let signatures = signers
+        .iter()
+        .filter_map(|private_key| {
+            let signer = LocalSigner::new(private_key.clone());
+            let token =
+                block_on(committee.make_vote_token(view_number, CHAIN_ID, &signer)).unwrap()?;
+            let key = Key::from_private(private_key);
+            Some((key.to_bytes(), (Key::sign(private_key, &data), token)))
+        })
+        .collect();
+    Qc {
+        leaf_commitment: leaf.commit(),
+        view_number,
+        signatures: CertificateSignatures::Individual(signatures),
+        is_genesis: false,
+    }
+}
+
+/// A rotation needs distinct keys, both signing the same keys, chain and view
+#[test]
+fn test_key_rotation_signatures() {
+    let (old, new) = (fresh_key(0), fresh_key(1));
+    let rotation = KeyRotation::<Key>::new(&old, &new, CHAIN_ID, EFFECTIVE_VIEW);
+    assert!(rotation.is_valid());
+    assert_eq!(rotation.old_key, Key::from_private(&old));
+    assert_eq!(rotation.new_key, Key::from_private(&new));
+
+    // the signatures do not carry over to another view or chain
+    let mut moved = rotation.clone();
+    moved.effective_view += 1;
+    assert!(!moved.is_valid());
+    let mut replayed = rotation.clone();
+    replayed.chain_id = ChainId(8);
+    assert!(!replayed.is_valid());
+
+    // both keys have to sign
+    let mut unsigned = rotation.clone();
+    unsigned.new_signature = rotation.old_signature.clone();
+    assert!(!unsigned.is_valid());
+    let mut claimed = rotation.clone();
+    claimed.new_key = Key::from_private(&fresh_key(2));
+    assert!(!claimed.is_valid());
+
+    // signatures over the bare commitment, as any other protocol could make, are refused
+    let mut untagged = rotation.clone();
+    untagged.old_signature = Key::sign(&old, rotation.commit().as_ref());
+    untagged.new_signature = Key::sign(&new, rotation.commit().as_ref());
+    assert!(!untagged.is_valid());
+
+    assert!(!KeyRotation::<Key>::new(&old, &old, CHAIN_ID, EFFECTIVE_VIEW).is_valid());
+}
+
+/// The old key holds the slot before the effective view, and the new key from then on, with the
+/// same stake and the same views as leader
+#[test]
+fn test_key_rotation_keeps_stake_slot() {
+    let stake = [1, 2, 3, 4];
+    let (_, reference) = committee(&stake);
+    let (keys, committee) = committee(&stake);
+    let old_key = Key::from_private(&keys[1]);
+    let new_key = Key::from_private(&fresh_key(0));
+
+    let rotation = KeyRotation::new(&keys[1], &fresh_key(0), CHAIN_ID, EFFECTIVE_VIEW);
+    committee.rotate_key(&rotation).unwrap();
+    // clones share the rotations
+    let clone = committee.clone();
+
+    let two = NonZeroU64::new(2);
+    assert_eq!(clone.get_stake(EFFECTIVE_VIEW - 1, &old_key), two);
+    assert_eq!(clone.get_stake(EFFECTIVE_VIEW - 1, &new_key), None);
+    assert_eq!(clone.get_stake(EFFECTIVE_VIEW, &old_key), None);
+    assert_eq!(clone.get_stake(EFFECTIVE_VIEW, &new_key), two);
+    assert_eq!(clone.threshold(), reference.threshold());
+
+    let before = ViewNumber::new(EFFECTIVE_VIEW - 1);
+    let after = ViewNumber::new(EFFECTIVE_VIEW);
+    assert!(committee.get_committee(before).contains(&old_key));
+    assert!(committee.get_committee(after).contains(&new_key));
+    assert!(!committee.get_committee(after).contains(&old_key));
+    assert_eq!(committee.keys_at(*after)[1], new_key);
+
+    for view in 0..4 * EFFECTIVE_VIEW {
+        let view_number = ViewNumber::new(view);
+        let mut expected = reference.get_leader(view_number).unwrap();
+        if view >= EFFECTIVE_VIEW && expected == old_key {
+            expected = new_key.clone();
+        }
+        assert_eq!(committee.get_leader(view_number).unwrap(), expected);
+    }
+
+    let signer = LocalSigner::new(fresh_key(0));
+    assert!(block_on(committee.make_vote_token(before, CHAIN_ID, &signer))
+        .unwrap()
+        .is_none());
+    let token = block_on(committee.make_vote_token(after, CHAIN_ID, &signer)).unwrap();
+    assert!(token.is_some());
+}
+
+/// Rotations that are not signed, that do not start from a slot holder, that reuse a key, or that
+/// reorder the rotations of a slot are rejected
+#[test]
+fn test_key_rotation_rejected() {
+    let (keys, committee) = committee(&[1, 1, 1, 1]);
+    let rejected = |rotation: &KeyRotation<Key>| {
+        matches!(
+            committee.rotate_key(rotation),
+            Err(ElectionError::InvalidKeyRotation { .. })
+        )
+    };
+
+    let mut unsigned = KeyRotation::new(&keys[0], &fresh_key(0), CHAIN_ID, EFFECTIVE_VIEW);
+    unsigned.new_signature = unsigned.old_signature.clone();
+    assert!(rejected(&unsigned));
+    assert!(rejected(&KeyRotation::new(&fresh_key(1), &fresh_key(0), CHAIN_ID, EFFECTIVE_VIEW)));
+    assert!(rejected(&KeyRotation::new(&keys[0], &keys[1], CHAIN_ID, EFFECTIVE_VIEW)));
+
+    let rotation = KeyRotation::new(&keys[0], &fresh_key(0), CHAIN_ID, EFFECTIVE_VIEW);
+    committee.rotate_key(&rotation).unwrap();
+    // applying a rotation again changes nothing
+    committee.rotate_key(&rotation).unwrap();
+
+    // the old key no longer holds the slot, and the new key cannot be handed out again
+    assert!(rejected(&KeyRotation::new(&keys[0], &fresh_key(1), CHAIN_ID, EFFECTIVE_VIEW + 1)));
+    assert!(rejected(&KeyRotation::new(&keys[1], &fresh_key(0), CHAIN_ID, EFFECTIVE_VIEW + 1)));
+    // the slot is already rotated after the old key's earlier views
+    assert!(rejected(&KeyRotation::new(&keys[0], &fresh_key(1), CHAIN_ID, EFFECTIVE_VIEW - 1)));
+
+    // the new key may rotate on
+    let next = KeyRotation::new(&fresh_key(0), &fresh_key(1), CHAIN_ID, 2 * EFFECTIVE_VIEW);
+    committee.rotate_key(&next).unwrap();
+    let newest = Key::from_private(&fresh_key(1));
+    let view_number = ViewNumber::new(2 * EFFECTIVE_VIEW);
+    assert!(committee.get_committee(view_number).contains(&newest));
+}
+
+/// Of two conflicting rotations of a key decided in one leaf, only the first applies, and
+/// rotations for another chain or that do not start a late enough epoch are skipped
+#[test]
+fn test_decided_key_rotations_conflict() {
+    let (keys, committee) = committee(&[1, 1, 1, 1]);
+    let epoch_length = NonZeroU64::new(EFFECTIVE_VIEW).unwrap();
+    let decided_view = ViewNumber::new(EFFECTIVE_VIEW / 2);
+    let effective_view = 3 * EFFECTIVE_VIEW;
+
+    let first = KeyRotation::new(&keys[0], &fresh_key(0), CHAIN_ID, effective_view);
+    let conflicting = KeyRotation::new(&keys[0], &fresh_key(1), CHAIN_ID, effective_view);
+    let other_chain = KeyRotation::new(&keys[1], &fresh_key(2), ChainId(8), effective_view);
+    // mid-epoch, and in the epoch right after the one of the decided view
+    let unaligned = KeyRotation::new(&keys[2], &fresh_key(3), CHAIN_ID, effective_view + 1);
+    let early = KeyRotation::new(&keys[3], &fresh_key(4), CHAIN_ID, EFFECTIVE_VIEW);
+
+    let rotations = [first.clone(), conflicting, other_chain, unaligned, early];
+    let applied =
+        apply_decided::<Types, _>(&committee, CHAIN_ID, epoch_length, decided_view, &rotations);
+    assert_eq!(applied, vec![first.clone()]);
+
+    let after = ViewNumber::new(effective_view);
+    let members = committee.get_committee(after);
+    assert!(members.contains(&first.new_key));
+    assert!(!members.contains(&Key::from_private(&fresh_key(1))));
+    assert!(!members.contains(&Key::from_private(&keys[0])));
+    for (index, private_key) in keys.iter().enumerate().skip(1) {
+        assert!(members.contains(&Key::from_private(private_key)), "key {index} rotated");
+    }
+
+    // deciding the conflicting rotation again in a later leaf changes nothing either
+    let conflicting = KeyRotation::new(&keys[0], &fresh_key(1), CHAIN_ID, 4 * EFFECTIVE_VIEW);
+    let later = ViewNumber::new(effective_view - EFFECTIVE_VIEW);
+    let rotations = [conflicting];
+    let applied = apply_decided::<Types, _>(&committee, CHAIN_ID, epoch_length, later, &rotations);
+    assert!(applied.is_empty());
+    assert_eq!(committee.keys_at(4 * EFFECTIVE_VIEW)[0], first.new_key);
+}
+
+/// Certificates of views before the rotation verify with the old key, and those of later views
+/// with the new one, so a chain across the rotation decides
+#[test]
+fn test_key_rotation_certificates_span_transition() {
+    let (keys, committee) = committee(&[1, 1, 1, 1]);
+    let rotation = KeyRotation::new(&keys[1], &fresh_key(0), CHAIN_ID, EFFECTIVE_VIEW);
+    committee.rotate_key(&rotation).unwrap();
+    let old_signers = &keys[..3];
+    let new_signers = [keys[0].clone(), fresh_key(0), keys[2].clone()];
+
+    let mut leaves: Vec<Leaf> = Vec::new();
+    let mut certificates = Vec::new();
+    for view in EFFECTIVE_VIEW - 2..EFFECTIVE_VIEW + 2 {
+        let justify_qc = certificates.last().cloned().unwrap_or_else(Qc::genesis);
+        let mut leaf = <Leaf as LeafType>::new(
+            ViewNumber::new(view),
+            justify_qc,
+            VDemoBlock::genesis(),
+            VDemoState::default(),
+        );
+        if let Some(parent) = leaves.last() {
+            leaf.parent_commitment = parent.commit();
+            leaf.height = parent.height + 1;
+        }
+        let signers = if view < EFFECTIVE_VIEW {
+            old_signers
+        } else {
+            &new_signers[..]
+        };
+        certificates.push(certify(&committee, signers, &leaf));
+        leaves.push(leaf);
+    }
+    assert!(certificates.iter().all(|qc| verify_certificate(&committee, CHAIN_ID, qc)));
+
+    let decided = verify_decided(&committee, CHAIN_ID, &leaves[..3], &certificates[..3]).unwrap();
+    assert_eq!(decided, &leaves[0]);
+    let decided = verify_decided(&committee, CHAIN_ID, &leaves[1..], &certificates[1..]).unwrap();
+    assert_eq!(decided, &leaves[1]);
+
+    // votes of the old key after the rotation, and of the new key before it, carry no stake
+    let stale = certify(&committee, old_signers, &leaves[2]);
+    assert!(!verify_certificate(&committee, CHAIN_ID, &stale));
+    let early = certify(&committee, &new_signers, &leaves[1]);
+    assert!(!verify_certificate(&committee, CHAIN_ID, &early));
+}
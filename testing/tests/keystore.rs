@@ -0,0 +1,94 @@
+use hotshot::traits::election::vrf::BlsPubKey;
+use hotshot_types::traits::signature_key::{
+    keystore::{Keystore, KeystoreError, KeystoreKey, KEYSTORE_VERSION},
+    SignatureKey,
+};
+
+/// A keystore decrypts to the key it was written with, and records its public key in the clear
+#[test]
+fn test_keystore_roundtrip() {
+    let private_key = BlsPubKey::generate_private_key();
+    let keystore = Keystore::encrypt::<BlsPubKey>(&private_key, b"hunter2").unwrap();
+    assert_eq!(keystore.version, KEYSTORE_VERSION);
+    assert_eq!(keystore.key_type, BlsPubKey::KEY_TYPE);
+    assert_eq!(
+        keystore.public_key::<BlsPubKey>().unwrap(),
+        BlsPubKey::from_private(&private_key)
+    );
+
+    let decrypted = keystore.decrypt::<BlsPubKey>(b"hunter2").unwrap();
+    assert_eq!(
+        BlsPubKey::private_key_to_bytes(&decrypted),
+        BlsPubKey::private_key_to_bytes(&private_key)
+    );
+}
+
+/// A wrong password is reported as such
+#[test]
+fn test_keystore_wrong_password() {
+    let private_key = BlsPubKey::generate_private_key();
+    let keystore = Keystore::encrypt::<BlsPubKey>(&private_key, b"hunter2").unwrap();
+    assert!(matches!(
+        keystore.decrypt::<BlsPubKey>(b"hunter3"),
+        Err(KeystoreError::Decryption)
+    ));
+}
+
+/// A keystore is only decrypted as the type of key it holds
+#[test]
+fn test_keystore_wrong_key_type() {
+    let private_key = BlsPubKey::generate_private_key();
+    let mut keystore = Keystore::encrypt::<BlsPubKey>(&private_key, b"hunter2").unwrap();
+    keystore.key_type = "ed25519".to_string();
+    assert!(matches!(
+        keystore.decrypt::<BlsPubKey>(b"hunter2"),
+        Err(KeystoreError::WrongKeyType { .. })
+    ));
+}
+
+/// Swapping in another public key makes decryption fail
+#[test]
+fn test_keystore_tampered_public_key() {
+    let private_key = BlsPubKey::generate_private_key();
+    let mut keystore = Keystore::encrypt::<BlsPubKey>(&private_key, b"hunter2").unwrap();
+    let other_key = BlsPubKey::from_private(&BlsPubKey::generate_private_key());
+    keystore.public_key = other_key.to_bytes();
+    assert!(matches!(
+        keystore.decrypt::<BlsPubKey>(b"hunter2"),
+        Err(KeystoreError::Decryption)
+    ));
+}
+
+/// Keystores survive a trip through a file, and are never overwritten
+#[test]
+fn test_keystore_save_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("node.json");
+    let private_key = BlsPubKey::generate_private_key();
+    let keystore = Keystore::encrypt::<BlsPubKey>(&private_key, b"hunter2").unwrap();
+    keystore.save(&path).unwrap();
+
+    let loaded = Keystore::load(&path).unwrap();
+    assert_eq!(loaded, keystore);
+    assert!(loaded.decrypt::<BlsPubKey>(b"hunter2").is_ok());
+    assert!(matches!(keystore.save(&path), Err(KeystoreError::Io { .. })));
+}
+
+/// `Ed25519Pub` keys can be stored too
+#[cfg(feature = "demo")]
+#[test]
+fn test_keystore_ed25519() {
+    use hotshot_types::traits::signature_key::ed25519::Ed25519Pub;
+
+    let private_key = Ed25519Pub::generate_private_key();
+    let keystore = Keystore::encrypt::<Ed25519Pub>(&private_key, b"hunter2").unwrap();
+    let decrypted = keystore.decrypt::<Ed25519Pub>(b"hunter2").unwrap();
+    assert_eq!(
+        Ed25519Pub::from_private(&decrypted),
+        Ed25519Pub::from_private(&private_key)
+    );
+    assert!(matches!(
+        keystore.decrypt::<BlsPubKey>(b"hunter2"),
+        Err(KeystoreError::WrongKeyType { .. })
+    ));
+}
@@ -0,0 +1,161 @@
+use std::{
+    collections::HashSet,
+    num::{NonZeroU64, NonZeroUsize},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_compatibility_layer::art::{async_sleep, async_timeout};
+use commit::Committable;
+use hotshot::traits::election::static_committee::{StaticCommittee, StaticElectionConfig};
+use hotshot_testing::{
+    test_description::{
+        DetailedTestDescriptionBuilder, GeneralTestDescriptionBuilder, TestDescription,
+    },
+    test_types::{StaticCommitteeTestTypes, StaticNodeImplType},
+    TestLauncher, TestRunner,
+};
+use hotshot_types::{
+    data::{ValidatingLeaf, ViewNumber},
+    traits::{
+        election::Membership, node_implementation::NodeType, signature_key::SignatureKey,
+        state::ConsensusTime,
+    },
+};
+use tracing::instrument;
+
+type TestCommittee =
+    StaticCommittee<StaticCommitteeTestTypes, ValidatingLeaf<StaticCommitteeTestTypes>>;
+
+type Key = <StaticCommitteeTestTypes as NodeType>::SignatureKey;
+
+/// The schedule lists the leader of every view in the range, in order
+#[test]
+fn test_leader_schedule_matches_get_leader() {
+    let keys: Vec<Key> = (0..4)
+        .map(|i| Key::generated_from_seed_indexed([0u8; 32], i).0)
+        .collect();
+    let config = StaticElectionConfig {
+        stake: [1, 2, 3, 7]
+            .into_iter()
+            .map(|stake| NonZeroU64::new(stake).unwrap())
+            .collect(),
+        ..StaticElectionConfig::default()
+    };
+    let committee =
+        <TestCommittee as Membership<StaticCommitteeTestTypes>>::create_election(keys, config);
+
+    for from in [0, 3, 1000] {
+        let schedule = committee.leader_schedule(ViewNumber::new(from), 20);
+        assert_eq!(schedule.len(), 20);
+        for (offset, leader) in (0..).zip(schedule) {
+            let expected = committee.get_leader(ViewNumber::new(from + offset)).unwrap();
+            assert_eq!(leader, expected);
+        }
+    }
+    assert!(committee.leader_schedule(ViewNumber::new(5), 0).is_empty());
+}
+
+/// A runner whose nodes forward transactions to the next two leaders
+fn forwarding_runner(
+    description: &TestDescription<StaticCommitteeTestTypes, StaticNodeImplType>,
+) -> TestRunner<StaticCommitteeTestTypes, StaticNodeImplType> {
+    let election_config =
+        <TestCommittee as Membership<StaticCommitteeTestTypes>>::default_election_config(
+            description.total_nodes as u64,
+        );
+    TestLauncher::new(
+        description.total_nodes,
+        description.num_bootstrap_nodes,
+        description.min_transactions,
+        election_config,
+    )
+    .modify_default_config(|config| {
+        config.transaction_forwarding = NonZeroUsize::new(2);
+    })
+    .launch()
+}
+
+/// Consensus makes progress when transactions are only sent to the next leaders
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_transaction_forwarding_to_next_leaders() {
+    let description: DetailedTestDescriptionBuilder<StaticCommitteeTestTypes, StaticNodeImplType> =
+        DetailedTestDescriptionBuilder {
+            general_info: GeneralTestDescriptionBuilder {
+                total_nodes: 5,
+                start_nodes: 5,
+                num_succeeds: 5,
+                ..GeneralTestDescriptionBuilder::default()
+            },
+            rounds: None,
+            gen_runner: Some(Arc::new(forwarding_runner)),
+        };
+
+    description.build().execute().await.unwrap();
+}
+
+/// Transactions are forwarded to the scheduled leaders, and to no other node
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_transactions_only_reach_next_leaders() {
+    let description = GeneralTestDescriptionBuilder {
+        total_nodes: 5,
+        start_nodes: 5,
+        ..GeneralTestDescriptionBuilder::default()
+    }
+    .build::<StaticCommitteeTestTypes, StaticNodeImplType>();
+    let mut runner = forwarding_runner(&description);
+    runner.add_nodes(5).await;
+
+    let first = runner.get_handle(0).unwrap();
+    let leaders: HashSet<Key> = first
+        .leader_schedule(first.get_current_view().await, 2)
+        .into_iter()
+        .collect();
+    let (leader_ids, other_ids): (Vec<u64>, Vec<u64>) = runner
+        .ids()
+        .into_iter()
+        .partition(|id| leaders.contains(&runner.get_handle(*id).unwrap().get_public_key()));
+    let submitter = other_ids[0];
+    let transaction = runner
+        .add_random_transaction(Some(submitter as usize), &mut rand::thread_rng())
+        .await;
+
+    let has_transaction = |id: u64| {
+        let handle = runner.get_handle(id).unwrap();
+        let commitment = transaction.commit();
+        async move {
+            handle
+                .get_pending_transactions()
+                .await
+                .iter()
+                .any(|pending| pending.commit() == commitment)
+        }
+    };
+    async_timeout(Duration::from_secs(10), async {
+        for id in &leader_ids {
+            while !has_transaction(*id).await {
+                async_sleep(Duration::from_millis(10)).await;
+            }
+        }
+    })
+    .await
+    .expect("Transaction did not reach the scheduled leaders");
+    for id in other_ids {
+        assert!(
+            !has_transaction(id).await,
+            "Node {id} is not a scheduled leader but received the transaction"
+        );
+    }
+
+    runner.shutdown_all().await;
+}
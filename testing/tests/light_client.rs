@@ -0,0 +1,363 @@
+use commit::Committable;
+use futures::executor::block_on;
+use hotshot::{
+    certificate::{CertificateSignatures, QuorumCertificate},
+    demos::vdemo::{VDemoBlock, VDemoState},
+    traits::{
+        election::static_committee::{StaticCommittee, StaticElectionConfig},
+        implementations::MemoryStorage,
+        Storage,
+    },
+};
+use hotshot_light_client::{
+    verify_certificate, verify_decided, verify_finality_proof, LightClientError,
+};
+use hotshot_testing::test_types::StaticCommitteeTestTypes;
+use hotshot_types::{
+    data::{fake_commitment, LeafType, ValidatingLeaf, ViewNumber},
+    finality::FinalityProof,
+    traits::{
+        election::{Membership, SignedCertificate, VoteData},
+        node_implementation::NodeType,
+        signature_key::{EncodedSignature, SignatureKey},
+        signer::{signing_payload, ChainId, LocalSigner, SigningKind},
+        state::{ConsensusTime, TestableBlock},
+        storage::TestableStorage,
+    },
+};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+type Types = StaticCommitteeTestTypes;
+type Leaf = ValidatingLeaf<Types>;
+type Key = <Types as NodeType>::SignatureKey;
+type Committee = StaticCommittee<Types, Leaf>;
+type Qc = QuorumCertificate<Types, Leaf>;
+type PrivateKey = <Key as SignatureKey>::PrivateKey;
+
+/// The network the certificates of these tests are signed for
+const CHAIN_ID: ChainId = ChainId(7);
+
+/// The private keys of a committee of `size`, and the committee itself
+fn committee(size: u64) -> (Vec<PrivateKey>, Committee) {
+    let keys: Vec<_> = (0..size)
+        .map(|i| Key::generated_from_seed_indexed([0u8; 32], i))
+        .collect();
+    let committee = Committee::create_election(
+        keys.iter().map(|(key, _)| key.clone()).collect(),
+        StaticElectionConfig::default(),
+    );
+    let private_keys = keys.into_iter().map(|(_, private_key)| private_key);
+    (private_keys.collect(), committee)
+}
+
+/// A certificate for `leaf` with the votes of `signers`
+fn certify(committee: &Committee, signers: &[PrivateKey], leaf: &Leaf) -> Qc {
+    let view_number = leaf.get_view_number();
+    let data = VoteData::<Types, Leaf>::Yes(leaf.commit())
+        .signing_payload(CHAIN_ID, view_number);
+    let signatures = signers
+        .iter()
+        .map(|private_key| {
+            let signer = LocalSigner::new(private_key.clone());
+            let token = block_on(committee.make_vote_token(view_number, CHAIN_ID, &signer))
+                .unwrap()
+                .unwrap();
+            let key = Key::from_private(private_key);
+            (key.to_bytes(), (Key::sign(private_key, &data), token))
+        })
+        .collect();
+    Qc {
+        leaf_commitment: leaf.commit(),
+        view_number,
+        signatures: CertificateSignatures::Individual(signatures),
+        is_genesis: false,
+    }
+}
+
+/// The signature of the leader of the view of `leaf` on its proposal, `keys` holding the private
+/// keys of the whole committee
+fn propose(committee: &Committee, keys: &[PrivateKey], leaf: &Leaf) -> EncodedSignature {
+    let view_number = leaf.get_view_number();
+    let leader = committee.get_leader(view_number).unwrap();
+    let private_key = keys
+        .iter()
+        .find(|private_key| Key::from_private(private_key) == leader)
+        .unwrap();
+    let payload = signing_payload(
+        SigningKind::QuorumProposal,
+        CHAIN_ID,
+        *view_number,
+        leaf.commit().as_ref(),
+    );
+    Key::sign(private_key, &payload)
+}
+
+/// A chain of leaves at `views`, each justified by a certificate for the previous one, and the
+/// certificates of all of them
+fn chain(committee: &Committee, signers: &[PrivateKey], views: &[u64]) -> (Vec<Leaf>, Vec<Qc>) {
+    let mut leaves: Vec<Leaf> = Vec::new();
+    let mut certificates = Vec::new();
+    for view in views {
+        let justify_qc = certificates.last().cloned().unwrap_or_else(Qc::genesis);
+        let mut leaf = <Leaf as LeafType>::new(
+            ViewNumber::new(*view),
+            justify_qc,
+            VDemoBlock::genesis(),
+            VDemoState::default(),
+        );
+        if let Some(parent) = leaves.last() {
+            leaf.parent_commitment = parent.commit();
+            leaf.height = parent.height + 1;
+        }
+        certificates.push(certify(committee, signers, &leaf));
+        leaves.push(leaf);
+    }
+    (leaves, certificates)
+}
+
+/// Three leaves in consecutive views decide the first, and every ancestor in the chain
+#[test]
+fn test_light_client_decides() {
+    let (keys, committee) = committee(4);
+    let (leaves, certificates) = chain(&committee, &keys[..3], &[1, 2, 3, 4]);
+
+    let decided = verify_decided(&committee, CHAIN_ID, &leaves[..3], &certificates[..3]).unwrap();
+    assert_eq!(decided, &leaves[0]);
+    let decided = verify_decided(&committee, CHAIN_ID, &leaves[1..], &certificates[1..]).unwrap();
+    assert_eq!(decided, &leaves[1]);
+    let decided = verify_decided(&committee, CHAIN_ID, &leaves, &certificates).unwrap();
+    assert_eq!(decided, &leaves[0]);
+
+    assert!(verify_certificate(&committee, CHAIN_ID, &Qc::genesis()));
+}
+
+/// Chains that are too short, or that come with the wrong number of certificates, are rejected
+#[test]
+fn test_light_client_chain_shape() {
+    let (keys, committee) = committee(4);
+    let (leaves, certificates) = chain(&committee, &keys[..3], &[1, 2, 3]);
+
+    assert_eq!(
+        verify_decided(&committee, CHAIN_ID, &leaves[..2], &certificates[..2]),
+        Err(LightClientError::ChainTooShort { len: 2 })
+    );
+    assert_eq!(
+        verify_decided(&committee, CHAIN_ID, &leaves, &certificates[..2]),
+        Err(LightClientError::CertificateCount {
+            expected: 3,
+            found: 2
+        })
+    );
+}
+
+/// A newest leaf without a certificate of its own cannot be forged into a deciding chain
+#[test]
+fn test_light_client_rejects_forged_tip() {
+    let (keys, committee) = committee(4);
+    let (mut leaves, mut certificates) = chain(&committee, &keys[..3], &[1, 2, 3]);
+    // the tip swapped for one with another block
+    leaves[2].deltas = VDemoBlock::genesis_from(BTreeMap::new());
+    assert_eq!(
+        verify_decided(&committee, CHAIN_ID, &leaves, &certificates),
+        Err(LightClientError::WrongLeaf {
+            view_number: ViewNumber::new(3)
+        })
+    );
+
+    // a certificate for the forged leaf needs the votes of the committee
+    certificates[2] = certify(&committee, &keys[..1], &leaves[2]);
+    assert_eq!(
+        verify_decided(&committee, CHAIN_ID, &leaves, &certificates),
+        Err(LightClientError::InvalidCertificate {
+            view_number: ViewNumber::new(3)
+        })
+    );
+}
+
+/// Certificates without the threshold of stake, or for other leaves, are rejected
+#[test]
+fn test_light_client_rejects_certificates() {
+    let (keys, committee) = committee(4);
+    let (leaves, mut certificates) = chain(&committee, &keys[..3], &[1, 2, 3]);
+
+    // two of four votes are below the two thirds threshold
+    let weak = certify(&committee, &keys[..2], &leaves[1]);
+    assert!(!verify_certificate(&committee, CHAIN_ID, &weak));
+    let mut weak_certificates = certificates.clone();
+    weak_certificates[1] = weak;
+    assert_eq!(
+        verify_decided(&committee, CHAIN_ID, &leaves, &weak_certificates),
+        Err(LightClientError::InvalidCertificate {
+            view_number: ViewNumber::new(2)
+        })
+    );
+
+    // votes from outside the committee carry no stake
+    let (larger_keys, larger_committee) = committee(5);
+    let signers = [0, 1, 4].map(|i| larger_keys[i].clone());
+    let padded = certify(&larger_committee, &signers, &leaves[0]);
+    assert!(!verify_certificate(&committee, CHAIN_ID, &padded));
+
+    // a valid certificate for another leaf does not certify this one
+    certificates.swap(0, 1);
+    assert_eq!(
+        verify_decided(&committee, CHAIN_ID, &leaves, &certificates),
+        Err(LightClientError::WrongLeaf {
+            view_number: ViewNumber::new(1)
+        })
+    );
+}
+
+/// Certificates signed on one network do not verify on another
+#[test]
+fn test_light_client_rejects_other_chains() {
+    let (keys, committee) = committee(4);
+    let (leaves, certificates) = chain(&committee, &keys[..3], &[1, 2, 3]);
+    assert!(!verify_certificate(&committee, ChainId(8), &certificates[0]));
+    assert_eq!(
+        verify_decided(&committee, ChainId(8), &leaves, &certificates),
+        Err(LightClientError::InvalidCertificate {
+            view_number: ViewNumber::new(1)
+        })
+    );
+}
+
+/// Leaves that do not extend each other, or a deciding chain with a gap, decide nothing
+#[test]
+fn test_light_client_rejects_chains() {
+    let (keys, committee) = committee(4);
+    let (mut leaves, mut certificates) = chain(&committee, &keys[..3], &[1, 2, 3]);
+    leaves[2].parent_commitment = leaves[0].commit();
+    certificates[2] = certify(&committee, &keys[..3], &leaves[2]);
+    assert_eq!(
+        verify_decided(&committee, CHAIN_ID, &leaves, &certificates),
+        Err(LightClientError::BrokenChain {
+            view_number: ViewNumber::new(3)
+        })
+    );
+
+    let (leaves, certificates) = chain(&committee, &keys[..3], &[1, 2, 4]);
+    assert_eq!(
+        verify_decided(&committee, CHAIN_ID, &leaves, &certificates),
+        Err(LightClientError::NotConsecutive {
+            first: ViewNumber::new(1),
+            last: ViewNumber::new(4)
+        })
+    );
+}
+
+/// The proof of a decision proves each of its leaves decided, and opens them to their block
+/// commitments
+#[test]
+fn test_finality_proofs_verify() {
+    let (keys, committee) = committee(4);
+    let (leaves, _) = chain(&committee, &keys[..3], &[1, 2, 3, 4]);
+
+    // leaves 2 and 3 decide leaf 1, and with it leaf 0
+    let leaf_chain = [leaves[1].clone(), leaves[0].clone()];
+    let tip_signature = propose(&committee, &keys, &leaves[3]);
+    let proof = FinalityProof::for_leaf_chain(&leaf_chain, &leaves[2], &leaves[3], tip_signature);
+    assert_eq!(proof.headers.len(), 4);
+    assert_eq!(proof.decided_headers().len(), 2);
+    for leaf in &leaf_chain {
+        let proof = proof.for_height(leaf.get_height()).unwrap();
+        let decided = verify_finality_proof(&committee, CHAIN_ID, &proof).unwrap();
+        assert_eq!(decided, &leaf.header());
+        assert_eq!(decided.leaf_commitment(), leaf.commit());
+        assert_eq!(decided.block_commitment, leaf.deltas.commit());
+    }
+    assert_eq!(proof.for_height(1).unwrap().headers.len(), 3);
+    assert_eq!(proof.for_height(2), None);
+
+    // a header opening its leaf to another block no longer matches the certificate of the leaf
+    let mut forged = proof.for_height(1).unwrap();
+    forged.headers[0].block_commitment = fake_commitment();
+    assert_eq!(
+        verify_finality_proof(&committee, CHAIN_ID, &forged),
+        Err(LightClientError::WrongLeaf {
+            view_number: ViewNumber::new(2)
+        })
+    );
+
+    // without the tip, nothing is decided
+    let mut truncated = proof.for_height(1).unwrap();
+    truncated.headers.pop();
+    assert_eq!(
+        verify_finality_proof(&committee, CHAIN_ID, &truncated),
+        Err(LightClientError::ChainTooShort { len: 2 })
+    );
+}
+
+/// The tip of a finality proof has no certificate, so it is forged unless its leader signed it
+#[test]
+fn test_finality_proofs_reject_forged_tip() {
+    let (keys, committee) = committee(4);
+    let (leaves, _) = chain(&committee, &keys[..3], &[1, 2, 3]);
+    let tip_signature = propose(&committee, &keys, &leaves[2]);
+    let proof = FinalityProof::for_leaf_chain(&leaves[..1], &leaves[1], &leaves[2], tip_signature);
+    assert!(verify_finality_proof(&committee, CHAIN_ID, &proof).is_ok());
+
+    // a tip opening to another block, under the signature of the real one
+    let mut forged = proof.clone();
+    forged.headers[2].block_commitment = fake_commitment();
+    assert_eq!(
+        verify_finality_proof(&committee, CHAIN_ID, &forged),
+        Err(LightClientError::InvalidProposal {
+            view_number: ViewNumber::new(3)
+        })
+    );
+
+    // a tip signed by a node that does not lead its view
+    let leader = committee.get_leader(ViewNumber::new(3)).unwrap();
+    let other = keys
+        .iter()
+        .find(|private_key| Key::from_private(private_key) != leader)
+        .unwrap();
+    let mut forged = proof;
+    forged.tip_signature = Key::sign(other, &[]);
+    assert_eq!(
+        verify_finality_proof(&committee, CHAIN_ID, &forged),
+        Err(LightClientError::InvalidProposal {
+            view_number: ViewNumber::new(3)
+        })
+    );
+}
+
+/// Finality proofs are stored once per decision, and retrieved by the height of any of its leaves
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_finality_proofs_in_storage() {
+    let (keys, committee) = committee(4);
+    let (leaves, _) = chain(&committee, &keys[..3], &[1, 2, 3, 4, 5]);
+    // leaves 2 and 3 decide leaves 0 and 1, then leaves 3 and 4 decide leaf 2
+    let leaf_chain = [leaves[1].clone(), leaves[0].clone()];
+    let tip_signature = propose(&committee, &keys, &leaves[3]);
+    let first = FinalityProof::for_leaf_chain(&leaf_chain, &leaves[2], &leaves[3], tip_signature);
+    let tip_signature = propose(&committee, &keys, &leaves[4]);
+    let second =
+        FinalityProof::for_leaf_chain(&leaves[2..3], &leaves[3], &leaves[4], tip_signature);
+
+    let storage = MemoryStorage::<Types, Leaf>::construct_tmp_storage().unwrap();
+    storage.append_finality_proof(first.clone()).await.unwrap();
+    storage.append_finality_proof(second.clone()).await.unwrap();
+    let stored = storage.get_finality_proof(0).await.unwrap();
+    assert_eq!(stored.as_ref(), Some(&first));
+    let stored = storage.get_finality_proof(1).await.unwrap();
+    assert_eq!(stored, first.for_height(1));
+    let stored = storage.get_finality_proof(2).await.unwrap();
+    assert_eq!(stored.as_ref(), Some(&second));
+    assert_eq!(storage.get_finality_proof(3).await.unwrap(), None);
+
+    // proofs outlive the views they were stored with
+    storage
+        .cleanup_storage_up_to_view(ViewNumber::new(5))
+        .await
+        .unwrap();
+    let stored = storage.get_finality_proof(2).await.unwrap();
+    assert_eq!(stored, Some(second));
+}
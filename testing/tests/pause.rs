@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use async_compatibility_layer::art::{async_sleep, async_timeout};
+use commit::Committable;
+use futures::future::join_all;
+use hotshot::traits::election::static_committee::StaticCommittee;
+use hotshot_testing::{
+    test_types::{StaticCommitteeTestTypes, StaticNodeImplType},
+    TestLauncher, TestRunner,
+};
+use hotshot_types::{
+    data::{ValidatingLeaf, ViewNumber},
+    event::EventType,
+    traits::election::Membership,
+};
+use tracing::instrument;
+
+type TestCommittee =
+    StaticCommittee<StaticCommitteeTestTypes, ValidatingLeaf<StaticCommitteeTestTypes>>;
+
+/// The number of nodes in the test networks
+const NUM_NODES: usize = 4;
+
+/// How long the tests wait for something they expect to happen
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A runner with [`NUM_NODES`] started nodes
+async fn launch() -> TestRunner<StaticCommitteeTestTypes, StaticNodeImplType> {
+    let election_config =
+        <TestCommittee as Membership<StaticCommitteeTestTypes>>::default_election_config(
+            NUM_NODES as u64,
+        );
+    let mut runner = TestLauncher::new(NUM_NODES, NUM_NODES, 0, election_config).launch();
+    runner.add_nodes(NUM_NODES).await;
+    runner
+}
+
+/// The current view of every node of `runner`
+async fn current_views(
+    runner: &TestRunner<StaticCommitteeTestTypes, StaticNodeImplType>,
+) -> Vec<ViewNumber> {
+    join_all(runner.nodes().map(|handle| handle.get_current_view())).await
+}
+
+/// Paused nodes start no views until they are resumed
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_pause_and_resume() {
+    let runner = launch().await;
+    let mut handles: Vec<_> = runner
+        .ids()
+        .into_iter()
+        .map(|id| runner.get_handle(id).unwrap())
+        .collect();
+
+    for handle in &handles {
+        handle.pause().await;
+        assert!(handle.is_paused().await);
+    }
+    let before = current_views(&runner).await;
+    for handle in &handles {
+        handle.start_one_round().await;
+    }
+    async_sleep(Duration::from_millis(
+        4 * handles[0].get_next_view_timeout(),
+    ))
+    .await;
+    assert_eq!(current_views(&runner).await, before);
+
+    for handle in &handles {
+        handle.resume().await;
+        assert!(!handle.is_paused().await);
+    }
+    for handle in &mut handles {
+        async_timeout(TIMEOUT, handle.collect_round_events())
+            .await
+            .expect("Round did not finish after resuming")
+            .unwrap();
+    }
+    for (after, before) in current_views(&runner).await.into_iter().zip(before) {
+        assert!(after > before);
+    }
+
+    runner.shutdown_all().await;
+}
+
+/// A graceful shutdown waits for the view in progress to finish
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_graceful_shutdown_drains_view() {
+    let runner = launch().await;
+    let handles: Vec<_> = runner
+        .ids()
+        .into_iter()
+        .map(|id| runner.get_handle(id).unwrap())
+        .collect();
+    // Subscribed before the round starts, so they see every event of it
+    let mut observers = handles.clone();
+
+    let before = current_views(&runner).await;
+    for handle in &handles {
+        handle.start_one_round().await;
+    }
+    // Only shut down once every node is inside the view
+    async_timeout(TIMEOUT, async {
+        while current_views(&runner)
+            .await
+            .into_iter()
+            .zip(&before)
+            .any(|(view, before)| view <= *before)
+        {
+            async_sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Nodes did not start the round");
+    let in_flight = current_views(&runner).await;
+
+    async_timeout(
+        TIMEOUT,
+        join_all(handles.into_iter().map(|handle| handle.shut_down_gracefully())),
+    )
+    .await
+    .expect("Graceful shutdown did not finish");
+
+    for (observer, view) in observers.iter_mut().zip(in_flight) {
+        let finished = observer.available_events().unwrap().into_iter().any(|event| {
+            matches!(event.event, EventType::ViewFinished { view_number } if view_number == view)
+        });
+        assert!(finished, "View {view:?} was interrupted by the shutdown");
+    }
+}
+
+/// A graceful shutdown returns the transactions submitted through the node, and not the ones it
+/// received from other nodes
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_graceful_shutdown_returns_submitted_transactions() {
+    let mut runner = launch().await;
+    let own = runner
+        .add_random_transaction(Some(0), &mut rand::thread_rng())
+        .await;
+    let other = runner
+        .add_random_transaction(Some(1), &mut rand::thread_rng())
+        .await;
+    let handle = runner.get_handle(0).unwrap();
+    async_timeout(TIMEOUT, async {
+        while !handle
+            .get_pending_transactions()
+            .await
+            .iter()
+            .any(|pending| pending.commit() == other.commit())
+        {
+            async_sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("Transaction of another node did not reach the mempool");
+
+    let unsubmitted = async_timeout(TIMEOUT, handle.shut_down_gracefully())
+        .await
+        .expect("Graceful shutdown did not finish");
+    let unsubmitted: Vec<_> = unsubmitted.iter().map(Committable::commit).collect();
+    assert_eq!(unsubmitted, vec![own.commit()]);
+
+    for id in 1..NUM_NODES as u64 {
+        runner.shutdown(id).await.unwrap();
+    }
+}
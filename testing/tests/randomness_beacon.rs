@@ -0,0 +1,139 @@
+use std::{collections::BTreeMap, num::NonZeroU64};
+
+use hotshot::{
+    certificate::{CertificateSignatures, QuorumCertificate},
+    traits::election::static_committee::StaticVoteToken,
+};
+use hotshot_testing::test_types::StaticCommitteeTestTypes;
+use hotshot_types::{
+    data::{random_commitment, ValidatingLeaf, ViewNumber},
+    traits::{
+        node_implementation::NodeType,
+        randomness_beacon::{BeaconConfig, QcRandomnessBeacon, RandomnessBeacon},
+        signature_key::SignatureKey,
+        signer::ChainId,
+        state::ConsensusTime,
+    },
+};
+use rand::SeedableRng;
+
+type Key = <StaticCommitteeTestTypes as NodeType>::SignatureKey;
+
+type Qc = QuorumCertificate<StaticCommitteeTestTypes, ValidatingLeaf<StaticCommitteeTestTypes>>;
+
+const CONFIG: BeaconConfig = BeaconConfig {
+    epoch_length: match NonZeroU64::new(10) {
+        Some(length) => length,
+        None => unreachable!(),
+    },
+    lookback: 2,
+    genesis_seed: [7; 32],
+};
+
+/// A certificate for `view` signed by the first three of four nodes
+fn qc(view: u64) -> Qc {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(view);
+    let signatures = (0..3)
+        .map(|i| {
+            let (key, private_key) = Key::generated_from_seed_indexed([0u8; 32], i);
+            let signature = Key::sign(&private_key, &view.to_le_bytes());
+            let stake = NonZeroU64::new(1).unwrap();
+            let token = StaticVoteToken::new(view, ChainId::default(), &private_key, stake);
+            (key.to_bytes(), (signature, token))
+        })
+        .collect::<BTreeMap<_, _>>();
+    QuorumCertificate {
+        leaf_commitment: random_commitment(&mut rng),
+        view_number: ViewNumber::new(view),
+        signatures: CertificateSignatures::Individual(signatures),
+        is_genesis: false,
+    }
+}
+
+fn randomness(beacon: &QcRandomnessBeacon, epoch: u64) -> Option<[u8; 32]> {
+    RandomnessBeacon::<StaticCommitteeTestTypes>::randomness(beacon, epoch)
+}
+
+/// Randomness is determined once the epoch before it is closed, and replicas recording the same
+/// certificates agree on it
+#[test]
+fn test_epoch_randomness_is_agreed() {
+    let views = [1, 2, 5, 9, 12, 13, 31];
+    let beacon = QcRandomnessBeacon::new(CONFIG);
+    let other = QcRandomnessBeacon::new(CONFIG);
+
+    assert_eq!(randomness(&beacon, 0), Some(CONFIG.genesis_seed));
+    for view in views {
+        beacon.record_decided_qc(&qc(view));
+        other.record_decided_qc(&qc(view));
+        // recording a certificate twice or out of order changes nothing
+        other.record_decided_qc(&qc(view));
+        other.record_decided_qc(&qc(1));
+        if view == 9 {
+            assert_eq!(randomness(&beacon, 1), None);
+        }
+    }
+
+    // epochs 1 to 3 are closed by the certificate of view 31, epoch 4 is not
+    for epoch in 0..=3 {
+        assert!(randomness(&beacon, epoch).is_some());
+        assert_eq!(randomness(&beacon, epoch), randomness(&other, epoch));
+    }
+    assert_eq!(randomness(&beacon, 4), None);
+    assert_ne!(randomness(&beacon, 1), randomness(&beacon, 2));
+
+    // different certificates give different randomness
+    let diverging = QcRandomnessBeacon::new(CONFIG);
+    for view in [1, 2, 5, 8, 12] {
+        diverging.record_decided_qc(&qc(view));
+    }
+    assert_ne!(randomness(&beacon, 1), randomness(&diverging, 1));
+    assert_eq!(randomness(&beacon, 0), randomness(&diverging, 0));
+}
+
+/// Views are seeded with the randomness of `lookback` epochs before them
+#[test]
+fn test_seed_for_view_looks_back() {
+    let beacon = QcRandomnessBeacon::new(CONFIG);
+    for view in [3, 14, 25, 36] {
+        beacon.record_decided_qc(&qc(view));
+    }
+    let seed = |view| {
+        RandomnessBeacon::<StaticCommitteeTestTypes>::seed_for_view(&beacon, ViewNumber::new(view))
+    };
+    assert_eq!(seed(5), Some(CONFIG.genesis_seed));
+    assert_eq!(seed(25), Some(CONFIG.genesis_seed));
+    assert_eq!(seed(35), randomness(&beacon, 1));
+    assert_eq!(seed(55), randomness(&beacon, 3));
+    // epoch 4 is not determined yet, so there is no seed rather than a stale one
+    assert_eq!(seed(65), None);
+}
+
+/// Light clients can check the randomness of an epoch from the certificates of the one before
+#[test]
+fn test_verify_randomness() {
+    let beacon = QcRandomnessBeacon::new(CONFIG);
+    for view in [1, 4, 8, 11, 15, 22] {
+        beacon.record_decided_qc(&qc(view));
+    }
+    let previous = randomness(&beacon, 1).unwrap();
+    let claimed = randomness(&beacon, 2).unwrap();
+    let verify = |qcs: &[Qc], claimed: &[u8; 32]| {
+        RandomnessBeacon::<StaticCommitteeTestTypes>::verify_randomness(
+            &beacon, &previous, 2, qcs, claimed,
+        )
+    };
+
+    assert!(verify(&[qc(11), qc(15)], &claimed));
+    // a missing, extra, reordered or foreign certificate
+    assert!(!verify(&[qc(11)], &claimed));
+    assert!(!verify(&[qc(11), qc(15), qc(16)], &claimed));
+    assert!(!verify(&[qc(15), qc(11)], &claimed));
+    assert!(!verify(&[qc(8), qc(11), qc(15)], &claimed));
+    // the wrong randomness
+    assert!(!verify(&[qc(11), qc(15)], &previous));
+
+    assert!(RandomnessBeacon::<StaticCommitteeTestTypes>::verify_randomness::<
+        ValidatingLeaf<StaticCommitteeTestTypes>,
+    >(&beacon, &[0; 32], 0, &[], &CONFIG.genesis_seed));
+}
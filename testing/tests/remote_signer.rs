@@ -0,0 +1,273 @@
+#![cfg(unix)]
+
+use std::{num::NonZeroU64, os::unix::net::UnixListener, path::Path, sync::Arc};
+
+use hotshot::traits::{
+    election::{
+        static_committee::{StaticCommittee, StaticElectionConfig},
+        vrf::VRFStakeTableConfig,
+    },
+    implementations::{serve_signer, RemoteSigner},
+};
+use hotshot_testing::test_types::{StaticCommitteeTestTypes, VrfMembership, VrfTestTypes};
+use hotshot_types::{
+    data::{ValidatingLeaf, ViewNumber},
+    traits::{
+        election::{ElectionError, Membership},
+        node_implementation::NodeType,
+        signature_key::SignatureKey,
+        signer::{
+            ChainId, DoubleSignGuard, LocalSigner, Signer, SignerError, SigningKind,
+            SigningRequest,
+        },
+        state::ConsensusTime,
+    },
+};
+use tracing::instrument;
+
+type Key = <StaticCommitteeTestTypes as NodeType>::SignatureKey;
+
+type StaticMembership =
+    StaticCommittee<StaticCommitteeTestTypes, ValidatingLeaf<StaticCommitteeTestTypes>>;
+
+/// Serve `signer` on a socket at `path`, in the background
+fn spawn_signer(path: &Path, signer: LocalSigner<Key>) {
+    let listener = UnixListener::bind(path).unwrap();
+    let signer: Arc<dyn Signer<Key>> = Arc::new(signer);
+    std::thread::spawn(move || serve_signer(&listener, &signer));
+}
+
+/// A request to sign `data` of kind `kind` for `view_number`
+fn request(view_number: u64, kind: SigningKind, data: &[u8]) -> SigningRequest {
+    SigningRequest {
+        view_number,
+        kind,
+        chain_id: ChainId::default(),
+        data: data.to_vec(),
+    }
+}
+
+/// A remote signer signs with the key held by the signer process
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_remote_signer_signs_with_served_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("signer.sock");
+    let (public_key, private_key) = Key::generated_from_seed_indexed([0u8; 32], 0);
+    spawn_signer(&path, LocalSigner::new(private_key));
+
+    let signer = RemoteSigner::<Key>::connect(&path).unwrap();
+    assert_eq!(*signer.public_key(), public_key);
+    let request = request(1, SigningKind::YesVote, b"leaf");
+    let signature = signer.sign(request.clone()).await.unwrap();
+    assert!(public_key.validate(&signature, &request.payload()));
+    assert!(!public_key.validate(&signature, b"leaf"));
+}
+
+/// The signer process refuses a second payload for the same view and kind, whichever node asks
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_remote_signer_refuses_double_sign() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("signer.sock");
+    let (_, private_key) = Key::generated_from_seed_indexed([0u8; 32], 1);
+    spawn_signer(&path, LocalSigner::new(private_key));
+
+    let first = RemoteSigner::<Key>::connect(&path).unwrap();
+    let second = RemoteSigner::<Key>::connect(&path).unwrap();
+    first
+        .sign(request(3, SigningKind::YesVote, b"a"))
+        .await
+        .unwrap();
+    assert_eq!(
+        second.sign(request(3, SigningKind::YesVote, b"b")).await,
+        Err(SignerError::DoubleSign {
+            view_number: 3,
+            kind: SigningKind::YesVote,
+        })
+    );
+
+    // the same payload, another kind or another view can still be signed
+    second
+        .sign(request(3, SigningKind::YesVote, b"a"))
+        .await
+        .unwrap();
+    second
+        .sign(request(3, SigningKind::TimeoutVote, b"b"))
+        .await
+        .unwrap();
+    second
+        .sign(request(4, SigningKind::YesVote, b"b"))
+        .await
+        .unwrap();
+}
+
+/// A yes and a no vote on the same view conflict, even for the same leaf
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_signer_refuses_yes_and_no_vote() {
+    let (_, private_key) = Key::generated_from_seed_indexed([0u8; 32], 3);
+    let signer = LocalSigner::<Key>::new(private_key);
+
+    signer
+        .sign(request(7, SigningKind::YesVote, b"leaf"))
+        .await
+        .unwrap();
+    assert_eq!(
+        signer.sign(request(7, SigningKind::NoVote, b"leaf")).await,
+        Err(SignerError::DoubleSign {
+            view_number: 7,
+            kind: SigningKind::NoVote,
+        })
+    );
+    signer
+        .sign(request(8, SigningKind::NoVote, b"leaf"))
+        .await
+        .unwrap();
+    assert_eq!(
+        signer.sign(request(8, SigningKind::YesVote, b"leaf")).await,
+        Err(SignerError::DoubleSign {
+            view_number: 8,
+            kind: SigningKind::YesVote,
+        })
+    );
+}
+
+/// A guard with a state file still refuses double signs and stale views after being reopened
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_signer_guard_survives_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("guard");
+    let (_, private_key) = Key::generated_from_seed_indexed([0u8; 32], 4);
+
+    let guard = DoubleSignGuard::open(&path, 10).unwrap();
+    let signer = LocalSigner::<Key>::with_guard(private_key.clone(), guard);
+    signer
+        .sign(request(100, SigningKind::YesVote, b"a"))
+        .await
+        .unwrap();
+    drop(signer);
+
+    let guard = DoubleSignGuard::open(&path, 10).unwrap();
+    let signer = LocalSigner::<Key>::with_guard(private_key, guard);
+    assert_eq!(
+        signer.sign(request(100, SigningKind::YesVote, b"b")).await,
+        Err(SignerError::DoubleSign {
+            view_number: 100,
+            kind: SigningKind::YesVote,
+        })
+    );
+    assert_eq!(
+        signer.sign(request(50, SigningKind::YesVote, b"b")).await,
+        Err(SignerError::StaleView {
+            view_number: 50,
+            watermark: 90,
+        })
+    );
+    signer
+        .sign(request(100, SigningKind::YesVote, b"a"))
+        .await
+        .unwrap();
+}
+
+/// Views the signer no longer remembers are refused
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_remote_signer_refuses_stale_views() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("signer.sock");
+    let (_, private_key) = Key::generated_from_seed_indexed([0u8; 32], 2);
+    spawn_signer(&path, LocalSigner::with_guard(private_key, DoubleSignGuard::new(10)));
+
+    let signer = RemoteSigner::<Key>::connect(&path).unwrap();
+    signer
+        .sign(request(100, SigningKind::QuorumProposal, b"leaf"))
+        .await
+        .unwrap();
+    assert_eq!(
+        signer
+            .sign(request(50, SigningKind::QuorumProposal, b"leaf"))
+            .await,
+        Err(SignerError::StaleView {
+            view_number: 50,
+            watermark: 90,
+        })
+    );
+    signer
+        .sign(request(95, SigningKind::QuorumProposal, b"leaf"))
+        .await
+        .unwrap();
+}
+
+/// Without a signer process, signing fails instead of blocking
+#[test]
+fn test_remote_signer_unavailable() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("signer.sock");
+    assert!(matches!(
+        RemoteSigner::<Key>::connect(&path),
+        Err(SignerError::Unavailable { .. })
+    ));
+}
+
+/// VRF elections need the private key in memory, so they cannot draw vote tokens through a remote
+/// signer, and say so up front
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_vrf_election_needs_local_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("signer.sock");
+    let keys: Vec<_> = (0..4)
+        .map(|i| Key::generated_from_seed_indexed([0u8; 32], i))
+        .collect();
+    spawn_signer(&path, LocalSigner::new(keys[0].1.clone()));
+    let signer = RemoteSigner::<Key>::connect(&path).unwrap();
+
+    let static_committee =
+        <StaticMembership as Membership<StaticCommitteeTestTypes>>::create_election(
+            keys.iter().map(|(key, _)| key.clone()).collect(),
+            StaticElectionConfig::default(),
+        );
+    assert!(!static_committee.needs_local_key());
+
+    let vrf = <VrfMembership as Membership<VrfTestTypes>>::create_election(
+        keys.iter().map(|(key, _)| key.clone()).collect(),
+        VRFStakeTableConfig {
+            distribution: vec![NonZeroU64::new(1).unwrap(); keys.len()],
+            ..VRFStakeTableConfig::default()
+        },
+    );
+    assert!(vrf.needs_local_key());
+    assert!(matches!(
+        vrf.make_vote_token(ViewNumber::new(1), ChainId::default(), &signer)
+            .await,
+        Err(ElectionError::Signer {
+            source: SignerError::NoLocalKey
+        })
+    ));
+}
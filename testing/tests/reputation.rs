@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+use futures::{future::LocalBoxFuture, FutureExt};
+use hotshot_testing::{
+    test_description::{DetailedTestDescriptionBuilder, GeneralTestDescriptionBuilder},
+    test_types::{ReputationNodeImplType, ReputationTestTypes},
+    ConsensusRoundError, RoundResult, TestRunner,
+};
+use hotshot_types::{data::ValidatingLeaf, traits::node_implementation::NodeType};
+use tracing::instrument;
+
+/// The number of views ahead of the current one whose leaders the nodes must agree on
+const SCHEDULE_LEN: usize = 30;
+
+/// Checks that all nodes still running agree on the upcoming leaders
+fn leaders_agree(
+    runner: &TestRunner<ReputationTestTypes, ReputationNodeImplType>,
+    _results: RoundResult<ReputationTestTypes, ValidatingLeaf<ReputationTestTypes>>,
+) -> LocalBoxFuture<Result<(), ConsensusRoundError>> {
+    async move {
+        let mut schedules = Vec::new();
+        for handle in runner.nodes() {
+            let from = handle.get_current_view().await;
+            schedules.push((from, handle.leader_schedule(from, SCHEDULE_LEN)));
+        }
+        if schedules.windows(2).any(|pair| pair[0] != pair[1]) {
+            return Err(ConsensusRoundError::SafetyFailed {
+                description: format!("Nodes disagree on the leader schedule: {schedules:?}"),
+            });
+        }
+        Ok(())
+    }
+    .boxed_local()
+}
+
+/// Restarts node 0 from its storage, and checks that it schedules the same leaders as before
+fn restart_keeps_schedule(
+    runner: &mut TestRunner<ReputationTestTypes, ReputationNodeImplType>,
+) -> LocalBoxFuture<Vec<<ReputationTestTypes as NodeType>::Transaction>> {
+    async move {
+        let handle = runner.get_handle(0).unwrap();
+        let from = handle.get_current_view().await;
+        let before = handle.leader_schedule(from, SCHEDULE_LEN);
+        runner.restart(0).await.unwrap();
+        let after = runner.get_handle(0).unwrap().leader_schedule(from, SCHEDULE_LEN);
+        assert_eq!(before, after);
+        Vec::new()
+    }
+    .boxed_local()
+}
+
+/// Nodes agree on the leaders while the views of a node that went down fail, and skip it
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_reputation_skips_failed_leader() {
+    let num_rounds = 20;
+    let description: DetailedTestDescriptionBuilder<ReputationTestTypes, ReputationNodeImplType> =
+        DetailedTestDescriptionBuilder {
+            general_info: GeneralTestDescriptionBuilder {
+                total_nodes: 5,
+                start_nodes: 5,
+                num_succeeds: num_rounds,
+                // the views of the downed node fail until its failure enters the window
+                failure_threshold: 5,
+                ids_to_shut_down: vec![HashSet::from([4])],
+                ..GeneralTestDescriptionBuilder::default()
+            },
+            rounds: None,
+            gen_runner: None,
+        };
+    let mut test = description.build();
+
+    for round in &mut test.rounds {
+        round.safety_check_post = Some(Box::new(leaders_agree));
+    }
+
+    test.execute().await.unwrap();
+}
+
+/// A restarted node replays the outcomes of past views from its stored finality proofs, and so
+/// keeps skipping the leader that failed before the restart
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_reputation_survives_restart() {
+    let num_rounds = 20;
+    let description: DetailedTestDescriptionBuilder<ReputationTestTypes, ReputationNodeImplType> =
+        DetailedTestDescriptionBuilder {
+            general_info: GeneralTestDescriptionBuilder {
+                total_nodes: 5,
+                start_nodes: 5,
+                num_succeeds: num_rounds,
+                failure_threshold: 5,
+                ids_to_shut_down: vec![HashSet::from([4])],
+                ..GeneralTestDescriptionBuilder::default()
+            },
+            rounds: None,
+            gen_runner: None,
+        };
+    let mut test = description.build();
+
+    for round in &mut test.rounds {
+        round.safety_check_post = Some(Box::new(leaders_agree));
+    }
+    // the restarted node has to catch up, so the last round is not checked
+    let last = test.rounds.last_mut().unwrap();
+    last.setup_round = Some(Box::new(restart_keeps_schedule));
+    last.safety_check_post = None;
+
+    test.execute().await.unwrap();
+}
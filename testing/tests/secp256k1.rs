@@ -0,0 +1,69 @@
+use futures::executor::block_on;
+use hotshot::traits::election::static_committee::StaticElectionConfig;
+use hotshot_testing::{
+    test_description::GeneralTestDescriptionBuilder,
+    test_types::{Secp256k1Membership, Secp256k1NodeImplType, Secp256k1TestTypes},
+};
+use hotshot_types::{
+    data::ViewNumber,
+    traits::{
+        election::{Checked, Membership, VoteToken},
+        signature_key::{secp256k1::Secp256k1Pub, SignatureKey},
+        signer::{ChainId, LocalSigner},
+        state::ConsensusTime,
+    },
+};
+use tracing::instrument;
+
+/// A static committee of secp256k1 keys hands out and accepts vote tokens
+#[test]
+fn test_secp256k1_static_committee_votes() {
+    let keys: Vec<_> = (0..4)
+        .map(|i| Secp256k1Pub::generated_from_seed_indexed([0u8; 32], i))
+        .collect();
+    let committee = <Secp256k1Membership as Membership<Secp256k1TestTypes>>::create_election(
+        keys.iter().map(|(key, _)| *key).collect(),
+        StaticElectionConfig::default(),
+    );
+
+    let view_number = ViewNumber::new(1);
+    for (key, private_key) in &keys {
+        let signer = LocalSigner::new(private_key.clone());
+        let token = block_on(committee.make_vote_token(view_number, ChainId::default(), &signer))
+            .unwrap()
+            .unwrap();
+        assert_eq!(token.vote_count().get(), 1);
+        let token = Checked::Unchecked(token);
+        let validated = committee.validate_vote_token(view_number, ChainId::default(), *key, token);
+        assert!(matches!(validated, Ok(Checked::Valid(_))));
+    }
+
+    // keys outside the committee get no token
+    let (_, outsider) = Secp256k1Pub::generated_from_seed_indexed([0u8; 32], 4);
+    let outsider = LocalSigner::new(outsider);
+    assert!(block_on(committee.make_vote_token(view_number, ChainId::default(), &outsider))
+        .unwrap()
+        .is_none());
+}
+
+/// Consensus makes progress with secp256k1 validator keys
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn test_secp256k1_consensus() {
+    let description = GeneralTestDescriptionBuilder {
+        total_nodes: 5,
+        start_nodes: 5,
+        num_succeeds: 5,
+        ..GeneralTestDescriptionBuilder::default()
+    };
+
+    description
+        .build::<Secp256k1TestTypes, Secp256k1NodeImplType>()
+        .execute()
+        .await
+        .unwrap();
+}
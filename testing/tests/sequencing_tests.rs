@@ -12,7 +12,10 @@ use hotshot::{
         NodeImplementation,
     },
 };
-use hotshot_testing::test_description::GeneralTestDescriptionBuilder;
+use hotshot_testing::{
+    test_description::GeneralTestDescriptionBuilder,
+    test_types::{SequencingVrfNodeImplType, SequencingVrfTestTypes},
+};
 use hotshot_types::data::CommitmentProposal;
 use hotshot_types::message::Message;
 
@@ -223,3 +226,20 @@ async fn sequencing_centralized_server_test() {
         .await
         .unwrap();
 }
+
+// Test the memory network with sequencing consensus and a VRF election.
+#[cfg_attr(
+    feature = "tokio-executor",
+    tokio::test(flavor = "multi_thread", worker_threads = 2)
+)]
+#[cfg_attr(feature = "async-std-executor", async_std::test)]
+#[instrument]
+async fn sequencing_vrf_memory_network_test() {
+    let builder = GeneralTestDescriptionBuilder::default_multiple_rounds();
+
+    builder
+        .build::<SequencingVrfTestTypes, SequencingVrfNodeImplType>()
+        .execute()
+        .await
+        .unwrap();
+}
@@ -0,0 +1,59 @@
+use futures::executor::block_on;
+use hotshot_testing::test_types::StaticCommitteeTestTypes;
+use hotshot_types::traits::{
+    node_implementation::NodeType,
+    signature_key::SignatureKey,
+    signer::{signing_payload, ChainId, LocalSigner, Signer, SigningKind, SigningRequest},
+};
+
+type Key = <StaticCommitteeTestTypes as NodeType>::SignatureKey;
+
+/// A signature is valid for the kind, chain and view it was requested for, and for no other
+#[test]
+fn test_signatures_are_domain_separated() {
+    let (public_key, private_key) = Key::generated_from_seed_indexed([0u8; 32], 0);
+    let signer = LocalSigner::new(private_key);
+    let request = SigningRequest {
+        view_number: 5,
+        kind: SigningKind::YesVote,
+        chain_id: ChainId(1),
+        data: b"leaf".to_vec(),
+    };
+    let signature = block_on(signer.sign(request.clone())).unwrap();
+    let valid_for = |kind, chain_id, view_number| {
+        let payload = signing_payload(kind, chain_id, view_number, b"leaf");
+        public_key.validate(&signature, &payload)
+    };
+
+    assert!(valid_for(SigningKind::YesVote, ChainId(1), 5));
+    assert!(!valid_for(SigningKind::NoVote, ChainId(1), 5));
+    assert!(!valid_for(SigningKind::QuorumProposal, ChainId(1), 5));
+    assert!(!valid_for(SigningKind::YesVote, ChainId(2), 5));
+    assert!(!valid_for(SigningKind::YesVote, ChainId(1), 6));
+    assert!(!public_key.validate(&signature, b"leaf"));
+}
+
+/// Payloads differing in any of their parts never coincide
+#[test]
+fn test_signing_payloads_are_distinct() {
+    let kinds = [
+        SigningKind::QuorumProposal,
+        SigningKind::DAProposal,
+        SigningKind::YesVote,
+        SigningKind::NoVote,
+        SigningKind::TimeoutVote,
+        SigningKind::DAVote,
+        SigningKind::VoteToken,
+        SigningKind::KeyRotation,
+    ];
+    let mut payloads = std::collections::HashSet::new();
+    for kind in kinds {
+        for chain_id in [ChainId(0), ChainId(1)] {
+            for view_number in [0, 1] {
+                for data in [&b""[..], b"a", b"ab"] {
+                    assert!(payloads.insert(signing_payload(kind, chain_id, view_number, data)));
+                }
+            }
+        }
+    }
+}
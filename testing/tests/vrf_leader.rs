@@ -0,0 +1,148 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroU64,
+};
+
+use hotshot::{
+    certificate::{CertificateSignatures, QuorumCertificate},
+    traits::election::vrf::VRFStakeTableConfig,
+};
+use hotshot_testing::test_types::{VrfMembership, VrfTestTypes};
+use hotshot_types::{
+    data::{random_commitment, ValidatingLeaf, ViewNumber},
+    traits::{
+        election::{ElectionError, Membership},
+        node_implementation::NodeType,
+        randomness_beacon::BeaconConfig,
+        signature_key::SignatureKey,
+        state::ConsensusTime,
+    },
+};
+use rand::SeedableRng;
+
+const NUM_VIEWS: u64 = 20_000;
+
+/// Build a VRF membership where node `i` holds `stake[i]` units of stake
+fn build_membership(
+    stake: &[u64],
+) -> (Vec<<VrfTestTypes as NodeType>::SignatureKey>, VrfMembership) {
+    build_membership_with_beacon(stake, None)
+}
+
+/// Build a VRF membership where node `i` holds `stake[i]` units of stake, seeded by `beacon`
+fn build_membership_with_beacon(
+    stake: &[u64],
+    beacon: Option<BeaconConfig>,
+) -> (Vec<<VrfTestTypes as NodeType>::SignatureKey>, VrfMembership) {
+    let keys: Vec<<VrfTestTypes as NodeType>::SignatureKey> = (0..stake.len() as u64)
+        .map(|i| SignatureKey::generated_from_seed_indexed([0u8; 32], i).0)
+        .collect();
+    let config = VRFStakeTableConfig {
+        distribution: stake
+            .iter()
+            .map(|stake| NonZeroU64::new(*stake).unwrap())
+            .collect(),
+        beacon,
+        ..VRFStakeTableConfig::default()
+    };
+    let membership =
+        <VrfMembership as Membership<VrfTestTypes>>::create_election(keys.clone(), config);
+    (keys, membership)
+}
+
+/// Leader frequency converges to the share of stake of each node
+#[test]
+fn test_vrf_leader_frequency_follows_stake() {
+    let stake = [100, 200, 300, 400];
+    let total: u64 = stake.iter().sum();
+    let (keys, membership) = build_membership(&stake);
+
+    let mut counts = HashMap::new();
+    for view in 0..NUM_VIEWS {
+        let leader = membership.get_leader(ViewNumber::new(view)).unwrap();
+        *counts.entry(leader).or_insert(0u64) += 1;
+    }
+
+    for (key, stake) in keys.iter().zip(stake) {
+        let expected = stake as f64 / total as f64;
+        let observed = *counts.get(key).unwrap_or(&0) as f64 / NUM_VIEWS as f64;
+        assert!(
+            (observed - expected).abs() < 0.02,
+            "node with stake share {expected} led {observed} of the views"
+        );
+    }
+}
+
+/// Nodes without a meaningful share of stake are rarely leader, and all replicas agree
+#[test]
+fn test_vrf_leader_is_verifiable() {
+    let stake = [1, 1000, 1000];
+    let (keys, membership) = build_membership(&stake);
+    // another replica builds its own membership from the same public data
+    let (_, other) = build_membership(&stake);
+
+    let mut small = 0;
+    for view in 0..NUM_VIEWS {
+        let leader = membership.get_leader(ViewNumber::new(view)).unwrap();
+        assert_eq!(leader, other.get_leader(ViewNumber::new(view)).unwrap());
+        if leader == keys[0] {
+            small += 1;
+        }
+    }
+    assert!(small < NUM_VIEWS / 100);
+}
+
+/// With a beacon, the leaders of later epochs depend on the decided certificates
+#[test]
+fn test_vrf_leader_follows_beacon() {
+    let stake = [100, 100, 100, 100];
+    let beacon = BeaconConfig {
+        epoch_length: NonZeroU64::new(10).unwrap(),
+        lookback: 2,
+        genesis_seed: [0u8; 32],
+    };
+    let (_, fixed) = build_membership(&stake);
+    let (_, membership) = build_membership_with_beacon(&stake, Some(beacon));
+    let (_, other) = build_membership_with_beacon(&stake, Some(beacon));
+    let (_, diverging) = build_membership_with_beacon(&stake, Some(beacon));
+
+    let qc = |view: u64| {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(view);
+        QuorumCertificate::<VrfTestTypes, ValidatingLeaf<VrfTestTypes>> {
+            leaf_commitment: random_commitment(&mut rng),
+            view_number: ViewNumber::new(view),
+            signatures: CertificateSignatures::default(),
+            is_genesis: false,
+        }
+    };
+    for view in 1..40 {
+        membership.record_decided_qc(&qc(view));
+        other.record_decided_qc(&qc(view));
+        if view != 15 {
+            diverging.record_decided_qc(&qc(view));
+        }
+    }
+
+    let leaders = |membership: &VrfMembership, from: u64| {
+        membership.leader_schedule(ViewNumber::new(from), 10)
+    };
+    // the first `lookback` epochs are seeded by the genesis seed
+    for from in [0, 10, 20] {
+        assert_eq!(leaders(&membership, from), leaders(&fixed, from));
+    }
+    // later ones by the certificates, on which replicas agree
+    assert_ne!(leaders(&membership, 30), leaders(&fixed, 30));
+    for from in [30, 40] {
+        assert_eq!(leaders(&membership, from), leaders(&other, from));
+    }
+    // epoch 4 is seeded by the certificates of epoch 1, where the replicas differ
+    assert_eq!(leaders(&membership, 30), leaders(&diverging, 30));
+    assert_ne!(leaders(&membership, 40), leaders(&diverging, 40));
+
+    // the leaders of epochs whose randomness is not decided yet are unknown, not guessed
+    assert!(matches!(
+        membership.get_leader(ViewNumber::new(100)),
+        Err(ElectionError::UnknownRandomness { view_number: 100 })
+    ));
+    assert!(leaders(&membership, 100).is_empty());
+}
@@ -0,0 +1,203 @@
+use std::{collections::HashMap, num::NonZeroU64};
+
+use futures::executor::block_on;
+use hotshot::traits::election::static_committee::{StaticCommittee, StaticElectionConfig};
+use hotshot_testing::test_types::StaticCommitteeTestTypes;
+use hotshot_types::{
+    data::{ValidatingLeaf, ViewNumber},
+    traits::{
+        election::{Checked, ElectionConfig, Membership, StakeThresholds, VoteToken},
+        node_implementation::NodeType,
+        signature_key::SignatureKey,
+        signer::{ChainId, LocalSigner},
+        state::ConsensusTime,
+    },
+};
+
+type WeightedCommittee =
+    StaticCommittee<StaticCommitteeTestTypes, ValidatingLeaf<StaticCommitteeTestTypes>>;
+
+type Key = <StaticCommitteeTestTypes as NodeType>::SignatureKey;
+
+type KeyPair = (Key, <Key as SignatureKey>::PrivateKey);
+
+/// Build a committee where node `i` holds `stake[i]` units of stake
+fn build_committee(stake: &[u64]) -> (Vec<KeyPair>, WeightedCommittee) {
+    let keys: Vec<KeyPair> = (0..stake.len() as u64)
+        .map(|i| Key::generated_from_seed_indexed([0u8; 32], i))
+        .collect();
+    let config = StaticElectionConfig {
+        stake: stake
+            .iter()
+            .map(|stake| NonZeroU64::new(*stake).unwrap())
+            .collect(),
+        ..StaticElectionConfig::default()
+    };
+    let committee = <WeightedCommittee as Membership<StaticCommitteeTestTypes>>::create_election(
+        keys.iter().map(|(key, _)| key.clone()).collect(),
+        config,
+    );
+    (keys, committee)
+}
+
+/// Every node leads exactly as many views as it has units of stake, in every run of views as long
+/// as the total stake
+#[test]
+fn test_weighted_leader_schedule() {
+    let stake = [1, 2, 3, 7];
+    let total: u64 = stake.iter().sum();
+    let (keys, committee) = build_committee(&stake);
+
+    for start in [0, 5, 1000] {
+        let mut counts = HashMap::new();
+        for view in start..start + total {
+            let leader = committee.get_leader(ViewNumber::new(view)).unwrap();
+            *counts.entry(leader).or_insert(0u64) += 1;
+        }
+        for ((key, _), stake) in keys.iter().zip(stake) {
+            assert_eq!(counts.get(key).copied().unwrap_or(0), stake);
+        }
+    }
+
+    // the heaviest node does not lead all of its views in a row
+    let heaviest = &keys[3].0;
+    let longest_run = (0..total)
+        .map(|view| committee.get_leader(ViewNumber::new(view)).unwrap() == *heaviest)
+        .fold((0, 0), |(longest, current), leads| {
+            let current = if leads { current + 1 } else { 0 };
+            (longest.max(current), current)
+        })
+        .0;
+    assert!(longest_run < 7);
+}
+
+/// Vote tokens carry the stake of their signer, and the threshold is two thirds of the total stake
+#[test]
+fn test_weighted_votes() {
+    let stake = [1, 2, 3, 7];
+    let (keys, committee) = build_committee(&stake);
+    assert_eq!(committee.threshold().get(), 9);
+
+    let view_number = ViewNumber::new(1);
+    for ((key, private_key), stake) in keys.iter().zip(stake) {
+        let signer = LocalSigner::new(private_key.clone());
+        let token = block_on(committee.make_vote_token(view_number, ChainId::default(), &signer))
+            .unwrap()
+            .unwrap();
+        assert_eq!(token.vote_count().get(), stake);
+        let token = Checked::Unchecked(token);
+        let validated =
+            committee.validate_vote_token(view_number, ChainId::default(), key.clone(), token);
+        assert!(matches!(validated, Ok(Checked::Valid(_))));
+    }
+
+    // a token claiming more stake than configured is rejected
+    let (_, inflated) = build_committee(&[1, 2, 3, 50]);
+    let (key, private_key) = &keys[3];
+    let signer = LocalSigner::new(private_key.clone());
+    let token = block_on(inflated.make_vote_token(view_number, ChainId::default(), &signer))
+        .unwrap()
+        .unwrap();
+    let token = Checked::Unchecked(token);
+    let validated =
+        committee.validate_vote_token(view_number, ChainId::default(), key.clone(), token);
+    assert!(matches!(validated, Ok(Checked::Inval(_))));
+}
+
+/// A token only counts in the view and on the network it was signed for
+#[test]
+fn test_vote_token_signature_checked() {
+    let (keys, committee) = build_committee(&[1, 1, 1, 1]);
+    let (key, private_key) = &keys[0];
+    let signer = LocalSigner::new(private_key.clone());
+    let view_number = ViewNumber::new(1);
+    let token = block_on(committee.make_vote_token(view_number, ChainId(1), &signer))
+        .unwrap()
+        .unwrap();
+    let validate = |view_number: ViewNumber, chain_id: ChainId| {
+        let token = Checked::Unchecked(token.clone());
+        committee.validate_vote_token(view_number, chain_id, key.clone(), token)
+    };
+
+    assert!(matches!(validate(view_number, ChainId(1)), Ok(Checked::Valid(_))));
+    assert!(matches!(validate(view_number, ChainId(2)), Ok(Checked::Inval(_))));
+    let next_view = ViewNumber::new(2);
+    assert!(matches!(validate(next_view, ChainId(1)), Ok(Checked::Inval(_))));
+
+    // a token of another key
+    let (other, _) = &keys[1];
+    let token = Checked::Unchecked(token.clone());
+    let validated = committee.validate_vote_token(view_number, ChainId(1), other.clone(), token);
+    assert!(matches!(validated, Ok(Checked::Inval(_))));
+}
+
+/// Without configured stake, the committee is equal-weight round robin
+#[test]
+fn test_unweighted_committee() {
+    let (keys, committee) = build_committee(&[1, 1, 1, 1]);
+    let default = <WeightedCommittee as Membership<StaticCommitteeTestTypes>>::create_election(
+        keys.iter().map(|(key, _)| key.clone()).collect(),
+        StaticElectionConfig::default(),
+    );
+    assert_eq!(committee, default);
+    for view in 0..8 {
+        assert_eq!(
+            default.get_leader(ViewNumber::new(view)).unwrap(),
+            keys[view as usize % 4].0
+        );
+    }
+    assert_eq!(default.threshold().get(), 3);
+}
+
+/// Quorum and DA thresholds are configured separately, and checked against the BFT bound
+#[test]
+fn test_configured_thresholds() {
+    let keys: Vec<Key> = (0..4)
+        .map(|i| Key::generated_from_seed_indexed([0u8; 32], i).0)
+        .collect();
+    let config = StaticElectionConfig {
+        stake: vec![NonZeroU64::new(25).unwrap(); 4],
+        thresholds: StakeThresholds {
+            quorum: (3, 4),
+            da: (1, 3),
+        },
+    };
+    assert!(config.validate(4).is_ok());
+    let committee =
+        <WeightedCommittee as Membership<StaticCommitteeTestTypes>>::create_election(keys, config);
+    assert_eq!(committee.threshold().get(), 76);
+    assert_eq!(committee.da_threshold().get(), 34);
+
+    for (quorum, da) in [((1, 2), (1, 3)), ((2, 3), (1, 4)), ((1, 1), (1, 3)), ((2, 3), (1, 0))] {
+        let config = StaticElectionConfig {
+            thresholds: StakeThresholds { quorum, da },
+            ..StaticElectionConfig::default()
+        };
+        assert!(config.validate(4).is_err(), "{quorum:?} {da:?} should be rejected");
+    }
+}
+
+/// Configurations whose stake does not fit the committee are rejected
+#[test]
+fn test_stake_validation() {
+    let stake = |stake: &[u64]| StaticElectionConfig {
+        stake: stake.iter().map(|s| NonZeroU64::new(*s).unwrap()).collect(),
+        ..StaticElectionConfig::default()
+    };
+
+    assert!(stake(&[]).validate(3).is_ok());
+    assert!(stake(&[1, 2, 3]).validate(3).is_ok());
+    assert!(stake(&[]).validate(0).is_err());
+    assert!(stake(&[1, 2]).validate(3).is_err());
+    assert!(stake(&[u64::MAX, 1]).validate(2).is_err());
+}
+
+/// A committee is never built from fewer stakes than nodes
+#[test]
+#[should_panic(expected = "every node of a static committee needs a stake")]
+fn test_mismatched_stake_is_rejected() {
+    let keys = (0..3)
+        .map(|i| Key::generated_from_seed_indexed([0u8; 32], i).0)
+        .collect();
+    let _ = WeightedCommittee::with_stake(keys, vec![NonZeroU64::new(1).unwrap(); 2]);
+}
@@ -0,0 +1,98 @@
+use hotshot_types::traits::network::{decode_message, encode_message, NetworkError, NetworkMsg};
+use hotshot_utils::version::ProtocolVersion;
+use serde::{Deserialize, Serialize};
+
+/// A message carried in the default layout of every version
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Ping {
+    /// The number of pings sent before this one
+    seq: u64,
+}
+
+impl NetworkMsg for Ping {}
+
+/// A message remembering the version it was decoded from
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Echo {
+    /// The version of the header of the message
+    version: ProtocolVersion,
+    /// The payload following the header
+    payload: Vec<u8>,
+}
+
+impl NetworkMsg for Echo {
+    fn decode_payload(version: ProtocolVersion, payload: &[u8]) -> Result<Self, bincode::Error> {
+        Ok(Self {
+            version,
+            payload: payload.to_vec(),
+        })
+    }
+}
+
+/// A version `minor` minor versions after the current one
+fn minor(minor: u16) -> ProtocolVersion {
+    ProtocolVersion {
+        minor: ProtocolVersion::CURRENT.minor + minor,
+        ..ProtocolVersion::CURRENT
+    }
+}
+
+/// Nodes one minor version apart take part in the same network and read what the other writes
+#[test]
+fn test_protocol_version_compatibility() {
+    let (oldest, old, new) = (minor(1), minor(2), minor(3));
+    assert!(old.is_compatible(new));
+    assert!(new.is_compatible(old));
+    assert!(old.reads(new.written()));
+    assert!(new.reads(old.written()));
+    assert!(new.reads(new.written()));
+
+    assert!(!oldest.is_compatible(new));
+    assert!(!oldest.reads(new.written()));
+    assert!(!new.reads(oldest.written()));
+    let next_major = ProtocolVersion {
+        major: ProtocolVersion::CURRENT.major + 1,
+        ..ProtocolVersion::CURRENT
+    };
+    assert!(!ProtocolVersion::CURRENT.is_compatible(next_major));
+    assert!(!next_major.reads(ProtocolVersion::CURRENT.written()));
+
+    assert_eq!(new.to_string().parse(), Ok(new));
+    assert!("1".parse::<ProtocolVersion>().is_err());
+}
+
+/// Messages go on the wire behind the header of the version they are written in
+#[test]
+fn test_versioned_envelope() {
+    let ping = Ping { seq: 3 };
+    let bytes = encode_message(&ping).unwrap();
+    let written = ProtocolVersion::CURRENT.written();
+    assert_eq!(bytes[..ProtocolVersion::HEADER_LEN], written.to_header());
+    assert_eq!(ProtocolVersion::split_header(&bytes).unwrap().0, written);
+    assert_eq!(decode_message::<Ping>(&bytes).unwrap(), ping);
+
+    assert!(matches!(
+        decode_message::<Ping>(&bytes[..2]),
+        Err(NetworkError::MissingVersion)
+    ));
+    let mut unsupported = bytes.clone();
+    unsupported[..ProtocolVersion::HEADER_LEN].copy_from_slice(&minor(1).to_header());
+    assert!(matches!(
+        decode_message::<Ping>(&unsupported),
+        Err(NetworkError::UnsupportedVersion { version }) if version == minor(1)
+    ));
+    assert!(matches!(
+        decode_message::<Ping>(&bytes[..bytes.len() - 1]),
+        Err(NetworkError::FailedToDeserialize { .. })
+    ));
+}
+
+/// Payloads are decoded in the layout of the version of their header
+#[test]
+fn test_decoding_dispatches_on_version() {
+    let mut bytes = ProtocolVersion::CURRENT.to_header().to_vec();
+    bytes.extend(b"payload");
+    let echo = decode_message::<Echo>(&bytes).unwrap();
+    assert_eq!(echo.version, ProtocolVersion::CURRENT);
+    assert_eq!(echo.payload, b"payload");
+}
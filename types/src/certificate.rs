@@ -5,14 +5,18 @@ use crate::{
     traits::{
         election::{SignedCertificate, VoteData, VoteToken},
         node_implementation::NodeType,
-        signature_key::{EncodedPublicKey, EncodedSignature},
+        signature_key::{EncodedPublicKey, EncodedSignature, SignatureKey},
         state::ConsensusTime,
     },
 };
 use commit::{Commitment, Committable};
 use espresso_systems_common::hotshot::tag;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt::Debug, ops::Deref};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+    ops::Deref,
+};
 
 /// A `DACertificate` is a threshold signature that some data is available.
 /// It is signed by the members of the DA committee, not the entire network. It is used
@@ -35,7 +39,7 @@ pub struct DACertificate<TYPES: NodeType> {
     /// These formats are deliberatly done as a `Vec` instead of an array to prevent creating the
     /// assumption that singatures are constant in length
     /// TODO (da) make a separate vote token type for DA and QC
-    pub signatures: BTreeMap<EncodedPublicKey, (EncodedSignature, TYPES::VoteTokenType)>,
+    pub signatures: CertificateSignatures<TYPES::VoteTokenType>,
     // no genesis bc not meaningful
 }
 
@@ -56,11 +60,187 @@ pub struct QuorumCertificate<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>>
     /// Which view this QC relates to
     pub view_number: TYPES::Time,
     /// Threshold Signature
-    pub signatures: BTreeMap<EncodedPublicKey, (EncodedSignature, TYPES::VoteTokenType)>,
+    pub signatures: CertificateSignatures<TYPES::VoteTokenType>,
     /// If this QC is for the genesis block
     pub is_genesis: bool,
 }
 
+/// The signatures of a certificate, in one of the representations certificates can use
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CertificateSignatures<TOKEN> {
+    /// Every signature and vote token, keyed by the byte encoded public key of its signer
+    Individual(BTreeMap<EncodedPublicKey, (EncodedSignature, TOKEN)>),
+    /// A single signature aggregated from the votes of the signers
+    Aggregated(AggregateSignature<TOKEN>),
+}
+
+impl<TOKEN> Default for CertificateSignatures<TOKEN> {
+    fn default() -> Self {
+        Self::Individual(BTreeMap::new())
+    }
+}
+
+/// A signature aggregated from the votes of several members of a committee, all on the same data
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AggregateSignature<TOKEN> {
+    /// The aggregate signature, as produced by [`SignatureKey::aggregate`]
+    pub signature: EncodedSignature,
+    /// Which members of the committee signed, in stake table order
+    pub signers: SignerBitmap,
+    /// The vote tokens of the signers, in the order of `signers`
+    pub tokens: Vec<TOKEN>,
+}
+
+/// A bitmap over the members of a committee, in the order of their public keys
+///
+/// The committee is the one returned by
+/// [`Membership::get_committee`](crate::traits::election::Membership::get_committee) for the view
+/// of the certificate, both when aggregating and when verifying. After a key rotation the new key
+/// may sort to another position, so a bitmap is only meaningful for the view it was made in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SignerBitmap {
+    /// number of members of the committee
+    len: u64,
+    /// the bits, least significant bit of the first byte first
+    bits: Vec<u8>,
+}
+
+impl SignerBitmap {
+    /// A bitmap over a committee of `len` members, none of which are set
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            len: len as u64,
+            bits: vec![0; (len + 7) / 8],
+        }
+    }
+
+    /// Number of members of the committee
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Whether the committee is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Mark member `index` as a signer
+    ///
+    /// # Panics
+    /// If `index` is not a member of the committee
+    pub fn set(&mut self, index: usize) {
+        assert!(index < self.len(), "signer {index} is out of range");
+        self.bits[index / 8] |= 1 << (index % 8);
+    }
+
+    /// Whether member `index` signed
+    #[must_use]
+    pub fn get(&self, index: usize) -> bool {
+        index < self.len()
+            && self
+                .bits
+                .get(index / 8)
+                .map_or(false, |byte| byte & (1 << (index % 8)) != 0)
+    }
+
+    /// The indices of the signers, in increasing order
+    pub fn signers(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len()).filter(|index| self.get(*index))
+    }
+
+    /// Whether the encoding is canonical: one bit per member, with no bits set past the end
+    ///
+    /// Bitmaps received from the network must be checked with this before use, so that a
+    /// certificate has a single encoding.
+    #[must_use]
+    pub fn is_well_formed(&self) -> bool {
+        let len = self.len();
+        self.bits.len() == (len + 7) / 8
+            && (len % 8 == 0 || self.bits.last().map_or(true, |last| last >> (len % 8) == 0))
+    }
+
+    /// The raw bits
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+}
+
+impl<TOKEN: VoteToken> CertificateSignatures<TOKEN> {
+    /// Aggregate `signatures` on the same data against `committee`, whose order is the stake
+    /// table order
+    ///
+    /// Falls back to the individual representation if the signature scheme cannot aggregate, or
+    /// if a signer is not a member of the committee.
+    #[must_use]
+    pub fn aggregate<KEY: SignatureKey>(
+        signatures: BTreeMap<EncodedPublicKey, (EncodedSignature, TOKEN)>,
+        committee: &BTreeSet<KEY>,
+    ) -> Self {
+        let mut signers = SignerBitmap::new(committee.len());
+        let mut keys = Vec::with_capacity(signatures.len());
+        let mut encoded_signatures = Vec::with_capacity(signatures.len());
+        let mut tokens = Vec::with_capacity(signatures.len());
+        for (index, key) in committee.iter().enumerate() {
+            if let Some((signature, token)) = signatures.get(&key.to_bytes()) {
+                signers.set(index);
+                keys.push(key.clone());
+                encoded_signatures.push(signature.clone());
+                tokens.push(token.clone());
+            }
+        }
+        if keys.len() != signatures.len() {
+            return Self::Individual(signatures);
+        }
+        match KEY::aggregate(&keys, &encoded_signatures) {
+            Some(signature) => Self::Aggregated(AggregateSignature {
+                signature,
+                signers,
+                tokens,
+            }),
+            None => Self::Individual(signatures),
+        }
+    }
+
+    /// Number of signers
+    #[must_use]
+    pub fn num_signers(&self) -> usize {
+        match self {
+            Self::Individual(signatures) => signatures.len(),
+            Self::Aggregated(aggregate) => aggregate.tokens.len(),
+        }
+    }
+
+    /// Bytes committing to the signatures, for commitments to structures containing certificates
+    #[must_use]
+    pub fn commitment_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        match self {
+            Self::Individual(signatures) => {
+                for (k, v) in signatures {
+                    bytes.extend(&k.0);
+                    bytes.extend(&v.0 .0);
+                    bytes.extend::<&[u8]>(v.1.commit().as_ref());
+                }
+            }
+            Self::Aggregated(aggregate) => {
+                bytes.extend(b"aggregate");
+                bytes.extend(aggregate.signers.len.to_le_bytes());
+                bytes.extend(aggregate.signers.as_bytes());
+                bytes.extend(&aggregate.signature.0);
+                for token in &aggregate.tokens {
+                    bytes.extend::<&[u8]>(token.commit().as_ref());
+                }
+            }
+        }
+        bytes
+    }
+}
+
 /// Data from a vote needed to accumulate into a `SignedCertificate`
 pub struct VoteMetaData<TYPES: NodeType, C: Committable, T: VoteToken, TIME, LEAF: LeafType> {
     /// Voter's public key
@@ -83,7 +263,7 @@ impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>>
 {
     fn from_signatures_and_commitment(
         view_number: TYPES::Time,
-        signatures: BTreeMap<EncodedPublicKey, (EncodedSignature, TYPES::VoteTokenType)>,
+        signatures: CertificateSignatures<TYPES::VoteTokenType>,
         commit: Commitment<LEAF>,
     ) -> Self {
         QuorumCertificate {
@@ -98,8 +278,8 @@ impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>>
         self.view_number
     }
 
-    fn signatures(&self) -> BTreeMap<EncodedPublicKey, (EncodedSignature, TYPES::VoteTokenType)> {
-        self.signatures.clone()
+    fn signatures(&self) -> &CertificateSignatures<TYPES::VoteTokenType> {
+        &self.signatures
     }
 
     fn leaf_commitment(&self) -> Commitment<LEAF> {
@@ -118,7 +298,7 @@ impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>>
         Self {
             leaf_commitment: fake_commitment::<LEAF>(),
             view_number: <TYPES::Time as ConsensusTime>::genesis(),
-            signatures: BTreeMap::default(),
+            signatures: CertificateSignatures::default(),
             is_genesis: true,
         }
     }
@@ -136,11 +316,24 @@ impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> Committable
             .field("Leaf commitment", self.leaf_commitment)
             .u64_field("View number", *self.view_number.deref());
 
-        for (idx, (k, v)) in self.signatures.iter().enumerate() {
-            builder = builder
-                .var_size_field(&format!("Signature {idx} public key"), &k.0)
-                .var_size_field(&format!("Signature {idx} signature"), &v.0 .0)
-                .field(&format!("Signature {idx} signature"), v.1.commit());
+        match &self.signatures {
+            CertificateSignatures::Individual(signatures) => {
+                for (idx, (k, v)) in signatures.iter().enumerate() {
+                    builder = builder
+                        .var_size_field(&format!("Signature {idx} public key"), &k.0)
+                        .var_size_field(&format!("Signature {idx} signature"), &v.0 .0)
+                        .field(&format!("Signature {idx} signature"), v.1.commit());
+                }
+            }
+            CertificateSignatures::Aggregated(aggregate) => {
+                builder = builder
+                    .var_size_field("Aggregate signature", &aggregate.signature.0)
+                    .u64_field("Signers count", aggregate.signers.len() as u64)
+                    .var_size_field("Signers", aggregate.signers.as_bytes());
+                for (idx, token) in aggregate.tokens.iter().enumerate() {
+                    builder = builder.field(&format!("Signer {idx} vote token"), token.commit());
+                }
+            }
         }
 
         builder
@@ -159,7 +352,7 @@ impl<TYPES: NodeType>
 {
     fn from_signatures_and_commitment(
         view_number: TYPES::Time,
-        signatures: BTreeMap<EncodedPublicKey, (EncodedSignature, TYPES::VoteTokenType)>,
+        signatures: CertificateSignatures<TYPES::VoteTokenType>,
         commit: Commitment<TYPES::BlockType>,
     ) -> Self {
         DACertificate {
@@ -173,8 +366,8 @@ impl<TYPES: NodeType>
         self.view_number
     }
 
-    fn signatures(&self) -> BTreeMap<EncodedPublicKey, (EncodedSignature, TYPES::VoteTokenType)> {
-        self.signatures.clone()
+    fn signatures(&self) -> &CertificateSignatures<TYPES::VoteTokenType> {
+        &self.signatures
     }
 
     fn leaf_commitment(&self) -> Commitment<TYPES::BlockType> {
@@ -197,3 +390,33 @@ impl<TYPES: NodeType>
 }
 
 impl<TYPES: NodeType> Eq for DACertificate<TYPES> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn signer_bitmap() {
+        let mut bitmap = SignerBitmap::new(11);
+        assert_eq!(bitmap.len(), 11);
+        assert_eq!(bitmap.as_bytes().len(), 2);
+        for index in [0, 3, 10] {
+            bitmap.set(index);
+        }
+        assert!(bitmap.get(3) && !bitmap.get(4) && !bitmap.get(11));
+        assert_eq!(bitmap.signers().collect::<Vec<_>>(), vec![0, 3, 10]);
+        assert!(bitmap.is_well_formed());
+        assert!(SignerBitmap::new(0).is_empty());
+        assert!(SignerBitmap::new(0).is_well_formed());
+
+        // bits past the end of the committee, or a short encoding, are not canonical
+        let mut padded = bitmap.clone();
+        padded.bits[1] |= 0x80;
+        assert!(!padded.is_well_formed());
+        let short = SignerBitmap {
+            len: 11,
+            bits: vec![1],
+        };
+        assert!(!short.is_well_formed());
+    }
+}
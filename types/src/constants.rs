@@ -1,10 +1,17 @@
 //! configurable constants for hotshot
 
 use crate::traits::signature_key::EncodedPublicKey;
+use std::num::NonZeroU64;
 
 /// the number of views to gather information for ahead of time
 pub const LOOK_AHEAD: u64 = 5;
 
+/// the number of views in an epoch of the stake table, if the configuration does not set one
+#[must_use]
+pub fn default_key_rotation_epoch() -> NonZeroU64 {
+    NonZeroU64::new(100).unwrap()
+}
+
 /// the genesis proposer pk
 /// unfortunately need to allocate on the heap (for vec), so this ends up as a function instead of a
 /// const
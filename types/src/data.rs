@@ -6,10 +6,12 @@
 use crate::{
     certificate::{DACertificate, QuorumCertificate},
     constants::genesis_proposer_id,
+    key_rotation::KeyRotation,
     traits::{
         election::SignedCertificate,
         node_implementation::NodeType,
         signature_key::EncodedPublicKey,
+        signer::ChainId,
         state::{ConsensusTime, TestableBlock, TestableState, ValidatingConsensusType},
         storage::StoredView,
         Block, State,
@@ -115,6 +117,9 @@ where
     /// Transactions that were marked for rejection while collecting deltas
     pub rejected: Vec<<TYPES::BlockType as Block>::Transaction>,
 
+    /// Key rotations the leaf carries, applied when it is decided
+    pub key_rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
+
     /// the propser id
     pub proposer_id: EncodedPublicKey,
 }
@@ -147,6 +152,9 @@ pub struct CommitmentProposal<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>>
     /// Data availibity certificate
     pub dac: DACertificate<TYPES>,
 
+    /// Key rotations the leaf carries, applied when it is decided
+    pub key_rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
+
     /// the propser id
     pub proposer_id: EncodedPublicKey,
 }
@@ -342,6 +350,8 @@ pub trait LeafType:
     fn get_height(&self) -> u64;
     /// Change the height of this leaf.
     fn set_height(&mut self, height: u64);
+    /// Change the commitment to this leaf's parent.
+    fn set_parent_commitment(&mut self, parent_commitment: Commitment<Self>);
     /// The QC linking this leaf to its parent in the chain.
     fn get_justify_qc(&self) -> QuorumCertificate<Self::NodeType, Self>;
     /// Commitment to this leaf's parent.
@@ -362,12 +372,16 @@ pub trait LeafType:
     fn get_state(&self) -> Self::StateCommitmentType;
     /// Transactions rejected or invalidated by the application of this leaf.
     fn get_rejected(&self) -> Vec<LeafTransaction<Self>>;
+    /// Key rotations carried by this leaf, applied to the stake table when it is decided.
+    fn get_key_rotations(&self) -> Vec<KeyRotation<LeafKey<Self>>>;
     /// Real-world time when this leaf was created.
     fn get_timestamp(&self) -> i128;
     /// Identity of the network participant who proposed this leaf.
     fn get_proposer_id(&self) -> EncodedPublicKey;
     /// Create a leaf from information stored about a view.
     fn from_stored_view(stored_view: StoredView<Self::NodeType, Self>) -> Self;
+    /// The header opening the commitment of this leaf to the commitment of its block.
+    fn header(&self) -> LeafHeader<Self::NodeType, Self>;
 
     /// A commitment to the block contained in this leaf.
     fn get_deltas_commitment(&self) -> Commitment<LeafBlock<Self>> {
@@ -389,6 +403,8 @@ pub type LeafBlock<LEAF> = <LeafNode<LEAF> as NodeType>::BlockType;
 pub type LeafTransaction<LEAF> = <LeafBlock<LEAF> as Block>::Transaction;
 /// The [`ConsensusTime`] used by a [`LeafType`].
 pub type LeafTime<LEAF> = <LeafNode<LEAF> as NodeType>::Time;
+/// The [`SignatureKey`](crate::traits::signature_key::SignatureKey) used by a [`LeafType`].
+pub type LeafKey<LEAF> = <LeafNode<LEAF> as NodeType>::SignatureKey;
 
 /// Additional functions required to use a [`LeafType`] with hotshot-testing.
 pub trait TestableLeaf {
@@ -403,6 +419,96 @@ pub trait TestableLeaf {
     ) -> <<Self::NodeType as NodeType>::BlockType as Block>::Transaction;
 }
 
+/// The fields of a leaf that its commitment binds, with its block and state replaced by their
+/// commitments
+///
+/// A header opens the commitment of its leaf: [`LeafHeader::leaf_commitment`] recomputes it, so
+/// whoever trusts the leaf commitment can check the block commitment, and so the block, without
+/// the rest of the leaf.
+#[derive(Serialize, Deserialize, Clone, Debug, Derivative)]
+#[serde(bound(deserialize = ""))]
+#[derivative(Hash, PartialEq, Eq)]
+pub struct LeafHeader<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> {
+    /// The view of the leaf
+    pub view_number: TYPES::Time,
+
+    /// Number of leaves before the leaf in the chain
+    pub height: u64,
+
+    /// The commitment of the parent of the leaf
+    pub parent_commitment: Commitment<LEAF>,
+
+    /// The commitment of the block of the leaf
+    pub block_commitment: Commitment<TYPES::BlockType>,
+
+    /// The commitment of the state after the leaf, for leaves that commit to their state
+    pub state_commitment: Option<Commitment<TYPES::StateType>>,
+
+    /// The certificate of the parent of the leaf
+    pub justify_qc: QuorumCertificate<TYPES, LEAF>,
+
+    /// The key rotations carried by the leaf, so that light clients can follow the stake table
+    pub key_rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
+
+    /// The key of the node that proposed the leaf, as the node storing the header saw it
+    ///
+    /// The leaf does not commit to its proposer, so only the node's own headers can be trusted
+    /// for it; it lets a restarted node replay the outcomes of past views.
+    pub proposer_id: EncodedPublicKey,
+}
+
+impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> LeafHeader<TYPES, LEAF> {
+    /// The commitment of the leaf this is the header of
+    #[must_use]
+    pub fn leaf_commitment(&self) -> Commitment<LEAF> {
+        leaf_commitment(
+            self.view_number,
+            self.height,
+            self.parent_commitment,
+            self.block_commitment,
+            self.state_commitment,
+            &self.justify_qc,
+            &self.key_rotations,
+        )
+    }
+}
+
+/// The commitment of a leaf with the given fields, shared by leaves and their [`LeafHeader`]s
+fn leaf_commitment<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>>(
+    view_number: TYPES::Time,
+    height: u64,
+    parent_commitment: Commitment<LEAF>,
+    block_commitment: Commitment<TYPES::BlockType>,
+    state_commitment: Option<Commitment<TYPES::StateType>>,
+    justify_qc: &QuorumCertificate<TYPES, LEAF>,
+    key_rotations: &[KeyRotation<TYPES::SignatureKey>],
+) -> Commitment<LEAF> {
+    let signatures_bytes = justify_qc.signatures.commitment_bytes();
+    let mut builder = commit::RawCommitmentBuilder::new("Leaf Comm")
+        .u64_field("view_number", *view_number)
+        .u64_field("height", height)
+        .field("parent Leaf commitment", parent_commitment)
+        .field("block commitment", block_commitment);
+    if let Some(state_commitment) = state_commitment {
+        builder = builder.field("state commitment", state_commitment);
+    }
+    if !key_rotations.is_empty() {
+        builder = builder
+            .constant_str("key rotations")
+            .u64(key_rotations.len() as u64);
+        for rotation in key_rotations {
+            builder = builder.field("key rotation", rotation.commit());
+        }
+    }
+    builder
+        .constant_str("justify_qc view number")
+        .u64(*justify_qc.view_number)
+        .field("justify_qc leaf commitment", justify_qc.leaf_commitment())
+        .constant_str("justify_qc signatures")
+        .var_size_bytes(&signatures_bytes)
+        .finalize()
+}
+
 /// This is the consensus-internal analogous concept to a block, and it contains the block proper,
 /// as well as the hash of its parent `Leaf`.
 /// NOTE: `State` is constrained to implementing `BlockContents`, is `TypeMap::Block`
@@ -432,6 +538,9 @@ pub struct ValidatingLeaf<TYPES: NodeType> {
     /// Transactions that were marked for rejection while collecting deltas
     pub rejected: Vec<<TYPES::BlockType as Block>::Transaction>,
 
+    /// Key rotations carried by the leaf, applied to the stake table when it is decided
+    pub key_rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
+
     /// the timestamp the leaf was constructed at, in nanoseconds. Only exposed for dashboard stats
     #[derivative(PartialEq = "ignore")]
     #[derivative(Hash = "ignore")]
@@ -469,6 +578,9 @@ pub struct SequencingLeaf<TYPES: NodeType> {
     /// Transactions that were marked for rejection while collecting deltas
     pub rejected: Vec<<TYPES::BlockType as Block>::Transaction>,
 
+    /// Key rotations carried by the leaf, applied to the stake table when it is decided
+    pub key_rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
+
     /// the timestamp the leaf was constructed at, in nanoseconds. Only exposed for dashboard stats
     #[derivative(PartialEq = "ignore")]
     pub timestamp: i128,
@@ -497,6 +609,7 @@ impl<TYPES: NodeType> LeafType for ValidatingLeaf<TYPES> {
             deltas,
             state,
             rejected: Vec::new(),
+            key_rotations: Vec::new(),
             timestamp: time::OffsetDateTime::now_utc().unix_timestamp_nanos(),
             proposer_id: genesis_proposer_id(),
         }
@@ -514,6 +627,10 @@ impl<TYPES: NodeType> LeafType for ValidatingLeaf<TYPES> {
         self.height = height;
     }
 
+    fn set_parent_commitment(&mut self, parent_commitment: Commitment<Self>) {
+        self.parent_commitment = parent_commitment;
+    }
+
     fn get_justify_qc(&self) -> QuorumCertificate<TYPES, Self> {
         self.justify_qc.clone()
     }
@@ -542,6 +659,10 @@ impl<TYPES: NodeType> LeafType for ValidatingLeaf<TYPES> {
         self.rejected.clone()
     }
 
+    fn get_key_rotations(&self) -> Vec<KeyRotation<TYPES::SignatureKey>> {
+        self.key_rotations.clone()
+    }
+
     fn get_timestamp(&self) -> i128 {
         self.timestamp
     }
@@ -559,10 +680,24 @@ impl<TYPES: NodeType> LeafType for ValidatingLeaf<TYPES> {
             deltas: stored_view.deltas,
             state: stored_view.state,
             rejected: stored_view.rejected,
+            key_rotations: stored_view.key_rotations,
             timestamp: stored_view.timestamp,
             proposer_id: stored_view.proposer_id,
         }
     }
+
+    fn header(&self) -> LeafHeader<TYPES, Self> {
+        LeafHeader {
+            view_number: self.view_number,
+            height: self.height,
+            parent_commitment: self.parent_commitment,
+            block_commitment: self.deltas.commit(),
+            state_commitment: Some(self.state.commit()),
+            justify_qc: self.justify_qc.clone(),
+            key_rotations: self.key_rotations.clone(),
+            proposer_id: self.proposer_id.clone(),
+        }
+    }
 }
 
 impl<TYPES: NodeType> TestableLeaf for ValidatingLeaf<TYPES>
@@ -603,6 +738,7 @@ impl<TYPES: NodeType> LeafType for SequencingLeaf<TYPES> {
             parent_commitment: fake_commitment(),
             deltas: Either::Left(deltas),
             rejected: Vec::new(),
+            key_rotations: Vec::new(),
             timestamp: time::OffsetDateTime::now_utc().unix_timestamp_nanos(),
             proposer_id: genesis_proposer_id(),
         }
@@ -620,6 +756,10 @@ impl<TYPES: NodeType> LeafType for SequencingLeaf<TYPES> {
         self.height = height;
     }
 
+    fn set_parent_commitment(&mut self, parent_commitment: Commitment<Self>) {
+        self.parent_commitment = parent_commitment;
+    }
+
     fn get_justify_qc(&self) -> QuorumCertificate<TYPES, Self> {
         self.justify_qc.clone()
     }
@@ -647,6 +787,10 @@ impl<TYPES: NodeType> LeafType for SequencingLeaf<TYPES> {
         self.rejected.clone()
     }
 
+    fn get_key_rotations(&self) -> Vec<KeyRotation<TYPES::SignatureKey>> {
+        self.key_rotations.clone()
+    }
+
     fn get_timestamp(&self) -> i128 {
         self.timestamp
     }
@@ -663,10 +807,24 @@ impl<TYPES: NodeType> LeafType for SequencingLeaf<TYPES> {
             parent_commitment: stored_view.parent,
             deltas: stored_view.deltas,
             rejected: stored_view.rejected,
+            key_rotations: stored_view.key_rotations,
             timestamp: stored_view.timestamp,
             proposer_id: stored_view.proposer_id,
         }
     }
+
+    fn header(&self) -> LeafHeader<TYPES, Self> {
+        LeafHeader {
+            view_number: self.view_number,
+            height: self.height,
+            parent_commitment: self.parent_commitment,
+            block_commitment: self.deltas.block_commitment(),
+            state_commitment: None,
+            justify_qc: self.justify_qc.clone(),
+            key_rotations: self.key_rotations.clone(),
+            proposer_id: self.proposer_id.clone(),
+        }
+    }
 }
 
 impl<TYPES: NodeType> TestableLeaf for SequencingLeaf<TYPES>
@@ -690,6 +848,17 @@ pub fn fake_commitment<S: Committable>() -> Commitment<S> {
     commit::RawCommitmentBuilder::new("Dummy commitment for arbitrary genesis").finalize()
 }
 
+/// The commitment the genesis leaf of `chain_id` has as its parent
+///
+/// Every leaf commits to its parent, so through the genesis leaf every leaf commitment is bound to
+/// the chain id: chains sharing a genesis block but not a chain id have no leaf in common.
+#[must_use]
+pub fn genesis_parent_commitment<LEAF: Committable>(chain_id: ChainId) -> Commitment<LEAF> {
+    commit::RawCommitmentBuilder::new("Genesis parent")
+        .u64_field("chain_id", chain_id.0)
+        .finalize()
+}
+
 /// create a random commitment
 #[must_use]
 pub fn random_commitment<S: Committable>(rng: &mut dyn rand::RngCore) -> Commitment<S> {
@@ -702,27 +871,15 @@ pub fn random_commitment<S: Committable>(rng: &mut dyn rand::RngCore) -> Commitm
 
 impl<TYPES: NodeType> Committable for ValidatingLeaf<TYPES> {
     fn commit(&self) -> commit::Commitment<Self> {
-        let mut signatures_bytes = vec![];
-        for (k, v) in &self.justify_qc.signatures {
-            signatures_bytes.extend(&k.0);
-            signatures_bytes.extend(&v.0 .0);
-            signatures_bytes.extend::<&[u8]>(v.1.commit().as_ref());
-        }
-        commit::RawCommitmentBuilder::new("Leaf Comm")
-            .u64_field("view_number", *self.view_number)
-            .u64_field("height", self.height)
-            .field("parent Leaf commitment", self.parent_commitment)
-            .field("block commitment", self.deltas.commit())
-            .field("state commitment", self.state.commit())
-            .constant_str("justify_qc view number")
-            .u64(*self.justify_qc.view_number)
-            .field(
-                "justify_qc leaf commitment",
-                self.justify_qc.leaf_commitment(),
-            )
-            .constant_str("justify_qc signatures")
-            .var_size_bytes(&signatures_bytes)
-            .finalize()
+        leaf_commitment(
+            self.view_number,
+            self.height,
+            self.parent_commitment,
+            self.deltas.commit(),
+            Some(self.state.commit()),
+            &self.justify_qc,
+            &self.key_rotations,
+        )
     }
 
     fn tag() -> String {
@@ -734,30 +891,15 @@ impl<TYPES: NodeType> Committable for SequencingLeaf<TYPES> {
     fn commit(&self) -> commit::Commitment<Self> {
         // Commit the block commitment, rather than the block, so that the replicas can reconstruct
         // the leaf.
-        let block_commitment = match &self.deltas {
-            Either::Left(block) => block.commit(),
-            Either::Right(commitment) => *commitment,
-        };
-        let mut signatures_bytes = vec![];
-        for (k, v) in &self.justify_qc.signatures {
-            signatures_bytes.extend(&k.0);
-            signatures_bytes.extend(&v.0 .0);
-            signatures_bytes.extend::<&[u8]>(v.1.commit().as_ref());
-        }
-        commit::RawCommitmentBuilder::new("Leaf Comm")
-            .u64_field("view_number", *self.view_number)
-            .u64_field("height", self.height)
-            .field("parent Leaf commitment", self.parent_commitment)
-            .field("block commitment", block_commitment)
-            .constant_str("justify_qc view number")
-            .u64(*self.justify_qc.view_number)
-            .field(
-                "justify_qc leaf commitment",
-                self.justify_qc.leaf_commitment(),
-            )
-            .constant_str("justify_qc signatures")
-            .var_size_bytes(&signatures_bytes)
-            .finalize()
+        leaf_commitment(
+            self.view_number,
+            self.height,
+            self.parent_commitment,
+            self.deltas.block_commitment(),
+            None,
+            &self.justify_qc,
+            &self.key_rotations,
+        )
     }
 }
 
@@ -773,6 +915,7 @@ impl<TYPES: NodeType> From<ValidatingLeaf<TYPES>>
             deltas: leaf.deltas.clone(),
             state_commitment: leaf.state.commit(),
             rejected: leaf.rejected,
+            key_rotations: leaf.key_rotations,
             proposer_id: leaf.proposer_id,
             block_commitment: leaf.deltas.commit(),
         }
@@ -797,6 +940,7 @@ where
         view_number: TYPES::Time,
         height: u64,
         rejected: Vec<<TYPES::BlockType as Block>::Transaction>,
+        key_rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
         timestamp: i128,
         proposer_id: EncodedPublicKey,
     ) -> Self {
@@ -808,6 +952,7 @@ where
             deltas,
             state,
             rejected,
+            key_rotations,
             timestamp,
             proposer_id,
         }
@@ -817,12 +962,13 @@ where
     /// from the genesis block (deltas, application supplied)
     /// and genesis state (result of deltas applied to the default state)
     /// justified by the genesis qc (special case)
+    /// on the chain `chain_id`
     ///
     /// # Panics
     ///
     /// Panics if deltas is not a valid genesis block,
     /// or if state cannot extend deltas from default()
-    pub fn genesis(deltas: TYPES::BlockType) -> Self {
+    pub fn genesis(deltas: TYPES::BlockType, chain_id: ChainId) -> Self {
         // if this fails, we're not able to initialize consensus.
         let state = <TYPES as NodeType>::StateType::append(
             &TYPES::StateType::default(),
@@ -834,10 +980,11 @@ where
             view_number: TYPES::Time::genesis(),
             height: 0,
             justify_qc: QuorumCertificate::genesis(),
-            parent_commitment: fake_commitment(),
+            parent_commitment: genesis_parent_commitment(chain_id),
             deltas,
             state,
             rejected: Vec::new(),
+            key_rotations: Vec::new(),
             timestamp: time::OffsetDateTime::now_utc().unix_timestamp_nanos(),
             proposer_id: genesis_proposer_id(),
         }
@@ -857,6 +1004,7 @@ where
             append.view_number,
             append.height,
             Vec::new(),
+            append.key_rotations,
             append.timestamp,
             append.proposer_id,
         )
@@ -877,6 +1025,7 @@ where
             state: leaf.get_state(),
             deltas: leaf.get_deltas(),
             rejected: leaf.get_rejected(),
+            key_rotations: leaf.get_key_rotations(),
             timestamp: leaf.get_timestamp(),
             proposer_id: leaf.get_proposer_id(),
         }
@@ -3,16 +3,19 @@
 //! This module provides [`HotShotError`], which is an enum representing possible faults that can
 //! occur while interacting with this crate.
 
-use crate::traits::{node_implementation::NodeType, storage::StorageError};
+use crate::traits::{
+    election::ElectionError, node_implementation::NodeType, storage::StorageError,
+};
 use snafu::Snafu;
 use std::num::NonZeroU64;
 
 #[cfg(feature = "async-std-executor")]
 use async_std::future::TimeoutError;
+// Without an async runtime, for users such as light clients, nothing can time out
+#[cfg(not(any(feature = "async-std-executor", feature = "tokio-executor")))]
+use std::convert::Infallible as TimeoutError;
 #[cfg(feature = "tokio-executor")]
 use tokio::time::error::Elapsed as TimeoutError;
-#[cfg(not(any(feature = "async-std-executor", feature = "tokio-executor")))]
-std::compile_error! {"Either feature \"async-std-executor\" or feature \"tokio-executor\" must be enabled for this crate."}
 
 /// Error type for `HotShot`
 #[derive(Snafu, Debug)]
@@ -76,6 +79,21 @@ pub enum HotShotError<TYPES: NodeType> {
         /// Threshold of signatures needed for a quorum
         threshold: NonZeroU64,
     },
+    /// A configuration was rejected
+    #[snafu(display("Invalid configuration: {}", context))]
+    InvalidConfig {
+        /// Context
+        context: String,
+    },
+    /// A key rotation was rejected by the membership
+    #[snafu(display("Key rotation rejected: {source}"))]
+    InvalidKeyRotation {
+        /// why the membership rejected the rotation
+        source: ElectionError,
+    },
+    /// `HotShot` is shutting down and no longer accepts transactions
+    #[snafu(display("HotShot is shutting down and no longer accepts transactions"))]
+    ShuttingDown {},
     /// Miscelaneous error
     /// TODO fix this with
     /// #181 <https://github.com/EspressoSystems/HotShot/issues/181>
@@ -1,7 +1,11 @@
 //! Events that a `HotShot` instance can emit
 
-use crate::certificate::QuorumCertificate;
-use crate::{data::LeafType, error::HotShotError, traits::node_implementation::NodeType};
+use crate::certificate::{DACertificate, QuorumCertificate};
+use crate::{
+    data::LeafType, error::HotShotError, finality::FinalityProof,
+    traits::node_implementation::NodeType,
+};
+use commit::Commitment;
 use std::sync::Arc;
 /// A status event emitted by a `HotShot` instance
 ///
@@ -41,6 +45,11 @@ pub enum EventType<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> {
         /// Note that the QC for each additional leaf in the chain can be obtained from the leaf
         /// before it using
         qc: Arc<QuorumCertificate<TYPES, LEAF>>,
+        /// The proof that the leaves in `leaf_chain` were decided
+        ///
+        /// [`FinalityProof::for_height`] cuts out the proof of a single leaf. This is `None` for
+        /// the genesis leaf, which is decided by definition rather than by certificates.
+        finality_proof: Option<Arc<FinalityProof<TYPES, LEAF>>>,
     },
     /// A replica task was canceled by a timeout interrupt
     ReplicaViewTimeout {
@@ -57,4 +66,48 @@ pub enum EventType<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> {
         /// The view number that has just finished
         view_number: TYPES::Time,
     },
+    /// A proposal for the view was received from the leader and passed validation
+    ProposalReceived {
+        /// The leader that sent the proposal
+        leader: TYPES::SignatureKey,
+        /// Commitment to the proposed leaf
+        leaf_commitment: Commitment<LEAF>,
+    },
+    /// This node sent a vote for the view
+    VoteSent {
+        /// The node the vote was sent to
+        recipient: TYPES::SignatureKey,
+    },
+    /// This node formed a quorum certificate from the votes it collected
+    QCFormed {
+        /// The newly formed certificate
+        qc: Arc<QuorumCertificate<TYPES, LEAF>>,
+    },
+    /// This node formed a data availability certificate from the votes it collected
+    DACertificateFormed {
+        /// The newly formed certificate
+        certificate: Arc<DACertificate<TYPES>>,
+    },
+    /// Transactions were included in a proposal for the view
+    TransactionsIncluded {
+        /// Commitments to the included transactions
+        transactions: Arc<Vec<Commitment<TYPES::Transaction>>>,
+    },
+    /// Transactions were left out of a proposal by this node because they failed validation
+    TransactionsRejected {
+        /// Commitments to the rejected transactions
+        transactions: Arc<Vec<Commitment<TYPES::Transaction>>>,
+    },
+    /// This node moved on to a new view
+    ViewChanged {
+        /// The view that was entered
+        view_number: TYPES::Time,
+    },
+    /// The leader of an upcoming view is known
+    LeaderElected {
+        /// The upcoming view
+        view_number: TYPES::Time,
+        /// The leader of `view_number`
+        leader: TYPES::SignatureKey,
+    },
 }
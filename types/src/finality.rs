@@ -0,0 +1,78 @@
+//! Proofs that leaves were decided
+//!
+//! A [`FinalityProof`] lets anyone holding the stake table check that a leaf was decided, and so
+//! that its block is final, without trusting the node that handed out the proof.
+
+use crate::data::{LeafHeader, LeafType};
+use crate::traits::{node_implementation::NodeType, signature_key::EncodedSignature};
+use derivative::Derivative;
+use serde::{Deserialize, Serialize};
+
+/// The chain of certificates that decided the leaves of a decision
+///
+/// The chain runs from the oldest leaf of the decision, through its descendants, to the leaf
+/// whose proposal completed the deciding chain of three leaves in consecutive views. The
+/// `justify_qc` of each header is the certificate of the one before it, so the headers carry the
+/// certificate chain without repeating it, and each header opens its leaf to its block
+/// commitment. No certificate for the tip exists yet when the decision is made, so the proof
+/// carries the signature its leader made on its proposal instead, which is what a replica checked
+/// before deciding.
+///
+/// Every suffix of the chain starting at a decided leaf proves that leaf decided, so one proof
+/// covers the whole decision and [`Self::for_height`] cuts out the proof of a single leaf.
+#[derive(Serialize, Deserialize, Clone, Debug, Derivative)]
+#[serde(bound(deserialize = ""))]
+#[derivative(Hash, PartialEq, Eq)]
+pub struct FinalityProof<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> {
+    /// The headers of the decided leaves and their descendants, oldest first
+    pub headers: Vec<LeafHeader<TYPES, LEAF>>,
+
+    /// The signature of the leader of the view of the tip on its proposal
+    pub tip_signature: EncodedSignature,
+}
+
+impl<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> FinalityProof<TYPES, LEAF> {
+    /// The proof for the leaves of a decision, given newest first as in
+    /// [`EventType::Decide`](crate::event::EventType::Decide)
+    ///
+    /// `locked` is the child of the newest leaf of `leaf_chain` and `tip` is its child, the
+    /// proposal that completed the deciding chain, signed by its leader with `tip_signature`.
+    #[must_use]
+    pub fn for_leaf_chain(
+        leaf_chain: &[LEAF],
+        locked: &LEAF,
+        tip: &LEAF,
+        tip_signature: EncodedSignature,
+    ) -> Self {
+        let decided = leaf_chain.iter().rev().map(LeafType::header);
+        Self {
+            headers: decided.chain([locked.header(), tip.header()]).collect(),
+            tip_signature,
+        }
+    }
+
+    /// The headers of the leaves this proves decided, oldest first
+    #[must_use]
+    pub fn decided_headers(&self) -> &[LeafHeader<TYPES, LEAF>] {
+        &self.headers[..self.headers.len().saturating_sub(2)]
+    }
+
+    /// The header of the oldest leaf this proves decided
+    #[must_use]
+    pub fn decided(&self) -> Option<&LeafHeader<TYPES, LEAF>> {
+        self.decided_headers().first()
+    }
+
+    /// The proof for the leaf at `height` alone, if this proves it decided
+    #[must_use]
+    pub fn for_height(&self, height: u64) -> Option<Self> {
+        let index = self
+            .decided_headers()
+            .iter()
+            .position(|header| header.height == height)?;
+        Some(Self {
+            headers: self.headers[index..].to_vec(),
+            tip_signature: self.tip_signature.clone(),
+        })
+    }
+}
@@ -0,0 +1,147 @@
+//! Rotation of a validator key without leaving the stake table
+//!
+//! A [`KeyRotation`] hands the stake slot of `old_key` to `new_key` from `effective_view` on. It
+//! is signed with both keys: the old signature shows the slot holder agrees to give up the slot,
+//! and the new signature shows the holder of the new key exists and agrees to take it, so nobody
+//! can claim a slot with a key they cannot sign with. The new signature covers the new key, so it
+//! also stands in for the proof of possession that keys taking part in aggregate signatures need.
+//! Both signatures are over a [`SigningKind::KeyRotation`] payload, so they are never valid as a
+//! consensus signature, nor a consensus signature as a rotation.
+//! Certificates of views before `effective_view` keep verifying against `old_key`, and those of
+//! later views against `new_key`. The rotation names the chain it is for, so it cannot be replayed
+//! on another network the keys validate on.
+//!
+//! Rotations go through consensus. Leaders carry the rotations submitted to them in the leaves
+//! they propose, and every node applies the rotations of a leaf when it decides the leaf, with
+//! [`apply_decided`]. Nodes decide the same leaves in the same order, so they agree on the stake
+//! table even when conflicting rotations of one key are submitted: the first one decided wins.
+//! A rotation only applies if it takes effect at the start of an epoch, a whole epoch after the
+//! leaf carrying it, so that every node has decided it before it takes effect.
+
+use crate::traits::{
+    election::Membership,
+    node_implementation::NodeType,
+    signature_key::{EncodedSignature, SignatureKey},
+    signer::{signing_payload, ChainId, SigningKind},
+};
+use commit::{Commitment, Committable, RawCommitmentBuilder};
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU64;
+use tracing::warn;
+
+/// A signed request to replace `old_key` with `new_key` from `effective_view` on
+#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+#[serde(bound(deserialize = ""))]
+pub struct KeyRotation<KEY: SignatureKey> {
+    /// The key giving up its stake slot
+    pub old_key: KEY,
+    /// The key taking over the stake slot
+    pub new_key: KEY,
+    /// The network the rotation is for
+    pub chain_id: ChainId,
+    /// The first view in which `new_key` holds the slot
+    pub effective_view: u64,
+    /// The signature of `old_key` on the rotation
+    pub old_signature: EncodedSignature,
+    /// The signature of `new_key` on the rotation
+    pub new_signature: EncodedSignature,
+}
+
+impl<KEY: SignatureKey> KeyRotation<KEY> {
+    /// A rotation from the key of `old_private_key` to the key of `new_private_key` on
+    /// `chain_id`, taking effect at `effective_view`
+    ///
+    /// The rotation is signed outside of any [`Signer`](crate::traits::signer::Signer), as it is
+    /// not tied to a view the signer has to guard against double signing, and is usually made
+    /// offline by the operator holding both keys.
+    #[must_use]
+    pub fn new(
+        old_private_key: &KEY::PrivateKey,
+        new_private_key: &KEY::PrivateKey,
+        chain_id: ChainId,
+        effective_view: u64,
+    ) -> Self {
+        let mut rotation = Self {
+            old_key: KEY::from_private(old_private_key),
+            new_key: KEY::from_private(new_private_key),
+            chain_id,
+            effective_view,
+            old_signature: EncodedSignature(Vec::new()),
+            new_signature: EncodedSignature(Vec::new()),
+        };
+        let data = rotation.signed_data();
+        rotation.old_signature = KEY::sign(old_private_key, &data);
+        rotation.new_signature = KEY::sign(new_private_key, &data);
+        rotation
+    }
+
+    /// Whether the rotation changes the key, and both keys signed it
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        let data = self.signed_data();
+        self.old_key != self.new_key
+            && self.old_key.validate(&self.old_signature, &data)
+            && self.new_key.validate(&self.new_signature, &data)
+    }
+
+    /// The bytes both keys sign: the commitment to the rotation, tagged as a rotation
+    fn signed_data(&self) -> Vec<u8> {
+        signing_payload(
+            SigningKind::KeyRotation,
+            self.chain_id,
+            self.effective_view,
+            self.commit().as_ref(),
+        )
+    }
+
+    /// Whether the rotation may be applied when decided in `decided_view`, with epochs of
+    /// `epoch_length` views
+    ///
+    /// The rotation has to take effect at the start of an epoch, and no earlier than the second
+    /// epoch after the one of `decided_view`.
+    #[must_use]
+    pub fn is_timely(&self, decided_view: u64, epoch_length: NonZeroU64) -> bool {
+        let epoch_length = epoch_length.get();
+        self.effective_view % epoch_length == 0
+            && self.effective_view / epoch_length >= decided_view / epoch_length + 2
+    }
+}
+
+/// Apply to `membership` the rotations carried by a leaf decided in `decided_view`, in order, and
+/// return those applied
+///
+/// Rotations for another chain, that are not [timely](KeyRotation::is_timely), or that the
+/// membership rejects, such as a second rotation of a key that already rotated away, are skipped.
+pub fn apply_decided<TYPES: NodeType, MEMBERSHIP: Membership<TYPES>>(
+    membership: &MEMBERSHIP,
+    chain_id: ChainId,
+    epoch_length: NonZeroU64,
+    decided_view: TYPES::Time,
+    rotations: &[KeyRotation<TYPES::SignatureKey>],
+) -> Vec<KeyRotation<TYPES::SignatureKey>> {
+    let mut applied = Vec::new();
+    for rotation in rotations {
+        if rotation.chain_id != chain_id || !rotation.is_timely(*decided_view, epoch_length) {
+            warn!(?rotation, "Skipping a decided key rotation for another chain or epoch");
+            continue;
+        }
+        match membership.rotate_key(rotation) {
+            Ok(()) => applied.push(rotation.clone()),
+            Err(e) => warn!(?rotation, ?e, "Skipping a decided key rotation"),
+        }
+    }
+    applied
+}
+
+impl<KEY: SignatureKey> Committable for KeyRotation<KEY> {
+    /// The commitment both keys sign, binding the keys, the chain and the view so that neither
+    /// signature can be reused for another rotation
+    fn commit(&self) -> Commitment<Self> {
+        RawCommitmentBuilder::new("Key Rotation")
+            .var_size_field("old_key", &self.old_key.to_bytes().0)
+            .var_size_field("new_key", &self.new_key.to_bytes().0)
+            .u64_field("chain_id", self.chain_id.0)
+            .u64_field("effective_view", self.effective_view)
+            .finalize()
+    }
+}
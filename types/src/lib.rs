@@ -9,13 +9,22 @@
 )]
 #![allow(clippy::module_name_repetitions)]
 
-use std::{num::NonZeroUsize, time::Duration};
+use std::{
+    num::{NonZeroU64, NonZeroUsize},
+    time::Duration,
+};
+use traits::{
+    signature_key::{EncodedSignature, SignatureKey},
+    signer::ChainId,
+};
 
 pub mod certificate;
 pub mod constants;
 pub mod data;
 pub mod error;
 pub mod event;
+pub mod finality;
+pub mod key_rotation;
 pub mod message;
 pub mod traits;
 pub mod vote;
@@ -46,6 +55,10 @@ pub struct HotShotConfig<K, ELECTIONCONFIG> {
     pub max_transactions: NonZeroUsize,
     /// List of known node's public keys, including own, sorted by nonce ()
     pub known_nodes: Vec<K>,
+    /// Proofs of possession of the private keys of `known_nodes`, in the same order. Required by
+    /// key schemes that aggregate signatures, so that no key of the committee is a rogue key.
+    #[serde(default)]
+    pub possession_proofs: Vec<EncodedSignature>,
     /// Base duration for next-view timeout, in milliseconds
     pub next_view_timeout: u64,
     /// The exponential backoff ration for the next-view timeout
@@ -62,4 +75,155 @@ pub struct HotShotConfig<K, ELECTIONCONFIG> {
     pub propose_max_round_time: Duration,
     /// the election configuration
     pub election_config: Option<ELECTIONCONFIG>,
+    /// Bounds for adapting the next-view timeout to observed latencies. If `None`,
+    /// `next_view_timeout` is used as is.
+    #[serde(default)]
+    pub adaptive_timeout: Option<AdaptiveTimeoutConfig>,
+    /// Number of upcoming leaders submitted transactions are sent to directly. If `None`,
+    /// transactions are broadcast to every node.
+    #[serde(default)]
+    pub transaction_forwarding: Option<NonZeroUsize>,
+    /// The network this node takes part in. Nodes only accept peers, messages and signatures of
+    /// their own network.
+    #[serde(default)]
+    pub chain_id: ChainId,
+    /// Number of views in an epoch of the stake table. Decided key rotations only take effect at
+    /// the start of an epoch.
+    #[serde(default = "constants::default_key_rotation_epoch")]
+    pub key_rotation_epoch: NonZeroU64,
+}
+
+/// Configuration of the adaptive next-view timeout
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct AdaptiveTimeoutConfig {
+    /// Lowest timeout that will be used, in milliseconds
+    pub floor: u64,
+    /// Highest timeout that will be used, in milliseconds
+    pub ceiling: u64,
+    /// Number of recent successful views the timeout is derived from
+    pub window: usize,
+    /// Ratio the observed latency is multiplied by to get the timeout
+    pub slack: (u64, u64),
+}
+
+impl Default for AdaptiveTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            floor: 500,
+            ceiling: 60_000,
+            window: 20,
+            slack: (2, 1),
+        }
+    }
+}
+
+/// A change to the parameters of a [`HotShotConfig`] that may be adjusted while `HotShot` runs
+///
+/// Fields left as `None` keep their current value.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct HotShotConfigUpdate {
+    /// New base duration for next-view timeout, in milliseconds
+    pub next_view_timeout: Option<u64>,
+    /// New exponential backoff ration for the next-view timeout
+    pub timeout_ratio: Option<(u64, u64)>,
+    /// New minimum transactions per block
+    pub min_transactions: Option<usize>,
+    /// New maximum transactions per block
+    pub max_transactions: Option<NonZeroUsize>,
+    /// New minimum amount of time a leader has to wait to start a round
+    pub propose_min_round_time: Option<Duration>,
+    /// New maximum amount of time a leader can wait to start a round
+    pub propose_max_round_time: Option<Duration>,
+    /// New bounds of the adaptive view timeout, where `Some(None)` turns it off
+    pub adaptive_timeout: Option<Option<AdaptiveTimeoutConfig>>,
+}
+
+impl HotShotConfigUpdate {
+    /// Overwrite the fields of `config` that are set in this update
+    pub fn apply<K, ELECTIONCONFIG>(&self, config: &mut HotShotConfig<K, ELECTIONCONFIG>) {
+        if let Some(next_view_timeout) = self.next_view_timeout {
+            config.next_view_timeout = next_view_timeout;
+        }
+        if let Some(timeout_ratio) = self.timeout_ratio {
+            config.timeout_ratio = timeout_ratio;
+        }
+        if let Some(min_transactions) = self.min_transactions {
+            config.min_transactions = min_transactions;
+        }
+        if let Some(max_transactions) = self.max_transactions {
+            config.max_transactions = max_transactions;
+        }
+        if let Some(propose_min_round_time) = self.propose_min_round_time {
+            config.propose_min_round_time = propose_min_round_time;
+        }
+        if let Some(propose_max_round_time) = self.propose_max_round_time {
+            config.propose_max_round_time = propose_max_round_time;
+        }
+        if let Some(adaptive_timeout) = self.adaptive_timeout {
+            config.adaptive_timeout = adaptive_timeout;
+        }
+    }
+}
+
+impl<K, ELECTIONCONFIG> HotShotConfig<K, ELECTIONCONFIG> {
+    /// Check that the runtime-adjustable parameters are consistent with each other
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first inconsistency found
+    pub fn validate_parameters(&self) -> Result<(), String> {
+        if self.next_view_timeout == 0 {
+            return Err("next_view_timeout must be greater than zero".to_string());
+        }
+        if self.timeout_ratio.1 == 0 {
+            return Err("the denominator of timeout_ratio must be greater than zero".to_string());
+        }
+        if self.min_transactions > self.max_transactions.get() {
+            return Err(format!(
+                "min_transactions ({}) is larger than max_transactions ({})",
+                self.min_transactions, self.max_transactions
+            ));
+        }
+        if let Some(adaptive) = &self.adaptive_timeout {
+            if adaptive.floor == 0 || adaptive.floor > adaptive.ceiling {
+                return Err(format!(
+                    "adaptive timeout floor ({}) must be non-zero and at most the ceiling ({})",
+                    adaptive.floor, adaptive.ceiling
+                ));
+            }
+            if adaptive.window == 0 || adaptive.slack.1 == 0 {
+                return Err(
+                    "adaptive timeout window and slack denominator must be non-zero".to_string(),
+                );
+            }
+        }
+        if self.propose_min_round_time > self.propose_max_round_time {
+            return Err(format!(
+                "propose_min_round_time ({:?}) is larger than propose_max_round_time ({:?})",
+                self.propose_min_round_time, self.propose_max_round_time
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<K: SignatureKey, ELECTIONCONFIG> HotShotConfig<K, ELECTIONCONFIG> {
+    /// Check that every known node proved possession of its private key, if the key scheme needs
+    /// it
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first known node without a valid proof
+    pub fn validate_known_nodes(&self) -> Result<(), String> {
+        let missing = EncodedSignature(Vec::new());
+        for (index, key) in self.known_nodes.iter().enumerate() {
+            let proof = self.possession_proofs.get(index).unwrap_or(&missing);
+            if !key.verify_possession(proof) {
+                return Err(format!(
+                    "known node {index} did not prove possession of its private key"
+                ));
+            }
+        }
+        Ok(())
+    }
 }
@@ -6,6 +6,7 @@
 use crate::traits::network::ViewMessage;
 use crate::{
     data::ProposalType,
+    key_rotation::KeyRotation,
     traits::{
         network::NetworkMsg,
         node_implementation::{
@@ -13,6 +14,8 @@ use crate::{
             QuorumVoteType,
         },
         signature_key::EncodedSignature,
+        signer::ChainId,
+        state::ConsensusTime,
     },
     vote::VoteType,
 };
@@ -27,6 +30,9 @@ pub struct Message<TYPES: NodeType, I: NodeImplementation<TYPES>> {
     /// The sender of this message
     pub sender: TYPES::SignatureKey,
 
+    /// The network this message was sent on
+    pub chain_id: ChainId,
+
     /// The message kind
     pub kind: MessageKind<TYPES, I>,
 }
@@ -47,6 +53,9 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> ViewMessage<TYPES> for Messa
                 },
             },
             MessageKind::Data(DataMessage::SubmitTransaction(_, v)) => *v,
+            MessageKind::Data(DataMessage::KeyRotation(rotation)) => {
+                TYPES::Time::new(rotation.effective_view)
+            }
         }
     }
 }
@@ -191,6 +200,8 @@ pub enum DataMessage<TYPES: NodeType> {
     /// TODO rethink this when we start to send these messages
     /// we only need the view number for broadcast
     SubmitTransaction(TYPES::Transaction, TYPES::Time),
+    /// Contains a key rotation every node should apply before its effective view
+    KeyRotation(KeyRotation<TYPES::SignatureKey>),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -4,7 +4,9 @@ pub mod election;
 pub mod metrics;
 pub mod network;
 pub mod node_implementation;
+pub mod randomness_beacon;
 pub mod signature_key;
+pub mod signer;
 pub mod state;
 pub mod storage;
 
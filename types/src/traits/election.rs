@@ -2,11 +2,15 @@
 
 use super::node_implementation::{NodeImplementation, NodeType};
 use super::signature_key::{EncodedPublicKey, EncodedSignature};
+use super::signer::{signing_payload, ChainId, Signer, SignerError, SigningKind, SigningRequest};
 use crate::certificate::VoteMetaData;
-use crate::certificate::{DACertificate, QuorumCertificate};
+use crate::certificate::{
+    AggregateSignature, CertificateSignatures, DACertificate, QuorumCertificate,
+};
 use crate::data::ProposalType;
 
 use crate::data::DAProposal;
+use crate::key_rotation::KeyRotation;
 use crate::message::ConsensusMessage;
 use crate::message::Message;
 use crate::traits::network::CommunicationChannel;
@@ -15,6 +19,7 @@ use crate::traits::state::ConsensusTime;
 use crate::vote::VoteAccumulator;
 use crate::vote::{Accumulator, DAVote, QuorumVote, TimeoutVote, VoteType, YesOrNoVote};
 use crate::{data::LeafType, traits::signature_key::SignatureKey};
+use async_trait::async_trait;
 use bincode::Options;
 use commit::{Commitment, Committable};
 use either::Either;
@@ -27,7 +32,8 @@ use std::fmt::Debug;
 use std::hash::Hash;
 use std::marker::PhantomData;
 use std::num::NonZeroU64;
-use tracing::error;
+use std::sync::Arc;
+use tracing::{error, warn};
 
 /// Error for election problems
 #[derive(Snafu, Debug)]
@@ -38,6 +44,27 @@ pub enum ElectionError {
     /// NOTE: it would be better to make Election polymorphic over
     /// the election error and then have specific math errors
     MathError,
+    /// The signer refused to sign a vote token, or is unavailable
+    #[snafu(display("Could not sign vote token: {source}"))]
+    Signer {
+        /// the signer's error
+        source: SignerError,
+    },
+    /// The membership does not support rotating keys
+    KeyRotationUnsupported,
+    /// A key rotation was rejected
+    #[snafu(display("Invalid key rotation: {reason}"))]
+    InvalidKeyRotation {
+        /// why the rotation was rejected
+        reason: String,
+    },
+    /// The randomness seeding a view is not known yet, because the certificates it derives from
+    /// are not all decided by this node
+    #[snafu(display("The randomness of view {view_number} is not known yet"))]
+    UnknownRandomness {
+        /// the view
+        view_number: u64,
+    },
 }
 
 /// For items that will always have the same validity outcome on a successful check,
@@ -76,6 +103,28 @@ impl<TYPES: NodeType, LEAF: LeafType> VoteData<TYPES, LEAF> {
     pub fn as_bytes(&self) -> Vec<u8> {
         bincode_opts().serialize(&self).unwrap()
     }
+
+    /// The kind of signature a vote on this data is.
+    #[must_use]
+    pub fn signing_kind(&self) -> SigningKind {
+        match self {
+            VoteData::DA(_) => SigningKind::DAVote,
+            VoteData::Yes(_) => SigningKind::YesVote,
+            VoteData::No(_) => SigningKind::NoVote,
+            VoteData::Timeout(_) => SigningKind::TimeoutVote,
+        }
+    }
+
+    /// The bytes signed by a vote on this data in `view_number` on `chain_id`.
+    #[must_use]
+    pub fn signing_payload(&self, chain_id: ChainId, view_number: TYPES::Time) -> Vec<u8> {
+        signing_payload(
+            self.signing_kind(),
+            chain_id,
+            *view_number,
+            &self.as_bytes(),
+        )
+    }
 }
 
 /// Proof of this entity's right to vote, and of the weight of those votes
@@ -102,6 +151,89 @@ pub trait VoteToken:
 pub trait ElectionConfig:
     Default + Clone + Serialize + DeserializeOwned + Sync + Send + core::fmt::Debug
 {
+    /// Check that the configuration is safe to run consensus with, among `num_nodes` nodes
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first problem found
+    fn validate(&self, num_nodes: usize) -> Result<(), String> {
+        if num_nodes == 0 {
+            return Err("the committee must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Fractions of the total stake needed to form certificates
+///
+/// A certificate needs strictly more than the given fraction of the stake, so the default of
+/// two thirds gives the usual `((n * 2) / 3) + 1` out of `n`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct StakeThresholds {
+    /// Fraction of the stake needed for a quorum certificate
+    pub quorum: (u64, u64),
+    /// Fraction of the stake needed for a DA certificate
+    pub da: (u64, u64),
+}
+
+impl Default for StakeThresholds {
+    fn default() -> Self {
+        Self {
+            quorum: (2, 3),
+            da: (2, 3),
+        }
+    }
+}
+
+impl StakeThresholds {
+    /// Stake needed for a quorum certificate, out of `total_stake`
+    #[must_use]
+    pub fn quorum_threshold(&self, total_stake: u64) -> NonZeroU64 {
+        Self::threshold(self.quorum, total_stake)
+    }
+
+    /// Stake needed for a DA certificate, out of `total_stake`
+    #[must_use]
+    pub fn da_threshold(&self, total_stake: u64) -> NonZeroU64 {
+        Self::threshold(self.da, total_stake)
+    }
+
+    /// Smallest amount of stake strictly greater than `fraction` of `total_stake`
+    fn threshold(fraction: (u64, u64), total_stake: u64) -> NonZeroU64 {
+        let below = u128::from(total_stake) * u128::from(fraction.0) / u128::from(fraction.1);
+        let below = u64::try_from(below).unwrap_or(u64::MAX);
+        NonZeroU64::new(below.saturating_add(1)).unwrap()
+    }
+
+    /// Check the thresholds against the BFT bound of less than a third of the stake being faulty
+    ///
+    /// Quorums need at least two thirds, so that any two quorums share an honest member. DA
+    /// certificates need at least a third, so that at least one honest member stores the data.
+    /// Both need to be less than the whole stake, so that certificates can form despite faults.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the first threshold outside of these bounds
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, (num, den), (min_num, min_den)) in
+            [("quorum", self.quorum, (2, 3)), ("DA", self.da, (1, 3))]
+        {
+            if den == 0 {
+                return Err(format!("the denominator of the {name} threshold must be non-zero"));
+            }
+            if u128::from(num) * min_den < min_num * u128::from(den) {
+                return Err(format!(
+                    "the {name} threshold {num}/{den} is below the safety bound of {min_num}/{min_den}"
+                ));
+            }
+            if num >= den {
+                return Err(format!(
+                    "the {name} threshold {num}/{den} leaves no room for faulty nodes"
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// A certificate of some property which has been signed by a quroum of nodes.
@@ -111,17 +243,19 @@ where
     LEAF: Committable,
 {
     /// Build a QC from the threshold signature and commitment
+    ///
+    /// The signatures may be in any representation; see [`CertificateSignatures`].
     fn from_signatures_and_commitment(
         view_number: TIME,
-        signatures: BTreeMap<EncodedPublicKey, (EncodedSignature, TOKEN)>,
+        signatures: CertificateSignatures<TOKEN>,
         commit: Commitment<LEAF>,
     ) -> Self;
 
     /// Get the view number.
     fn view_number(&self) -> TIME;
 
-    /// Get signatures.
-    fn signatures(&self) -> BTreeMap<EncodedPublicKey, (EncodedSignature, TOKEN)>;
+    /// Get signatures, in the representation the certificate was built with.
+    fn signatures(&self) -> &CertificateSignatures<TOKEN>;
 
     // TODO (da) the following functions should be refactored into a QC-specific trait.
 
@@ -139,6 +273,7 @@ where
 }
 
 /// A protocol for determining membership in and participating in a ccommittee.
+#[async_trait]
 pub trait Membership<TYPES: NodeType>: Clone + Eq + PartialEq + Send + Sync + 'static {
     /// Data used to determine the weight (voting power) of participants.
     type StakeTable: Send + Sync;
@@ -158,35 +293,227 @@ pub trait Membership<TYPES: NodeType>: Clone + Eq + PartialEq + Send + Sync + 's
     ) -> Self::StakeTable;
 
     /// The leader of the committee for view `view_number`.
-    fn get_leader(&self, view_number: TYPES::Time) -> TYPES::SignatureKey;
+    ///
+    /// # Errors
+    /// If the leader of the view cannot be known yet, such as when it is drawn from randomness
+    /// that has not been decided
+    fn get_leader(&self, view_number: TYPES::Time) -> Result<TYPES::SignatureKey, ElectionError>;
+
+    /// The leaders of the `count` views starting at `from`, in view order.
+    ///
+    /// Stops before the first view whose leader cannot be known yet. Elections whose schedule
+    /// depends on consensus progress, such as reputation based ones, may return leaders of views
+    /// beyond the last decided one that later change.
+    fn leader_schedule(&self, from: TYPES::Time, count: usize) -> Vec<TYPES::SignatureKey> {
+        (0..count as u64)
+            .map_while(|offset| self.get_leader(from + offset).ok())
+            .collect()
+    }
 
     /// The members of the committee for view `view_number`.
     fn get_committee(&self, view_number: TYPES::Time) -> BTreeSet<TYPES::SignatureKey>;
 
-    /// Attempts to generate a vote token for self
+    /// Attempts to generate a vote token for the key of `signer`, on the network `chain_id`
     ///
     /// Returns `None` if the number of seats would be zero
     /// # Errors
-    /// TODO tbd
-    fn make_vote_token(
+    /// If `signer` refuses to sign the token, or does not hold a key the election needs
+    async fn make_vote_token(
         &self,
         view_number: TYPES::Time,
-        priv_key: &<TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        chain_id: ChainId,
+        signer: &dyn Signer<TYPES::SignatureKey>,
     ) -> Result<Option<TYPES::VoteTokenType>, ElectionError>;
 
-    /// Checks the claims of a received vote token
+    /// Whether [`Membership::make_vote_token`] needs the private key in memory, so that nodes
+    /// cannot use a signer without a [local key](Signer::local_private_key), such as a remote one
+    fn needs_local_key(&self) -> bool {
+        false
+    }
+
+    /// Checks the claims of a received vote token, made on the network `chain_id`
     ///
     /// # Errors
     /// TODO tbd
     fn validate_vote_token(
         &self,
         view_number: TYPES::Time,
+        chain_id: ChainId,
         pub_key: TYPES::SignatureKey,
         token: Checked<TYPES::VoteTokenType>,
     ) -> Result<Checked<TYPES::VoteTokenType>, ElectionError>;
 
     /// Returns the threshold for a specific `Membership` implementation
     fn threshold(&self) -> NonZeroU64;
+
+    /// Returns the threshold for a DA certificate, which is [`Membership::threshold`] unless the
+    /// implementation configures it separately
+    fn da_threshold(&self) -> NonZeroU64 {
+        self.threshold()
+    }
+
+    /// Notifies the membership that the leaf proposed by `proposer` in `view_number` was decided.
+    ///
+    /// `parent_view` is the view of the leaf's `justify_qc`, so the views in between produced no
+    /// leaf on the chain. Called once per decided leaf, in increasing view order, as part of
+    /// deciding it. Memberships whose leader schedule depends on the history of the chain use
+    /// this; the default ignores it.
+    fn record_decided_leaf(
+        &self,
+        _view_number: TYPES::Time,
+        _parent_view: TYPES::Time,
+        _proposer: &EncodedPublicKey,
+    ) {
+    }
+
+    /// Notifies the membership of the `justify_qc` of a decided leaf.
+    ///
+    /// Called once per decided leaf, in increasing view order, so memberships can feed a
+    /// [`RandomnessBeacon`](crate::traits::randomness_beacon::RandomnessBeacon). The default
+    /// ignores it.
+    fn record_decided_qc<LEAF: LeafType<NodeType = TYPES>>(
+        &self,
+        _qc: &QuorumCertificate<TYPES, LEAF>,
+    ) {
+    }
+
+    /// Hands the stake slot of `rotation.old_key` to `rotation.new_key` from
+    /// `rotation.effective_view` on.
+    ///
+    /// Views before the effective view keep their committee, so certificates formed before the
+    /// rotation still verify. All nodes must apply the same rotations before the effective view.
+    ///
+    /// # Errors
+    /// If the rotation is not signed by both keys, the old key does not hold a slot at the
+    /// effective view, or the new key already is a member. The default supports no rotations.
+    fn rotate_key(
+        &self,
+        _rotation: &KeyRotation<TYPES::SignatureKey>,
+    ) -> Result<(), ElectionError> {
+        Err(ElectionError::KeyRotationUnsupported)
+    }
+}
+
+/// The stake of the valid votes on `data` among `signatures`, as judged by `membership`, with
+/// vote tokens made on the network `chain_id`.
+///
+/// This is the check behind [`ConsensusExchange::is_valid_cert`], for callers holding only the
+/// stake table. Returns `None` if an aggregate signature is invalid.
+pub fn certificate_stake<TYPES: NodeType, MEMBERSHIP: Membership<TYPES>>(
+    membership: &MEMBERSHIP,
+    chain_id: ChainId,
+    signatures: &CertificateSignatures<TYPES::VoteTokenType>,
+    data: &[u8],
+    view_number: TYPES::Time,
+) -> Option<u64> {
+    match signatures {
+        CertificateSignatures::Individual(signatures) => Some(individual_signature_stake(
+            membership,
+            chain_id,
+            signatures,
+            data,
+            view_number,
+        )),
+        CertificateSignatures::Aggregated(aggregate) => {
+            aggregate_signature_stake(membership, chain_id, aggregate, data, view_number)
+        }
+    }
+}
+
+/// See [`ConsensusExchange::individual_stake`].
+fn individual_signature_stake<TYPES: NodeType, MEMBERSHIP: Membership<TYPES>>(
+    membership: &MEMBERSHIP,
+    chain_id: ChainId,
+    signatures: &BTreeMap<EncodedPublicKey, (EncodedSignature, TYPES::VoteTokenType)>,
+    data: &[u8],
+    view_number: TYPES::Time,
+) -> u64 {
+    let votes: Vec<_> = signatures
+        .iter()
+        .filter_map(|(encoded_key, (signature, token))| {
+            let key = <TYPES::SignatureKey as SignatureKey>::from_bytes(encoded_key)?;
+            is_valid_vote_token(
+                membership,
+                view_number,
+                chain_id,
+                key.clone(),
+                token.clone(),
+            )
+            .then_some((key, signature, u64::from(token.vote_count())))
+        })
+        .collect();
+
+    let batch: Vec<_> = votes
+        .iter()
+        .map(|(key, signature, _)| (key.clone(), (*signature).clone(), data))
+        .collect();
+    if <TYPES::SignatureKey as SignatureKey>::batch_validate(&batch) {
+        return votes.iter().map(|(_, _, stake)| stake).sum();
+    }
+    warn!("Certificate signature batch failed, validating signatures one at a time");
+    votes
+        .iter()
+        .filter(|(key, signature, _)| key.validate(signature, data))
+        .map(|(_, _, stake)| stake)
+        .sum()
+}
+
+/// See [`ConsensusExchange::aggregate_stake`].
+fn aggregate_signature_stake<TYPES: NodeType, MEMBERSHIP: Membership<TYPES>>(
+    membership: &MEMBERSHIP,
+    chain_id: ChainId,
+    aggregate: &AggregateSignature<TYPES::VoteTokenType>,
+    data: &[u8],
+    view_number: TYPES::Time,
+) -> Option<u64> {
+    let committee = membership.get_committee(view_number);
+    if !aggregate.signers.is_well_formed() || aggregate.signers.len() != committee.len() {
+        return None;
+    }
+    let signers: Vec<TYPES::SignatureKey> = committee
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| aggregate.signers.get(*index))
+        .map(|(_, key)| key)
+        .collect();
+    if signers.len() != aggregate.tokens.len() {
+        return None;
+    }
+
+    let mut stake = 0;
+    for (key, token) in signers.iter().zip(&aggregate.tokens) {
+        if !is_valid_vote_token(
+            membership,
+            view_number,
+            chain_id,
+            key.clone(),
+            token.clone(),
+        ) {
+            return None;
+        }
+        stake += u64::from(token.vote_count());
+    }
+
+    <TYPES::SignatureKey as SignatureKey>::validate_aggregate(&signers, &aggregate.signature, data)
+        .then_some(stake)
+}
+
+/// See [`ConsensusExchange::is_valid_vote_token`].
+fn is_valid_vote_token<TYPES: NodeType, MEMBERSHIP: Membership<TYPES>>(
+    membership: &MEMBERSHIP,
+    view_number: TYPES::Time,
+    chain_id: ChainId,
+    key: TYPES::SignatureKey,
+    token: TYPES::VoteTokenType,
+) -> bool {
+    match membership.validate_vote_token(view_number, chain_id, key, Checked::Unchecked(token)) {
+        Err(_) => {
+            error!("Vote token was invalid");
+            false
+        }
+        Ok(Checked::Valid(_)) => true,
+        Ok(Checked::Inval(_) | Checked::Unchecked(_)) => false,
+    }
 }
 
 /// Protocol for exchanging proposals and votes to make decisions in a distributed network.
@@ -194,6 +521,7 @@ pub trait Membership<TYPES: NodeType>: Clone + Eq + PartialEq + Send + Sync + 's
 /// An instance of [`ConsensusExchange`] represents the state of one participant in the protocol,
 /// allowing them to vote and query information about the overall state of the protocol (such as
 /// membership and leader status).
+#[async_trait]
 pub trait ConsensusExchange<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>, M: NetworkMsg>:
     Send + Sync
 {
@@ -212,26 +540,36 @@ pub trait ConsensusExchange<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>, M
     /// Commitments to items which are the subject of proposals and decisions.
     type Commitment: Committable;
 
-    /// Join a [`ConsensusExchange`] with the given identity (`pk` and `sk`).
+    /// Join a [`ConsensusExchange`] on the network `chain_id` with the identity of `signer`.
     fn create(
         keys: Vec<TYPES::SignatureKey>,
         config: TYPES::ElectionConfigType,
         network: Self::Networking,
-        pk: TYPES::SignatureKey,
-        sk: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        chain_id: ChainId,
+        signer: Arc<dyn Signer<TYPES::SignatureKey>>,
     ) -> Self;
 
     /// The network being used by this exchange.
     fn network(&self) -> &Self::Networking;
 
     /// The leader of the [`Membership`](Self::Membership) at time `view_number`.
-    fn get_leader(&self, view_number: TYPES::Time) -> TYPES::SignatureKey {
+    ///
+    /// # Errors
+    /// If the membership cannot know the leader of the view yet
+    fn get_leader(&self, view_number: TYPES::Time) -> Result<TYPES::SignatureKey, ElectionError> {
         self.membership().get_leader(view_number)
     }
 
-    /// Whether this participant is leader at time `view_number`.
+    /// The leaders of the [`Membership`](Self::Membership) for the `count` views starting at `from`.
+    fn leader_schedule(&self, from: TYPES::Time, count: usize) -> Vec<TYPES::SignatureKey> {
+        self.membership().leader_schedule(from, count)
+    }
+
+    /// Whether this participant is leader at time `view_number`, which it is not while the
+    /// leader of the view cannot be known
     fn is_leader(&self, view_number: TYPES::Time) -> bool {
-        &self.get_leader(view_number) == self.public_key()
+        self.get_leader(view_number)
+            .map_or(false, |leader| &leader == self.public_key())
     }
 
     /// Threshold required to approve a [`Proposal`](Self::Proposal).
@@ -243,12 +581,13 @@ pub trait ConsensusExchange<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>, M
     ///
     /// # Errors
     /// When unable to make a vote token because not part of the committee
-    fn make_vote_token(
+    async fn make_vote_token(
         &self,
         view_number: TYPES::Time,
     ) -> std::result::Result<std::option::Option<TYPES::VoteTokenType>, ElectionError> {
         self.membership()
-            .make_vote_token(view_number, self.private_key())
+            .make_vote_token(view_number, self.chain_id(), self.signer().as_ref())
+            .await
     }
 
     /// The contents of a vote on `commit`.
@@ -265,23 +604,67 @@ pub trait ConsensusExchange<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>, M
             return false;
         }
 
-        let stake = qc
-            .signatures()
-            .iter()
-            .filter(|signature| {
-                self.is_valid_vote(
-                    signature.0,
-                    &signature.1 .0,
-                    self.vote_data(commit),
-                    qc.view_number(),
-                    Checked::Unchecked(signature.1 .1.clone()),
-                )
-            })
-            .fold(0, |acc, x| (acc + u64::from(x.1 .1.vote_count())));
+        let stake = match qc.signatures() {
+            CertificateSignatures::Individual(signatures) => {
+                self.individual_stake(signatures, commit, qc.view_number())
+            }
+            CertificateSignatures::Aggregated(aggregate) => {
+                match self.aggregate_stake(aggregate, commit, qc.view_number()) {
+                    Some(stake) => stake,
+                    None => return false,
+                }
+            }
+        };
 
         stake >= u64::from(self.threshold())
     }
 
+    /// The stake of the valid votes on `commit` among `signatures`.
+    ///
+    /// Vote tokens are checked one at a time and the signatures of the votes with valid tokens as
+    /// one batch. If the batch fails, the signatures are checked one at a time to leave out the
+    /// invalid ones.
+    fn individual_stake(
+        &self,
+        signatures: &BTreeMap<EncodedPublicKey, (EncodedSignature, TYPES::VoteTokenType)>,
+        commit: Commitment<Self::Commitment>,
+        view_number: TYPES::Time,
+    ) -> u64 {
+        let data = self
+            .vote_data(commit)
+            .signing_payload(self.chain_id(), view_number);
+        individual_signature_stake(
+            self.membership(),
+            self.chain_id(),
+            signatures,
+            &data,
+            view_number,
+        )
+    }
+
+    /// Validate an aggregate signature on `commit` and return the stake of its signers.
+    ///
+    /// Every signer's vote token is checked, and then the aggregate signature against all of the
+    /// signers' keys with a single pairing check. Returns `None` if anything is invalid, since an
+    /// aggregate signature cannot be split to discard the invalid votes.
+    fn aggregate_stake(
+        &self,
+        aggregate: &AggregateSignature<TYPES::VoteTokenType>,
+        commit: Commitment<Self::Commitment>,
+        view_number: TYPES::Time,
+    ) -> Option<u64> {
+        let data = self
+            .vote_data(commit)
+            .signing_payload(self.chain_id(), view_number);
+        aggregate_signature_stake(
+            self.membership(),
+            self.chain_id(),
+            aggregate,
+            &data,
+            view_number,
+        )
+    }
+
     /// Validate a vote by checking its signature and token.
     fn is_valid_vote(
         &self,
@@ -294,10 +677,14 @@ pub trait ConsensusExchange<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>, M
         let mut is_valid_vote_token = false;
         let mut is_valid_signature = false;
         if let Some(key) = <TYPES::SignatureKey as SignatureKey>::from_bytes(encoded_key) {
-            is_valid_signature = key.validate(encoded_signature, &data.as_bytes());
-            let valid_vote_token =
-                self.membership()
-                    .validate_vote_token(view_number, key, vote_token);
+            let payload = data.signing_payload(self.chain_id(), view_number);
+            is_valid_signature = key.validate(encoded_signature, &payload);
+            let valid_vote_token = self.membership().validate_vote_token(
+                view_number,
+                self.chain_id(),
+                key,
+                vote_token,
+            );
             is_valid_vote_token = match valid_vote_token {
                 Err(_) => {
                     error!("Vote token was invalid");
@@ -310,6 +697,16 @@ pub trait ConsensusExchange<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>, M
         is_valid_signature && is_valid_vote_token
     }
 
+    /// Validate the vote token of `key` for `view_number`.
+    fn is_valid_vote_token(
+        &self,
+        view_number: TYPES::Time,
+        key: TYPES::SignatureKey,
+        token: TYPES::VoteTokenType,
+    ) -> bool {
+        is_valid_vote_token(self.membership(), view_number, self.chain_id(), key, token)
+    }
+
     #[doc(hidden)]
     fn accumulate_internal(
         &self,
@@ -336,9 +733,10 @@ pub trait ConsensusExchange<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>, M
         )) {
             Either::Left(accumulator) => Either::Left(accumulator),
             Either::Right(signatures) => {
+                let committee = self.membership().get_committee(vota_meta.view_number);
                 Either::Right(Self::Certificate::from_signatures_and_commitment(
                     vota_meta.view_number,
-                    signatures,
+                    CertificateSignatures::aggregate(signatures, &committee),
                     vota_meta.commitment,
                 ))
             }
@@ -361,37 +759,91 @@ pub trait ConsensusExchange<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>, M
     fn membership(&self) -> &Self::Membership;
 
     /// This participant's public key.
-    fn public_key(&self) -> &TYPES::SignatureKey;
+    fn public_key(&self) -> &TYPES::SignatureKey {
+        self.signer().public_key()
+    }
 
-    /// This participant's private key.
-    fn private_key(&self) -> &<TYPES::SignatureKey as SignatureKey>::PrivateKey;
+    /// The signer holding this participant's key.
+    fn signer(&self) -> &Arc<dyn Signer<TYPES::SignatureKey>>;
+
+    /// The network this exchange signs and verifies signatures for.
+    fn chain_id(&self) -> ChainId;
+
+    /// Sign `data` of kind `kind` for `view_number` on this network with this participant's key.
+    ///
+    /// # Errors
+    /// If the signer refuses, for instance because it already signed other data of the same kind
+    /// for the view, or cannot be reached
+    async fn sign(
+        &self,
+        view_number: TYPES::Time,
+        kind: SigningKind,
+        data: Vec<u8>,
+    ) -> Result<EncodedSignature, SignerError> {
+        self.signer()
+            .sign(SigningRequest {
+                view_number: *view_number,
+                kind,
+                chain_id: self.chain_id(),
+                data,
+            })
+            .await
+    }
+
+    /// Whether `signature` is `key` signing `data` of kind `kind` for `view_number` on this
+    /// network, as [`sign`](Self::sign) would.
+    fn is_valid_signature(
+        &self,
+        key: &TYPES::SignatureKey,
+        signature: &EncodedSignature,
+        view_number: TYPES::Time,
+        kind: SigningKind,
+        data: &[u8],
+    ) -> bool {
+        let payload = signing_payload(kind, self.chain_id(), *view_number, data);
+        key.validate(signature, &payload)
+    }
 }
 
 /// A [`ConsensusExchange`] where participants vote to provide availability for blobs of data.
+#[async_trait]
 pub trait CommitteeExchangeType<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>, M: NetworkMsg>:
     ConsensusExchange<TYPES, LEAF, M>
 {
-    /// Sign a DA proposal.
-    fn sign_da_proposal(&self, block_commitment: &Commitment<TYPES::BlockType>)
-        -> EncodedSignature;
+    /// Sign a DA proposal for `view_number`.
+    ///
+    /// # Errors
+    /// If the signer refuses to sign
+    async fn sign_da_proposal(
+        &self,
+        view_number: TYPES::Time,
+        block_commitment: &Commitment<TYPES::BlockType>,
+    ) -> Result<EncodedSignature, SignerError>;
 
-    /// Sign a vote on DA proposal.
+    /// Sign a vote on DA proposal for `view_number`.
     ///
     /// The block commitment and the type of the vote (DA) are signed, which is the minimum amount
     /// of information necessary for checking that this node voted on that block.
-    fn sign_da_vote(
+    ///
+    /// # Errors
+    /// If the signer refuses to sign
+    async fn sign_da_vote(
         &self,
+        view_number: TYPES::Time,
         block_commitment: Commitment<TYPES::BlockType>,
-    ) -> (EncodedPublicKey, EncodedSignature);
+    ) -> Result<(EncodedPublicKey, EncodedSignature), SignerError>;
 
     /// Create a message with a vote on DA proposal.
-    fn create_da_message<I: NodeImplementation<TYPES, Leaf = LEAF>>(
+    ///
+    /// # Errors
+    /// If the signer refuses to sign the vote
+    async fn create_da_message<I: NodeImplementation<TYPES, Leaf = LEAF>>(
         &self,
         justify_qc_commitment: Commitment<QuorumCertificate<TYPES, LEAF>>,
         block_commitment: Commitment<TYPES::BlockType>,
         current_view: TYPES::Time,
         vote_token: TYPES::VoteTokenType,
-    ) -> ConsensusMessage<TYPES, I>
+    ) -> Result<ConsensusMessage<TYPES, I>, SignerError>
     where
         I::CommitteeExchange:
             ConsensusExchange<TYPES, I::Leaf, Message<TYPES, I>, Vote = DAVote<TYPES, I::Leaf>>;
@@ -409,14 +861,15 @@ pub struct CommitteeExchange<
     network: NETWORK,
     /// The committee which votes on proposals.
     membership: MEMBERSHIP,
-    /// This participant's public key.
-    public_key: TYPES::SignatureKey,
-    /// This participant's private key.
-    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// The network signatures are made for.
+    chain_id: ChainId,
+    /// The signer holding this participant's key.
+    signer: Arc<dyn Signer<TYPES::SignatureKey>>,
     #[doc(hidden)]
     _pd: PhantomData<(TYPES, LEAF, MEMBERSHIP, M)>,
 }
 
+#[async_trait]
 impl<
         TYPES: NodeType,
         LEAF: LeafType<NodeType = TYPES>,
@@ -427,50 +880,59 @@ impl<
     for CommitteeExchange<TYPES, LEAF, MEMBERSHIP, NETWORK, M>
 {
     /// Sign a DA proposal.
-    fn sign_da_proposal(
+    async fn sign_da_proposal(
         &self,
+        view_number: TYPES::Time,
         block_commitment: &Commitment<TYPES::BlockType>,
-    ) -> EncodedSignature {
-        let signature = TYPES::SignatureKey::sign(&self.private_key, block_commitment.as_ref());
-        signature
+    ) -> Result<EncodedSignature, SignerError> {
+        self.sign(
+            view_number,
+            SigningKind::DAProposal,
+            block_commitment.as_ref().to_vec(),
+        )
+        .await
     }
     /// Sign a vote on DA proposal.
     ///
     /// The block commitment and the type of the vote (DA) are signed, which is the minimum amount
     /// of information necessary for checking that this node voted on that block.
-    fn sign_da_vote(
+    async fn sign_da_vote(
         &self,
+        view_number: TYPES::Time,
         block_commitment: Commitment<TYPES::BlockType>,
-    ) -> (EncodedPublicKey, EncodedSignature) {
-        let signature = TYPES::SignatureKey::sign(
-            &self.private_key,
-            &VoteData::<TYPES, LEAF>::DA(block_commitment).as_bytes(),
-        );
-        (self.public_key.to_bytes(), signature)
+    ) -> Result<(EncodedPublicKey, EncodedSignature), SignerError> {
+        let signature = self.sign(
+            view_number,
+            SigningKind::DAVote,
+            VoteData::<TYPES, LEAF>::DA(block_commitment).as_bytes(),
+        )
+        .await?;
+        Ok((self.public_key().to_bytes(), signature))
     }
     /// Create a message with a vote on DA proposal.
-    fn create_da_message<I: NodeImplementation<TYPES, Leaf = LEAF>>(
+    async fn create_da_message<I: NodeImplementation<TYPES, Leaf = LEAF>>(
         &self,
         justify_qc_commitment: Commitment<QuorumCertificate<TYPES, LEAF>>,
         block_commitment: Commitment<TYPES::BlockType>,
         current_view: TYPES::Time,
         vote_token: TYPES::VoteTokenType,
-    ) -> ConsensusMessage<TYPES, I>
+    ) -> Result<ConsensusMessage<TYPES, I>, SignerError>
     where
         I::CommitteeExchange:
             ConsensusExchange<TYPES, I::Leaf, Message<TYPES, I>, Vote = DAVote<TYPES, I::Leaf>>,
     {
-        let signature = self.sign_da_vote(block_commitment);
-        ConsensusMessage::<TYPES, I>::DAVote(DAVote {
+        let signature = self.sign_da_vote(current_view, block_commitment).await?;
+        Ok(ConsensusMessage::<TYPES, I>::DAVote(DAVote {
             justify_qc_commitment,
             signature,
             block_commitment,
             current_view,
             vote_token,
-        })
+        }))
     }
 }
 
+#[async_trait]
 impl<
         TYPES: NodeType,
         LEAF: LeafType<NodeType = TYPES>,
@@ -490,34 +952,39 @@ impl<
         keys: Vec<TYPES::SignatureKey>,
         config: TYPES::ElectionConfigType,
         network: Self::Networking,
-        pk: TYPES::SignatureKey,
-        sk: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        chain_id: ChainId,
+        signer: Arc<dyn Signer<TYPES::SignatureKey>>,
     ) -> Self {
         let membership =
             <Self as ConsensusExchange<TYPES, LEAF, M>>::Membership::create_election(keys, config);
         Self {
             network,
             membership,
-            public_key: pk,
-            private_key: sk,
+            chain_id,
+            signer,
             _pd: PhantomData,
         }
     }
     fn network(&self) -> &NETWORK {
         &self.network
     }
-    fn make_vote_token(
+    async fn make_vote_token(
         &self,
         view_number: TYPES::Time,
     ) -> std::result::Result<std::option::Option<TYPES::VoteTokenType>, ElectionError> {
         self.membership
-            .make_vote_token(view_number, &self.private_key)
+            .make_vote_token(view_number, self.chain_id, self.signer.as_ref())
+            .await
     }
 
     fn vote_data(&self, commit: Commitment<Self::Commitment>) -> VoteData<TYPES, LEAF> {
         VoteData::DA(commit)
     }
 
+    fn threshold(&self) -> NonZeroU64 {
+        self.membership.da_threshold()
+    }
+
     /// Add a vote to the accumulating signature.  Return The certificate if the vote
     /// brings us over the threshould, Else return the accumulator.
     fn accumulate_vote(
@@ -542,56 +1009,72 @@ impl<
     fn membership(&self) -> &Self::Membership {
         &self.membership
     }
-    fn public_key(&self) -> &TYPES::SignatureKey {
-        &self.public_key
+    fn signer(&self) -> &Arc<dyn Signer<TYPES::SignatureKey>> {
+        &self.signer
     }
-    fn private_key(&self) -> &<<TYPES as NodeType>::SignatureKey as SignatureKey>::PrivateKey {
-        &self.private_key
+    fn chain_id(&self) -> ChainId {
+        self.chain_id
     }
 }
 
 /// A [`ConsensusExchange`] where participants vote to append items to a log.
+#[async_trait]
 pub trait QuorumExchangeType<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>, M: NetworkMsg>:
     ConsensusExchange<TYPES, LEAF, M>
 {
     /// Create a message with a positive vote on validating or commitment proposal.
-    fn create_yes_message<I: NodeImplementation<TYPES, Leaf = LEAF>>(
+    ///
+    /// # Errors
+    /// If the signer refuses to sign the vote
+    async fn create_yes_message<I: NodeImplementation<TYPES, Leaf = LEAF>>(
         &self,
         justify_qc_commitment: Commitment<Self::Certificate>,
         leaf_commitment: Commitment<LEAF>,
         current_view: TYPES::Time,
         vote_token: TYPES::VoteTokenType,
-    ) -> ConsensusMessage<TYPES, I>
+    ) -> Result<ConsensusMessage<TYPES, I>, SignerError>
     where
         <Self as ConsensusExchange<TYPES, LEAF, M>>::Certificate: commit::Committable,
         I::QuorumExchange:
             ConsensusExchange<TYPES, I::Leaf, Message<TYPES, I>, Vote = QuorumVote<TYPES, LEAF>>;
-    /// Sign a validating or commitment proposal.
-    fn sign_validating_or_commitment_proposal<I: NodeImplementation<TYPES>>(
+    /// Sign a validating or commitment proposal for `view_number`.
+    ///
+    /// # Errors
+    /// If the signer refuses to sign
+    async fn sign_validating_or_commitment_proposal<I: NodeImplementation<TYPES>>(
         &self,
+        view_number: TYPES::Time,
         leaf_commitment: &Commitment<LEAF>,
-    ) -> EncodedSignature;
+    ) -> Result<EncodedSignature, SignerError>;
 
-    /// Sign a positive vote on validating or commitment proposal.
+    /// Sign a positive vote on validating or commitment proposal for `view_number`.
     ///
     /// The leaf commitment and the type of the vote (yes) are signed, which is the minimum amount
     /// of information necessary for any user of the subsequently constructed QC to check that this
     /// node voted `Yes` on that leaf. The leaf is expected to be reconstructed based on other
     /// information in the yes vote.
-    fn sign_yes_vote(
+    ///
+    /// # Errors
+    /// If the signer refuses to sign
+    async fn sign_yes_vote(
         &self,
+        view_number: TYPES::Time,
         leaf_commitment: Commitment<LEAF>,
-    ) -> (EncodedPublicKey, EncodedSignature);
+    ) -> Result<(EncodedPublicKey, EncodedSignature), SignerError>;
 
-    /// Sign a neagtive vote on validating or commitment proposal.
+    /// Sign a neagtive vote on validating or commitment proposal for `view_number`.
     ///
     /// The leaf commitment and the type of the vote (no) are signed, which is the minimum amount
     /// of information necessary for any user of the subsequently constructed QC to check that this
     /// node voted `No` on that leaf.
-    fn sign_no_vote(
+    ///
+    /// # Errors
+    /// If the signer refuses to sign
+    async fn sign_no_vote(
         &self,
+        view_number: TYPES::Time,
         leaf_commitment: Commitment<LEAF>,
-    ) -> (EncodedPublicKey, EncodedSignature);
+    ) -> Result<(EncodedPublicKey, EncodedSignature), SignerError>;
 
     /// Sign a timeout vote.
     ///
@@ -600,26 +1083,38 @@ pub trait QuorumExchangeType<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>,
     ///
     /// This also allows for the high QC included with the vote to be spoofed in a MITM scenario,
     /// but it is outside our threat model.
-    fn sign_timeout_vote(&self, view_number: TYPES::Time) -> (EncodedPublicKey, EncodedSignature);
+    ///
+    /// # Errors
+    /// If the signer refuses to sign
+    async fn sign_timeout_vote(
+        &self,
+        view_number: TYPES::Time,
+    ) -> Result<(EncodedPublicKey, EncodedSignature), SignerError>;
     /// Create a message with a negative vote on validating or commitment proposal.
-    fn create_no_message<I: NodeImplementation<TYPES>>(
+    ///
+    /// # Errors
+    /// If the signer refuses to sign the vote
+    async fn create_no_message<I: NodeImplementation<TYPES>>(
         &self,
         justify_qc_commitment: Commitment<QuorumCertificate<TYPES, LEAF>>,
         leaf_commitment: Commitment<LEAF>,
         current_view: TYPES::Time,
         vote_token: TYPES::VoteTokenType,
-    ) -> ConsensusMessage<TYPES, I>
+    ) -> Result<ConsensusMessage<TYPES, I>, SignerError>
     where
         I::QuorumExchange:
             ConsensusExchange<TYPES, I::Leaf, Message<TYPES, I>, Vote = QuorumVote<TYPES, LEAF>>;
 
     /// Create a message with a timeout vote on validating or commitment proposal.
-    fn create_timeout_message<I: NodeImplementation<TYPES>>(
+    ///
+    /// # Errors
+    /// If the signer refuses to sign the vote
+    async fn create_timeout_message<I: NodeImplementation<TYPES>>(
         &self,
         justify_qc: QuorumCertificate<TYPES, LEAF>,
         current_view: TYPES::Time,
         vote_token: TYPES::VoteTokenType,
-    ) -> ConsensusMessage<TYPES, I>
+    ) -> Result<ConsensusMessage<TYPES, I>, SignerError>
     where
         I::QuorumExchange:
             ConsensusExchange<TYPES, I::Leaf, Message<TYPES, I>, Vote = QuorumVote<TYPES, LEAF>>;
@@ -638,14 +1133,15 @@ pub struct QuorumExchange<
     network: NETWORK,
     /// The committee which votes on proposals.
     membership: MEMBERSHIP,
-    /// This participant's public key.
-    public_key: TYPES::SignatureKey,
-    /// This participant's private key.
-    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// The network signatures are made for.
+    chain_id: ChainId,
+    /// The signer holding this participant's key.
+    signer: Arc<dyn Signer<TYPES::SignatureKey>>,
     #[doc(hidden)]
     _pd: PhantomData<(LEAF, PROPOSAL, MEMBERSHIP, M)>,
 }
 
+#[async_trait]
 impl<
         TYPES: NodeType,
         LEAF: LeafType<NodeType = TYPES>,
@@ -657,33 +1153,38 @@ impl<
     for QuorumExchange<TYPES, LEAF, PROPOSAL, MEMBERSHIP, NETWORK, M>
 {
     /// Create a message with a positive vote on validating or commitment proposal.
-    fn create_yes_message<I: NodeImplementation<TYPES, Leaf = LEAF>>(
+    async fn create_yes_message<I: NodeImplementation<TYPES, Leaf = LEAF>>(
         &self,
         justify_qc_commitment: Commitment<QuorumCertificate<TYPES, LEAF>>,
         leaf_commitment: Commitment<LEAF>,
         current_view: TYPES::Time,
         vote_token: TYPES::VoteTokenType,
-    ) -> ConsensusMessage<TYPES, I>
+    ) -> Result<ConsensusMessage<TYPES, I>, SignerError>
     where
         I::QuorumExchange:
             ConsensusExchange<TYPES, I::Leaf, Message<TYPES, I>, Vote = QuorumVote<TYPES, LEAF>>,
     {
-        let signature = self.sign_yes_vote(leaf_commitment);
-        ConsensusMessage::<TYPES, I>::Vote(QuorumVote::Yes(YesOrNoVote {
+        let signature = self.sign_yes_vote(current_view, leaf_commitment).await?;
+        Ok(ConsensusMessage::<TYPES, I>::Vote(QuorumVote::Yes(YesOrNoVote {
             justify_qc_commitment,
             signature,
             leaf_commitment,
             current_view,
             vote_token,
-        }))
+        })))
     }
     /// Sign a validating or commitment proposal.
-    fn sign_validating_or_commitment_proposal<I: NodeImplementation<TYPES>>(
+    async fn sign_validating_or_commitment_proposal<I: NodeImplementation<TYPES>>(
         &self,
+        view_number: TYPES::Time,
         leaf_commitment: &Commitment<LEAF>,
-    ) -> EncodedSignature {
-        let signature = TYPES::SignatureKey::sign(&self.private_key, leaf_commitment.as_ref());
-        signature
+    ) -> Result<EncodedSignature, SignerError> {
+        self.sign(
+            view_number,
+            SigningKind::QuorumProposal,
+            leaf_commitment.as_ref().to_vec(),
+        )
+        .await
     }
 
     /// Sign a positive vote on validating or commitment proposal.
@@ -692,15 +1193,18 @@ impl<
     /// of information necessary for any user of the subsequently constructed QC to check that this
     /// node voted `Yes` on that leaf. The leaf is expected to be reconstructed based on other
     /// information in the yes vote.
-    fn sign_yes_vote(
+    async fn sign_yes_vote(
         &self,
+        view_number: TYPES::Time,
         leaf_commitment: Commitment<LEAF>,
-    ) -> (EncodedPublicKey, EncodedSignature) {
-        let signature = TYPES::SignatureKey::sign(
-            &self.private_key,
-            &VoteData::<TYPES, LEAF>::Yes(leaf_commitment).as_bytes(),
-        );
-        (self.public_key.to_bytes(), signature)
+    ) -> Result<(EncodedPublicKey, EncodedSignature), SignerError> {
+        let signature = self.sign(
+            view_number,
+            SigningKind::YesVote,
+            VoteData::<TYPES, LEAF>::Yes(leaf_commitment).as_bytes(),
+        )
+        .await?;
+        Ok((self.public_key().to_bytes(), signature))
     }
 
     /// Sign a neagtive vote on validating or commitment proposal.
@@ -708,15 +1212,18 @@ impl<
     /// The leaf commitment and the type of the vote (no) are signed, which is the minimum amount
     /// of information necessary for any user of the subsequently constructed QC to check that this
     /// node voted `No` on that leaf.
-    fn sign_no_vote(
+    async fn sign_no_vote(
         &self,
+        view_number: TYPES::Time,
         leaf_commitment: Commitment<LEAF>,
-    ) -> (EncodedPublicKey, EncodedSignature) {
-        let signature = TYPES::SignatureKey::sign(
-            &self.private_key,
-            &VoteData::<TYPES, LEAF>::No(leaf_commitment).as_bytes(),
-        );
-        (self.public_key.to_bytes(), signature)
+    ) -> Result<(EncodedPublicKey, EncodedSignature), SignerError> {
+        let signature = self.sign(
+            view_number,
+            SigningKind::NoVote,
+            VoteData::<TYPES, LEAF>::No(leaf_commitment).as_bytes(),
+        )
+        .await?;
+        Ok((self.public_key().to_bytes(), signature))
     }
 
     /// Sign a timeout vote.
@@ -726,56 +1233,62 @@ impl<
     ///
     /// This also allows for the high QC included with the vote to be spoofed in a MITM scenario,
     /// but it is outside our threat model.
-    fn sign_timeout_vote(&self, view_number: TYPES::Time) -> (EncodedPublicKey, EncodedSignature) {
-        let signature = TYPES::SignatureKey::sign(
-            &self.private_key,
-            &VoteData::<TYPES, LEAF>::Timeout(view_number).as_bytes(),
-        );
-        (self.public_key.to_bytes(), signature)
+    async fn sign_timeout_vote(
+        &self,
+        view_number: TYPES::Time,
+    ) -> Result<(EncodedPublicKey, EncodedSignature), SignerError> {
+        let signature = self.sign(
+            view_number,
+            SigningKind::TimeoutVote,
+            VoteData::<TYPES, LEAF>::Timeout(view_number).as_bytes(),
+        )
+        .await?;
+        Ok((self.public_key().to_bytes(), signature))
     }
     /// Create a message with a negative vote on validating or commitment proposal.
-    fn create_no_message<I: NodeImplementation<TYPES>>(
+    async fn create_no_message<I: NodeImplementation<TYPES>>(
         &self,
         justify_qc_commitment: Commitment<QuorumCertificate<TYPES, LEAF>>,
         leaf_commitment: Commitment<LEAF>,
         current_view: TYPES::Time,
         vote_token: TYPES::VoteTokenType,
-    ) -> ConsensusMessage<TYPES, I>
+    ) -> Result<ConsensusMessage<TYPES, I>, SignerError>
     where
         I::QuorumExchange:
             ConsensusExchange<TYPES, I::Leaf, Message<TYPES, I>, Vote = QuorumVote<TYPES, LEAF>>,
     {
-        let signature = self.sign_no_vote(leaf_commitment);
-        ConsensusMessage::<TYPES, I>::Vote(QuorumVote::No(YesOrNoVote {
+        let signature = self.sign_no_vote(current_view, leaf_commitment).await?;
+        Ok(ConsensusMessage::<TYPES, I>::Vote(QuorumVote::No(YesOrNoVote {
             justify_qc_commitment,
             signature,
             leaf_commitment,
             current_view,
             vote_token,
-        }))
+        })))
     }
 
     /// Create a message with a timeout vote on validating or commitment proposal.
-    fn create_timeout_message<I: NodeImplementation<TYPES>>(
+    async fn create_timeout_message<I: NodeImplementation<TYPES>>(
         &self,
         justify_qc: QuorumCertificate<TYPES, LEAF>,
         current_view: TYPES::Time,
         vote_token: TYPES::VoteTokenType,
-    ) -> ConsensusMessage<TYPES, I>
+    ) -> Result<ConsensusMessage<TYPES, I>, SignerError>
     where
         I::QuorumExchange:
             ConsensusExchange<TYPES, I::Leaf, Message<TYPES, I>, Vote = QuorumVote<TYPES, LEAF>>,
     {
-        let signature = self.sign_timeout_vote(current_view);
-        ConsensusMessage::<TYPES, I>::Vote(QuorumVote::Timeout(TimeoutVote {
+        let signature = self.sign_timeout_vote(current_view).await?;
+        Ok(ConsensusMessage::<TYPES, I>::Vote(QuorumVote::Timeout(TimeoutVote {
             justify_qc,
             signature,
             current_view,
             vote_token,
-        }))
+        })))
     }
 }
 
+#[async_trait]
 impl<
         TYPES: NodeType,
         LEAF: LeafType<NodeType = TYPES>,
@@ -797,16 +1310,16 @@ impl<
         keys: Vec<TYPES::SignatureKey>,
         config: TYPES::ElectionConfigType,
         network: Self::Networking,
-        pk: TYPES::SignatureKey,
-        sk: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        chain_id: ChainId,
+        signer: Arc<dyn Signer<TYPES::SignatureKey>>,
     ) -> Self {
         let membership =
             <Self as ConsensusExchange<TYPES, LEAF, M>>::Membership::create_election(keys, config);
         Self {
             network,
             membership,
-            public_key: pk,
-            private_key: sk,
+            chain_id,
+            signer,
             _pd: PhantomData,
         }
     }
@@ -843,11 +1356,11 @@ impl<
     fn membership(&self) -> &Self::Membership {
         &self.membership
     }
-    fn public_key(&self) -> &TYPES::SignatureKey {
-        &self.public_key
+    fn signer(&self) -> &Arc<dyn Signer<TYPES::SignatureKey>> {
+        &self.signer
     }
-    fn private_key(&self) -> &<<TYPES as NodeType>::SignatureKey as SignatureKey>::PrivateKey {
-        &self.private_key
+    fn chain_id(&self) -> ChainId {
+        self.chain_id
     }
 }
 
@@ -4,19 +4,24 @@
 
 #[cfg(feature = "async-std-executor")]
 use async_std::future::TimeoutError;
+#[cfg(any(feature = "async-std-executor", feature = "tokio-executor"))]
 use libp2p_networking::network::NetworkNodeHandleError;
+// Without an async runtime, for users such as light clients, nothing can time out
+#[cfg(not(any(feature = "async-std-executor", feature = "tokio-executor")))]
+use std::convert::Infallible as TimeoutError;
 #[cfg(feature = "tokio-executor")]
 use tokio::time::error::Elapsed as TimeoutError;
-#[cfg(not(any(feature = "async-std-executor", feature = "tokio-executor")))]
-std::compile_error! {"Either feature \"async-std-executor\" or feature \"tokio-executor\" must be enabled for this crate."}
 
 use super::{election::Membership, node_implementation::NodeType, signature_key::SignatureKey};
 use crate::{data::ProposalType, vote::VoteType};
 use async_trait::async_trait;
+use bincode::Options;
+use hotshot_utils::{bincode::bincode_opts, version::ProtocolVersion};
 use serde::{Deserialize, Serialize};
-use snafu::Snafu;
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use std::{collections::BTreeSet, time::Duration};
 
+#[cfg(any(feature = "async-std-executor", feature = "tokio-executor"))]
 impl From<NetworkNodeHandleError> for NetworkError {
     fn from(error: NetworkNodeHandleError) -> Self {
         match error {
@@ -77,6 +82,7 @@ pub enum TransmitType {
 #[snafu(visibility(pub))]
 pub enum NetworkError {
     /// Libp2p specific errors
+    #[cfg(any(feature = "async-std-executor", feature = "tokio-executor"))]
     Libp2p {
         /// source of error
         source: NetworkNodeHandleError,
@@ -125,12 +131,69 @@ pub enum NetworkError {
     ShutDown,
     /// unable to cancel a request, the request has already been cancelled
     UnableToCancel,
+    /// A network message too short to announce the protocol version it was written in
+    MissingVersion,
+    /// A network message written in a protocol version this node does not read
+    UnsupportedVersion {
+        /// The version the message was written in
+        version: ProtocolVersion,
+    },
 }
 
 /// common traits we would like our network messages to implement
+///
+/// Messages go on the wire in the layout of a [`ProtocolVersion`]. A release changing the layout
+/// of a message overrides [`NetworkMsg::encode_payload`] and [`NetworkMsg::decode_payload`] to keep
+/// the layouts of the versions it writes and reads.
 pub trait NetworkMsg:
     Serialize + for<'a> Deserialize<'a> + Clone + Sync + Send + std::fmt::Debug + 'static
 {
+    /// Serializes this message in the layout of `version`
+    ///
+    /// # Errors
+    /// If the message cannot be serialized
+    fn encode_payload(&self, _version: ProtocolVersion) -> Result<Vec<u8>, bincode::Error> {
+        bincode_opts().serialize(self)
+    }
+
+    /// Deserializes a message written in the layout of `version`
+    ///
+    /// # Errors
+    /// If `payload` is not a message in the layout of `version`
+    fn decode_payload(_version: ProtocolVersion, payload: &[u8]) -> Result<Self, bincode::Error> {
+        bincode_opts().deserialize(payload)
+    }
+}
+
+/// Encodes `message` for the wire, as the header of the version this node writes followed by the
+/// message in the layout of that version
+///
+/// # Errors
+/// If the message cannot be serialized
+pub fn encode_message<M: NetworkMsg>(message: &M) -> Result<Vec<u8>, NetworkError> {
+    let version = ProtocolVersion::CURRENT.written();
+    let mut bytes = version.to_header().to_vec();
+    bytes.extend(
+        message
+            .encode_payload(version)
+            .context(FailedToSerializeSnafu)?,
+    );
+    Ok(bytes)
+}
+
+/// Decodes a message encoded by [`encode_message`], in the layout of the version of its header
+///
+/// # Errors
+/// - [`NetworkError::MissingVersion`] if `bytes` do not start with a version header
+/// - [`NetworkError::UnsupportedVersion`] if this node does not read that version
+/// - [`NetworkError::FailedToDeserialize`] if the message is not in the layout of that version
+pub fn decode_message<M: NetworkMsg>(bytes: &[u8]) -> Result<M, NetworkError> {
+    let (version, payload) = ProtocolVersion::split_header(bytes).context(MissingVersionSnafu)?;
+    ensure!(
+        ProtocolVersion::CURRENT.reads(version),
+        UnsupportedVersionSnafu { version }
+    );
+    M::decode_payload(version, payload).context(FailedToDeserializeSnafu)
 }
 
 /// a message
@@ -0,0 +1,239 @@
+//! Per-epoch randomness derived from the history of the chain
+//!
+//! The randomness of an epoch is a hash chain over the quorum certificates decided in the epoch
+//! before it. These are the `justify_qc`s of decided leaves, which are committed to by the leaves
+//! themselves, so every replica (and every light client following the chain) derives the same
+//! values. The signatures, and the vote tokens carrying VRF proofs, are only known once a quorum
+//! has voted, so randomness is not predictable before the epoch that produces it.
+//!
+//! The leader of a view chooses which of the valid votes end up in its certificate, which gives it
+//! a limited ability to bias the contribution of that certificate.
+
+use crate::{
+    certificate::{CertificateSignatures, QuorumCertificate},
+    data::LeafType,
+    traits::{node_implementation::NodeType, state::ConsensusTime},
+};
+use commit::Committable;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, num::NonZeroU64, sync::Mutex};
+
+/// Domain separator of the epoch randomness hash chain
+const EPOCH_RANDOMNESS_TAG: &[u8] = b"HotShot epoch randomness";
+
+/// Source of unpredictable per-epoch randomness that all replicas agree on
+pub trait RandomnessBeacon<TYPES: NodeType>: Send + Sync {
+    /// The epoch `view_number` belongs to
+    fn epoch(&self, view_number: TYPES::Time) -> u64;
+
+    /// Feed the beacon the `justify_qc` of a decided leaf
+    ///
+    /// Certificates must be recorded in increasing view order. Certificates for views already
+    /// covered are ignored.
+    fn record_decided_qc<LEAF: LeafType<NodeType = TYPES>>(
+        &self,
+        qc: &QuorumCertificate<TYPES, LEAF>,
+    );
+
+    /// The randomness of `epoch`, or `None` if the certificates it derives from are not all
+    /// decided yet
+    fn randomness(&self, epoch: u64) -> Option<[u8; 32]>;
+
+    /// The seed elections should use for `view_number`, or `None` if the randomness it is derived
+    /// from is not determined yet
+    fn seed_for_view(&self, view_number: TYPES::Time) -> Option<[u8; 32]>;
+
+    /// Check that `claimed` is the randomness of `epoch`, given the randomness of the epoch before
+    /// it and the certificates decided during that epoch, in view order
+    ///
+    /// This is meant for light clients. It does not check the certificates themselves, which the
+    /// caller must have validated against the stake table.
+    fn verify_randomness<LEAF: LeafType<NodeType = TYPES>>(
+        &self,
+        previous: &[u8; 32],
+        epoch: u64,
+        qcs: &[QuorumCertificate<TYPES, LEAF>],
+        claimed: &[u8; 32],
+    ) -> bool;
+}
+
+/// Configuration of a [`QcRandomnessBeacon`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BeaconConfig {
+    /// Number of views in an epoch
+    pub epoch_length: NonZeroU64,
+    /// Views of epoch `e` are seeded with the randomness of epoch `e - lookback`
+    ///
+    /// With a lookback of at least 2, the seed of an epoch is known to every replica that has
+    /// decided a leaf in the epoch before it.
+    pub lookback: u64,
+    /// The randomness of epoch 0
+    pub genesis_seed: [u8; 32],
+}
+
+impl Default for BeaconConfig {
+    fn default() -> Self {
+        Self {
+            epoch_length: NonZeroU64::new(100).unwrap(),
+            lookback: 2,
+            genesis_seed: [0; 32],
+        }
+    }
+}
+
+/// A [`RandomnessBeacon`] hashing the signatures and vote tokens of decided quorum certificates
+#[derive(Debug)]
+pub struct QcRandomnessBeacon {
+    /// the configuration
+    config: BeaconConfig,
+    /// the epochs derived so far and the certificates of the current epoch
+    state: Mutex<BeaconState>,
+}
+
+/// The mutable part of a [`QcRandomnessBeacon`]
+#[derive(Debug)]
+struct BeaconState {
+    /// the randomness of every epoch up to and including the current one
+    randomness: BTreeMap<u64, [u8; 32]>,
+    /// the epoch whose certificates are being accumulated
+    current_epoch: u64,
+    /// hash of the certificates of the current epoch so far
+    accumulator: blake3::Hasher,
+    /// the view of the last recorded certificate
+    last_view: Option<u64>,
+}
+
+/// Start the hash producing the randomness of `epoch + 1` from the one of `epoch`
+fn start_epoch(randomness: &[u8; 32], epoch: u64) -> blake3::Hasher {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(EPOCH_RANDOMNESS_TAG);
+    hasher.update(randomness);
+    hasher.update(&(epoch + 1).to_le_bytes());
+    hasher
+}
+
+/// The contribution of a certificate to the randomness of the next epoch
+fn qc_entropy<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>>(
+    qc: &QuorumCertificate<TYPES, LEAF>,
+) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&qc.view_number.to_le_bytes());
+    match &qc.signatures {
+        CertificateSignatures::Individual(signatures) => {
+            for (key, (signature, token)) in signatures {
+                hasher.update(&(key.0.len() as u64).to_le_bytes());
+                hasher.update(&key.0);
+                hasher.update(&(signature.0.len() as u64).to_le_bytes());
+                hasher.update(&signature.0);
+                hasher.update(token.commit().as_ref());
+            }
+        }
+        // aggregate BLS signatures are deterministic in the set of signers, so this is as
+        // unpredictable as the individual signatures
+        CertificateSignatures::Aggregated(aggregate) => {
+            hasher.update(&(aggregate.signers.len() as u64).to_le_bytes());
+            hasher.update(aggregate.signers.as_bytes());
+            hasher.update(&(aggregate.signature.0.len() as u64).to_le_bytes());
+            hasher.update(&aggregate.signature.0);
+            for token in &aggregate.tokens {
+                hasher.update(token.commit().as_ref());
+            }
+        }
+    }
+    *hasher.finalize().as_bytes()
+}
+
+impl QcRandomnessBeacon {
+    /// Create a beacon at genesis
+    #[must_use]
+    pub fn new(config: BeaconConfig) -> Self {
+        let state = BeaconState {
+            randomness: BTreeMap::from([(0, config.genesis_seed)]),
+            current_epoch: 0,
+            accumulator: start_epoch(&config.genesis_seed, 0),
+            last_view: None,
+        };
+        Self {
+            config,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// The configuration of this beacon
+    #[must_use]
+    pub fn config(&self) -> &BeaconConfig {
+        &self.config
+    }
+}
+
+impl<TYPES: NodeType> RandomnessBeacon<TYPES> for QcRandomnessBeacon {
+    fn epoch(&self, view_number: TYPES::Time) -> u64 {
+        *view_number / self.config.epoch_length
+    }
+
+    fn record_decided_qc<LEAF: LeafType<NodeType = TYPES>>(
+        &self,
+        qc: &QuorumCertificate<TYPES, LEAF>,
+    ) {
+        if qc.is_genesis {
+            return;
+        }
+        let view = *qc.view_number;
+        let mut state = self.state.lock().unwrap();
+        if state.last_view.map_or(false, |last| view <= last) {
+            return;
+        }
+        state.last_view = Some(view);
+
+        // a certificate of a later epoch closes every epoch before it
+        let epoch = view / self.config.epoch_length;
+        while state.current_epoch < epoch {
+            let closed = state.current_epoch;
+            let randomness = *state.accumulator.finalize().as_bytes();
+            state.randomness.insert(closed + 1, randomness);
+            state.current_epoch = closed + 1;
+            state.accumulator = start_epoch(&randomness, closed + 1);
+        }
+        state.accumulator.update(&qc_entropy(qc));
+    }
+
+    fn randomness(&self, epoch: u64) -> Option<[u8; 32]> {
+        self.state.lock().unwrap().randomness.get(&epoch).copied()
+    }
+
+    /// The randomness of `lookback` epochs ago
+    ///
+    /// If that epoch is not determined yet, because this replica is behind in deciding, there is no
+    /// seed until it catches up: any other seed would select other leaders and committees than the
+    /// rest of the network does.
+    fn seed_for_view(&self, view_number: TYPES::Time) -> Option<[u8; 32]> {
+        let epoch = <Self as RandomnessBeacon<TYPES>>::epoch(self, view_number);
+        let target = epoch.saturating_sub(self.config.lookback);
+        <Self as RandomnessBeacon<TYPES>>::randomness(self, target)
+    }
+
+    fn verify_randomness<LEAF: LeafType<NodeType = TYPES>>(
+        &self,
+        previous: &[u8; 32],
+        epoch: u64,
+        qcs: &[QuorumCertificate<TYPES, LEAF>],
+        claimed: &[u8; 32],
+    ) -> bool {
+        let Some(previous_epoch) = epoch.checked_sub(1) else {
+            return claimed == &self.config.genesis_seed;
+        };
+        let mut hasher = start_epoch(previous, previous_epoch);
+        let mut last_view = None;
+        for qc in qcs.iter().filter(|qc| !qc.is_genesis) {
+            let view = *qc.view_number;
+            if view / self.config.epoch_length != previous_epoch
+                || last_view.map_or(false, |last| view <= last)
+            {
+                return false;
+            }
+            last_view = Some(view);
+            hasher.update(&qc_entropy(qc));
+        }
+        hasher.finalize().as_bytes() == claimed
+    }
+}
@@ -7,6 +7,8 @@ use tagged_base64::tagged;
 
 #[cfg(feature = "demo")]
 pub mod ed25519;
+pub mod keystore;
+pub mod secp256k1;
 
 /// Type saftey wrapper for byte encoded keys
 #[tagged(tag::ENCODED_PUB_KEY)]
@@ -35,6 +37,24 @@ impl AsRef<[u8]> for EncodedSignature {
     }
 }
 
+/// Tag starting the payload of a proof of possession
+///
+/// It is length prefixed and differs from the tag of every
+/// [`SigningKind`](crate::traits::signer::SigningKind), so no consensus signature is ever a proof
+/// of possession.
+pub const POSSESSION_DOMAIN_TAG: &str = "HotShot/ProofOfPossession";
+
+/// The bytes signed by a proof of possession of the private key of `key`
+#[must_use]
+pub fn possession_payload(key: &EncodedPublicKey) -> Vec<u8> {
+    let tag = POSSESSION_DOMAIN_TAG.as_bytes();
+    let mut payload = Vec::with_capacity(8 + tag.len() + key.0.len());
+    payload.extend((tag.len() as u64).to_le_bytes());
+    payload.extend(tag);
+    payload.extend(&key.0);
+    payload
+}
+
 /// Trait for abstracting public key signatures
 pub trait SignatureKey:
     Send
@@ -65,6 +85,51 @@ pub trait SignatureKey:
     /// Deserialize a public key from bytes
     fn from_bytes(bytes: &EncodedPublicKey) -> Option<Self>;
 
+    /// Validate a batch of signatures, each by its key on its data
+    ///
+    /// Returns `true` only if the whole batch is valid. The default validates the signatures one
+    /// at a time; schemes that can check many signatures faster override it, but must accept
+    /// exactly the batches whose every signature passes [`SignatureKey::validate`]. When a batch
+    /// fails, callers fall back to [`SignatureKey::validate`] to find the invalid signatures.
+    fn batch_validate(batch: &[(Self, EncodedSignature, &[u8])]) -> bool {
+        batch
+            .iter()
+            .all(|(key, signature, data)| key.validate(signature, data))
+    }
+
+    /// Aggregate signatures of `keys` on the same data into a single signature, pairwise in order
+    ///
+    /// Returns `None` if the scheme does not support aggregation, which is the default.
+    fn aggregate(_keys: &[Self], _signatures: &[EncodedSignature]) -> Option<EncodedSignature> {
+        None
+    }
+
+    /// Validate a signature produced by [`SignatureKey::aggregate`] from signatures of `keys` on
+    /// `data`
+    ///
+    /// Only sound if every key of `keys` passed [`SignatureKey::verify_possession`]: otherwise a
+    /// key chosen as a function of the others can forge an aggregate on their behalf. Schemes that
+    /// do not support aggregation reject every aggregate signature.
+    fn validate_aggregate(_keys: &[Self], _signature: &EncodedSignature, _data: &[u8]) -> bool {
+        false
+    }
+
+    /// Prove that the holder of `private_key` knows it, so that its key can take part in
+    /// aggregate signatures
+    ///
+    /// Returns `None` if the scheme does not support aggregation, which is the default.
+    fn prove_possession(_private_key: &Self::PrivateKey) -> Option<EncodedSignature> {
+        None
+    }
+
+    /// Whether `proof` shows that the holder of this key knows its private key
+    ///
+    /// Schemes that support aggregation check a proof made by [`SignatureKey::prove_possession`].
+    /// The others need no proof and accept any, which is the default.
+    fn verify_possession(&self, _proof: &EncodedSignature) -> bool {
+        true
+    }
+
     /// Generate a new key pair
     fn generated_from_seed_indexed(seed: [u8; 32], index: u64) -> (Self, Self::PrivateKey);
 }
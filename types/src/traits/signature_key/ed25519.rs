@@ -30,6 +30,49 @@ mod tests {
         assert!(pub_key.validate(&signature, &data));
     }
 
+    // A batch validates only if every signature in it does
+    #[test]
+    fn batch_should_validate() {
+        let keys: Vec<_> = (0..4)
+            .map(|i| Ed25519Pub::generated_from_seed_indexed([0_u8; 32], i))
+            .collect();
+        let data: Vec<[u8; 8]> = (0_u64..4).map(u64::to_le_bytes).collect();
+        let mut batch: Vec<_> = keys
+            .iter()
+            .zip(&data)
+            .map(|((pub_key, priv_key), data)| {
+                (*pub_key, Ed25519Pub::sign(priv_key, data), &data[..])
+            })
+            .collect();
+        assert!(Ed25519Pub::batch_validate(&batch));
+        assert!(Ed25519Pub::batch_validate(&[]));
+
+        // a signature on other data, and a malformed signature
+        batch[2].2 = &data[1][..];
+        assert!(!Ed25519Pub::batch_validate(&batch));
+        batch[2].2 = &data[2][..];
+        batch[3].1 = EncodedSignature(vec![0; 10]);
+        assert!(!Ed25519Pub::batch_validate(&batch));
+    }
+
+    // One bad signature among many on the same data fails the batch, as it fails `validate`
+    #[test]
+    fn batch_should_reject_one_bad_signature() {
+        let data = b"yes";
+        let mut batch: Vec<_> = (0..6)
+            .map(|i| {
+                let (pub_key, priv_key) = Ed25519Pub::generated_from_seed_indexed([0_u8; 32], i);
+                (pub_key, Ed25519Pub::sign(&priv_key, data), &data[..])
+            })
+            .collect();
+        assert!(Ed25519Pub::batch_validate(&batch));
+
+        let (_, outsider) = Ed25519Pub::generated_from_seed_indexed([1_u8; 32], 0);
+        batch[4].1 = Ed25519Pub::sign(&outsider, data);
+        assert!(!batch[4].0.validate(&batch[4].1, data));
+        assert!(!Ed25519Pub::batch_validate(&batch));
+    }
+
     // Make sure serialization round trip works
     #[test]
     fn serialize_key() {
@@ -0,0 +1,307 @@
+//! Password-encrypted files holding a validator's private key
+//!
+//! A [`Keystore`] is a JSON document recording the key type and the public key in the clear,
+//! and the private key encrypted with XChaCha20-Poly1305 under a key derived from a password
+//! with Argon2id. The key type and public key are authenticated along with the private key, so
+//! editing them makes decryption fail.
+
+use super::{EncodedPublicKey, SignatureKey};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    Key, XChaCha20Poly1305, XNonce,
+};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use std::{env, fs, io::Write, path::Path};
+use zeroize::Zeroizing;
+
+/// Version of the keystore format written by [`Keystore::encrypt`]
+pub const KEYSTORE_VERSION: u32 = 1;
+
+/// Environment variable the validator and key management binaries read a password from
+pub const KEYSTORE_PASSWORD_ENV: &str = "HOTSHOT_KEYSTORE_PASSWORD";
+
+/// Length of the Argon2id salt, in bytes
+const SALT_LEN: usize = 16;
+
+/// Length of the XChaCha20-Poly1305 nonce, in bytes
+const NONCE_LEN: usize = 24;
+
+/// Signature keys whose private keys can be stored in a [`Keystore`]
+pub trait KeystoreKey: SignatureKey {
+    /// Name of the key type, recorded in keystores
+    const KEY_TYPE: &'static str;
+
+    /// Generate a new private key from operating system randomness
+    fn generate_private_key() -> Self::PrivateKey;
+
+    /// Serialize a private key
+    fn private_key_to_bytes(private_key: &Self::PrivateKey) -> Vec<u8>;
+
+    /// Deserialize a private key serialized with [`KeystoreKey::private_key_to_bytes`]
+    fn private_key_from_bytes(bytes: &[u8]) -> Option<Self::PrivateKey>;
+}
+
+/// Errors reading, writing or decrypting a [`Keystore`]
+#[derive(Snafu, Debug)]
+pub enum KeystoreError {
+    /// The keystore file could not be read or written
+    #[snafu(display("could not access keystore file: {source}"))]
+    Io {
+        /// the underlying error
+        source: std::io::Error,
+    },
+    /// The keystore file is not a valid keystore
+    #[snafu(display("malformed keystore: {source}"))]
+    Format {
+        /// the underlying error
+        source: serde_json::Error,
+    },
+    /// The keystore was written by an unknown version of the format
+    #[snafu(display("unsupported keystore version {version}"))]
+    UnsupportedVersion {
+        /// the version of the keystore
+        version: u32,
+    },
+    /// The keystore holds another type of key
+    #[snafu(display("keystore holds a {found} key, expected {expected}"))]
+    WrongKeyType {
+        /// the requested key type
+        expected: String,
+        /// the key type of the keystore
+        found: String,
+    },
+    /// The key derivation parameters are invalid
+    #[snafu(display("invalid key derivation parameters: {reason}"))]
+    Kdf {
+        /// why the parameters are invalid
+        reason: String,
+    },
+    /// The password is wrong, or the keystore was modified
+    #[snafu(display("could not decrypt keystore, wrong password?"))]
+    Decryption,
+    /// The decrypted private key is malformed or does not match the public key
+    #[snafu(display("keystore holds an invalid private key"))]
+    InvalidKey,
+    /// No password file was given and the password environment variable is not set
+    #[snafu(display("no keystore password, set {KEYSTORE_PASSWORD_ENV} or pass a password file"))]
+    NoPassword,
+}
+
+/// Read a keystore password from `password_file`, or else from [`KEYSTORE_PASSWORD_ENV`]
+///
+/// A trailing newline in the file is not part of the password.
+///
+/// # Errors
+/// If the file cannot be read, or no file is given and the variable is not set
+pub fn read_password(password_file: Option<&Path>) -> Result<Zeroizing<String>, KeystoreError> {
+    let mut password = match password_file {
+        Some(path) => Zeroizing::new(fs::read_to_string(path).context(IoSnafu)?),
+        None => Zeroizing::new(
+            env::var(KEYSTORE_PASSWORD_ENV).map_err(|_| KeystoreError::NoPassword)?,
+        ),
+    };
+    while password.ends_with('\n') || password.ends_with('\r') {
+        password.pop();
+    }
+    Ok(password)
+}
+
+/// Argon2id parameters deriving the encryption key from the password
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost, in KiB
+    pub memory_kib: u32,
+    /// Number of passes over the memory
+    pub iterations: u32,
+    /// Number of lanes
+    pub parallelism: u32,
+    /// Random salt
+    #[serde(with = "hex")]
+    pub salt: Vec<u8>,
+}
+
+impl KdfParams {
+    /// The default Argon2id costs, with a fresh random salt
+    fn generate() -> Self {
+        let mut salt = vec![0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+            salt,
+        }
+    }
+
+    /// Derive the encryption key from `password`
+    fn derive_key(&self, password: &[u8]) -> Result<Zeroizing<[u8; 32]>, KeystoreError> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(32))
+            .map_err(|e| KeystoreError::Kdf {
+                reason: e.to_string(),
+            })?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password, &self.salt, &mut *key)
+            .map_err(|e| KeystoreError::Kdf {
+                reason: e.to_string(),
+            })?;
+        Ok(key)
+    }
+}
+
+/// The encrypted private key of a [`Keystore`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    /// Derivation of the encryption key from the password
+    pub kdf: KdfParams,
+    /// XChaCha20-Poly1305 nonce
+    #[serde(with = "hex")]
+    pub nonce: Vec<u8>,
+    /// The private key, encrypted and authenticated with XChaCha20-Poly1305
+    #[serde(with = "hex")]
+    pub ciphertext: Vec<u8>,
+}
+
+/// A private key encrypted with a password, along with its public key
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    /// Version of the format, [`KEYSTORE_VERSION`]
+    pub version: u32,
+    /// The [`KeystoreKey::KEY_TYPE`] of the key
+    pub key_type: String,
+    /// The public key, as tagged base64
+    pub public_key: EncodedPublicKey,
+    /// The encrypted private key
+    pub crypto: KeystoreCrypto,
+}
+
+impl Keystore {
+    /// Encrypt `private_key` with `password`
+    ///
+    /// # Errors
+    /// If the key derivation fails
+    #[allow(clippy::missing_panics_doc)] // encryption only fails for messages of 256 GiB or more
+    pub fn encrypt<KEY: KeystoreKey>(
+        private_key: &KEY::PrivateKey,
+        password: &[u8],
+    ) -> Result<Self, KeystoreError> {
+        let key_type = KEY::KEY_TYPE.to_string();
+        let public_key = KEY::from_private(private_key).to_bytes();
+        let kdf = KdfParams::generate();
+        let key = kdf.derive_key(password)?;
+        let mut nonce = vec![0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+
+        let plaintext = Zeroizing::new(KEY::private_key_to_bytes(private_key));
+        let aad = associated_data(&key_type, &public_key);
+        let ciphertext = XChaCha20Poly1305::new(Key::from_slice(&*key))
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &aad,
+                },
+            )
+            .expect("a private key is short enough to encrypt");
+
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            key_type,
+            public_key,
+            crypto: KeystoreCrypto {
+                kdf,
+                nonce,
+                ciphertext,
+            },
+        })
+    }
+
+    /// Decrypt the private key with `password`
+    ///
+    /// # Errors
+    /// If the keystore is not a supported keystore for `KEY`, the password is wrong, or the
+    /// private key does not match the public key
+    pub fn decrypt<KEY: KeystoreKey>(
+        &self,
+        password: &[u8],
+    ) -> Result<KEY::PrivateKey, KeystoreError> {
+        let public_key = self.public_key::<KEY>()?;
+        if self.crypto.nonce.len() != NONCE_LEN {
+            return Err(KeystoreError::Decryption);
+        }
+        let key = self.crypto.kdf.derive_key(password)?;
+        let aad = associated_data(&self.key_type, &self.public_key);
+        let plaintext = Zeroizing::new(
+            XChaCha20Poly1305::new(Key::from_slice(&*key))
+                .decrypt(
+                    XNonce::from_slice(&self.crypto.nonce),
+                    Payload {
+                        msg: &self.crypto.ciphertext,
+                        aad: &aad,
+                    },
+                )
+                .map_err(|_| KeystoreError::Decryption)?,
+        );
+        let private_key =
+            KEY::private_key_from_bytes(&plaintext).ok_or(KeystoreError::InvalidKey)?;
+        if KEY::from_private(&private_key) != public_key {
+            return Err(KeystoreError::InvalidKey);
+        }
+        Ok(private_key)
+    }
+
+    /// The public key, without decrypting anything
+    ///
+    /// # Errors
+    /// If the keystore is not a supported keystore for `KEY`
+    pub fn public_key<KEY: KeystoreKey>(&self) -> Result<KEY, KeystoreError> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(KeystoreError::UnsupportedVersion {
+                version: self.version,
+            });
+        }
+        if self.key_type != KEY::KEY_TYPE {
+            return Err(KeystoreError::WrongKeyType {
+                expected: KEY::KEY_TYPE.to_string(),
+                found: self.key_type.clone(),
+            });
+        }
+        KEY::from_bytes(&self.public_key).ok_or(KeystoreError::InvalidKey)
+    }
+
+    /// Read a keystore from `path`
+    ///
+    /// # Errors
+    /// If the file cannot be read or is not a keystore
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KeystoreError> {
+        let contents = fs::read_to_string(path).context(IoSnafu)?;
+        serde_json::from_str(&contents).context(FormatSnafu)
+    }
+
+    /// Write the keystore to a new file at `path`, readable only by its owner on Unix
+    ///
+    /// # Errors
+    /// If the file already exists or cannot be written
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), KeystoreError> {
+        let contents = serde_json::to_string_pretty(self).context(FormatSnafu)?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(path).context(IoSnafu)?;
+        file.write_all(contents.as_bytes()).context(IoSnafu)?;
+        file.sync_all().context(IoSnafu)
+    }
+}
+
+/// The data authenticated along with the private key: the key type and the public key
+fn associated_data(key_type: &str, public_key: &EncodedPublicKey) -> Vec<u8> {
+    let mut aad = Vec::new();
+    aad.extend((key_type.len() as u64).to_le_bytes());
+    aad.extend(key_type.as_bytes());
+    aad.extend(&public_key.0);
+    aad
+}
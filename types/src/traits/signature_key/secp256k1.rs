@@ -0,0 +1,109 @@
+//! Ethereum compatible implementation of the [`SignatureKey`] trait using secp256k1 ECDSA
+use super::{EncodedPublicKey, EncodedSignature, SignatureKey, TestableSignatureKey};
+/// `Secp256k1Priv` implementation
+mod secp256k1_priv;
+/// `Secp256k1Pub` implementation
+mod secp256k1_pub;
+
+pub use self::{secp256k1_priv::Secp256k1Priv, secp256k1_pub::Secp256k1Pub};
+
+/// Tag of the tagged base64 encoding of a [`Secp256k1Pub`]
+pub const SECP256K1_PUB_TAG: &str = "SECP_PUB";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+
+    // Basic smoke test
+    #[test]
+    fn signature_should_validate() {
+        // Get some data to test sign with
+        let mut data = [0_u8; 64];
+        rand::thread_rng().fill_bytes(&mut data);
+
+        // Get a key to sign it with
+        let priv_key = Secp256k1Priv::generate();
+        // And the matching public key
+        let pub_key = Secp256k1Pub::from_private(&priv_key);
+
+        // Sign the data with it
+        let signature = Secp256k1Pub::sign(&priv_key, &data);
+        // Verify the signature
+        assert!(pub_key.validate(&signature, &data));
+        // It is 65 bytes, with an Ethereum recovery byte
+        assert_eq!(signature.0.len(), 65);
+        assert!(signature.0[64] == 27 || signature.0[64] == 28);
+
+        // Other data, another key, or another recovery byte do not validate
+        let other_key = Secp256k1Pub::from_private(&Secp256k1Priv::generate());
+        assert!(!pub_key.validate(&signature, &data[1..]));
+        assert!(!other_key.validate(&signature, &data));
+        let mut flipped = signature.clone();
+        flipped.0[64] ^= 1;
+        assert!(!pub_key.validate(&flipped, &data));
+        assert!(!pub_key.validate(&EncodedSignature(signature.0[..64].to_vec()), &data));
+    }
+
+    // Keys are derived reproducibly from a seed and an index
+    #[test]
+    fn seeded_keys_are_reproducible() {
+        let (pub_key, _) = Secp256k1Pub::generated_from_seed_indexed([1_u8; 32], 3);
+        let (pub_key_2, _) = Secp256k1Pub::generated_from_seed_indexed([1_u8; 32], 3);
+        let (pub_key_3, _) = Secp256k1Pub::generated_from_seed_indexed([1_u8; 32], 4);
+        assert_eq!(pub_key, pub_key_2);
+        assert_ne!(pub_key, pub_key_3);
+    }
+
+    // Addresses match the ones Ethereum derives
+    #[test]
+    fn ethereum_address() {
+        let priv_key = Secp256k1Priv::from_bytes(&[
+            0x4c, 0x08, 0x83, 0xa6, 0x91, 0x02, 0x93, 0x7d, 0x62, 0x31, 0x47, 0x1b, 0x5d, 0xbb,
+            0x62, 0x04, 0xfe, 0x51, 0x29, 0x61, 0x70, 0x82, 0x79, 0x2a, 0xe4, 0x68, 0xd0, 0x1a,
+            0x3f, 0x36, 0x23, 0x18,
+        ])
+        .expect("Failed to deser key");
+        let address = Secp256k1Pub::from_private(&priv_key).address();
+        assert_eq!(
+            address,
+            [
+                0x2c, 0x75, 0x36, 0xe3, 0x60, 0x5d, 0x9c, 0x16, 0xa7, 0xa3, 0xd7, 0xb1, 0x89, 0x8e,
+                0x52, 0x93, 0x96, 0xa6, 0x5c, 0x23,
+            ]
+        );
+    }
+
+    // Make sure serialization round trip works
+    #[test]
+    fn serialize_key() {
+        // Get a private key
+        let priv_key = Secp256k1Priv::generate();
+        // And the matching public key
+        let pub_key = Secp256k1Pub::from_private(&priv_key);
+
+        // Convert the private key to bytes and back, then verify equality
+        let priv_key_bytes = priv_key.to_bytes();
+        let priv_key_2 = Secp256k1Priv::from_bytes(&priv_key_bytes).expect("Failed to deser key");
+        assert!(priv_key == priv_key_2);
+
+        // Convert the public key to bytes and back, then verify equality
+        let pub_key_bytes = pub_key.to_bytes();
+        assert_eq!(pub_key_bytes.0.len(), 33);
+        let pub_key_2 = Secp256k1Pub::from_bytes(&pub_key_bytes).expect("Failed to deser key");
+        assert_eq!(pub_key, pub_key_2);
+
+        // Serialize the public key and back, then verify equality
+        let serialized = serde_json::to_string(&pub_key).expect("Failed to ser key");
+        let pub_key_2: Secp256k1Pub =
+            serde_json::from_str(&serialized).expect("Failed to deser key");
+        assert_eq!(pub_key, pub_key_2);
+
+        // .to_string() and FromStr
+        let str = pub_key.to_string();
+        assert!(str.starts_with("SECP_PUB~"));
+        let pub_key_2: Secp256k1Pub = str.parse().expect("Failed to parse key");
+        assert_eq!(pub_key, pub_key_2);
+        assert!("PEER_ID~AAAA".parse::<Secp256k1Pub>().is_err());
+    }
+}
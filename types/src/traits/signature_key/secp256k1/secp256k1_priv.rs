@@ -0,0 +1,57 @@
+use k256::ecdsa::SigningKey;
+use rand::{rngs::StdRng, SeedableRng};
+use tracing::{instrument, warn};
+
+/// Private key type for a secp256k1 keypair
+#[derive(PartialEq, Eq, Clone)]
+pub struct Secp256k1Priv {
+    /// The private key for this keypair
+    pub(super) priv_key: SigningKey,
+}
+
+impl Secp256k1Priv {
+    /// Generate a new private key from scratch
+    #[must_use]
+    pub fn generate() -> Self {
+        let priv_key = SigningKey::random(&mut rand::rngs::OsRng);
+        Self { priv_key }
+    }
+
+    /// Generate a new private key from a seed and a number
+    ///
+    /// Hashes the seed and the number together using blake3, and draws the key from a PRNG
+    /// seeded with the hash. This method is useful for testing
+    #[must_use]
+    pub fn generated_from_seed_indexed(seed: [u8; 32], index: u64) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&seed);
+        hasher.update(&index.to_le_bytes());
+        let new_seed = *hasher.finalize().as_bytes();
+        let priv_key = SigningKey::random(&mut StdRng::from_seed(new_seed));
+        Self { priv_key }
+    }
+
+    /// Create an existing private key from its 32 byte big endian scalar
+    #[instrument(skip(bytes))]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match SigningKey::from_slice(bytes) {
+            Ok(priv_key) => Some(Self { priv_key }),
+            Err(e) => {
+                warn!(?e, "Failed to decode private key");
+                None
+            }
+        }
+    }
+
+    /// Convert a private key to its 32 byte big endian scalar
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.priv_key.to_bytes().to_vec()
+    }
+}
+
+impl std::fmt::Debug for Secp256k1Priv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Secp256k1Priv").finish_non_exhaustive()
+    }
+}
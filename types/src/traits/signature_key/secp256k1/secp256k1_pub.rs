@@ -0,0 +1,222 @@
+use super::{
+    EncodedPublicKey, EncodedSignature, Secp256k1Priv, SignatureKey, TestableSignatureKey,
+    SECP256K1_PUB_TAG,
+};
+use crate::traits::signature_key::keystore::KeystoreKey;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use serde::{de::Error, Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+use tagged_base64::TaggedBase64;
+use tracing::{debug, instrument};
+
+/// Length of an encoded signature: `r`, `s` and the recovery byte `v`
+const SIGNATURE_LEN: usize = 65;
+
+/// Public key type for a secp256k1 [`SignatureKey`] pair
+///
+/// Signatures are Ethereum compatible: ECDSA over the Keccak-256 hash of the data, encoded as
+/// `r || s || v` with `v` 27 or 28, so a contract can check them with `ecrecover` against
+/// [`Secp256k1Pub::address`]. Only low `s` signatures are valid.
+#[derive(Clone, PartialEq, Eq, Copy)]
+pub struct Secp256k1Pub {
+    /// The public key for this keypair
+    pub_key: VerifyingKey,
+}
+
+impl Secp256k1Pub {
+    /// The SEC1 compressed encoding of this key
+    fn compressed(&self) -> Vec<u8> {
+        self.pub_key.to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    /// The Ethereum address of this key: the last 20 bytes of the Keccak-256 hash of the
+    /// uncompressed key
+    #[must_use]
+    pub fn address(&self) -> [u8; 20] {
+        let uncompressed = self.pub_key.to_encoded_point(false);
+        let hash = Keccak256::digest(&uncompressed.as_bytes()[1..]);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&hash[12..]);
+        address
+    }
+
+    /// Return the [`TaggedBase64`] representation of this key.
+    #[allow(clippy::missing_panics_doc)] // `TaggedBase64::new()` only panics if `SECP256K1_PUB_TAG` is not valid base64, which it is.
+    #[must_use]
+    pub fn to_tagged_base64(&self) -> TaggedBase64 {
+        TaggedBase64::new(SECP256K1_PUB_TAG, &self.compressed()).unwrap()
+    }
+}
+
+impl fmt::Debug for Secp256k1Pub {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Secp256k1Pub")
+            .field(&tagged_base64::to_string(&self.to_tagged_base64()))
+            .finish()
+    }
+}
+
+impl Hash for Secp256k1Pub {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.compressed().hash(state);
+    }
+}
+
+impl PartialOrd for Secp256k1Pub {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Secp256k1Pub {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compressed().cmp(&other.compressed())
+    }
+}
+
+impl SignatureKey for Secp256k1Pub {
+    type PrivateKey = Secp256k1Priv;
+
+    #[instrument(skip(self))]
+    fn validate(&self, signature: &EncodedSignature, data: &[u8]) -> bool {
+        let signature = &signature.0[..];
+        if signature.len() != SIGNATURE_LEN {
+            debug!("signature was structurally invalid");
+            return false;
+        }
+        let (Ok(sig), Some(recovery_id)) = (
+            Signature::from_slice(&signature[..64]),
+            signature[64]
+                .checked_sub(27)
+                .and_then(RecoveryId::from_byte),
+        ) else {
+            debug!("signature was structurally invalid");
+            return false;
+        };
+        // high `s` signatures are malleable, and rejected by `ecrecover` users
+        if sig.normalize_s().is_some() {
+            debug!("signature has a high s");
+            return false;
+        }
+        // recovering the key checks the signature, and that `v` is the one `ecrecover` needs
+        let hash = Keccak256::digest(data);
+        match VerifyingKey::recover_from_prehash(&hash, &sig, recovery_id) {
+            Ok(recovered) => recovered == self.pub_key,
+            Err(e) => {
+                debug!(?e, "Signature failed verification");
+                false
+            }
+        }
+    }
+
+    fn sign(private_key: &Self::PrivateKey, data: &[u8]) -> EncodedSignature {
+        let hash = Keccak256::digest(data);
+        let (signature, recovery_id) = private_key
+            .priv_key
+            .sign_prehash_recoverable(&hash)
+            .expect("Signing a 32 byte hash should not be able to fail");
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(27 + recovery_id.to_byte());
+        EncodedSignature(bytes)
+    }
+
+    fn from_private(private_key: &Self::PrivateKey) -> Self {
+        let pub_key = *private_key.priv_key.verifying_key();
+        Self { pub_key }
+    }
+
+    fn to_bytes(&self) -> EncodedPublicKey {
+        EncodedPublicKey(self.compressed())
+    }
+
+    #[instrument]
+    fn from_bytes(bytes: &EncodedPublicKey) -> Option<Self> {
+        match VerifyingKey::from_sec1_bytes(&bytes.0) {
+            Ok(pub_key) => Some(Self { pub_key }),
+            Err(e) => {
+                debug!(?e, "Failed to deserialize public key");
+                None
+            }
+        }
+    }
+
+    fn generated_from_seed_indexed(seed: [u8; 32], index: u64) -> (Self, Self::PrivateKey) {
+        let priv_key = Self::PrivateKey::generated_from_seed_indexed(seed, index);
+        (Self::from_private(&priv_key), priv_key)
+    }
+}
+
+impl TestableSignatureKey for Secp256k1Pub {
+    fn generate_test_key(id: u64) -> Self::PrivateKey {
+        Secp256k1Priv::generated_from_seed_indexed([0_u8; 32], id)
+    }
+}
+
+impl KeystoreKey for Secp256k1Pub {
+    const KEY_TYPE: &'static str = "secp256k1";
+
+    fn generate_private_key() -> Self::PrivateKey {
+        Secp256k1Priv::generate()
+    }
+
+    fn private_key_to_bytes(private_key: &Self::PrivateKey) -> Vec<u8> {
+        private_key.to_bytes()
+    }
+
+    fn private_key_from_bytes(bytes: &[u8]) -> Option<Self::PrivateKey> {
+        Secp256k1Priv::from_bytes(bytes)
+    }
+}
+
+impl FromStr for Secp256k1Pub {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let base64 = TaggedBase64::from_str(s)
+            .map_err(|e| format!("Could not decode Secp256k1Pub: {e:?}"))?;
+        if base64.tag() != SECP256K1_PUB_TAG {
+            return Err(format!(
+                "Invalid Secp256k1Pub tag: {:?}, expected {:?}",
+                base64.tag(),
+                SECP256K1_PUB_TAG
+            ));
+        }
+
+        match Self::from_bytes(&EncodedPublicKey(base64.value())) {
+            Some(key) => Ok(key),
+            None => Err("Failed to decode secp256k1 key".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Secp256k1Pub {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let base64 = self.to_tagged_base64();
+        write!(f, "{}", tagged_base64::to_string(&base64))
+    }
+}
+
+impl Serialize for Secp256k1Pub {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Secp256k1Pub {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let base64 = String::deserialize(deserializer)?;
+        Self::from_str(&base64).map_err(D::Error::custom)
+    }
+}
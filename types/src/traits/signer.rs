@@ -0,0 +1,367 @@
+//! Signing with a validator key that may be held outside of the node process
+//!
+//! Every signature a node produces for consensus (proposals, votes and vote tokens) goes through
+//! a [`Signer`]. A [`LocalSigner`] holds the private key in memory; other implementations forward
+//! requests to an external process, such as an HSM or KMS front end, so the key never enters the
+//! node.
+//!
+//! Signers never sign raw data: they sign the [`signing_payload`] of a request, which binds the
+//! data to its [`SigningKind`], the [`ChainId`] of the network and the view. A signature made for
+//! one purpose, network or view is therefore never valid for another.
+//!
+//! Signers refuse to sign two conflicting payloads for the same view, which is what a node would
+//! need to do to equivocate. When the signer runs in another process, a compromised or buggy node
+//! cannot get around the rule.
+
+use crate::traits::signature_key::{EncodedSignature, SignatureKey};
+use async_trait::async_trait;
+use bincode::Options;
+use hotshot_utils::bincode::bincode_opts;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Default number of views a [`DoubleSignGuard`] remembers behind the highest view it signed for
+pub const DEFAULT_RETAINED_VIEWS: u64 = 1000;
+
+/// Identifies a deployment of `HotShot`, such as a testnet or mainnet
+///
+/// Deployments sharing validator keys still never accept each other's signatures, as every
+/// payload names the chain it was signed for.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct ChainId(pub u64);
+
+/// What a signature is for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum SigningKind {
+    /// A validating or commitment proposal
+    QuorumProposal,
+    /// A DA proposal
+    DAProposal,
+    /// A vote to append a leaf to the log
+    YesVote,
+    /// A vote to reject a leaf from the log
+    NoVote,
+    /// A vote to time out a view
+    TimeoutVote,
+    /// A vote that a block is available
+    DAVote,
+    /// A vote token proving membership in the committee of a view
+    VoteToken,
+    /// A [`KeyRotation`](crate::key_rotation::KeyRotation), signed for the view it takes effect in
+    KeyRotation,
+}
+
+impl SigningKind {
+    /// The kinds of which a signer signs at most one payload per view
+    ///
+    /// A yes and a no vote on the same view conflict, so they share a class.
+    #[must_use]
+    pub fn conflict_class(self) -> Self {
+        match self {
+            Self::NoVote => Self::YesVote,
+            kind => kind,
+        }
+    }
+
+    /// The tag starting every payload of this kind
+    #[must_use]
+    pub fn domain_tag(self) -> &'static str {
+        match self {
+            Self::QuorumProposal => "HotShot/QuorumProposal",
+            Self::DAProposal => "HotShot/DAProposal",
+            Self::YesVote => "HotShot/YesVote",
+            Self::NoVote => "HotShot/NoVote",
+            Self::TimeoutVote => "HotShot/TimeoutVote",
+            Self::DAVote => "HotShot/DAVote",
+            Self::VoteToken => "HotShot/VoteToken",
+            Self::KeyRotation => "HotShot/KeyRotation",
+        }
+    }
+}
+
+/// The bytes signed for `data` of kind `kind`, in `view_number` on `chain_id`
+///
+/// The domain tag is length prefixed and the chain id and view have a fixed length, so payloads
+/// differing in any of them never coincide.
+#[must_use]
+pub fn signing_payload(
+    kind: SigningKind,
+    chain_id: ChainId,
+    view_number: u64,
+    data: &[u8],
+) -> Vec<u8> {
+    let tag = kind.domain_tag().as_bytes();
+    let mut payload = Vec::with_capacity(24 + tag.len() + data.len());
+    payload.extend((tag.len() as u64).to_le_bytes());
+    payload.extend(tag);
+    payload.extend(chain_id.0.to_le_bytes());
+    payload.extend(view_number.to_le_bytes());
+    payload.extend(data);
+    payload
+}
+
+/// A request to sign `data` for `view_number` on `chain_id`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigningRequest {
+    /// The view the signature is for
+    pub view_number: u64,
+    /// What the signature is for
+    pub kind: SigningKind,
+    /// The network the signature is for
+    pub chain_id: ChainId,
+    /// The data to sign
+    pub data: Vec<u8>,
+}
+
+impl SigningRequest {
+    /// The bytes a signer signs for this request
+    #[must_use]
+    pub fn payload(&self) -> Vec<u8> {
+        signing_payload(self.kind, self.chain_id, self.view_number, &self.data)
+    }
+}
+
+/// Errors produced by a [`Signer`]
+#[derive(Snafu, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignerError {
+    /// Different data of the same kind was already signed for this view
+    #[snafu(display("refusing to sign a second {kind:?} for view {view_number}"))]
+    DoubleSign {
+        /// the view of the request
+        view_number: u64,
+        /// the kind of the request
+        kind: SigningKind,
+    },
+    /// The view is older than any the signer still remembers, so it cannot rule out a double sign
+    #[snafu(display("view {view_number} is below the signer's watermark {watermark}"))]
+    StaleView {
+        /// the view of the request
+        view_number: u64,
+        /// the oldest view the signer remembers
+        watermark: u64,
+    },
+    /// The signer could not be reached or answered with something unexpected
+    #[snafu(display("signer unavailable: {reason}"))]
+    Unavailable {
+        /// why the signer is unavailable
+        reason: String,
+    },
+    /// The operation needs the private key in memory, and this signer does not hold it
+    #[snafu(display("the operation needs the private key, which this signer does not hold"))]
+    NoLocalKey,
+}
+
+/// Something that signs on behalf of a validator key
+#[async_trait]
+pub trait Signer<KEY: SignatureKey>: Debug + Send + Sync {
+    /// The public key of the validator
+    fn public_key(&self) -> &KEY;
+
+    /// Sign the [`payload`](SigningRequest::payload) of `request`, unless that would sign two
+    /// conflicting payloads for the same view
+    ///
+    /// # Errors
+    /// If the request is refused or the signer cannot be reached
+    async fn sign(&self, request: SigningRequest) -> Result<EncodedSignature, SignerError>;
+
+    /// The private key, if this signer holds it in memory
+    ///
+    /// Only for operations that cannot be expressed as a [`SigningRequest`], which is VRF
+    /// sortition: elections drawing vote tokens with a VRF refuse signers without the key with
+    /// [`SignerError::NoLocalKey`], so their nodes cannot use a remote signer, and `HotShot`
+    /// refuses to start with one (see
+    /// [`Membership::needs_local_key`](crate::traits::election::Membership::needs_local_key)).
+    /// Remote signers return `None`, the default.
+    fn local_private_key(&self) -> Option<&KEY::PrivateKey> {
+        None
+    }
+}
+
+/// What a [`DoubleSignGuard`] remembers, and persists if it has a state file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GuardState {
+    /// digest of the payload signed for each view and conflict class
+    signed: BTreeMap<(u64, SigningKind), [u8; 32]>,
+    /// views below this one are forgotten
+    watermark: u64,
+}
+
+/// Enforces the anti-double-sign rule: at most one payload per view and
+/// [conflict class](SigningKind::conflict_class)
+///
+/// Requests repeating an already signed payload are allowed, so a vote can be signed again
+/// after a restart of the node. Views more than `retained_views` behind the highest one signed
+/// for are forgotten, and requests for them are refused.
+///
+/// A guard with a state file writes what it remembers to the file before allowing a signature,
+/// and starts from the file when reopened, so the rule holds across restarts of the signer. The
+/// write is a small synchronous one, made once per signature.
+#[derive(Debug)]
+pub struct DoubleSignGuard {
+    /// the payloads signed so far
+    state: GuardState,
+    /// number of views to remember behind the highest one
+    retained_views: u64,
+    /// the file the state is persisted to, if any
+    path: Option<PathBuf>,
+}
+
+impl DoubleSignGuard {
+    /// A guard remembering `retained_views` views behind the highest one signed for, in memory
+    #[must_use]
+    pub fn new(retained_views: u64) -> Self {
+        Self {
+            state: GuardState::default(),
+            retained_views,
+            path: None,
+        }
+    }
+
+    /// A guard remembering `retained_views` views behind the highest one signed for, persisted to
+    /// the file at `path`
+    ///
+    /// Starts from the state in the file if it exists.
+    ///
+    /// # Errors
+    /// If the file exists but cannot be read or does not hold a guard state
+    pub fn open(path: impl AsRef<Path>, retained_views: u64) -> Result<Self, SignerError> {
+        let path = path.as_ref().to_path_buf();
+        let unavailable = |e: &dyn Display| SignerError::Unavailable {
+            reason: format!("cannot load guard state from {}: {e}", path.display()),
+        };
+        let state = match fs::read(&path) {
+            Ok(bytes) => bincode_opts()
+                .deserialize(&bytes)
+                .map_err(|e| unavailable(&e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => GuardState::default(),
+            Err(e) => return Err(unavailable(&e)),
+        };
+        Ok(Self {
+            state,
+            retained_views,
+            path: Some(path),
+        })
+    }
+
+    /// Check `request` against the payloads signed so far, and record it if it may be signed
+    ///
+    /// # Errors
+    /// If `request` would be a double sign, is for a forgotten view, or cannot be persisted
+    pub fn check_and_record(&mut self, request: &SigningRequest) -> Result<(), SignerError> {
+        if request.view_number < self.state.watermark {
+            return Err(SignerError::StaleView {
+                view_number: request.view_number,
+                watermark: self.state.watermark,
+            });
+        }
+        let slot = (request.view_number, request.kind.conflict_class());
+        let digest = *blake3::hash(&request.payload()).as_bytes();
+        match self.state.signed.get(&slot) {
+            Some(signed) if *signed != digest => {
+                return Err(SignerError::DoubleSign {
+                    view_number: request.view_number,
+                    kind: request.kind,
+                });
+            }
+            Some(_) => return Ok(()),
+            None => {}
+        }
+        self.state.signed.insert(slot, digest);
+        let watermark = self.state.watermark;
+        let retained_from = request.view_number.saturating_sub(self.retained_views);
+        self.state.watermark = watermark.max(retained_from);
+        if let Err(e) = self.persist() {
+            self.state.signed.remove(&slot);
+            self.state.watermark = watermark;
+            return Err(e);
+        }
+
+        if self.state.watermark > watermark {
+            let retained = (self.state.watermark, SigningKind::QuorumProposal);
+            self.state.signed = self.state.signed.split_off(&retained);
+        }
+        Ok(())
+    }
+
+    /// Write the state to the state file, if any, replacing it atomically
+    fn persist(&self) -> Result<(), SignerError> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let unavailable = |e: &dyn Display| SignerError::Unavailable {
+            reason: format!("cannot persist guard state to {}: {e}", path.display()),
+        };
+        let bytes = bincode_opts()
+            .serialize(&self.state)
+            .map_err(|e| unavailable(&e))?;
+        let staging = path.with_extension("tmp");
+        fs::write(&staging, bytes).map_err(|e| unavailable(&e))?;
+        fs::rename(&staging, path).map_err(|e| unavailable(&e))
+    }
+}
+
+impl Default for DoubleSignGuard {
+    fn default() -> Self {
+        Self::new(DEFAULT_RETAINED_VIEWS)
+    }
+}
+
+/// A [`Signer`] holding the private key in memory
+pub struct LocalSigner<KEY: SignatureKey> {
+    /// the public key
+    public_key: KEY,
+    /// the private key
+    private_key: KEY::PrivateKey,
+    /// the payloads signed so far
+    guard: Mutex<DoubleSignGuard>,
+}
+
+impl<KEY: SignatureKey> LocalSigner<KEY> {
+    /// A signer for `private_key`
+    #[must_use]
+    pub fn new(private_key: KEY::PrivateKey) -> Self {
+        Self::with_guard(private_key, DoubleSignGuard::default())
+    }
+
+    /// A signer for `private_key` enforcing the anti-double-sign rule with `guard`
+    #[must_use]
+    pub fn with_guard(private_key: KEY::PrivateKey, guard: DoubleSignGuard) -> Self {
+        Self {
+            public_key: KEY::from_private(&private_key),
+            private_key,
+            guard: Mutex::new(guard),
+        }
+    }
+}
+
+impl<KEY: SignatureKey> Debug for LocalSigner<KEY> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalSigner")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<KEY: SignatureKey> Signer<KEY> for LocalSigner<KEY> {
+    fn public_key(&self) -> &KEY {
+        &self.public_key
+    }
+
+    async fn sign(&self, request: SigningRequest) -> Result<EncodedSignature, SignerError> {
+        self.guard.lock().unwrap().check_and_record(&request)?;
+        Ok(KEY::sign(&self.private_key, &request.payload()))
+    }
+
+    fn local_private_key(&self) -> Option<&KEY::PrivateKey> {
+        Some(&self.private_key)
+    }
+}
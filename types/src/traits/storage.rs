@@ -4,6 +4,8 @@ use super::{node_implementation::NodeType, signature_key::EncodedPublicKey};
 use crate::certificate::QuorumCertificate;
 use crate::{
     data::LeafType,
+    finality::FinalityProof,
+    key_rotation::KeyRotation,
     traits::{election::SignedCertificate, Block},
 };
 use async_trait::async_trait;
@@ -42,6 +44,23 @@ where
     async fn get_anchored_view(&self) -> Result<StoredView<TYPES, LEAF>>;
     /// Commit this storage.
     async fn commit(&self) -> Result;
+    /// Store the proof that the leaves of a decision were decided, once for all of them.
+    ///
+    /// Unlike views, proofs are kept when the storage is cleaned up.
+    async fn append_finality_proof(&self, proof: FinalityProof<TYPES, LEAF>) -> Result;
+    /// Get the proof that the leaf at `height` was decided, if one was stored, cut out of the
+    /// proof of its decision with [`FinalityProof::for_height`]
+    async fn get_finality_proof(&self, height: u64) -> Result<Option<FinalityProof<TYPES, LEAF>>>;
+    /// Store key rotations applied to the stake table, after those stored before.
+    ///
+    /// Like proofs, rotations are kept when the storage is cleaned up, as a restarted node
+    /// replays them onto the stake table of its configuration.
+    async fn append_key_rotations(
+        &self,
+        rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
+    ) -> Result;
+    /// Get every key rotation applied to the stake table, in the order they were applied
+    async fn get_key_rotations(&self) -> Result<Vec<KeyRotation<TYPES::SignatureKey>>>;
 
     /// Insert a single view. Shorthand for
     /// ```rust,ignore
@@ -139,6 +158,8 @@ pub struct StoredView<TYPES: NodeType, LEAF: LeafType<NodeType = TYPES>> {
     pub deltas: LEAF::DeltasType,
     /// transactions rejected in this view
     pub rejected: Vec<TYPES::Transaction>,
+    /// key rotations carried by this view
+    pub key_rotations: Vec<KeyRotation<TYPES::SignatureKey>>,
     /// the timestamp this view was recv-ed in nanonseconds
     #[derivative(PartialEq = "ignore")]
     pub timestamp: i128,
@@ -172,6 +193,7 @@ where
             justify_qc: qc,
             state,
             rejected,
+            key_rotations: Vec::new(),
             timestamp: time::OffsetDateTime::now_utc().unix_timestamp_nanos(),
             proposer_id,
         }
@@ -11,3 +11,6 @@
 
 /// Provides bincode options
 pub mod bincode;
+
+/// Provides the version of the wire protocol
+pub mod version;
@@ -0,0 +1,98 @@
+//! The version of the wire protocol, and the header announcing it in front of every message.
+//!
+//! A node writes messages in the layout of the minor version before its own, and reads everything
+//! a node at most one minor version apart writes. A release changing the layout of a message thus
+//! only puts the new layout on the wire one release later, once every node it may meet reads it,
+//! which lets a cluster upgrade one node at a time.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// The version of the wire protocol spoken by a node
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ProtocolVersion {
+    /// Bumped by changes nodes of the previous version cannot follow
+    pub major: u16,
+    /// Bumped by changes to the layout of messages
+    pub minor: u16,
+}
+
+impl ProtocolVersion {
+    /// The version of this build, which it advertises to its peers
+    pub const CURRENT: Self = Self { major: 1, minor: 0 };
+
+    /// The length of the header announcing a version in front of a message
+    pub const HEADER_LEN: usize = 4;
+
+    /// Whether nodes of this version and of `other` take part in the same network, which they do
+    /// if they are at most one minor version apart
+    #[must_use]
+    pub fn is_compatible(self, other: Self) -> bool {
+        self.major == other.major && self.minor.abs_diff(other.minor) <= 1
+    }
+
+    /// The version whose layout nodes of this version write
+    #[must_use]
+    pub fn written(self) -> Self {
+        Self {
+            major: self.major,
+            minor: self.minor.saturating_sub(1),
+        }
+    }
+
+    /// Whether nodes of this version read messages written in the layout of `written`, which they
+    /// do for everything the nodes they are compatible with write
+    #[must_use]
+    pub fn reads(self, written: Self) -> bool {
+        self.major == written.major
+            && written.minor <= self.minor
+            && self.minor - written.minor <= 2
+    }
+
+    /// The header announcing this version in front of a message
+    #[must_use]
+    pub fn to_header(self) -> [u8; Self::HEADER_LEN] {
+        let [major_low, major_high] = self.major.to_le_bytes();
+        let [minor_low, minor_high] = self.minor.to_le_bytes();
+        [major_low, major_high, minor_low, minor_high]
+    }
+
+    /// Splits `bytes` into the version announced by their header and the message following it, or
+    /// returns `None` if they are too short to hold a header
+    #[must_use]
+    pub fn split_header(bytes: &[u8]) -> Option<(Self, &[u8])> {
+        if bytes.len() < Self::HEADER_LEN {
+            return None;
+        }
+        let (header, message) = bytes.split_at(Self::HEADER_LEN);
+        let version = Self {
+            major: u16::from_le_bytes([header[0], header[1]]),
+            minor: u16::from_le_bytes([header[2], header[3]]),
+        };
+        Some((version, message))
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for ProtocolVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (major, minor) = s
+            .split_once('.')
+            .ok_or_else(|| format!("Invalid protocol version {s:?}, expected major.minor"))?;
+        let parse = |part: &str| {
+            part.parse()
+                .map_err(|e| format!("Invalid protocol version {s:?}: {e}"))
+        };
+        Ok(Self {
+            major: parse(major)?,
+            minor: parse(minor)?,
+        })
+    }
+}
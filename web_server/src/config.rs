@@ -1,5 +1,13 @@
 pub const DEFAULT_WEB_SERVER_PORT: u16 = 9000;
 
+pub fn get_chain_id_route() -> String {
+    "api/chainid".to_string()
+}
+
+pub fn get_version_route() -> String {
+    "api/version".to_string()
+}
+
 pub fn get_proposal_route(view_number: u64) -> String {
     format!("api/proposal/{view_number}")
 }
@@ -8,6 +8,8 @@ use futures::FutureExt;
 
 use hotshot_types::traits::signature_key::EncodedPublicKey;
 use hotshot_types::traits::signature_key::SignatureKey;
+use hotshot_types::traits::signer::ChainId;
+use hotshot_utils::version::ProtocolVersion;
 use rand::SeedableRng;
 use std::collections::HashMap;
 use std::io;
@@ -51,6 +53,8 @@ struct WebServerState<KEY> {
     shutdown: Option<OneShotReceiver<()>>,
     /// stake table with leader keys
     stake_table: Vec<KEY>,
+    /// the chain id of the network the server is serving
+    chain_id: ChainId,
     /// prng for generating endpoint
     _prng: rand::rngs::StdRng,
 }
@@ -65,6 +69,7 @@ impl<KEY: SignatureKey + 'static> WebServerState<KEY> {
             oldest_proposal: 0,
             shutdown: None,
             stake_table: Vec::new(),
+            chain_id: ChainId::default(),
             vote_index: HashMap::new(),
             transactions: HashMap::new(),
             _prng: rand::rngs::StdRng::from_entropy(),
@@ -77,10 +82,16 @@ impl<KEY: SignatureKey + 'static> WebServerState<KEY> {
         self.shutdown = shutdown_listener;
         self
     }
+    pub fn with_chain_id(mut self, chain_id: ChainId) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
 }
 
 /// Trait defining methods needed for the `WebServerState`
 pub trait WebServerDataSource<KEY> {
+    fn get_chain_id(&self) -> Result<ChainId, Error>;
+    fn get_version(&self) -> Result<ProtocolVersion, Error>;
     fn get_proposals(&self, view_number: u64) -> Result<Option<Vec<Vec<u8>>>, Error>;
     fn get_votes(&self, view_number: u64, index: u64) -> Result<Option<Vec<Vec<u8>>>, Error>;
     fn get_transactions(&self, index: u64) -> Result<Option<Vec<Vec<u8>>>, Error>;
@@ -91,6 +102,17 @@ pub trait WebServerDataSource<KEY> {
 }
 
 impl<KEY: SignatureKey> WebServerDataSource<KEY> for WebServerState<KEY> {
+    /// Return the chain id of the network, so clients of other networks can refuse to take part
+    fn get_chain_id(&self) -> Result<ChainId, Error> {
+        Ok(self.chain_id)
+    }
+
+    /// Return the version of the wire protocol, so clients of incompatible versions can refuse to
+    /// take part
+    fn get_version(&self) -> Result<ProtocolVersion, Error> {
+        Ok(ProtocolVersion::CURRENT)
+    }
+
     /// Return all proposals the server has received for a particular view
     // TODO ED: Update so that only 1 proposal is ever stored per view
     fn get_proposals(&self, view_number: u64) -> Result<Option<Vec<Vec<u8>>>, Error> {
@@ -224,7 +246,13 @@ where
             Api::<State, Error>::new(toml)?
         }
     };
-    api.get("getproposal", |req, state| {
+    api.get("getchainid", |_req, state| {
+        async move { state.get_chain_id() }.boxed()
+    })?
+    .get("getversion", |_req, state| {
+        async move { state.get_version() }.boxed()
+    })?
+    .get("getproposal", |req, state| {
         async move {
             let view_number: u64 = req.integer_param("view_number")?;
             state.get_proposals(view_number)
@@ -283,10 +311,15 @@ where
 
 pub async fn run_web_server<KEY: SignatureKey + 'static>(
     shutdown_listener: Option<OneShotReceiver<()>>,
+    chain_id: ChainId,
 ) -> io::Result<()> {
     let options = Options::default();
     let api = define_api(&options).unwrap();
-    let state = State::new(WebServerState::new().with_shutdown_signal(shutdown_listener));
+    let state = State::new(
+        WebServerState::new()
+            .with_shutdown_signal(shutdown_listener)
+            .with_chain_id(chain_id),
+    );
     let mut app = App::<State<KEY>, Error>::with_state(state);
 
     app.register_module("api", api).unwrap();
@@ -297,8 +330,8 @@ pub async fn run_web_server<KEY: SignatureKey + 'static>(
 #[cfg(test)]
 mod test {
     use crate::config::{
-        get_proposal_route, get_transactions_route, get_vote_route, post_proposal_route,
-        post_transactions_route, post_vote_route,
+        get_chain_id_route, get_proposal_route, get_transactions_route, get_version_route,
+        get_vote_route, post_proposal_route, post_transactions_route, post_vote_route,
     };
 
     use super::*;
@@ -320,7 +353,8 @@ mod test {
         let base_url = format!("0.0.0.0:{port}");
         let options = Options::default();
         let api = define_api(&options).unwrap();
-        let mut app = App::<State, Error>::with_state(State::new(WebServerState::new()));
+        let state = State::new(WebServerState::new().with_chain_id(ChainId(3)));
+        let mut app = App::<State, Error>::with_state(state);
 
         app.register_module("api", api).unwrap();
         let _handle = async_spawn(app.serve(base_url.clone()));
@@ -329,6 +363,22 @@ mod test {
         let client = surf_disco::Client::<ClientError>::new(base_url);
         assert!(client.connect(None).await);
 
+        // Test getting the chain id
+        let chain_id = client
+            .get::<ChainId>(&get_chain_id_route())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(chain_id, ChainId(3));
+
+        // Test getting the version
+        let version = client
+            .get::<ProtocolVersion>(&get_version_route())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(version, ProtocolVersion::CURRENT);
+
         // Test posting and getting proposals
         let prop1 = "prop1";
         client